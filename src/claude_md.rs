@@ -1,6 +1,8 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GrootError, Result};
 
 pub struct ClaudeMdVars<'a> {
     pub worktree_path: &'a str,
@@ -9,6 +11,8 @@ pub struct ClaudeMdVars<'a> {
     pub project_name: &'a str,
     pub task_type: &'a str,
     pub detected_types: &'a str,
+    /// The type the project is mainly built with, e.g. "rails"
+    pub primary_type: &'a str,
     pub compose_enabled: bool,
     pub compose_file: &'a str,
     pub compose_project: &'a str,
@@ -17,19 +21,130 @@ pub struct ClaudeMdVars<'a> {
     pub redis_port: u16,
     pub shared_compose: bool,
     pub shared_grove_name: &'a str,
+    /// Detected package managers, e.g. "bundler, pnpm"
+    pub pkg_manager: &'a str,
+    /// Install command for the primary package manager, e.g. "pnpm install --frozen-lockfile"
+    pub install_command: &'a str,
+    /// Detected test frameworks, e.g. "rspec, jest"
+    pub test_frameworks: &'a str,
+    /// One test command per line
+    pub test_commands: &'a str,
+    /// Detected linters, e.g. "rubocop, eslint"
+    pub linters: &'a str,
+    /// One lint command per line
+    pub lint_commands: &'a str,
+    /// Database engine, e.g. "postgres"; empty when unknown
+    pub db_engine: &'a str,
+    /// The task description given with `--prompt` alongside `--prompt-template`
+    pub description: &'a str,
+    /// Notes added with `groot worker note`, one list item each
+    pub notes: &'a str,
 }
 
-/// Load a user-provided claude-md template, or fall back to the built-in default.
+/// Placeholders substituted by `render`.
+pub const TEMPLATE_VARS: &[&str] = &[
+    "WORKTREE_PATH",
+    "WORKER_NAME",
+    "BRANCH_NAME",
+    "PROJECT_NAME",
+    "TASK_TYPE",
+    "DETECTED_TYPES",
+    "PRIMARY_TYPE",
+    "COMPOSE_ENABLED",
+    "COMPOSE_FILE",
+    "COMPOSE_PROJECT",
+    "APP_PORT",
+    "DB_PORT",
+    "REDIS_PORT",
+    "SHARED_COMPOSE",
+    "SHARED_GROVE",
+    "PKG_MANAGER",
+    "INSTALL_COMMAND",
+    "TEST_FRAMEWORKS",
+    "TEST_COMMANDS",
+    "LINTERS",
+    "LINT_COMMANDS",
+    "DB_ENGINE",
+    "DESCRIPTION",
+    "NOTES",
+];
+
+/// Names accepted by `{{#if ...}}` blocks.
+pub const CONDITIONAL_VARS: &[&str] = &[
+    "COMPOSE_ENABLED",
+    "SHARED_COMPOSE",
+    "PLAIN_TREE",
+    "TESTS_DETECTED",
+    "LINTERS_DETECTED",
+    "HAS_DESCRIPTION",
+    "HAS_NOTES",
+];
+
+/// Load a user-provided claude-md template (with its partials included), or
+/// fall back to the built-in default.
 /// Returns (template_content, is_custom).
 pub fn load_or_default(groot_dir: &Path) -> Result<(String, bool)> {
     let custom_path = groot_dir.join("claude-md.template");
     if custom_path.exists() {
         let contents = std::fs::read_to_string(&custom_path)?;
-        return Ok((contents, true));
+        return Ok((expand_partials(&contents, groot_dir)?, true));
     }
     Ok((default_template().to_string(), false))
 }
 
+/// `.groot/claude-partials/`, holding the sections included with `{{> name}}`.
+pub fn partials_dir(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("claude-partials")
+}
+
+/// Names of the partials in `.groot/claude-partials/`, sorted.
+pub fn list_partials(groot_dir: &Path) -> Vec<String> {
+    markdown_stems(&partials_dir(groot_dir))
+}
+
+/// Replace every `{{> name}}` with `.groot/claude-partials/<name>.md` (whose
+/// own includes are expanded too), so shared sections are maintained once.
+/// Runs before `render`, so partials may use placeholders and `{{#if}}` blocks.
+pub fn expand_partials(template: &str, groot_dir: &Path) -> Result<String> {
+    expand_partials_from(template, &partials_dir(groot_dir), &mut Vec::new())
+}
+
+/// `stack` holds the partials being expanded, to catch include cycles.
+fn expand_partials_from(template: &str, dir: &Path, stack: &mut Vec<String>) -> Result<String> {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{>") {
+        let after = &rest[start + 3..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(GrootError::Other(format!("invalid partial name '{name}'")));
+        }
+        if stack.iter().any(|n| n == name) {
+            return Err(GrootError::Other(format!(
+                "partial '{name}' includes itself ({} -> {name})",
+                stack.join(" -> ")
+            )));
+        }
+        let path = dir.join(format!("{name}.md"));
+        let partial = std::fs::read_to_string(&path)
+            .map_err(|e| GrootError::Other(format!("partial '{name}' ({}): {e}", path.display())))?;
+
+        output.push_str(&rest[..start]);
+        stack.push(name.to_string());
+        // The include's own line break ends the partial's last line
+        output.push_str(&expand_partials_from(partial.trim_end_matches('\n'), dir, stack)?);
+        stack.pop();
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
 /// Render template variables and conditional blocks.
 ///
 /// Variable substitution: `{{VAR}}` → value
@@ -81,6 +196,7 @@ pub fn render(template: &str, vars: &ClaudeMdVars) -> String {
     output = output.replace("{{PROJECT_NAME}}", vars.project_name);
     output = output.replace("{{TASK_TYPE}}", vars.task_type);
     output = output.replace("{{DETECTED_TYPES}}", vars.detected_types);
+    output = output.replace("{{PRIMARY_TYPE}}", vars.primary_type);
     output = output.replace(
         "{{COMPOSE_ENABLED}}",
         if vars.compose_enabled { "true" } else { "false" },
@@ -95,10 +211,62 @@ pub fn render(template: &str, vars: &ClaudeMdVars) -> String {
         if vars.shared_compose { "true" } else { "false" },
     );
     output = output.replace("{{SHARED_GROVE}}", vars.shared_grove_name);
+    output = output.replace("{{PKG_MANAGER}}", vars.pkg_manager);
+    output = output.replace("{{INSTALL_COMMAND}}", vars.install_command);
+    output = output.replace("{{TEST_FRAMEWORKS}}", vars.test_frameworks);
+    output = output.replace("{{TEST_COMMANDS}}", vars.test_commands);
+    output = output.replace("{{LINTERS}}", vars.linters);
+    output = output.replace("{{LINT_COMMANDS}}", vars.lint_commands);
+    output = output.replace("{{DB_ENGINE}}", vars.db_engine);
+    output = output.replace("{{DESCRIPTION}}", vars.description);
+    output = output.replace("{{NOTES}}", vars.notes);
 
     output
 }
 
+/// The prompt claude is launched with in a new worker, rendered with the same
+/// variables as CLAUDE.local.md once the worker's ports and paths are known.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InitialPrompt {
+    /// `--prompt`/`--prompt-file` text, or a `.groot/prompts/<name>.md` template
+    pub template: String,
+    /// Fills `{{DESCRIPTION}}`
+    pub description: String,
+    /// The model claude is launched with; its default when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl InitialPrompt {
+    pub fn render(&self, vars: &ClaudeMdVars) -> String {
+        render(&self.template, vars).trim_end().to_string()
+    }
+}
+
+/// `.groot/prompts/<name>.md`
+pub fn prompt_template_path(groot_dir: &Path, name: &str) -> PathBuf {
+    groot_dir.join("prompts").join(format!("{name}.md"))
+}
+
+/// Names of the templates in `.groot/prompts/`, sorted.
+pub fn list_prompt_templates(groot_dir: &Path) -> Vec<String> {
+    markdown_stems(&groot_dir.join("prompts"))
+}
+
+/// Names of the `.md` files in `dir`, without the extension, sorted.
+fn markdown_stems(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
 /// Resolve a conditional variable name to its string value.
 fn resolve_var(name: &str, vars: &ClaudeMdVars) -> String {
     match name {
@@ -123,11 +291,119 @@ fn resolve_var(name: &str, vars: &ClaudeMdVars) -> String {
                 "false"
             }
         }
+        "TESTS_DETECTED" => {
+            if !vars.test_commands.is_empty() {
+                "true"
+            } else {
+                "false"
+            }
+        }
+        "LINTERS_DETECTED" => {
+            if !vars.lint_commands.is_empty() {
+                "true"
+            } else {
+                "false"
+            }
+        }
+        "HAS_DESCRIPTION" => {
+            if !vars.description.trim().is_empty() {
+                "true"
+            } else {
+                "false"
+            }
+        }
+        "HAS_NOTES" => {
+            if !vars.notes.is_empty() {
+                "true"
+            } else {
+                "false"
+            }
+        }
         _ => "",
     }
     .to_string()
 }
 
+/// Collect the names of all `{{VAR}}` placeholders in `text`, skipping
+/// conditional markers (`{{#if ...}}`, `{{/if}}`) and includes (`{{> name}}`).
+pub fn find_placeholders(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() && !name.starts_with(['#', '/', '>']) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+
+    names
+}
+
+/// Check a claude-md template for unknown placeholders and malformed conditionals.
+/// Returns one human-readable message per problem (empty when the template is valid).
+pub fn validate(template: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut open_since: Option<usize> = None;
+
+    for (idx, line) in template.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = line.trim();
+
+        let condition = trimmed
+            .strip_prefix("{{#if ")
+            .and_then(|rest| rest.strip_suffix("}}"));
+        if let Some(var_name) = condition {
+            let key = var_name.trim().trim_start_matches('!').trim();
+            if !CONDITIONAL_VARS.contains(&key) {
+                problems.push(format!("line {line_no}: unknown condition '{key}'"));
+            }
+            if let Some(open_line) = open_since {
+                problems.push(format!(
+                    "line {line_no}: nested conditional (block opened on line {open_line} is still open)"
+                ));
+            }
+            open_since = Some(line_no);
+            continue;
+        }
+
+        if trimmed == "{{/if}}" {
+            if open_since.take().is_none() {
+                problems.push(format!("line {line_no}: {{{{/if}}}} without a matching {{{{#if}}}}"));
+            }
+            continue;
+        }
+
+        for name in find_placeholders(line) {
+            if !TEMPLATE_VARS.contains(&name.as_str()) {
+                problems.push(format!("line {line_no}: unknown placeholder {{{{{name}}}}}"));
+            }
+        }
+    }
+
+    if let Some(open_line) = open_since {
+        problems.push(format!("line {open_line}: {{{{#if}}}} is never closed"));
+    }
+
+    problems
+}
+
+/// Check that every `{{> name}}` in a template resolves, without include
+/// cycles. One message per problem, like `validate`.
+pub fn validate_partials(template: &str, groot_dir: &Path) -> Vec<String> {
+    template
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains("{{>"))
+        .filter_map(|(idx, line)| expand_partials(line, groot_dir).err().map(|e| format!("line {}: {e}", idx + 1)))
+        .collect()
+}
+
 /// Load template, render with vars, and write CLAUDE.md into the worktree.
 pub fn generate(worktree_path: &Path, groot_dir: &Path, vars: &ClaudeMdVars) -> Result<()> {
     let (template, _is_custom) = load_or_default(groot_dir)?;
@@ -137,6 +413,62 @@ pub fn generate(worktree_path: &Path, groot_dir: &Path, vars: &ClaudeMdVars) ->
     Ok(())
 }
 
+/// `.groot/claude-settings.template`: rendered into each worktree's
+/// `.claude/settings.local.json` when present.
+pub fn settings_template_path(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("claude-settings.template")
+}
+
+/// Render `{{VAR}}` placeholders in every string of a settings template (a
+/// JSON document), so substituted values never need escaping.
+pub fn render_settings(template: &str, vars: &ClaudeMdVars) -> Result<String> {
+    let mut settings: serde_json::Value = serde_json::from_str(template)?;
+    render_json_strings(&mut settings, vars);
+    Ok(serde_json::to_string_pretty(&settings)? + "\n")
+}
+
+fn render_json_strings(value: &mut serde_json::Value, vars: &ClaudeMdVars) {
+    match value {
+        serde_json::Value::String(s) => {
+            let rendered = render(s, vars);
+            // `render` ends every line with a newline
+            *s = match rendered.strip_suffix('\n') {
+                Some(r) if !s.ends_with('\n') => r.to_string(),
+                _ => rendered,
+            };
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| render_json_strings(v, vars)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| render_json_strings(v, vars)),
+        _ => {}
+    }
+}
+
+/// Check a settings template parses as JSON and uses only known placeholders.
+pub fn validate_settings(template: &str) -> Vec<String> {
+    if let Err(e) = serde_json::from_str::<serde_json::Value>(template) {
+        return vec![format!("invalid JSON: {e}")];
+    }
+    find_placeholders(template)
+        .into_iter()
+        .filter(|name| !TEMPLATE_VARS.contains(&name.as_str()))
+        .map(|name| format!("unknown placeholder {{{{{name}}}}}"))
+        .collect()
+}
+
+/// Write `.claude/settings.local.json` into the worktree from the project's
+/// settings template. Returns false when the project has none.
+pub fn generate_settings(worktree_path: &Path, groot_dir: &Path, vars: &ClaudeMdVars) -> Result<bool> {
+    let template_path = settings_template_path(groot_dir);
+    if !template_path.exists() {
+        return Ok(false);
+    }
+    let rendered = render_settings(&std::fs::read_to_string(&template_path)?, vars)?;
+    let claude_dir = worktree_path.join(".claude");
+    std::fs::create_dir_all(&claude_dir)?;
+    std::fs::write(claude_dir.join("settings.local.json"), rendered)?;
+    Ok(true)
+}
+
 /// Built-in default CLAUDE.md template for groot environments.
 pub fn default_template() -> &'static str {
     r#"# CLAUDE.local.md — Auto-generated by groot
@@ -147,8 +479,25 @@ pub fn default_template() -> &'static str {
 - **Task**: {{WORKER_NAME}}
 - **Branch**: {{BRANCH_NAME}}
 - **Task Type**: {{TASK_TYPE}}
+- **Primary Type**: {{PRIMARY_TYPE}}
 - **Detected Types**: {{DETECTED_TYPES}}
+- **Package Managers**: {{PKG_MANAGER}}
+- **Database**: {{DB_ENGINE}}
 - **Worktree**: `{{WORKTREE_PATH}}`
+{{#if HAS_DESCRIPTION}}
+
+## Your Task
+
+{{DESCRIPTION}}
+{{/if}}
+{{#if HAS_NOTES}}
+
+## Notes So Far
+
+Decisions and findings recorded for this task, oldest first:
+
+{{NOTES}}
+{{/if}}
 
 ## Worktree Isolation
 
@@ -187,8 +536,8 @@ docker compose -f "{{COMPOSE_FILE}}" -p "{{COMPOSE_PROJECT}}" exec -e DATABASE_U
 # Run migrations
 docker compose -f "{{COMPOSE_FILE}}" -p "{{COMPOSE_PROJECT}}" exec app rails db:migrate
 
-# Install gems
-docker compose -f "{{COMPOSE_FILE}}" -p "{{COMPOSE_PROJECT}}" exec app bundle install
+# Install dependencies
+docker compose -f "{{COMPOSE_FILE}}" -p "{{COMPOSE_PROJECT}}" exec app {{INSTALL_COMMAND}}
 ```
 
 ### Ports
@@ -242,6 +591,28 @@ Run commands locally in this worktree. Connect to the grove's database and Redis
 No Docker Compose environment is configured for this tree.
 Run all commands directly in this worktree directory.
 {{/if}}
+{{#if TESTS_DETECTED}}
+
+## Running Tests
+
+Detected test frameworks: {{TEST_FRAMEWORKS}}. Run the suite with:
+
+```bash
+{{TEST_COMMANDS}}
+```
+
+When a compose environment is running, run these inside the app container as shown above.
+{{/if}}
+{{#if LINTERS_DETECTED}}
+
+## Linting
+
+Detected linters: {{LINTERS}}. Check your changes before committing with:
+
+```bash
+{{LINT_COMMANDS}}
+```
+{{/if}}
 
 ## Git Conventions
 
@@ -264,6 +635,7 @@ mod tests {
             project_name: "myproj",
             task_type: "bugfix",
             detected_types: "rails, ruby",
+            primary_type: "rails",
             compose_enabled: true,
             compose_file: "/tmp/compose/fix-login/docker-compose.yml",
             compose_project: "groot-fix-login",
@@ -272,6 +644,15 @@ mod tests {
             redis_port: 6380,
             shared_compose: false,
             shared_grove_name: "",
+            pkg_manager: "bundler, yarn",
+            install_command: "bundle install",
+            test_frameworks: "rspec",
+            test_commands: "bundle exec rspec",
+            linters: "rubocop",
+            lint_commands: "bundle exec rubocop",
+            db_engine: "postgres",
+            description: "",
+            notes: "",
         }
     }
 
@@ -337,6 +718,51 @@ mod tests {
         assert!(!rendered.contains("No Docker Compose environment"));
     }
 
+    #[test]
+    fn test_find_placeholders_skips_conditionals() {
+        let text = "{{#if COMPOSE_ENABLED}} {{WORKER_NAME}} {{ APP_PORT }} {{/if}} {{> tests}} {{unclosed";
+        assert_eq!(find_placeholders(text), vec!["WORKER_NAME", "APP_PORT"]);
+    }
+
+    #[test]
+    fn test_expand_partials() {
+        let dir = tempfile::tempdir().unwrap();
+        let partials = partials_dir(dir.path());
+        std::fs::create_dir_all(&partials).unwrap();
+        std::fs::write(partials.join("tests.md"), "{{#if TESTS_DETECTED}}\n{{TEST_COMMANDS}}\n{{/if}}\n").unwrap();
+        std::fs::write(partials.join("conventions.md"), "Conventions for {{PROJECT_NAME}}\n{{> tests}}\n").unwrap();
+
+        let template = "# {{WORKER_NAME}}\n{{> conventions}}\nend\n";
+        let expanded = expand_partials(template, dir.path()).unwrap();
+        assert_eq!(
+            expanded,
+            "# {{WORKER_NAME}}\nConventions for {{PROJECT_NAME}}\n{{#if TESTS_DETECTED}}\n{{TEST_COMMANDS}}\n{{/if}}\nend\n"
+        );
+        assert!(validate(&expanded).is_empty());
+
+        std::fs::write(partials.join("tests.md"), "{{> conventions}}").unwrap();
+        let problems = validate_partials("ok\n{{> conventions}}\n{{> missing}}\n", dir.path());
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("line 2: partial 'conventions' includes itself"));
+        assert!(problems[1].starts_with("line 3: partial 'missing'"));
+    }
+
+    #[test]
+    fn test_validate_default_template_is_clean() {
+        assert!(validate(default_template()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let template = "{{WORKER_NAEM}}\n{{#if COMPOSE}}\n{{#if SHARED_COMPOSE}}\n{{/if}}\n{{/if}}\n";
+        let problems = validate(template);
+
+        assert!(problems.iter().any(|p| p.contains("unknown placeholder {{WORKER_NAEM}}")));
+        assert!(problems.iter().any(|p| p.contains("unknown condition 'COMPOSE'")));
+        assert!(problems.iter().any(|p| p.contains("nested conditional")));
+        assert!(problems.iter().any(|p| p.contains("without a matching")));
+    }
+
     #[test]
     fn test_negated_conditional() {
         let template = "{{#if !COMPOSE_ENABLED}}\nno compose\n{{/if}}\n";
@@ -350,4 +776,80 @@ mod tests {
         let rendered = render(template, &vars);
         assert!(rendered.contains("no compose"));
     }
+
+    #[test]
+    fn test_running_tests_section() {
+        let mut vars = test_vars();
+        let rendered = render(default_template(), &vars);
+        assert!(rendered.contains("## Running Tests"));
+        assert!(rendered.contains("bundle exec rspec"));
+
+        vars.test_frameworks = "";
+        vars.test_commands = "";
+        let rendered = render(default_template(), &vars);
+        assert!(!rendered.contains("## Running Tests"));
+    }
+
+    #[test]
+    fn test_task_and_linting_sections() {
+        let mut vars = test_vars();
+        let rendered = render(default_template(), &vars);
+        assert!(!rendered.contains("## Your Task"));
+        assert!(rendered.contains("## Linting\n\nDetected linters: rubocop."));
+        assert!(rendered.contains("- **Database**: postgres\n"));
+
+        vars.description = "Fix the login redirect";
+        vars.lint_commands = "";
+        let rendered = render(default_template(), &vars);
+        assert!(rendered.contains("## Your Task\n\nFix the login redirect\n"));
+        assert!(!rendered.contains("## Linting"));
+    }
+
+    #[test]
+    fn test_notes_section() {
+        let mut vars = test_vars();
+        assert!(!render(default_template(), &vars).contains("## Notes So Far"));
+
+        vars.notes = "- 2026-10-16 09:30: keep the old redirect for API clients";
+        let rendered = render(default_template(), &vars);
+        assert!(rendered.contains("## Notes So Far\n"));
+        assert!(rendered.contains("\n- 2026-10-16 09:30: keep the old redirect for API clients\n"));
+    }
+
+    #[test]
+    fn test_initial_prompt_renders_description() {
+        let vars = test_vars();
+        let prompt = InitialPrompt {
+            template: "Fix in {{WORKTREE_PATH}} on port {{APP_PORT}}:\n\n{{DESCRIPTION}}\n".to_string(),
+            description: "login fails on Safari".to_string(),
+            model: None,
+        };
+        let vars = ClaudeMdVars { description: &prompt.description, ..vars };
+        assert_eq!(
+            prompt.render(&vars),
+            "Fix in /tmp/worktrees/fix-login on port 3001:\n\nlogin fails on Safari"
+        );
+    }
+
+    #[test]
+    fn test_render_settings_substitutes_inside_strings() {
+        let vars = ClaudeMdVars {
+            description: "say \"hi\"",
+            test_commands: "bundle exec rspec\nyarn test",
+            ..test_vars()
+        };
+        let template = r#"{
+  "env": { "DATABASE_URL": "postgres://localhost:{{DB_PORT}}/{{WORKER_NAME}}", "TASK": "{{DESCRIPTION}}" },
+  "hooks": { "Stop": [{ "hooks": [{ "type": "command", "command": "{{TEST_COMMANDS}}" }] }] },
+  "permissions": { "allow": ["Bash(git:*)"] }
+}"#;
+        let settings: serde_json::Value = serde_json::from_str(&render_settings(template, &vars).unwrap()).unwrap();
+
+        assert_eq!(settings["env"]["DATABASE_URL"], "postgres://localhost:5433/fix-login");
+        assert_eq!(settings["env"]["TASK"], "say \"hi\"");
+        assert_eq!(settings["hooks"]["Stop"][0]["hooks"][0]["command"], "bundle exec rspec\nyarn test");
+        assert_eq!(settings["permissions"]["allow"][0], "Bash(git:*)");
+        assert_eq!(validate_settings(template), Vec::<String>::new());
+        assert_eq!(validate_settings(r#"{"a": "{{NOPE}}"}"#), vec!["unknown placeholder {{NOPE}}"]);
+    }
 }
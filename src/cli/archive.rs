@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use console::style;
+
+use crate::context::GrootContext;
+use crate::error::Result;
+use crate::git::repo::GitRepo;
+use crate::orchestrator::archive;
+
+pub async fn export(path: &Path) -> Result<()> {
+    let ctx = GrootContext::load()?;
+    let manifest = archive::export(&ctx, path)?;
+    println!(
+        "{} Exported {} worker(s) and the project's config to {}",
+        style("✓").green().bold(),
+        manifest.workers.len(),
+        path.display()
+    );
+    for w in &manifest.workers {
+        let commits = match w.commits {
+            Some(n) => format!("{n} commit(s)"),
+            None => "whole branch".to_string(),
+        };
+        let uncommitted = if w.uncommitted { " + uncommitted changes" } else { "" };
+        println!("  {}  {}  {commits}{uncommitted}", style(&w.task_name).bold(), w.branch);
+    }
+    Ok(())
+}
+
+pub async fn import(path: &Path) -> Result<()> {
+    let git = GitRepo::discover()?;
+    let imported = archive::import(&git, path)?;
+    println!(
+        "{} Imported {} worker(s) from '{}' (exported {})",
+        style("✓").green().bold(),
+        imported.workers.len(),
+        imported.project,
+        imported.exported_at.format("%Y-%m-%d %H:%M")
+    );
+    if !imported.config.is_empty() {
+        println!("  Config restored: {}", imported.config.join(", "));
+    }
+    if !imported.config_kept.is_empty() {
+        println!("  Config kept as is here: {}", imported.config_kept.join(", "));
+    }
+    for w in &imported.workers {
+        println!("  {}  plant it again with: {}", style(&w.task_name).bold(), archive::replant_command(w));
+    }
+    for (name, why) in &imported.skipped {
+        eprintln!("{} Skipped {name}: {why}", style("✗").red().bold());
+    }
+    Ok(())
+}
@@ -1,7 +1,13 @@
+use std::process::Command;
+
 use console::style;
 use dialoguer::{Input, Select};
+use tracing::warn;
 
+use crate::cli::interactive;
 use crate::error::{GrootError, Result};
+use crate::orchestrator::run as orch_run;
+use crate::process::CommandExt;
 
 const COMMIT_TYPES: &[(&str, &str)] = &[
     ("feat", "A new feature"),
@@ -15,14 +21,55 @@ const COMMIT_TYPES: &[(&str, &str)] = &[
     ("ci", "CI configuration changes"),
 ];
 
-pub async fn run() -> Result<()> {
-    println!("{}", style("Conventional Commit Helper").bold());
-    println!();
+/// How much of the staged diff `--ai` shows the agent.
+const MAX_DIFF_CHARS: usize = 30_000;
+
+/// A commit message the agent proposed.
+#[derive(Debug, PartialEq)]
+struct Proposal {
+    commit_type: String,
+    scope: Option<String>,
+    subject: String,
+    body: String,
+}
+
+impl Proposal {
+    fn message(&self) -> String {
+        let header = match &self.scope {
+            Some(scope) => format!("{}({scope}): {}", self.commit_type, self.subject),
+            None => format!("{}: {}", self.commit_type, self.subject),
+        };
+        if self.body.is_empty() {
+            header
+        } else {
+            format!("{header}\n\n{}", self.body)
+        }
+    }
+}
+
+pub async fn run(commit_type: Option<String>, scope: Option<String>, message: Option<String>, ai: bool) -> Result<()> {
+    let unknown_type = commit_type
+        .as_ref()
+        .filter(|t| !COMMIT_TYPES.iter().any(|(name, _)| name == t));
+    if let Some(t) = unknown_type {
+        let names: Vec<&str> = COMMIT_TYPES.iter().map(|(name, _)| *name).collect();
+        return Err(GrootError::Other(format!(
+            "Unknown commit type '{t}'. Expected one of: {}",
+            names.join(", ")
+        )));
+    }
+    // Prompt only for what the flags left out
+    let prompting = ai || commit_type.is_none() || message.is_none();
+    if prompting {
+        interactive::require_terminal("commit", "--type feat --scope api -m \"add login\"")?;
+        println!("{}", style("Conventional Commit Helper").bold());
+        println!();
+    }
 
     // Check for staged changes
-    let status_output = std::process::Command::new("git")
+    let status_output = Command::new("git")
         .args(["diff", "--cached", "--stat"])
-        .output()?;
+        .logged_output()?;
 
     let staged = String::from_utf8_lossy(&status_output.stdout);
     if staged.trim().is_empty() {
@@ -30,36 +77,61 @@ pub async fn run() -> Result<()> {
         return Ok(());
     }
 
-    println!("{}", style("Staged changes:").bold());
-    println!("{staged}");
+    if prompting {
+        println!("{}", style("Staged changes:").bold());
+        println!("{staged}");
+    }
+
+    if ai {
+        if !orch_run::agent_available() {
+            println!("No agent found on PATH (claude); writing the message by hand.\n");
+        } else if commit_with_agent(commit_type.as_deref(), scope.as_deref())? {
+            return Ok(());
+        }
+    }
 
     // Select commit type
-    let type_labels: Vec<String> = COMMIT_TYPES
-        .iter()
-        .map(|(t, desc)| format!("{t}: {desc}"))
-        .collect();
+    let commit_type = match commit_type {
+        Some(t) => t,
+        None => {
+            let type_labels: Vec<String> = COMMIT_TYPES
+                .iter()
+                .map(|(t, desc)| format!("{t}: {desc}"))
+                .collect();
 
-    let type_idx = Select::new()
-        .with_prompt("Commit type")
-        .items(&type_labels)
-        .default(0)
-        .interact()
-        .map_err(|e| GrootError::Other(format!("Selection cancelled: {e}")))?;
+            let type_idx = Select::new()
+                .with_prompt("Commit type")
+                .items(&type_labels)
+                .default(0)
+                .interact()
+                .map_err(|e| GrootError::Other(format!("Selection cancelled: {e}")))?;
 
-    let commit_type = COMMIT_TYPES[type_idx].0;
+            COMMIT_TYPES[type_idx].0.to_string()
+        }
+    };
 
     // Optional scope
-    let scope: String = Input::new()
-        .with_prompt("Scope (optional, press Enter to skip)")
-        .allow_empty(true)
-        .interact_text()
-        .map_err(|e| GrootError::Other(format!("Input cancelled: {e}")))?;
+    let scope: String = match scope {
+        Some(s) => s,
+        None if !prompting => String::new(),
+        None => Input::new()
+            .with_prompt("Scope (optional, press Enter to skip)")
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|e| GrootError::Other(format!("Input cancelled: {e}")))?,
+    };
 
     // Commit message
-    let message: String = Input::new()
-        .with_prompt("Short description")
-        .interact_text()
-        .map_err(|e| GrootError::Other(format!("Input cancelled: {e}")))?;
+    let message: String = match message {
+        Some(m) => m,
+        None => Input::new()
+            .with_prompt("Short description")
+            .interact_text()
+            .map_err(|e| GrootError::Other(format!("Input cancelled: {e}")))?,
+    };
+    if message.trim().is_empty() {
+        return Err(GrootError::Other("Commit message is empty".to_string()));
+    }
 
     // Build the commit message
     let full_message = if scope.is_empty() {
@@ -68,13 +140,15 @@ pub async fn run() -> Result<()> {
         format!("{commit_type}({scope}): {message}")
     };
 
-    println!();
+    if prompting {
+        println!();
+    }
     println!("Commit message: {}", style(&full_message).green());
 
     // Execute commit
-    let output = std::process::Command::new("git")
+    let output = Command::new("git")
         .args(["commit", "-m", &full_message])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -87,3 +161,124 @@ pub async fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Ask the agent for a message for the staged diff, then commit it as is, in
+/// `$EDITOR`, or not at all. Returns false to fall back to the manual prompts.
+fn commit_with_agent(commit_type: Option<&str>, scope: Option<&str>) -> Result<bool> {
+    let diff = Command::new("git").args(["diff", "--cached"]).logged_output()?;
+    let diff = String::from_utf8_lossy(&diff.stdout);
+    let diff = match diff.char_indices().nth(MAX_DIFF_CHARS) {
+        Some((cut, _)) => format!("{}\n[diff truncated]", &diff[..cut]),
+        None => diff.to_string(),
+    };
+
+    let types: Vec<&str> = COMMIT_TYPES.iter().map(|(name, _)| *name).collect();
+    let mut prompt = format!(
+        "Write a Conventional Commits message for this staged diff. The first line is \
+         `type(scope): subject` with type one of {}, an optional short scope, and an imperative \
+         subject under 72 characters. Add a blank line and a short body only if the change needs \
+         explaining. Reply with the message only.",
+        types.join(", ")
+    );
+    if let Some(t) = commit_type {
+        prompt.push_str(&format!(" Use type `{t}`."));
+    }
+    if let Some(s) = scope {
+        prompt.push_str(&format!(" Use scope `{s}`."));
+    }
+    prompt.push_str(&format!("\n\n{diff}"));
+
+    println!("{}", style("Asking the agent for a commit message...").dim());
+    let reply = match orch_run::ask(&std::env::current_dir()?, &prompt) {
+        Ok(reply) => reply,
+        Err(e) => {
+            warn!("Warning: {e}");
+            return Ok(false);
+        }
+    };
+    let Some(mut proposal) = parse_proposal(&reply) else {
+        warn!("Warning: the agent's reply isn't a conventional commit message:\n{reply}");
+        return Ok(false);
+    };
+    // Flags win over what the agent picked
+    if let Some(t) = commit_type {
+        proposal.commit_type = t.to_string();
+    }
+    if let Some(s) = scope {
+        proposal.scope = Some(s.to_string()).filter(|s| !s.is_empty());
+    }
+
+    let message = proposal.message();
+    println!("\n{}\n", style(&message).green());
+    let choice = Select::new()
+        .with_prompt("Use this message?")
+        .items(["Commit", "Edit in $EDITOR, then commit", "Write it myself"])
+        .default(0)
+        .interact()
+        .map_err(|e| GrootError::Other(format!("Selection cancelled: {e}")))?;
+
+    let mut git = Command::new("git");
+    git.args(["commit", "-m", &message]);
+    match choice {
+        0 => {}
+        1 => {
+            git.arg("--edit");
+        }
+        _ => return Ok(false),
+    }
+    let status = git.logged_status()?;
+    if !status.success() {
+        return Err(GrootError::GitCommand("Commit failed".to_string()));
+    }
+    println!("{} Committed!", style("✓").green().bold());
+    Ok(true)
+}
+
+/// Read the agent's reply as `type(scope): subject`, an optional body, and
+/// nothing else but an enclosing code fence.
+fn parse_proposal(reply: &str) -> Option<Proposal> {
+    let lines: Vec<&str> = reply
+        .trim()
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("```"))
+        .collect();
+    let (header, rest) = lines.split_first()?;
+    let (prefix, subject) = header.trim().split_once(": ")?;
+    let (commit_type, scope) = match prefix.split_once('(') {
+        Some((t, s)) => (t, Some(s.strip_suffix(')')?.trim().to_string())),
+        None => (prefix, None),
+    };
+    if !COMMIT_TYPES.iter().any(|(name, _)| *name == commit_type) || subject.trim().is_empty() {
+        return None;
+    }
+    Some(Proposal {
+        commit_type: commit_type.to_string(),
+        scope: scope.filter(|s| !s.is_empty()),
+        subject: subject.trim().to_string(),
+        body: rest.join("\n").trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proposal() {
+        let proposal = parse_proposal("```\nfix(api): handle empty token\n\nReturn 401 instead of panicking.\n```").unwrap();
+        assert_eq!(
+            proposal,
+            Proposal {
+                commit_type: "fix".to_string(),
+                scope: Some("api".to_string()),
+                subject: "handle empty token".to_string(),
+                body: "Return 401 instead of panicking.".to_string(),
+            }
+        );
+        assert_eq!(proposal.message(), "fix(api): handle empty token\n\nReturn 401 instead of panicking.");
+
+        assert_eq!(parse_proposal("docs: update README").unwrap().message(), "docs: update README");
+        assert!(parse_proposal("Here is a message: fix things").is_none());
+        assert!(parse_proposal("feature: add login").is_none());
+    }
+}
@@ -0,0 +1,374 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use clap::Subcommand;
+use console::style;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::claude_md;
+use crate::cli::output;
+use crate::config::hooks;
+use crate::config::local::{self, LocalConfig};
+use crate::config::options::{self, ConfigOption};
+use crate::config::project::ProjectConfig;
+use crate::config::secret;
+use crate::config::state_dir;
+use crate::config::validate;
+use crate::error::{GrootError, Result};
+use crate::git::repo::GitRepo;
+use crate::process::CommandExt;
+use crate::tmux::workspace;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Check config.yml, local.yml, and the templates for unknown keys, bad values, and missing files
+    Validate,
+    /// Print the effective configuration, noting where each value comes from
+    Show,
+    /// List every supported key with its type, default, and description
+    Options {
+        /// Only list local.yml keys
+        #[arg(long, conflicts_with = "project")]
+        local: bool,
+        /// Only list config.yml keys
+        #[arg(long)]
+        project: bool,
+    },
+    /// Open config.yml (or local.yml) in $VISUAL / $EDITOR and validate it afterwards
+    Edit {
+        /// Edit local.yml (your machine's settings)
+        #[arg(long, conflicts_with = "project")]
+        local: bool,
+        /// Edit config.yml (shared project settings; the default)
+        #[arg(long)]
+        project: bool,
+    },
+    /// Store or remove secrets in the OS keyring, referenced from config as `keyring:<name>`
+    #[command(subcommand)]
+    Secret(SecretCommands),
+}
+
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// Store a secret (prompted for, or read from stdin when piped)
+    Set {
+        /// Name used in config as `keyring:<name>`
+        name: String,
+    },
+    /// Remove a stored secret
+    Delete {
+        /// Name of the secret
+        name: String,
+    },
+}
+
+pub async fn run(cmd: ConfigCommands) -> Result<()> {
+    match cmd {
+        ConfigCommands::Validate => validate().await,
+        ConfigCommands::Show => show().await,
+        ConfigCommands::Options { local, project } => options(!local, !project).await,
+        ConfigCommands::Edit { local, project: _ } => edit(local).await,
+        ConfigCommands::Secret(SecretCommands::Set { name }) => secret_set(&name).await,
+        ConfigCommands::Secret(SecretCommands::Delete { name }) => secret_delete(&name).await,
+    }
+}
+
+fn ensure_groot(git: &GitRepo) -> Result<std::path::PathBuf> {
+    let groot_dir = git.groot_dir();
+    if !groot_dir.join("config.yml").exists() {
+        return Err(GrootError::NotInitialized);
+    }
+    Ok(groot_dir)
+}
+
+/// Print one file's result and return its problem count.
+fn report(path: &std::path::Path, problems: &[String]) -> usize {
+    println!("{}", style(path.display()).bold());
+    if problems.is_empty() {
+        println!("  {} valid", style("✓").green().bold());
+    }
+    for p in problems {
+        println!("  {} {p}", style("✗").red().bold());
+    }
+    problems.len()
+}
+
+async fn validate() -> Result<()> {
+    let git = GitRepo::discover()?;
+    let groot_dir = ensure_groot(&git)?;
+    let mut problem_count = 0;
+
+    let config_path = groot_dir.join("config.yml");
+    problem_count += report(&config_path, &validate::validate_project(&config_path));
+
+    // local.yml is optional: defaults apply when it's missing
+    let local_path = groot_dir.join("local.yml");
+    if local_path.exists() {
+        problem_count += report(&local_path, &validate::validate_local(&local_path));
+    }
+    if let Some(overlay) = local::overlay_path(&local_path).filter(|p| p.exists()) {
+        problem_count += report(&overlay, &validate::validate_local(&overlay));
+    }
+
+    let hooks_path = hooks::path(&groot_dir);
+    if hooks_path.exists() {
+        problem_count += report(&hooks_path, &validate::validate_hooks(&hooks_path));
+    }
+
+    let compose_path = groot_dir.join("compose-template.yml");
+    if compose_path.exists() {
+        problem_count += report(
+            &compose_path,
+            &validate::validate_compose_template(&compose_path, &git.root),
+        );
+    }
+
+    let mut layouts = vec![groot_dir.join("tmux-layout.json")];
+    layouts.extend(
+        workspace::list_named_templates(&groot_dir)
+            .iter()
+            .filter_map(|name| workspace::named_template_path(&groot_dir, name).ok()),
+    );
+    for path in layouts.iter().filter(|p| p.exists()) {
+        problem_count += report(path, &validate::validate_layout(path));
+    }
+
+    let claude_path = groot_dir.join("claude-md.template");
+    if claude_path.exists() {
+        let template = std::fs::read_to_string(&claude_path)?;
+        let mut problems = claude_md::validate(&template);
+        problems.extend(claude_md::validate_partials(&template, &groot_dir));
+        problem_count += report(&claude_path, &problems);
+    }
+    for name in claude_md::list_partials(&groot_dir) {
+        let path = claude_md::partials_dir(&groot_dir).join(format!("{name}.md"));
+        let template = std::fs::read_to_string(&path)?;
+        problem_count += report(&path, &claude_md::validate(&template));
+    }
+    let settings_path = claude_md::settings_template_path(&groot_dir);
+    if settings_path.exists() {
+        let template = std::fs::read_to_string(&settings_path)?;
+        problem_count += report(&settings_path, &claude_md::validate_settings(&template));
+    }
+    for name in claude_md::list_prompt_templates(&groot_dir) {
+        let path = claude_md::prompt_template_path(&groot_dir, &name);
+        let template = std::fs::read_to_string(&path)?;
+        let mut problems = claude_md::validate(&template);
+        problems.extend(claude_md::validate_partials(&template, &groot_dir));
+        problem_count += report(&path, &problems);
+    }
+
+    if problem_count > 0 {
+        return Err(GrootError::Other(format!(
+            "{problem_count} configuration problem(s) found"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Render a config value on one line: scalars as-is, lists and maps as JSON.
+fn inline_value(value: &serde_yml::Value) -> String {
+    match value {
+        serde_yml::Value::Null => style("~").dim().to_string(),
+        serde_yml::Value::String(s) => s.clone(),
+        serde_yml::Value::Bool(_) | serde_yml::Value::Number(_) => {
+            serde_yml::to_string(value).unwrap_or_default().trim().to_string()
+        }
+        _ => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+/// Print every field of a config struct with its effective value and which of
+/// `paths` set it (later files override earlier ones), or that it was left at
+/// its default.
+fn show_section<T: Serialize + DeserializeOwned>(paths: &[PathBuf], effective: &T) -> Result<()> {
+    let mut sources: Vec<(String, serde_yml::Value)> = Vec::new();
+    for path in paths.iter().filter(|p| p.exists()) {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        sources.push((file_name, serde_yml::from_str(&std::fs::read_to_string(path)?)?));
+    }
+    let values = serde_yml::to_value(effective)?;
+
+    let overlays: Vec<String> = paths[1..]
+        .iter()
+        .filter(|p| p.exists())
+        .filter_map(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .collect();
+    if overlays.is_empty() {
+        println!("{}", style(paths[0].display()).bold());
+    } else {
+        println!(
+            "{} {}",
+            style(paths[0].display()).bold(),
+            style(format!("(+ {})", overlays.join(", "))).dim()
+        );
+    }
+    let fields = validate::struct_fields::<T>();
+    let rows: Vec<(&str, String)> = fields
+        .iter()
+        .map(|field| {
+            let value = values
+                .get(field)
+                .map(inline_value)
+                .unwrap_or_else(|| style("(unset)").dim().to_string());
+            (*field, value)
+        })
+        .collect();
+    let key_width = fields.iter().map(|f| f.len()).max().unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, v)| console::measure_text_width(v))
+        .max()
+        .unwrap_or(0)
+        .min(40);
+
+    for (field, value) in rows {
+        let source = match sources.iter().rev().find(|(_, raw)| raw.get(field).is_some()) {
+            Some((file_name, _)) => style(file_name.clone()).cyan(),
+            None => style("default".to_string()).dim(),
+        };
+        println!(
+            "  {field:<key_width$}  {}  {} {source}",
+            console::pad_str(&value, value_width, console::Alignment::Left, None),
+            style("#").dim()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+async fn show() -> Result<()> {
+    let git = GitRepo::discover()?;
+    let groot_dir = ensure_groot(&git)?;
+
+    let config_path = groot_dir.join("config.yml");
+    show_section(std::slice::from_ref(&config_path), &ProjectConfig::load(&config_path)?)?;
+
+    let local_path = groot_dir.join("local.yml");
+    let mut local_paths = vec![local_path.clone()];
+    local_paths.extend(local::overlay_path(&local_path));
+    show_section(&local_paths, &LocalConfig::load(&local_path)?)?;
+
+    Ok(())
+}
+
+/// Print one file's keys, each followed by its description.
+fn print_options(file: &str, options: &[ConfigOption]) {
+    println!("{}", style(file).bold());
+    for option in options {
+        let mut details = vec![style(&option.ty).cyan().to_string()];
+        if option.required {
+            details.push(style("required").yellow().to_string());
+        }
+        if let Some(default) = &option.default {
+            details.push(format!("default: {default}"));
+        }
+        println!("  {}  {}", style(&option.key).bold(), details.join(", "));
+        if !option.description.is_empty() {
+            println!("      {}", style(&option.description).dim());
+        }
+    }
+}
+
+async fn options(project: bool, local: bool) -> Result<()> {
+    if output::is_json() {
+        let mut files = serde_json::Map::new();
+        if project {
+            files.insert("config.yml".to_string(), serde_json::to_value(options::options::<ProjectConfig>())?);
+        }
+        if local {
+            files.insert("local.yml".to_string(), serde_json::to_value(options::options::<LocalConfig>())?);
+        }
+        return output::print_json(&files);
+    }
+    if project {
+        print_options(&format!("{}/config.yml", state_dir::name()), &options::options::<ProjectConfig>());
+    }
+    if project && local {
+        println!();
+    }
+    if local {
+        print_options(&format!("{}/local.yml", state_dir::name()), &options::options::<LocalConfig>());
+    }
+    Ok(())
+}
+
+async fn edit(local: bool) -> Result<()> {
+    let git = GitRepo::discover()?;
+    let groot_dir = ensure_groot(&git)?;
+
+    let path = if local {
+        groot_dir.join("local.yml")
+    } else {
+        groot_dir.join("config.yml")
+    };
+    if local && !path.exists() {
+        LocalConfig::with_defaults().save(&path)?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Through the shell so editors with arguments (`code --wait`) work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(&path)
+        .logged_status()
+        .map_err(|e| GrootError::Other(format!("Failed to start editor '{editor}': {e}")))?;
+    if !status.success() {
+        return Err(GrootError::Other(format!("Editor '{editor}' exited with {status}")));
+    }
+
+    let problems = if local {
+        validate::validate_local(&path)
+    } else {
+        validate::validate_project(&path)
+    };
+    if report(&path, &problems) > 0 {
+        return Err(GrootError::Other(format!(
+            "{} configuration problem(s) found; run `groot config edit{}` to fix them",
+            problems.len(),
+            if local { " --local" } else { "" }
+        )));
+    }
+
+    Ok(())
+}
+
+async fn secret_set(name: &str) -> Result<()> {
+    let value = if console::user_attended() {
+        dialoguer::Password::new()
+            .with_prompt(format!("Value for '{name}'"))
+            .interact()
+            .map_err(|e| GrootError::Other(format!("Input cancelled: {e}")))?
+    } else {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    };
+    if value.is_empty() {
+        return Err(GrootError::Other("Refusing to store an empty secret".to_string()));
+    }
+
+    secret::store(name, &value)?;
+    println!(
+        "{} Stored '{name}' in the keyring. Reference it in config as {}",
+        style("✓").green().bold(),
+        style(format!("{}{name}", secret::KEYRING_PREFIX)).cyan()
+    );
+    Ok(())
+}
+
+async fn secret_delete(name: &str) -> Result<()> {
+    secret::delete(name)?;
+    println!("{} Removed '{name}' from the keyring", style("✓").green().bold());
+    Ok(())
+}
@@ -1,42 +1,97 @@
 use std::path::Path;
 
+use clap::ValueEnum;
 use console::style;
 use dialoguer::{Confirm, Select};
 
+use crate::cli::interactive;
 use crate::config::project::ProjectConfig;
 use crate::container::templates;
 use crate::error::{GrootError, Result};
 use crate::git::repo::GitRepo;
 
-fn select_and_write_template(repo_root: &Path, dockerfile_path: &Path) -> Result<String> {
-    let options = vec!["Rails", "React Native", "Custom (Ubuntu base)"];
+/// Dockerfile templates the wizard offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    Rails,
+    ReactNative,
+    Django,
+    /// A bare Ubuntu base image
+    Custom,
+}
+
+const TEMPLATES: &[Template] = &[Template::Rails, Template::ReactNative, Template::Django, Template::Custom];
 
-    let selection = Select::new()
-        .with_prompt("Select a container template")
-        .items(&options)
-        .default(0)
+impl Template {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Rails => "Rails",
+            Self::ReactNative => "React Native",
+            Self::Django => "Django",
+            Self::Custom => "Custom (Ubuntu base)",
+        }
+    }
+
+    fn dockerfile(self) -> String {
+        match self {
+            Self::Rails => templates::rails_template().to_string(),
+            Self::ReactNative => templates::react_native_template().to_string(),
+            Self::Django => templates::django_template().to_string(),
+            Self::Custom => {
+                "FROM ubuntu:22.04\nRUN apt-get update -qq && apt-get install -y git curl\nWORKDIR /app\nCMD [\"sleep\", \"infinity\"]\n".to_string()
+            }
+        }
+    }
+}
+
+/// Ask a yes/no question, or answer yes without asking when `yes` is set.
+fn confirm(prompt: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    Confirm::new()
+        .with_prompt(prompt)
+        .default(true)
         .interact()
-        .map_err(|e| GrootError::Other(format!("Selection cancelled: {e}")))?;
+        .map_err(|e| GrootError::Other(format!("Confirm cancelled: {e}")))
+}
 
-    let dockerfile_content = match selection {
-        0 => templates::rails_template().to_string(),
-        1 => templates::react_native_template().to_string(),
-        _ => {
-            "FROM ubuntu:22.04\nRUN apt-get update -qq && apt-get install -y git curl\nWORKDIR /app\nCMD [\"sleep\", \"infinity\"]\n".to_string()
+fn select_and_write_template(
+    repo_root: &Path,
+    dockerfile_path: &Path,
+    primary_type: Option<&str>,
+    template: Option<Template>,
+    yes: bool,
+) -> Result<String> {
+    let template = match template {
+        Some(t) => t,
+        None => {
+            // Preselect the template matching the primary project type
+            let default = match primary_type {
+                Some("react-native") => 1,
+                Some("django") => 2,
+                _ => 0,
+            };
+            let labels: Vec<&str> = TEMPLATES.iter().map(|t| t.label()).collect();
+
+            let selection = Select::new()
+                .with_prompt("Select a container template")
+                .items(&labels)
+                .default(default)
+                .interact()
+                .map_err(|e| GrootError::Other(format!("Selection cancelled: {e}")))?;
+            TEMPLATES[selection]
         }
     };
 
-    let template_name = options[selection];
+    let dockerfile_content = template.dockerfile();
+
     println!();
-    println!("Template: {}", style(template_name).cyan());
+    println!("Template: {}", style(template.label()).cyan());
     println!();
     println!("{}", &dockerfile_content);
 
-    let proceed = Confirm::new()
-        .with_prompt("Write Dockerfile to project?")
-        .default(true)
-        .interact()
-        .map_err(|e| GrootError::Other(format!("Confirm cancelled: {e}")))?;
+    let proceed = confirm("Write Dockerfile to project?", yes)?;
 
     if !proceed {
         return Err(GrootError::Other("Cancelled.".to_string()));
@@ -58,7 +113,7 @@ fn select_and_write_template(repo_root: &Path, dockerfile_path: &Path) -> Result
     Ok(dockerfile_content)
 }
 
-pub async fn run() -> Result<()> {
+pub async fn run(template: Option<Template>, dockerfile: Option<String>, yes: bool) -> Result<()> {
     let git = GitRepo::discover()?;
     let groot_dir = git.groot_dir();
 
@@ -68,6 +123,11 @@ pub async fn run() -> Result<()> {
 
     let config = ProjectConfig::load(&groot_dir.join("config.yml"))?;
 
+    // Without a terminal every question needs an answer up front
+    if !yes || (template.is_none() && dockerfile.is_none()) {
+        interactive::require_terminal("containerize", "--template rails --yes (or --dockerfile Dockerfile.dev --yes)")?;
+    }
+
     println!("{}", style("Container Setup Wizard").bold());
     println!();
 
@@ -92,7 +152,30 @@ pub async fn run() -> Result<()> {
     let dockerfile_content;
     let dockerfile_path = git.root.join("Dockerfile.groot");
 
-    if !existing_dockerfiles.is_empty() {
+    if let Some(name) = dockerfile {
+        let source_path = git.root.join(&name);
+        if !source_path.is_file() {
+            return Err(GrootError::Other(format!("{} does not exist", source_path.display())));
+        }
+        if source_path != dockerfile_path {
+            std::fs::copy(&source_path, &dockerfile_path)?;
+            println!(
+                "{} Copied {} to {}",
+                style("✓").green().bold(),
+                name,
+                dockerfile_path.display()
+            );
+        }
+        dockerfile_content = std::fs::read_to_string(&dockerfile_path)?;
+    } else if template.is_some() || existing_dockerfiles.is_empty() {
+        dockerfile_content = select_and_write_template(
+            &git.root,
+            &dockerfile_path,
+            config.primary().as_deref(),
+            template,
+            yes,
+        )?;
+    } else {
         println!(
             "{} Found existing Dockerfile(s):",
             style("!").yellow()
@@ -138,10 +221,14 @@ pub async fn run() -> Result<()> {
             dockerfile_content = std::fs::read_to_string(&dockerfile_path)?;
         } else {
             // Fall through to template selection
-            dockerfile_content = select_and_write_template(&git.root, &dockerfile_path)?;
+            dockerfile_content = select_and_write_template(
+                &git.root,
+                &dockerfile_path,
+                config.primary().as_deref(),
+                None,
+                yes,
+            )?;
         }
-    } else {
-        dockerfile_content = select_and_write_template(&git.root, &dockerfile_path)?;
     }
 
     let _ = &dockerfile_content;
@@ -152,14 +239,13 @@ pub async fn run() -> Result<()> {
     config.save(&groot_dir.join("config.yml"))?;
 
     // Offer to generate compose template for per-worker stacks
-    let generate_compose = Confirm::new()
-        .with_prompt("Generate Docker Compose template for per-worker stacks?")
-        .default(true)
-        .interact()
-        .map_err(|e| GrootError::Other(format!("Confirm cancelled: {e}")))?;
+    let generate_compose = confirm("Generate Docker Compose template for per-worker stacks?", yes)?;
 
     if generate_compose {
-        let template_content = crate::compose::template::default_rails_template();
+        let template_content = crate::compose::template::default_template_for(
+            config.primary().as_deref(),
+            config.database.unwrap_or_default(),
+        );
         let template_path = groot_dir.join("compose-template.yml");
         std::fs::write(&template_path, template_content)?;
 
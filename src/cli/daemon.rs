@@ -0,0 +1,157 @@
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use clap::{Args, Subcommand};
+use console::style;
+
+use crate::cli::output;
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::orchestrator::daemon::{self, DaemonOptions};
+
+#[derive(Subcommand)]
+pub enum DaemonCommands {
+    /// Run the daemon in the foreground (under a service manager, or in a tmux window)
+    Run(DaemonArgs),
+    /// Start the daemon in the background, logging to .groot/logs/daemon.log
+    Start(DaemonArgs),
+    /// Stop the running daemon
+    Stop,
+    /// Show what the daemon last saw and did
+    Status,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DaemonArgs {
+    /// Seconds between rounds
+    #[arg(long, default_value_t = 30)]
+    interval: u64,
+    /// Minutes without output before an agent is flagged as idle
+    #[arg(long, value_name = "MINUTES", default_value_t = 10)]
+    idle: u64,
+    /// Send a desktop notification when an agent finishes or gets stuck, or a
+    /// grove's containers stop being ready
+    #[arg(long)]
+    notify: bool,
+}
+
+impl DaemonArgs {
+    fn options(&self) -> DaemonOptions {
+        DaemonOptions {
+            interval_secs: self.interval,
+            idle_mins: self.idle,
+            notify: self.notify,
+        }
+    }
+}
+
+pub async fn run(cmd: DaemonCommands, ctx: &GrootContext) -> Result<()> {
+    match cmd {
+        DaemonCommands::Run(args) => daemon::serve(&ctx.groot_dir, &ctx.git.root, args.options()).await,
+        DaemonCommands::Start(args) => start(ctx, &args),
+        DaemonCommands::Stop => {
+            if daemon::stop(&ctx.groot_dir) {
+                println!("{} Daemon stopped", style("✓").green().bold());
+            } else {
+                println!("No daemon is running.");
+            }
+            Ok(())
+        }
+        DaemonCommands::Status => status(ctx),
+    }
+}
+
+fn start(ctx: &GrootContext, args: &DaemonArgs) -> Result<()> {
+    if let Some(running) = daemon::status(&ctx.groot_dir) {
+        println!("The daemon is already running (pid {}).", running.pid);
+        return Ok(());
+    }
+    let log_path = ctx.groot_dir.join("logs").join("daemon.log");
+    std::fs::create_dir_all(ctx.groot_dir.join("logs"))?;
+    let log = std::fs::OpenOptions::new().create(true).append(true).open(&log_path)?;
+
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args(["daemon", "run", "--interval", &args.interval.to_string(), "--idle", &args.idle.to_string()]);
+    if args.notify {
+        cmd.arg("--notify");
+    }
+    cmd.current_dir(&ctx.git.root)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // Its own process group, so Ctrl-C in this terminal doesn't reach it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = cmd.spawn()?;
+
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(5) {
+        if let Some(running) = daemon::status(&ctx.groot_dir) {
+            println!(
+                "{} Daemon started (pid {}), logging to {}",
+                style("✓").green().bold(),
+                running.pid,
+                log_path.display()
+            );
+            return Ok(());
+        }
+        if let Some(exit) = child.try_wait()? {
+            return Err(GrootError::Other(format!(
+                "The daemon exited ({exit}); see {}",
+                log_path.display()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(GrootError::Other(format!(
+        "The daemon didn't answer within 5s; see {}",
+        log_path.display()
+    )))
+}
+
+fn status(ctx: &GrootContext) -> Result<()> {
+    let Some(status) = daemon::status(&ctx.groot_dir) else {
+        if output::is_json() {
+            return output::print_json(&serde_json::Value::Null);
+        }
+        println!("No daemon is running. Start one with `groot daemon start`.");
+        return Ok(());
+    };
+    if output::is_json() {
+        return output::print_json(&status);
+    }
+
+    let fmt = |t: chrono::DateTime<chrono::Utc>| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S");
+    println!("{}", style("Daemon").bold());
+    println!("  pid {}, running since {}", status.pid, fmt(status.started_at));
+    let last = status.last_round.map_or("-".to_string(), |t| fmt(t).to_string());
+    println!(
+        "  {} round(s) every {}s, last at {last}; agents idle after {}m{}",
+        status.rounds,
+        status.options.interval_secs,
+        status.options.idle_mins,
+        if status.options.notify { ", notifying" } else { "" }
+    );
+
+    if !status.agents.is_empty() {
+        println!();
+        println!("{}", style("Workers").bold());
+        for (name, agent) in &status.agents {
+            let health = match status.unhealthy.get(name) {
+                Some(services) => format!("  {}", style(format!("not ready: {services}")).red()),
+                None => String::new(),
+            };
+            let agent = if agent.is_stuck() { style(agent.describe()).yellow() } else { style(agent.describe()) };
+            println!("  {name}  agent: {agent}{health}");
+        }
+    }
+
+    if !status.recent.is_empty() {
+        println!();
+        println!("{}", style("Recent").bold());
+        for action in &status.recent {
+            println!("  {}  {}: {}", fmt(action.at), action.worker, action.what);
+        }
+    }
+    Ok(())
+}
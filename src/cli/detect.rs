@@ -1,19 +1,182 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use console::style;
+use serde::Serialize;
 
-use crate::detector;
-use crate::error::Result;
+use crate::config::project::ProjectConfig;
+use crate::detector::{self, PackageDetection, TypeEvidence};
+use crate::detector::database::DbEngine;
+use crate::detector::linter::Linter;
+use crate::detector::primary::{self, PrimaryType};
+use crate::detector::test_framework::TestFramework;
+use crate::error::{GrootError, Result};
 use crate::git::repo::GitRepo;
 
-pub async fn run() -> Result<()> {
+/// Everything `groot detect --json` reports, for editor plugins and scripts.
+#[derive(Serialize)]
+struct DetectReport {
+    root: String,
+    primary_type: Option<PrimaryType>,
+    types: Vec<TypeEvidence>,
+    versions: BTreeMap<String, String>,
+    package_managers: Vec<String>,
+    database: Option<DbEngine>,
+    test_frameworks: Vec<TestFramework>,
+    linters: Vec<Linter>,
+    workspace_tools: Vec<String>,
+    packages: Vec<PackageDetection>,
+}
+
+/// Rewrite the detection fields of config.yml, keeping everything else.
+fn update_config(
+    root: &Path,
+    config_path: &Path,
+    mut config: ProjectConfig,
+    types: &[TypeEvidence],
+    primary_type: Option<PrimaryType>,
+) -> Result<()> {
+    let detected: Vec<String> = types.iter().map(|t| t.name.clone()).collect();
+    let added: Vec<&String> = detected.iter().filter(|t| !config.detected_types.contains(t)).collect();
+    let removed: Vec<&String> = config.detected_types.iter().filter(|t| !detected.contains(t)).collect();
+
+    for t in &added {
+        println!("  {} {t}", style("+").green().bold());
+    }
+    for t in &removed {
+        println!("  {} {t}", style("-").red().bold());
+    }
+    let old_primary = config.primary();
+    let new_primary = primary_type.as_ref().map(|p| p.name.clone());
+    if old_primary != new_primary {
+        println!(
+            "  primary type: {} → {}",
+            old_primary.as_deref().unwrap_or("none"),
+            new_primary.as_deref().unwrap_or("none")
+        );
+    }
+
+    config.detected_types = detected;
+    config.primary_type = primary_type;
+    let detected_database = detector::database::detect(root);
+    match (config.database, detected_database) {
+        // Set by hand, or detected earlier: keep it
+        (Some(current), Some(found)) if current != found => println!(
+            "  database: keeping {} (detected {}; edit config.yml to switch)",
+            current.name(),
+            found.name()
+        ),
+        (Some(_), _) => {}
+        (None, found) => config.database = found,
+    }
+    config.workspace_tools = detector::workspace::detect_tools(root);
+    config.packages = detector::detect_packages(root, &config.detection);
+    config.save(config_path)?;
+
+    println!(
+        "{} Updated {}",
+        style("✓").green().bold(),
+        config_path.display()
+    );
+    Ok(())
+}
+
+pub async fn run(json: bool, update: bool) -> Result<()> {
     let git = GitRepo::discover()?;
-    let detected = detector::detect_project_types(&git.root);
 
-    if detected.is_empty() {
+    // User-defined rules, when the repo is initialized
+    let config_path = git.groot_dir().join("config.yml");
+    let config = if config_path.exists() {
+        Some(ProjectConfig::load(&config_path)?)
+    } else {
+        None
+    };
+    let user_rules = config.as_ref().map(|c| c.detection.clone()).unwrap_or_default();
+
+    let types = detector::detect_with_evidence(&git.root, &user_rules);
+    let primary_type = primary::choose(&types);
+
+    if update {
+        let config = config.ok_or(GrootError::NotInitialized)?;
+        return update_config(&git.root, &config_path, config, &types, primary_type);
+    }
+
+    if json {
+        let report = DetectReport {
+            root: git.root.to_string_lossy().to_string(),
+            primary_type,
+            types,
+            versions: detector::versions::detect(&git.root),
+            package_managers: detector::package_manager::detect(&git.root),
+            database: detector::database::detect(&git.root),
+            test_frameworks: detector::test_framework::detect(&git.root),
+            linters: detector::linter::detect(&git.root),
+            workspace_tools: detector::workspace::detect_tools(&git.root),
+            packages: detector::detect_packages(&git.root, &user_rules),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if types.is_empty() {
         println!("{} No project types detected", style("!").yellow());
     } else {
         println!("{} Detected project types:", style("✓").green().bold());
-        for t in &detected {
-            println!("  - {t}");
+        for t in &types {
+            println!("  - {}", t.name);
+        }
+    }
+    if let Some(primary) = &primary_type {
+        println!(
+            "{} Primary type: {} (confidence {:.2})",
+            style("✓").green().bold(),
+            primary.name,
+            primary.confidence
+        );
+    }
+
+    let versions = detector::versions::detect(&git.root);
+    if !versions.is_empty() {
+        let pinned: Vec<String> = versions.iter().map(|(tool, v)| format!("{tool} {v}")).collect();
+        println!("{} Versions: {}", style("✓").green().bold(), pinned.join(", "));
+    }
+
+    let managers = detector::package_manager::detect(&git.root);
+    if !managers.is_empty() {
+        println!("{} Package managers: {}", style("✓").green().bold(), managers.join(", "));
+    }
+
+    if let Some(engine) = detector::database::detect(&git.root) {
+        println!("{} Database: {}", style("✓").green().bold(), engine.name());
+    }
+
+    let tests = detector::test_framework::detect(&git.root);
+    if !tests.is_empty() {
+        println!("{} Test frameworks:", style("✓").green().bold());
+        for t in &tests {
+            println!("  - {}: {}", t.name, style(&t.command).cyan());
+        }
+    }
+
+    let linters = detector::linter::detect(&git.root);
+    if !linters.is_empty() {
+        println!("{} Linters:", style("✓").green().bold());
+        for l in &linters {
+            println!("  - {}: {}", l.name, style(&l.command).cyan());
+        }
+    }
+
+    let tools = detector::workspace::detect_tools(&git.root);
+    if !tools.is_empty() {
+        println!();
+        println!("{} Workspace tooling: {}", style("✓").green().bold(), tools.join(", "));
+        for package in detector::detect_packages(&git.root, &user_rules) {
+            let types = if package.types.is_empty() {
+                style("no types detected".to_string()).dim()
+            } else {
+                style(package.types.join(", ")).cyan()
+            };
+            println!("  - {}: {types}", package.path);
         }
     }
 
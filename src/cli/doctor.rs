@@ -0,0 +1,410 @@
+use std::collections::HashSet;
+use std::process::Command;
+
+use console::style;
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::compose::ports;
+use crate::context::GrootContext;
+use crate::detector::database::DbEngine;
+use crate::error::{GrootError, Result};
+use crate::orchestrator::{cleanup, disk, grove as orch_grove};
+use crate::process::CommandExt;
+use crate::terminal::TerminalBackend;
+use crate::tmux::session;
+use crate::wsl;
+
+/// Oldest git with `git worktree remove`.
+const MIN_GIT: (u32, u32) = (2, 17);
+/// Oldest tmux with `new-session -e`; popups need 3.2.
+const MIN_TMUX: (u32, u32) = (3, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), hint: Some(hint.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), hint: Some(hint.into()) }
+    }
+
+    /// Fail when the tool is required here, else only warn.
+    fn missing(name: &'static str, required: bool, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        if required {
+            Self::fail(name, detail, hint)
+        } else {
+            Self::warn(name, detail, hint)
+        }
+    }
+}
+
+/// Check the tools and state groot depends on, printing each result with how
+/// to fix it. Works before `groot init`; the project checks are skipped then.
+pub async fn run() -> Result<()> {
+    let ctx = GrootContext::load();
+    let ctx = match ctx {
+        Ok(ctx) => Some(ctx),
+        Err(GrootError::NotInitialized | GrootError::NotGitRepo | GrootError::Git(_)) => None,
+        Err(e) => return Err(e),
+    };
+
+    // Everything is checked afresh here, so drop what other commands cached
+    if let Some(ctx) = &ctx {
+        ctx.probes.clear();
+    }
+
+    let mut checks = vec![check_git()];
+    let needs_docker = ctx.as_ref().is_some_and(uses_compose);
+    checks.extend(check_docker(needs_docker));
+    checks.push(check_tmux(ctx.as_ref()));
+    if let Some(ctx) = &ctx {
+        checks.push(check_db_client(ctx));
+        checks.extend(check_disk(ctx, needs_docker));
+        checks.extend(check_wsl(ctx));
+        checks.extend(check_nix(ctx));
+        checks.push(check_ports(ctx));
+        checks.extend(check_state(ctx));
+    } else {
+        checks.push(Check::warn(
+            "project",
+            "not a groot project",
+            GrootError::NotInitialized.hint().unwrap_or_default(),
+        ));
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if output::is_json() {
+        output::print_json(&checks)?;
+    } else {
+        print_checks(&checks);
+    }
+    if failed > 0 {
+        return Err(GrootError::Other(format!("{failed} doctor check(s) failed")));
+    }
+    Ok(())
+}
+
+fn print_checks(checks: &[Check]) {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in checks {
+        let mark = match check.status {
+            Status::Pass => style("✓").green().bold(),
+            Status::Warn => style("!").yellow().bold(),
+            Status::Fail => style("✗").red().bold(),
+        };
+        println!("{mark} {:<width$}  {}", check.name, check.detail);
+        if let Some(hint) = &check.hint {
+            println!("  {:<width$}  {} {hint}", "", style("→").dim());
+        }
+    }
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    println!(
+        "\n{} passed, {} warning(s), {} failed",
+        count(Status::Pass),
+        count(Status::Warn),
+        count(Status::Fail)
+    );
+}
+
+/// Whether any worker in this project plants a compose stack.
+fn uses_compose(ctx: &GrootContext) -> bool {
+    ctx.project.container_enabled
+        || ctx.project.profiles.values().any(|p| p.compose == Some(true) || p.sandbox == Some(true))
+}
+
+/// First line of `program args` (stdout, else stderr), if it ran successfully.
+fn version_line(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).logged_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = if output.stdout.is_empty() { &output.stderr } else { &output.stdout };
+    String::from_utf8_lossy(text).lines().next().map(|l| l.trim().to_string())
+}
+
+/// `(major, minor)` from the first version-looking word, e.g. `tmux 3.3a` → `(3, 3)`.
+fn parse_version(line: &str) -> Option<(u32, u32)> {
+    let word = line
+        .split_whitespace()
+        .map(|w| w.trim_start_matches('v'))
+        .find(|w| w.starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = word.split('.').map(|p| {
+        p.chars().take_while(char::is_ascii_digit).collect::<String>().parse::<u32>()
+    });
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(|p| p.ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+fn check_git() -> Check {
+    let Some(line) = version_line("git", &["--version"]) else {
+        return Check::fail("git", "not found on PATH", "Install git 2.17 or newer");
+    };
+    match parse_version(&line) {
+        Some(v) if v < MIN_GIT => Check::fail(
+            "git",
+            line,
+            format!("groot needs git {}.{}+ for `git worktree remove`; upgrade git", MIN_GIT.0, MIN_GIT.1),
+        ),
+        _ => Check::pass("git", line),
+    }
+}
+
+fn check_docker(required: bool) -> Vec<Check> {
+    let docker_hint = GrootError::DockerNotAvailable.hint().unwrap_or_default();
+    if which::which("docker").is_err() {
+        return vec![Check::missing(
+            "docker",
+            required,
+            "not found on PATH (needed for groves)",
+            "Install Docker Desktop or Docker Engine: https://docs.docker.com/get-docker/",
+        )];
+    }
+    let docker = match version_line("docker", &["version", "--format", "{{.Server.Version}}"]) {
+        Some(server) => Check::pass("docker", format!("server {server}")),
+        None => Check::missing("docker", required, "installed, but the daemon isn't reachable", docker_hint),
+    };
+    let compose = match version_line("docker", &["compose", "version", "--short"]) {
+        Some(version) => Check::pass("compose", format!("docker compose {version}")),
+        None => Check::missing(
+            "compose",
+            required,
+            "`docker compose` is not available",
+            GrootError::ComposeNotAvailable.hint().unwrap_or_default(),
+        ),
+    };
+    vec![docker, compose]
+}
+
+fn check_tmux(ctx: Option<&GrootContext>) -> Check {
+    let backend = ctx.map_or(TerminalBackend::Tmux, |c| c.local.terminal_backend);
+    let required = backend == TerminalBackend::Tmux;
+    if backend != TerminalBackend::Tmux && !backend.is_available() {
+        return Check::fail(
+            "terminal",
+            format!("terminal_backend is '{}' but '{}' was not found on PATH", backend.name(), backend.program()),
+            "Install it, or set terminal_backend: tmux in .groot/local.yml",
+        );
+    }
+    let Some(line) = version_line("tmux", &["-V"]) else {
+        return Check::missing(
+            "tmux",
+            required,
+            "not found on PATH",
+            GrootError::TmuxNotAvailable.hint().unwrap_or_default(),
+        );
+    };
+    match parse_version(&line) {
+        Some(v) if v < MIN_TMUX => Check::missing(
+            "tmux",
+            required,
+            line,
+            format!("groot needs tmux {}.{}+ (3.2+ for popups); upgrade tmux", MIN_TMUX.0, MIN_TMUX.1),
+        ),
+        Some(v) if v < (3, 2) => Check::warn("tmux", line, "tmux 3.2+ is needed for `groot tmux` popups"),
+        _ => {
+            let auto_stop = ctx.is_some_and(|c| c.local.tmux_auto_stop);
+            let server = session::list_sessions().len();
+            let mut detail = format!("{line}, {server} session(s)");
+            if auto_stop {
+                detail.push_str(", auto-stop hook on");
+            }
+            Check::pass("tmux", detail)
+        }
+    }
+}
+
+fn check_db_client(ctx: &GrootContext) -> Check {
+    let (tool, package) = match ctx.project.database.unwrap_or_default() {
+        DbEngine::Postgres => ("pg_dump", "PostgreSQL client tools (`brew install libpq` / `apt install postgresql-client`)"),
+        DbEngine::Mysql => ("mysqldump", "MySQL client tools (`brew install mysql-client` / `apt install mysql-client`)"),
+        DbEngine::Sqlite => return Check::pass("db client", "SQLite needs no dump tool"),
+    };
+    match version_line(tool, &["--version"]) {
+        Some(line) => Check::pass("db client", line),
+        None => Check::warn(
+            "db client",
+            format!("{tool} not found on PATH (needed for --transplant)"),
+            format!("Install {package}"),
+        ),
+    }
+}
+
+/// One check per filesystem planting fills: the one holding `.groot/` and,
+/// for groves, Docker's data-root.
+fn check_disk(ctx: &GrootContext, docker: bool) -> Vec<Check> {
+    let min_mb = ctx.local.min_disk_space_mb;
+    let survey = disk::survey(&ctx.local.worktrees_root(&ctx.groot_dir), docker);
+    if survey.is_empty() {
+        let hint = format!("Check it with `df -h {}`", ctx.groot_dir.display());
+        return vec![Check::warn("disk", "could not read free space for .groot/", hint)];
+    }
+    survey
+        .into_iter()
+        .map(|fs| {
+            let location = fs.location();
+            if fs.free_mb < min_mb {
+                let hint = GrootError::InsufficientDiskSpace {
+                    location: location.clone(),
+                    available_mb: fs.free_mb,
+                    required_mb: min_mb,
+                    reclaimable: if docker { disk::docker_reclaimable() } else { None },
+                }
+                .hint()
+                .unwrap_or_default();
+                Check::fail("disk", format!("{} MB free on {location}, planting needs {min_mb} MB", fs.free_mb), hint)
+            } else {
+                Check::pass("disk", format!("{} MB free on {location}, min_disk_space_mb is {min_mb}", fs.free_mb))
+            }
+        })
+        .collect()
+}
+
+/// With `nix_develop`, trees' panes all start `nix develop`.
+fn check_nix(ctx: &GrootContext) -> Option<Check> {
+    if !ctx.project.nix_develop {
+        return None;
+    }
+    Some(match version_line("nix", &["--version"]) {
+        Some(line) => Check::pass("nix", line),
+        None => Check::fail(
+            "nix",
+            "nix_develop is on but nix was not found on PATH",
+            "Install Nix with flakes enabled, or set nix_develop: false in .groot/config.yml",
+        ),
+    })
+}
+
+/// Under WSL, worktrees on a Windows drive make bind mounts and file watching
+/// slow.
+fn check_wsl(ctx: &GrootContext) -> Option<Check> {
+    let worktrees_dir = ctx.local.worktrees_root(&ctx.groot_dir);
+    if !wsl::on_windows_drive(&worktrees_dir) {
+        return None;
+    }
+    let hint = match wsl::linux_worktrees_dir(&ctx.project.project_name) {
+        Some(dir) => format!("Set worktrees_dir: {} in .groot/local.yml", dir.display()),
+        None => "Set worktrees_dir in .groot/local.yml to a directory on the Linux filesystem".to_string(),
+    };
+    Some(Check::warn(
+        "wsl",
+        format!("worktrees are on a Windows drive ({}), which is slow for containers", worktrees_dir.display()),
+        hint,
+    ))
+}
+
+fn check_ports(ctx: &GrootContext) -> Check {
+    let next = ports::next_free(&ctx.groot_dir);
+    let detail = format!("next grove gets app:{} db:{} redis:{}", next.app, next.db, next.redis);
+    match ports::check_ports_available(&next) {
+        Ok(()) => Check::pass("ports", format!("{detail}, all free")),
+        Err(e) => {
+            let hint = e.hint().unwrap_or_default();
+            Check::warn("ports", format!("{detail}, but {e}"), hint)
+        }
+    }
+}
+
+/// State files, port allocations, worktrees, and sessions that disagree.
+fn check_state(ctx: &GrootContext) -> Vec<Check> {
+    let groot_dir = &ctx.groot_dir;
+    let workers = orch_grove::list_groves(groot_dir).unwrap_or_default();
+    let names: HashSet<&str> = workers.iter().map(|w| w.task_name.as_str()).collect();
+    let mut checks = Vec::new();
+
+    let missing: Vec<&str> = workers
+        .iter()
+        .filter(|w| !w.worktree_path.exists())
+        .map(|w| w.task_name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        checks.push(Check::fail(
+            "worktrees",
+            format!("state for {} but the worktree is gone", missing.join(", ")),
+            "Clean up with `groot tree uproot <task>` (`groot grove uproot <task>` for a grove)",
+        ));
+    }
+
+    let mut stale_ports: Vec<String> = ports::allocations(groot_dir)
+        .into_keys()
+        .filter(|name| !names.contains(name.as_str()))
+        .collect();
+    stale_ports.sort();
+    if !stale_ports.is_empty() {
+        checks.push(Check::warn(
+            "ports.json",
+            format!("ports still allocated to {}, which no longer exist", stale_ports.join(", ")),
+            "Delete those entries from .groot/ports.json so the ports can be reused",
+        ));
+    }
+
+    let mut untracked: Vec<String> = std::fs::read_dir(ctx.local.worktrees_root(groot_dir))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !names.contains(name.as_str()))
+        .collect();
+    untracked.sort();
+    if !untracked.is_empty() {
+        checks.push(Check::warn(
+            "worktrees",
+            format!("{} in .groot/worktrees with no worker state", untracked.join(", ")),
+            "Remove them with `git worktree remove .groot/worktrees/<name>` once their work is saved",
+        ));
+    }
+
+    let orphans: Vec<String> = cleanup::find_orphans(groot_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|o| o.task_name)
+        .collect();
+    if !orphans.is_empty() {
+        checks.push(Check::warn(
+            "sessions",
+            format!("{} have no running session", orphans.join(", ")),
+            "Restart them with `groot grove start <task>`, or clean up with `groot grove prune`",
+        ));
+    }
+
+    if checks.is_empty() {
+        checks.push(Check::pass("state", format!("{} worker(s), consistent", workers.len())));
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("git version 2.43.0"), Some((2, 43)));
+        assert_eq!(parse_version("tmux 3.3a"), Some((3, 3)));
+        assert_eq!(parse_version("tmux next-3.5"), None);
+        assert_eq!(parse_version("pg_dump (PostgreSQL) 16.2"), Some((16, 2)));
+        assert_eq!(parse_version("Docker Compose version v2.27.0"), Some((2, 27)));
+    }
+}
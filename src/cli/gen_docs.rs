@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use clap::{Command, CommandFactory};
+use console::style;
+
+use crate::error::Result;
+
+use super::Cli;
+
+pub async fn run(dir: &Path) -> Result<()> {
+    let pages = write(dir)?;
+    println!(
+        "{} Wrote {pages} man page(s) to {} and the markdown reference to {}",
+        style("✓").green().bold(),
+        dir.join("man").display(),
+        dir.join("groot.md").display()
+    );
+    Ok(())
+}
+
+/// Write `man/groot*.1` (one page per subcommand) and `groot.md` (the whole
+/// command reference) into `dir`, straight from the clap definitions.
+/// Returns the number of man pages.
+fn write(dir: &Path) -> Result<usize> {
+    // Build first so global flags (--json, -v) are propagated into the
+    // subcommands that refer to them; skip the `help` subcommands building adds
+    let mut cmd = without_help_subcommand(Cli::command());
+    cmd.build();
+
+    let man_dir = dir.join("man");
+    std::fs::create_dir_all(&man_dir)?;
+    clap_mangen::generate_to(cmd.clone(), &man_dir)?;
+    let pages = std::fs::read_dir(&man_dir)?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "1"))
+        .count();
+
+    std::fs::write(dir.join("groot.md"), clap_markdown::help_markdown_command(&cmd))?;
+    Ok(pages)
+}
+
+fn without_help_subcommand(cmd: Command) -> Command {
+    cmd.disable_help_subcommand(true)
+        .mut_subcommands(without_help_subcommand)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gen_docs_covers_subcommands() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(write(dir.path()).unwrap() > 10);
+
+        assert!(dir.path().join("man/groot.1").exists());
+        assert!(dir.path().join("man/groot-grove-plant.1").exists());
+        let markdown = std::fs::read_to_string(dir.path().join("groot.md")).unwrap();
+        assert!(markdown.contains("groot tree uproot"));
+    }
+}
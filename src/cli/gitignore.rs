@@ -0,0 +1,51 @@
+use clap::Subcommand;
+use console::style;
+
+use crate::config::state_dir;
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::git::gitignore;
+
+#[derive(Subcommand)]
+pub enum GitignoreCommands {
+    /// Rewrite the groot block in .gitignore and .git/info/exclude for the current
+    /// state directory and worktree_copy_files
+    Sync {
+        /// Only check that .gitignore's block is up to date, failing if it isn't (for CI)
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+pub async fn run(cmd: GitignoreCommands, ctx: &GrootContext) -> Result<()> {
+    match cmd {
+        GitignoreCommands::Sync { check } => sync(ctx, check),
+    }
+}
+
+fn sync(ctx: &GrootContext, check: bool) -> Result<()> {
+    let git = &ctx.git;
+    let state_dir = state_dir::name();
+    if check {
+        let entries = gitignore::entries(git, &state_dir, &ctx.project);
+        if !gitignore::is_current(&git.root.join(".gitignore"), &entries) {
+            return Err(GrootError::Other(
+                "The groot block in .gitignore is missing or out of date; run `groot gitignore sync`".to_string(),
+            ));
+        }
+        println!("{} .gitignore is up to date", style("✓").green().bold());
+        return Ok(());
+    }
+
+    let synced = gitignore::sync(git, &state_dir, &ctx.project)?;
+    if !synced.gitignore && !synced.exclude {
+        println!("{} .gitignore is up to date", style("✓").green().bold());
+        return Ok(());
+    }
+    for (changed, file) in [(synced.gitignore, ".gitignore"), (synced.exclude, ".git/info/exclude")] {
+        if changed {
+            println!("{} Updated the groot block in {file}", style("✓").green().bold());
+        }
+    }
+    Ok(())
+}
@@ -1,16 +1,25 @@
-use std::path::PathBuf;
+use std::time::Instant;
 
 use clap::Subcommand;
 use console::style;
+use tracing::warn;
 
 use crate::claude_md;
+use crate::cli::interactive;
+use crate::cli::output::{self, WorkerInfo};
+use crate::cli::prompt::PromptArgs;
+use crate::cli::task::{self, TaskArgs};
 use crate::compose::db as compose_db;
-use crate::config::local::LocalConfig;
-use crate::config::project::ProjectConfig;
+use crate::config::profile::DbStrategy;
+use crate::config::secret;
 use crate::container::docker::DockerClient;
+use crate::context::GrootContext;
 use crate::error::{GrootError, Result};
 use crate::git::{branch, repo::GitRepo};
-use crate::orchestrator::{cleanup, state::GroveState, grove as orch_grove};
+use crate::orchestrator::remote::RemoteBackend;
+use crate::orchestrator::{cleanup, daemon, disk, state::GroveState, grove as orch_grove};
+use crate::progress::{self, Event, Step};
+use crate::terminal::TerminalBackend;
 use crate::tmux::{layout, session, workspace};
 
 #[derive(Subcommand)]
@@ -22,63 +31,87 @@ pub enum GroveCommands {
         /// Task type (feature, bugfix, refactor, chore)
         #[arg(short = 't', long = "type", default_value = "feature")]
         task_type: String,
-        /// Launch claude with this prompt in the grove's tmux window
-        #[arg(long)]
-        prompt: Option<String>,
-        /// Launch claude with the prompt read from this file
-        #[arg(long, conflicts_with = "prompt")]
-        prompt_file: Option<PathBuf>,
+        #[command(flatten)]
+        prompt: PromptArgs,
+        #[command(flatten)]
+        task_args: Box<TaskArgs>,
         /// Clone the host's development database into the grove
         #[arg(long)]
         transplant: bool,
         /// Source database URL for --transplant (default: auto-detect from config/database.yml)
         #[arg(long, requires = "transplant")]
         db_source: Option<String>,
+        /// Run the agent in its own container, reaching only the hosts in `sandbox.allowed_hosts`
+        #[arg(long)]
+        sandbox: bool,
+        /// Provision the grove's environment on a remote backend instead of a local compose stack
+        #[arg(long, value_enum, conflicts_with_all = ["sandbox", "transplant"])]
+        remote: Option<RemoteBackend>,
+        /// Workspace layout from .groot/tmux-layouts/<name>.json (default: per task type)
+        #[arg(long)]
+        layout: Option<String>,
+        /// Print progress as JSON lines (step started/finished/failed) for editor plugins and wrappers
+        #[arg(long)]
+        porcelain: bool,
+        /// Skip pruning groves whose session is gone before planting (`grove prune` does it later)
+        #[arg(long)]
+        no_cleanup: bool,
     },
     /// List all groves
-    List,
+    List {
+        /// Skip pruning groves whose session is gone (faster; `grove prune` does it later)
+        #[arg(long)]
+        no_cleanup: bool,
+    },
     /// Show grove status and resource usage
-    Status,
+    Status {
+        /// Skip pruning groves whose session is gone (faster; `grove prune` does it later)
+        #[arg(long)]
+        no_cleanup: bool,
+    },
     /// Stop a grove (tear down containers/tmux but keep worktree and branch)
     Stop {
-        /// Task name of the grove to stop
-        task: String,
+        /// Task name of the grove to stop (picked from a list if omitted)
+        task: Option<String>,
         /// Force stop even if other trees share this grove's compose stack
         #[arg(long)]
         force: bool,
     },
     /// Start a stopped grove's containers
     Start {
-        /// Task name of the grove to start
-        task: String,
+        /// Task name of the grove to start (picked from a list if omitted)
+        task: Option<String>,
     },
     /// Uproot a grove and clean up all resources (worktree, branch, containers, tmux)
     Uproot {
-        /// Task name of the grove to uproot
-        task: String,
+        /// Task name of the grove to uproot (picked from a list if omitted)
+        task: Option<String>,
         /// Force uproot even if the worktree has uncommitted changes or unpushed commits
         #[arg(long)]
         force: bool,
+        /// With --force, skip the prompt to type the task name (for scripts)
+        #[arg(short, long)]
+        yes: bool,
     },
     /// Clean up orphaned groves
     Prune,
     /// Clone the host database into a running grove's compose stack
     Transplant {
-        /// Task name of the grove
-        task: String,
+        /// Task name of the grove (picked from a list if omitted)
+        task: Option<String>,
         /// Source database URL (default: auto-detect from config/database.yml)
         #[arg(long)]
         db_source: Option<String>,
     },
-    /// Attach to a grove's tmux session (picks first if no task specified)
+    /// Attach to a grove's tmux session
     Attach {
-        /// Task name of the grove to attach to (optional — attaches to first grove if omitted)
+        /// Task name of the grove to attach to (picked from the running sessions if omitted)
         task: Option<String>,
     },
     /// Rebuild a grove's container image
     Build {
-        /// Task name of the grove
-        task: String,
+        /// Task name of the grove (picked from a list if omitted)
+        task: Option<String>,
     },
     /// Set tmux layout for grove panes
     Layout {
@@ -86,99 +119,157 @@ pub enum GroveCommands {
         preset: String,
     },
     /// Generate a default tmux-layout.json template
-    InitTemplate,
+    InitTemplate {
+        /// Write a named layout to .groot/tmux-layouts/<name>.json instead
+        #[arg(long)]
+        name: Option<String>,
+    },
     /// Generate a default claude-md.template for customization
     InitClaudeTemplate,
 }
 
-pub async fn run(cmd: GroveCommands) -> Result<()> {
+pub async fn run(cmd: GroveCommands, ctx: &GrootContext) -> Result<()> {
     match cmd {
         GroveCommands::Plant {
             task,
             task_type,
             prompt,
-            prompt_file,
+            task_args,
             transplant,
             db_source,
-        } => plant(&task, &task_type, prompt, prompt_file, transplant, db_source).await,
-        GroveCommands::List => list().await,
-        GroveCommands::Status => status().await,
-        GroveCommands::Stop { task, force } => stop(&task, force).await,
-        GroveCommands::Start { task } => start(&task).await,
-        GroveCommands::Uproot { task, force } => uproot(&task, force).await,
-        GroveCommands::Prune => prune().await,
-        GroveCommands::Transplant { task, db_source } => transplant(&task, db_source).await,
-        GroveCommands::Attach { task } => attach(task.as_deref()).await,
-        GroveCommands::Build { task } => build(&task).await,
-        GroveCommands::Layout { preset } => set_layout(&preset).await,
-        GroveCommands::InitTemplate => init_template().await,
-        GroveCommands::InitClaudeTemplate => init_claude_template().await,
+            sandbox,
+            remote,
+            layout,
+            porcelain,
+            no_cleanup,
+        } => {
+            if porcelain {
+                progress::enable_porcelain();
+            }
+            let options = GroveOptions { transplant, db_source, sandbox, remote, no_cleanup };
+            plant(ctx, &task, &task_type, prompt, &task_args, options, layout.as_deref()).await
+        }
+        GroveCommands::List { no_cleanup } => list(ctx, no_cleanup).await,
+        GroveCommands::Status { no_cleanup } => status(ctx, no_cleanup).await,
+        GroveCommands::Stop { task, force } => {
+            let task = interactive::pick_worker(ctx, task, "grove stop", "groves", is_grove)?;
+            stop(ctx, &task, force).await
+        }
+        GroveCommands::Start { task } => {
+            let task = interactive::pick_worker(ctx, task, "grove start", "groves", is_grove)?;
+            start(&task).await
+        }
+        GroveCommands::Uproot { task, force, yes } => {
+            let task = interactive::pick_worker(ctx, task, "grove uproot", "groves", is_grove)?;
+            uproot(ctx, &task, force, yes).await
+        }
+        GroveCommands::Prune => prune(ctx).await,
+        GroveCommands::Transplant { task, db_source } => {
+            let task = interactive::pick_worker(ctx, task, "grove transplant", "groves with a compose stack", |g| {
+                g.compose_file.as_ref().is_some_and(|f| f.exists())
+            })?;
+            transplant(ctx, &task, db_source).await
+        }
+        GroveCommands::Attach { task } => {
+            let task = interactive::pick_worker(ctx, task, "grove attach", "groves with a running session", |g| {
+                is_grove(g) && g.tmux_session.as_ref().is_some_and(|ws| session::session_exists(ws))
+            })?;
+            attach(ctx, &task).await
+        }
+        GroveCommands::Build { task } => {
+            let task = interactive::pick_worker(ctx, task, "grove build", "groves", is_grove)?;
+            build(&task).await
+        }
+        GroveCommands::Layout { preset } => set_layout(ctx, &preset).await,
+        GroveCommands::InitTemplate { name } => init_template(ctx, name.as_deref()).await,
+        GroveCommands::InitClaudeTemplate => init_claude_template(ctx).await,
     }
 }
 
-fn ensure_groot(git: &GitRepo) -> Result<std::path::PathBuf> {
-    let groot_dir = git.groot_dir();
-    if !groot_dir.join("config.yml").exists() {
-        return Err(GrootError::NotInitialized);
-    }
-    Ok(groot_dir)
+/// Flags of `grove plant` that the task type's profile can also set.
+#[derive(Debug, Default)]
+pub(crate) struct GroveOptions {
+    /// Clone the host's development database into the grove
+    pub transplant: bool,
+    pub db_source: Option<String>,
+    /// Run the agent in the grove's compose stack
+    pub sandbox: bool,
+    /// Provision the environment remotely instead of with compose
+    pub remote: Option<RemoteBackend>,
+    /// Don't prune orphaned groves first
+    pub no_cleanup: bool,
 }
 
-async fn plant(
+pub(crate) async fn plant(
+    ctx: &GrootContext,
     task_name: &str,
     task_type: &str,
-    prompt: Option<String>,
-    prompt_file: Option<PathBuf>,
-    db_clone: bool,
-    db_source: Option<String>,
+    prompt: PromptArgs,
+    task_args: &TaskArgs,
+    options: GroveOptions,
+    layout: Option<&str>,
 ) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
 
-    let local = LocalConfig::load(&groot_dir.join("local.yml"))?;
-    let _ = cleanup_orphans(&groot_dir, &git);
+    let local = &ctx.local;
+    if !options.no_cleanup {
+        let _ = cleanup_orphans(groot_dir, git).await;
+    }
+    task_args.check(ctx, task_name)?;
 
     // Generate branch name from project config
-    let config = ProjectConfig::load(&groot_dir.join("config.yml"))?;
+    let config = &ctx.project;
     let branch_name = branch::format_branch_name(&config.project_name, task_type, task_name);
 
-    // Resolve prompt text
-    let prompt_text = match (prompt, prompt_file) {
-        (Some(p), _) => Some(p),
-        (_, Some(path)) => {
-            let text = std::fs::read_to_string(&path).map_err(|e| {
-                GrootError::Other(format!("Failed to read prompt file '{}': {e}", path.display()))
-            })?;
-            Some(text)
-        }
-        _ => None,
+    // Fill in whatever the flags left unset from the task type's profile
+    let profile = config.profile(task_type);
+    let db_clone = options.transplant || profile.db == Some(DbStrategy::Transplant);
+    let stack = if let Some(remote) = options.remote {
+        orch_grove::Stack::Remote(remote)
+    } else if options.sandbox || profile.sandbox == Some(true) {
+        orch_grove::Stack::Sandboxed
+    } else {
+        orch_grove::Stack::Compose
     };
-
-    let initial_command = prompt_text.as_ref().map(|text: &String| {
-        let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
-        format!("claude --prompt \"{escaped}\"")
-    });
-
-    let resolved_db_source = db_source.or(local.compose_db_source);
-
-    // Plant the grove (always with compose)
-    let state = orch_grove::plant(
-        &git,
-        &groot_dir,
+    let layout = layout.or(profile.layout.as_deref());
+    let initial_prompt = prompt.initial_prompt(ctx, &profile)?;
+
+    let resolved_db_source = options
+        .db_source
+        .or(profile.db_source)
+        .or(local.compose_db_source.clone())
+        .map(|src| secret::resolve(&src))
+        .transpose()?;
+
+    // Plant the grove
+    let started = Instant::now();
+    let planted = orch_grove::plant(
+        ctx,
         task_name,
         &branch_name,
         task_type,
-        &local.tmux_session_name,
-        local.min_disk_space_mb,
-        initial_command.as_deref(),
-        true, // always compose for grove
-        local.compose_health_timeout_secs,
-        &local.compose_post_start,
+        initial_prompt.as_ref(),
+        stack, // compose, unless the grove is remote
         db_clone,
         resolved_db_source.as_deref(),
         None, // not sharing another grove
         None,
-    )?;
+        layout,
+    );
+    orch_grove::notify_planted(groot_dir, task_name, &branch_name, &planted, started.elapsed());
+    let state = task_args.record(ctx, planted?)?;
+
+    if local.tmux_auto_stop && local.terminal_backend == TerminalBackend::Tmux {
+        cleanup::register_auto_stop_hook(&git.root).unwrap_or_else(|e| {
+            warn!("Warning: failed to register tmux session-closed hook: {e}");
+        });
+    }
+
+    if progress::is_porcelain() {
+        progress::emit(&Event::Planted { worker: &WorkerInfo::new(&state) });
+        return Ok(());
+    }
 
     println!(
         "{} Grove planted for task '{}'",
@@ -187,6 +278,15 @@ async fn plant(
     );
     println!("  Branch:   {}", state.branch);
     println!("  Worktree: {}", state.worktree_path.display());
+    if !state.labels.is_empty() {
+        println!("  Labels:   {}", state.labels.join(", "));
+    }
+    if !state.depends_on.is_empty() {
+        println!("  Depends:  {}", state.depends_on.join(", "));
+    }
+    if let Some(parent) = &state.parent {
+        println!("  Parent:   {parent}");
+    }
 
     if let Some(ref ws) = state.tmux_session {
         println!("  Session:  {ws}");
@@ -199,6 +299,10 @@ async fn plant(
         println!("    Redis: localhost:{}", ports.redis);
     }
 
+    if let Some(ref remote) = state.remote {
+        println!("  Remote:   {} {} (ssh {})", remote.backend.name(), remote.id, remote.ssh_host);
+    }
+
     if let Some(ref ws) = state.tmux_session {
         println!(
             "\nAttach: {}",
@@ -210,13 +314,19 @@ async fn plant(
     Ok(())
 }
 
-async fn list() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
-    let _ = cleanup_orphans(&groot_dir, &git);
+async fn list(ctx: &GrootContext, no_cleanup: bool) -> Result<()> {
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
+    if !no_cleanup {
+        let _ = cleanup_orphans(groot_dir, git).await;
+    }
+
+    let groves = orch_grove::list_groves(groot_dir)?;
+    let groves: Vec<_> = groves.iter().filter(|g| g.is_grove()).collect();
 
-    let groves = orch_grove::list_groves(&groot_dir)?;
-    let groves: Vec<_> = groves.iter().filter(|g| g.compose_file.is_some()).collect();
+    if output::is_json() {
+        return output::print_json(&output::worker_infos(git, &groves, None));
+    }
 
     if groves.is_empty() {
         println!("No active groves.");
@@ -259,18 +369,36 @@ async fn list() -> Result<()> {
     Ok(())
 }
 
-async fn status() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
-    let local = LocalConfig::load(&groot_dir.join("local.yml"))?;
-    let _ = cleanup_orphans(&groot_dir, &git);
+async fn status(ctx: &GrootContext, no_cleanup: bool) -> Result<()> {
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
+    let local = &ctx.local;
+    let config = &ctx.project;
+    let base = config.base_branch();
+    if !no_cleanup {
+        let _ = cleanup_orphans(groot_dir, git).await;
+    }
+
+    let groves = orch_grove::list_groves(groot_dir)?;
+    let groves: Vec<_> = groves.iter().filter(|g| g.is_grove()).collect();
 
-    let groves = orch_grove::list_groves(&groot_dir)?;
-    let groves: Vec<_> = groves.iter().filter(|g| g.compose_file.is_some()).collect();
+    if output::is_json() {
+        return output::print_json(&output::worker_infos(git, &groves, Some(base)));
+    }
 
+    let sessions = progress::timed("listing tmux sessions", session::list_sessions);
+    let distances = output::base_distances(git, &groves, base);
     println!("{}", style("Grove Status").bold());
     println!("Max environments: {}", local.max_workers);
     println!("Active groves: {}", groves.len());
+    for fs in disk::survey(&local.worktrees_root(groot_dir), true) {
+        let free = format!("{} MB free", fs.free_mb);
+        let free = if fs.free_mb < local.min_disk_space_mb { style(free).red() } else { style(free) };
+        println!("Disk: {free} on {}", fs.location());
+    }
+    if let Some(reclaimable) = disk::docker_reclaimable() {
+        println!("Docker reclaimable: {reclaimable}");
+    }
     println!();
 
     if groves.is_empty() {
@@ -282,7 +410,7 @@ async fn status() -> Result<()> {
             let mins = age.num_minutes() % 60;
 
             let session_status = g.tmux_session.as_ref().map(|ws| {
-                if session::session_exists(ws) {
+                if sessions.contains(ws) {
                     style("active").green()
                 } else {
                     style("inactive").red()
@@ -297,6 +425,9 @@ async fn status() -> Result<()> {
                 mins
             );
             println!("    Branch:   {}", g.branch);
+            if let Some((ahead, behind)) = distances.get(&g.branch) {
+                println!("    Base:     {base} ({ahead} ahead, {behind} behind)");
+            }
             println!("    Worktree: {}", g.worktree_path.display());
             if let Some(ref ws) = g.tmux_session {
                 println!("    Session:  {ws} [{}]", session_status.unwrap());
@@ -313,11 +444,10 @@ async fn status() -> Result<()> {
     Ok(())
 }
 
-async fn stop(task_name: &str, force: bool) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn stop(ctx: &GrootContext, task_name: &str, force: bool) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
 
-    orch_grove::stop(&groot_dir, task_name, force)?;
+    orch_grove::stop(groot_dir, task_name, force)?;
 
     println!(
         "{} Grove '{}' stopped (containers/tmux removed, worktree and branch preserved)",
@@ -357,26 +487,31 @@ async fn start(task_name: &str) -> Result<()> {
     Ok(())
 }
 
-async fn uproot(task_name: &str, force: bool) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn uproot(ctx: &GrootContext, task_name: &str, force: bool, yes: bool) -> Result<()> {
+    if force {
+        interactive::confirm_uproot(ctx, task_name, yes)?;
+    }
 
-    orch_grove::uproot(&git, &groot_dir, task_name, force)?;
+    let parent = orch_grove::get_grove_by_name(&ctx.groot_dir, task_name).ok().and_then(|s| s.parent);
+    orch_grove::uproot(ctx, task_name, force)?;
 
     println!(
         "{} Grove '{}' uprooted and resources cleaned up",
         style("✓").green().bold(),
         task_name
     );
+    if let Some(parent) = parent {
+        task::offer_parent_complete(ctx, &parent)?;
+    }
 
     Ok(())
 }
 
-async fn prune() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn prune(ctx: &GrootContext) -> Result<()> {
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
 
-    let orphans = cleanup::find_orphans(&groot_dir)?;
+    let orphans = cleanup::find_orphans(groot_dir)?;
 
     if orphans.is_empty() {
         println!("No orphaned groves found.");
@@ -404,12 +539,14 @@ async fn prune() -> Result<()> {
         );
     }
 
-    for o in &orphans {
-        cleanup::cleanup_orphan(&groot_dir, &git.root, o)?;
-    }
+    let failed = cleanup::cleanup_orphans(groot_dir, &git.root, &orphans).await;
 
+    let errors = match failed.as_slice() {
+        [] => String::new(),
+        names => format!(" ({} with teardown errors: {})", names.len(), names.join(", ")),
+    };
     println!(
-        "{} Pruned {} orphaned grove(s)",
+        "{} Pruned {} orphaned grove(s){errors}",
         style("✓").green().bold(),
         orphans.len()
     );
@@ -417,17 +554,13 @@ async fn prune() -> Result<()> {
     Ok(())
 }
 
-async fn transplant(task_name: &str, source: Option<String>) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
-    let local = LocalConfig::load(&groot_dir.join("local.yml"))?;
+async fn transplant(ctx: &GrootContext, task_name: &str, source: Option<String>) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
+    let local = &ctx.local;
+    let engine = ctx.project.database.unwrap_or_default();
 
     // Load grove state
-    let state_path = GroveState::state_path(&groot_dir, task_name);
-    if !state_path.exists() {
-        return Err(GrootError::GroveNotFound(task_name.to_string()));
-    }
-    let state = GroveState::load(&state_path)?;
+    let state = orch_grove::get_grove_by_name(groot_dir, task_name)?;
 
     // Verify grove has a compose stack
     let compose_file = state.compose_file.as_ref().ok_or_else(|| {
@@ -439,17 +572,22 @@ async fn transplant(task_name: &str, source: Option<String>) -> Result<()> {
 
     // Resolve source: CLI flag > config > auto-detect from worktree
     let source_url = if let Some(src) = source {
-        src
+        secret::resolve(&src)?
     } else if let Some(ref src) = local.compose_db_source {
         println!("Using configured source: {src}");
-        src.clone()
+        secret::resolve(src)?
     } else {
-        let url = compose_db::detect_source_db(&state.worktree_path)?;
-        println!("Auto-detected source database: {url}");
+        let url = compose_db::detect_source_db(&state.worktree_path, engine)?;
+        println!("Auto-detected source database: {}", compose_db::redact_url(&url));
         url
     };
 
-    compose_db::clone_database(compose_file, &source_url, task_name)?;
+    let step = Step::start("db_clone", "Cloning database");
+    let started = Instant::now();
+    let cloned = compose_db::clone_database(compose_file, &source_url, task_name, engine);
+    orch_grove::notify_db_cloned(groot_dir, task_name, &cloned, started.elapsed());
+    cloned?;
+    step.done();
 
     println!(
         "{} Database transplanted into grove '{task_name}'",
@@ -459,52 +597,28 @@ async fn transplant(task_name: &str, source: Option<String>) -> Result<()> {
     Ok(())
 }
 
-async fn attach(task_name: Option<&str>) -> Result<()> {
-    if !session::is_available() {
+async fn attach(ctx: &GrootContext, name: &str) -> Result<()> {
+    if !ctx.probes.tmux() {
         return Err(GrootError::TmuxNotAvailable);
     }
 
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
-    let groves = orch_grove::list_groves(&groot_dir)?;
-
-    if groves.is_empty() {
-        println!("No active groves. Plant a grove first.");
-        return Ok(());
-    }
-
-    if let Some(name) = task_name {
-        let grove = groves
-            .iter()
-            .find(|g| g.task_name == name)
-            .ok_or_else(|| GrootError::GroveNotFound(name.to_string()))?;
-
-        let ws_name = grove.tmux_session.as_ref().ok_or_else(|| {
-            GrootError::Other(format!("Grove '{name}' has no tmux session"))
-        })?;
-
-        if session::session_exists(ws_name) {
-            session::attach_session(ws_name)?;
-        } else {
-            println!("Session '{ws_name}' no longer exists.");
-        }
-        return Ok(());
-    }
+    let grove = orch_grove::get_grove_by_name(&ctx.groot_dir, name)?;
+    let ws_name = grove.tmux_session.as_ref().ok_or_else(|| {
+        GrootError::Other(format!("Grove '{name}' has no tmux session"))
+    })?;
 
-    // No task specified — attach to first grove's session
-    for g in &groves {
-        if let Some(ref ws_name) = g.tmux_session {
-            if session::session_exists(ws_name) {
-                session::attach_session(ws_name)?;
-                return Ok(());
-            }
-        }
+    if session::session_exists(ws_name) {
+        session::attach_session(ws_name)?;
+    } else {
+        println!("Session '{ws_name}' no longer exists.");
     }
-
-    println!("No active grove sessions found.");
     Ok(())
 }
 
+fn is_grove(state: &GroveState) -> bool {
+    state.is_grove()
+}
+
 async fn build(task_name: &str) -> Result<()> {
     let docker = DockerClient::connect().await?;
 
@@ -518,14 +632,12 @@ async fn build(task_name: &str) -> Result<()> {
     Ok(())
 }
 
-async fn set_layout(preset: &str) -> Result<()> {
-    if !session::is_available() {
+async fn set_layout(ctx: &GrootContext, preset: &str) -> Result<()> {
+    if !ctx.probes.tmux() {
         return Err(GrootError::TmuxNotAvailable);
     }
 
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
-    let local = LocalConfig::load(&groot_dir.join("local.yml"))?;
+    let local = &ctx.local;
     layout::apply_layout(&local.tmux_session_name, preset)?;
 
     println!(
@@ -536,11 +648,13 @@ async fn set_layout(preset: &str) -> Result<()> {
     Ok(())
 }
 
-async fn init_template() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn init_template(ctx: &GrootContext, name: Option<&str>) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
 
-    let path = groot_dir.join("tmux-layout.json");
+    let path = match name {
+        Some(n) => workspace::named_template_path(groot_dir, n)?,
+        None => groot_dir.join("tmux-layout.json"),
+    };
     if path.exists() {
         println!(
             "{} Template already exists at {}",
@@ -553,6 +667,9 @@ async fn init_template() -> Result<()> {
 
     let template = workspace::default_template();
     let json = serde_json::to_string_pretty(&template)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
     std::fs::write(&path, json)?;
 
     println!(
@@ -565,9 +682,8 @@ async fn init_template() -> Result<()> {
     Ok(())
 }
 
-async fn init_claude_template() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn init_claude_template(ctx: &GrootContext) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
 
     let path = groot_dir.join("claude-md.template");
     if path.exists() {
@@ -596,14 +712,16 @@ async fn init_claude_template() -> Result<()> {
     Ok(())
 }
 
-fn cleanup_orphans(
+/// Clean up orphaned groves inline, unless the daemon is doing it.
+async fn cleanup_orphans(
     groot_dir: &std::path::Path,
     git: &GitRepo,
 ) -> Result<()> {
-    let orphans = cleanup::find_orphans(groot_dir)?;
-    for orphan in &orphans {
-        cleanup::cleanup_orphan(groot_dir, &git.root, orphan)?;
+    if daemon::is_running(groot_dir) {
+        return Ok(());
     }
+    let orphans = cleanup::find_orphans(groot_dir)?;
+    cleanup::cleanup_orphans(groot_dir, &git.root, &orphans).await;
     if !orphans.is_empty() {
         println!(
             "{} Pruned {} orphaned grove(s)",
@@ -1,34 +1,45 @@
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use console::style;
+use dialoguer::Confirm;
 
 use crate::config::local::LocalConfig;
+use crate::config::preset::{BuiltinPreset, Preset};
 use crate::config::project::ProjectConfig;
+use crate::config::state_dir;
 use crate::detector;
-use crate::error::Result;
+use crate::error::{GrootError, Result};
+use crate::git::gitignore;
 use crate::git::repo::GitRepo;
+use crate::wsl;
 
-fn ensure_gitignore_entry(repo_root: &std::path::Path, entry: &str) {
-    let gitignore_path = repo_root.join(".gitignore");
-    let contents = fs::read_to_string(&gitignore_path).unwrap_or_default();
+use super::interactive;
 
-    // Check if any line already matches (ignoring trailing whitespace)
-    let already_present = contents
-        .lines()
-        .any(|line| line.trim() == entry);
-
-    if !already_present {
-        let mut new_contents = contents;
-        if !new_contents.is_empty() && !new_contents.ends_with('\n') {
-            new_contents.push('\n');
-        }
-        new_contents.push_str(entry);
-        new_contents.push('\n');
-        let _ = fs::write(&gitignore_path, new_contents);
+/// The repo is on a Windows drive under WSL, where containers bind-mounting
+/// worktrees crawl: offer to keep worktrees on the Linux filesystem instead.
+fn wsl_worktrees_dir(repo_root: &Path, project_name: &str) -> Result<Option<PathBuf>> {
+    let Some(dir) = wsl::linux_worktrees_dir(project_name) else {
+        return Ok(None);
+    };
+    println!(
+        "{} {} is on a Windows drive; under WSL, containers and file watchers are slow there.",
+        style("!").yellow().bold(),
+        repo_root.display()
+    );
+    if !interactive::is_interactive() {
+        println!("  Set worktrees_dir in .groot/local.yml to put worktrees on the Linux filesystem (e.g. {}).", dir.display());
+        return Ok(None);
     }
+    let accepted = Confirm::new()
+        .with_prompt(format!("Create worktrees in {} instead of .groot/worktrees/?", dir.display()))
+        .default(true)
+        .interact()
+        .map_err(|e| GrootError::Other(format!("Prompt cancelled: {e}")))?;
+    Ok(accepted.then_some(dir))
 }
 
-pub async fn run() -> Result<()> {
+pub async fn run(builtin: Option<BuiltinPreset>, from_url: Option<String>) -> Result<()> {
     let git = GitRepo::discover()?;
     let groot_dir = git.groot_dir();
 
@@ -41,13 +52,31 @@ pub async fn run() -> Result<()> {
         return Ok(());
     }
 
+    // Another build's state directory here would leave the repo with two
+    let legacy = state_dir::legacy_dirs(&git.root);
+    if let Some(old) = legacy.first() {
+        return Err(GrootError::Other(format!(
+            "Found an existing state directory '{old}'. Move it to '{}' with: groot migrate --from {old}",
+            state_dir::name()
+        )));
+    }
+
+    // Fetch a shared preset before writing anything, so a bad URL leaves no trace
+    let preset = match (builtin, from_url) {
+        (Some(builtin), _) => Some(builtin.preset()),
+        (None, Some(url)) => Some(Preset::fetch(&url)?),
+        (None, None) => None,
+    };
+
     // Create directory structure
     for dir in &["worktrees", "groves", "locks", "compose"] {
         fs::create_dir_all(groot_dir.join(dir))?;
     }
 
     // Detect project types
-    let detected = detector::detect_project_types(&git.root);
+    let with_evidence = detector::detect_with_evidence(&git.root, &[]);
+    let primary_type = detector::primary::choose(&with_evidence);
+    let detected: Vec<String> = with_evidence.into_iter().map(|t| t.name).collect();
     if detected.is_empty() {
         println!("{} No project types detected", style("!").yellow());
     } else {
@@ -57,6 +86,31 @@ pub async fn run() -> Result<()> {
             detected.join(", ")
         );
     }
+    if let Some(primary) = &primary_type {
+        println!(
+            "{} Primary type: {} (confidence {:.2})",
+            style("✓").green().bold(),
+            primary.name,
+            primary.confidence
+        );
+    }
+
+    let database = detector::database::detect(&git.root);
+    if let Some(engine) = database {
+        println!("{} Database: {}", style("✓").green().bold(), engine.name());
+    }
+
+    // Detect monorepo tooling and per-package types
+    let workspace_tools = detector::workspace::detect_tools(&git.root);
+    let packages = detector::detect_packages(&git.root, &[]);
+    if !workspace_tools.is_empty() {
+        println!(
+            "{} Workspace: {} ({} package(s))",
+            style("✓").green().bold(),
+            workspace_tools.join(", "),
+            packages.len()
+        );
+    }
 
     // Infer project name from directory
     let project_name = git
@@ -74,27 +128,49 @@ pub async fn run() -> Result<()> {
         .unwrap_or_else(|| "main".to_string());
 
     // Write project config
-    let project_config = ProjectConfig {
+    let nix_develop = detected.iter().any(|t| t == "nix");
+    let mut project_config = ProjectConfig {
         project_name: project_name.clone(),
         detected_types: detected,
+        primary_type,
         container_enabled: false,
+        nix_develop,
         default_branch,
+        base_branch: None,
+        protected_branches: Vec::new(),
+        database,
+        workspace_tools,
+        packages,
+        detection: Vec::new(),
+        worktree_copy_files: None,
+        worktree_create_dirs: Vec::new(),
+        profiles: Default::default(),
+        caches: Default::default(),
+        sandbox: Default::default(),
+        webhooks: Vec::new(),
     };
+    if let Some(preset) = &preset {
+        project_config = preset.apply_config(&project_config)?;
+    }
     project_config.save(&groot_dir.join("config.yml"))?;
+    let written = match &preset {
+        Some(preset) => preset.write_files(&groot_dir)?,
+        None => Vec::new(),
+    };
 
     // Write local config
-    let local_config = LocalConfig::with_defaults();
+    let mut local_config = LocalConfig::with_defaults();
+    if wsl::on_windows_drive(&git.root) {
+        local_config.worktrees_dir = wsl_worktrees_dir(&git.root, &project_name)?;
+    }
     local_config.save(&groot_dir.join("local.yml"))?;
 
-    // Ensure .env is in .gitignore to prevent secrets from being committed
-    ensure_gitignore_entry(&git.root, ".env");
-    ensure_gitignore_entry(&git.root, ".groot/worktrees/");
-    ensure_gitignore_entry(&git.root, ".groot/groves/");
-    ensure_gitignore_entry(&git.root, ".groot/compose/");
-    ensure_gitignore_entry(&git.root, ".groot/locks/");
-    ensure_gitignore_entry(&git.root, ".groot/local.yml");
-    ensure_gitignore_entry(&git.root, ".groot/ports.json");
-    ensure_gitignore_entry(&git.root, ".groot/ports.json.lock");
+    // Ignore groot's local state and the files copied into worktrees (.env),
+    // so secrets can't be committed
+    if let Err(e) = gitignore::sync(&git, &state_dir::name(), &project_config) {
+        println!("{} Couldn't update .gitignore: {e}", style("!").yellow().bold());
+    }
+
     println!(
         "{} Initialized groot for project '{}'",
         style("✓").green().bold(),
@@ -104,6 +180,15 @@ pub async fn run() -> Result<()> {
         "  Config: {}",
         groot_dir.join("config.yml").display()
     );
+    if let Some(preset) = &preset {
+        println!("{} Applied preset '{}'", style("✓").green().bold(), preset.name);
+        for rel in &written {
+            println!("  Wrote {}", groot_dir.join(rel).display());
+        }
+        if project_config.container_enabled && !git.root.join("Dockerfile.dev").exists() {
+            println!("  Next: create Dockerfile.dev for the compose template with `groot containerize`");
+        }
+    }
 
     Ok(())
 }
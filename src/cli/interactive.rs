@@ -0,0 +1,137 @@
+use std::io::IsTerminal;
+
+use console::style;
+use dialoguer::{FuzzySelect, Input};
+
+use crate::compose::manager as compose_mgr;
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::git::worktree;
+use crate::orchestrator::{grove as orch_grove, state::GroveState};
+
+/// Whether dialoguer prompts can be shown: they read keys from stdin and draw
+/// on stderr, so both must be terminals.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && console::user_attended_stderr()
+}
+
+/// Fail before prompting when there is no terminal, naming the flags that let
+/// `command` run without one.
+pub fn require_terminal(command: &str, flags: &str) -> Result<()> {
+    if is_interactive() {
+        return Ok(());
+    }
+    Err(GrootError::Other(format!(
+        "`groot {command}` needs a terminal to prompt for input. \
+         Pass the answers as flags instead: groot {command} {flags}"
+    )))
+}
+
+/// The worker a command acts on: `task` when given, else one picked from a
+/// fuzzy-searchable list of the workers `accept` allows. `what` describes
+/// those workers for the message shown when there are none.
+pub fn pick_worker(
+    ctx: &GrootContext,
+    task: Option<String>,
+    command: &str,
+    what: &str,
+    accept: impl Fn(&GroveState) -> bool,
+) -> Result<String> {
+    if let Some(task) = task {
+        return Ok(task);
+    }
+
+    let mut workers: Vec<GroveState> = orch_grove::list_groves(&ctx.groot_dir)?
+        .into_iter()
+        .filter(|w| accept(w))
+        .collect();
+    if workers.is_empty() {
+        return Err(GrootError::Other(format!("No {what}.")));
+    }
+    if !is_interactive() {
+        return Err(GrootError::Other(format!(
+            "No task given and no terminal to pick one in. Usage: groot {command} <task>"
+        )));
+    }
+    workers.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+
+    let name_width = workers.iter().map(|w| w.task_name.len()).max().unwrap_or(0);
+    let items: Vec<String> = workers
+        .iter()
+        .map(|w| {
+            let kind = w.kind();
+            format!("{:<name_width$}  {kind:<5}  {}", w.task_name, w.branch)
+        })
+        .collect();
+
+    let selection = FuzzySelect::new()
+        .with_prompt(format!("groot {command}"))
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| GrootError::Other(format!("Picker failed: {e}")))?
+        .ok_or_else(|| GrootError::Other("Cancelled.".to_string()))?;
+    Ok(workers.swap_remove(selection).task_name)
+}
+
+/// Before `uproot --force`, list what will be destroyed and have the user type
+/// the task name back. `--yes` skips the prompt; without a terminal it's required.
+pub fn confirm_uproot(ctx: &GrootContext, task_name: &str, yes: bool) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+    let state = orch_grove::get_grove_by_name(&ctx.groot_dir, task_name)?;
+    let kind = state.kind();
+    if !is_interactive() {
+        return Err(GrootError::Other(format!(
+            "`groot {kind} uproot --force` destroys unsaved work and needs confirmation. \
+             Pass --yes to confirm without a terminal."
+        )));
+    }
+
+    eprintln!("{} This will permanently delete:", style("!").yellow().bold());
+    for line in uproot_summary(ctx, &state) {
+        eprintln!("  {} {line}", style("●").red());
+    }
+    let typed: String = Input::new()
+        .with_prompt(format!("Type '{task_name}' to confirm"))
+        .allow_empty(true)
+        .interact_text()
+        .map_err(|e| GrootError::Other(format!("Confirm cancelled: {e}")))?;
+    if typed.trim() != task_name {
+        return Err(GrootError::Other(format!(
+            "Confirmation didn't match '{task_name}'; nothing was deleted."
+        )));
+    }
+    Ok(())
+}
+
+/// What uprooting `state` removes, with any work that would be lost.
+fn uproot_summary(ctx: &GrootContext, state: &GroveState) -> Vec<String> {
+    let base = ctx.project.base_branch();
+    let mut lines = Vec::new();
+
+    let ahead = worktree::commits_ahead_of(&ctx.git.root, &state.branch, base);
+    lines.push(match ahead {
+        0 => format!("branch {}", state.branch),
+        n => format!("branch {} ({n} commit(s) not on {base})", state.branch),
+    });
+    if state.worktree_path.exists() {
+        let dirty = if worktree::has_uncommitted_changes(&state.worktree_path) {
+            " (with uncommitted changes)"
+        } else {
+            ""
+        };
+        lines.push(format!("worktree {}{dirty}", state.worktree_path.display()));
+    }
+    if let Some(cf) = &state.compose_file {
+        lines.push(format!(
+            "compose project {} and its volumes (database data)",
+            compose_mgr::project_name(cf)
+        ));
+    }
+    if let Some(ws) = &state.tmux_session {
+        lines.push(format!("tmux session {ws}"));
+    }
+    lines
+}
@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+
+use console::style;
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::config::lock::{self, LockOwner, LockState};
+use crate::context::GrootContext;
+use crate::error::Result;
+
+/// A lock file as `groot locks --json` reports it.
+#[derive(Debug, Serialize)]
+struct LockInfo {
+    path: PathBuf,
+    /// `held`, `stale`, or `free`
+    state: &'static str,
+    owner: Option<LockOwner>,
+}
+
+/// Every lock file groot takes: per-task plant locks and the port registry's.
+fn lock_files(groot_dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(groot_dir.join("locks"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "lock"))
+        .collect();
+    paths.sort();
+    paths.push(groot_dir.join("ports.json.lock"));
+    paths.retain(|p| p.exists());
+    paths
+}
+
+pub async fn run(clear: bool) -> Result<()> {
+    let ctx = GrootContext::load()?;
+    let groot_dir = &ctx.groot_dir;
+
+    if clear {
+        let (mut removed, mut kept) = (0, 0);
+        for path in lock_files(groot_dir) {
+            if lock::clear(&path)? {
+                removed += 1;
+            } else {
+                kept += 1;
+            }
+        }
+        println!(
+            "{} Removed {removed} lock file(s); {kept} held by running commands were kept",
+            style("✓").green().bold()
+        );
+        return Ok(());
+    }
+
+    let mut locks = Vec::new();
+    for path in lock_files(groot_dir) {
+        let (state, owner) = match lock::inspect(&path)? {
+            LockState::Free => ("free", None),
+            LockState::Held(owner) => ("held", owner),
+            LockState::Stale(owner) => ("stale", Some(owner)),
+        };
+        let path = path.strip_prefix(groot_dir).map(Path::to_path_buf).unwrap_or(path);
+        locks.push(LockInfo { path, state, owner });
+    }
+    if output::is_json() {
+        return output::print_json(&locks);
+    }
+    if locks.is_empty() {
+        println!("No lock files.");
+        return Ok(());
+    }
+    for l in &locks {
+        let state = match l.state {
+            "held" => style(l.state).yellow(),
+            "stale" => style(l.state).red(),
+            _ => style(l.state).dim(),
+        };
+        let owner = l.owner.as_ref().map_or(String::new(), |o| {
+            format!("  pid {}  since {}  {}", o.pid, o.acquired_at.format("%Y-%m-%d %H:%M:%S"), o.command)
+        });
+        println!("{:<28} {state:<5}{owner}", l.path.display());
+    }
+    if locks.iter().any(|l| l.state != "held") {
+        println!("\nRemove the free and stale ones with `groot locks --clear`.");
+    }
+    Ok(())
+}
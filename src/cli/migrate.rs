@@ -0,0 +1,77 @@
+use console::style;
+
+use crate::config::project::ProjectConfig;
+use crate::config::state_dir;
+use crate::error::{GrootError, Result};
+use crate::git::gitignore;
+use crate::git::repo::GitRepo;
+use crate::store::{self, Backend};
+
+pub async fn run(from: Option<String>, state_backend: Option<Backend>) -> Result<()> {
+    let git = GitRepo::discover()?;
+    if let Some(backend) = state_backend {
+        return switch_backend(&git, backend);
+    }
+
+    let from = match from {
+        Some(from) => from,
+        None => {
+            let legacy = state_dir::legacy_dirs(&git.root);
+            match legacy.as_slice() {
+                [] => {
+                    println!("Nothing to migrate: no state directory other than '{}'.", state_dir::name());
+                    return Ok(());
+                }
+                [one] => one.clone(),
+                several => {
+                    return Err(GrootError::Other(format!(
+                        "Found several state directories ({}). Pick one with --from.",
+                        several.join(", ")
+                    )));
+                }
+            }
+        }
+    };
+
+    let migration = state_dir::migrate(&git.root, &from)?;
+    println!(
+        "{} Moved {} to {}",
+        style("✓").green().bold(),
+        migration.from.display(),
+        migration.to.display()
+    );
+    if migration.worktrees > 0 {
+        println!("  Repaired {} worktree(s)", migration.worktrees);
+    }
+    if migration.gitignore_lines > 0 {
+        println!("  Updated {} .gitignore line(s)", migration.gitignore_lines);
+    }
+    let config = ProjectConfig::load(&migration.to.join("config.yml")).unwrap_or_default();
+    match gitignore::sync(&git, &state_dir::name(), &config) {
+        Ok(synced) if synced.gitignore => println!("  Updated the groot block in .gitignore"),
+        Ok(_) => {}
+        Err(e) => println!("{} Couldn't update .gitignore: {e}", style("!").yellow().bold()),
+    }
+
+    Ok(())
+}
+
+fn switch_backend(git: &GitRepo, to: Backend) -> Result<()> {
+    let groot_dir = git.groot_dir();
+    if !groot_dir.join("config.yml").exists() {
+        return Err(GrootError::NotInitialized);
+    }
+    let from = store::current_backend(&groot_dir);
+    if from == to {
+        println!("Worker state is already stored in {}.", to.as_str());
+        return Ok(());
+    }
+    let moved = store::switch_backend(&groot_dir, to)?;
+    println!(
+        "{} Moved worker state from {} to {} ({moved} worker(s))",
+        style("✓").green().bold(),
+        from.as_str(),
+        to.as_str()
+    );
+    Ok(())
+}
@@ -1,15 +1,52 @@
+pub mod archive;
 pub mod commit;
+pub mod config;
 pub mod containerize;
+pub mod daemon;
 pub mod detect;
+pub mod doctor;
+pub mod gen_docs;
+pub mod gitignore;
 pub mod grove;
 pub mod init;
+pub mod interactive;
+pub mod locks;
+pub mod migrate;
+pub mod output;
+pub mod plan;
+pub mod prompt;
+pub mod stats;
+pub mod statusline;
+pub mod task;
+pub mod tmux;
 pub mod tree;
+pub mod worker;
+
+use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 
+use crate::context::GrootContext;
+
 #[derive(Parser)]
 #[command(name = "groot", version, about = "Parallel AI-assisted development orchestrator")]
 pub struct Cli {
+    /// Print machine-readable JSON instead of styled text (also: GROOT_OUTPUT=json)
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Output format; `--output json` is the same as `--json`
+    #[arg(long, global = true, value_enum, value_name = "FORMAT", conflicts_with = "json")]
+    pub output: Option<output::Format>,
+
+    /// Log subprocess command lines (-v), and their exit status and output (-vv)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Hide progress messages; only results, warnings, and errors are printed
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -17,10 +54,21 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize a new groot project
-    Init,
+    Init {
+        /// Also write config defaults and compose, tmux, CLAUDE.md, and prompt templates for a stack
+        #[arg(long, value_enum, conflicts_with = "from_url")]
+        preset: Option<crate::config::preset::BuiltinPreset>,
+        /// Like --preset, with a team-shared preset (a YAML document) downloaded from a URL
+        #[arg(long)]
+        from_url: Option<String>,
+    },
 
     /// Detect project type and frameworks
-    Detect,
+    Detect {
+        /// Rewrite the detected types, primary type, database, and packages in .groot/config.yml
+        #[arg(long, conflicts_with = "json")]
+        update: bool,
+    },
 
     /// Containerized development environments
     #[command(subcommand)]
@@ -30,20 +78,133 @@ pub enum Commands {
     #[command(subcommand)]
     Tree(tree::TreeCommands),
 
+    /// Tmux workspace tools
+    #[command(subcommand)]
+    Tmux(tmux::TmuxCommands),
+
+    /// Commands that work on any grove or tree
+    #[command(subcommand)]
+    Worker(worker::WorkerCommands),
+
+    /// Background process that cleans up orphans, stops workers past their TTL,
+    /// and watches agents and compose stacks, so other commands needn't
+    #[command(subcommand)]
+    Daemon(daemon::DaemonCommands),
+
+    /// Inspect and check groot's configuration files
+    #[command(subcommand)]
+    Config(config::ConfigCommands),
+
+    /// Keep the groot-managed block of ignore rules in .gitignore up to date
+    #[command(subcommand)]
+    Gitignore(gitignore::GitignoreCommands),
+
+    /// Move a state directory left by an older build (.devflow, .treehouse) to the current name,
+    /// or move worker state to another storage backend
+    Migrate {
+        /// Directory to migrate (default: the one found in the repo root)
+        #[arg(long)]
+        from: Option<String>,
+        /// Move worker state and port allocations to this backend instead
+        #[arg(long, value_enum, conflicts_with = "from")]
+        state_backend: Option<crate::store::Backend>,
+    },
+
+    /// Bundle every worktree (commits, uncommitted changes, transcripts) and the
+    /// project's config into a .tar.gz, to carry in-flight work to another machine
+    Export {
+        /// Archive to write, e.g. groot-work.tar.gz
+        archive: PathBuf,
+    },
+
+    /// Restore an archive from `groot export`: missing config, then each worker's
+    /// branch and worktree, stopped and ready to plant again
+    Import {
+        /// Archive written by `groot export`
+        archive: PathBuf,
+    },
+
     /// Interactive container setup wizard
-    Containerize,
+    Containerize {
+        /// Generate the Dockerfile from this template instead of asking
+        #[arg(long, value_enum, conflicts_with = "dockerfile")]
+        template: Option<containerize::Template>,
+        /// Use this existing Dockerfile (e.g. Dockerfile.dev) instead of asking
+        #[arg(long)]
+        dockerfile: Option<String>,
+        /// Answer yes to every confirmation (write the Dockerfile, generate the compose template)
+        #[arg(short, long)]
+        yes: bool,
+    },
 
     /// Interactive conventional commit helper
-    Commit,
+    Commit {
+        /// Commit type (feat, fix, docs, style, refactor, perf, test, chore, ci)
+        #[arg(short = 't', long = "type")]
+        commit_type: Option<String>,
+        /// Commit scope, e.g. api
+        #[arg(long)]
+        scope: Option<String>,
+        /// Short description; with --type, commits without prompting
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Have the agent propose the message from the staged diff, to accept or edit
+        #[arg(long, conflicts_with = "message")]
+        ai: bool,
+    },
+
+    /// One-line worker summary for tmux status-right or a shell prompt
+    Statusline,
+
+    /// Summarise the event log: spawns per week, mean plant and database clone
+    /// times, and how long workers of each task type live
+    Stats {
+        /// Weeks of spawns to show, up to this one
+        #[arg(long, default_value_t = 8)]
+        weeks: u32,
+    },
+
+    /// Show groot's lock files, who holds each, and which are stale
+    Locks {
+        /// Remove every lock file not held by a running command
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Check git, docker, compose, tmux, database tools, disk, ports, and worker state
+    Doctor,
+
+    /// Write man pages and a markdown reference for every command
+    GenDocs {
+        /// Output directory (man pages go in <dir>/man, the reference in <dir>/groot.md)
+        dir: PathBuf,
+    },
 }
 
 pub async fn dispatch(cmd: Commands) -> crate::error::Result<()> {
     match cmd {
-        Commands::Init => init::run().await,
-        Commands::Detect => detect::run().await,
-        Commands::Grove(cmd) => grove::run(cmd).await,
-        Commands::Tree(cmd) => tree::run(cmd).await,
-        Commands::Containerize => containerize::run().await,
-        Commands::Commit => commit::run().await,
+        Commands::Init { preset, from_url } => init::run(preset, from_url).await,
+        Commands::Detect { update } => detect::run(output::is_json(), update).await,
+        Commands::Grove(cmd) => grove::run(cmd, &GrootContext::load()?).await,
+        Commands::Tree(cmd) => tree::run(cmd, &GrootContext::load()?).await,
+        Commands::Tmux(cmd) => tmux::run(cmd, &GrootContext::load()?).await,
+        Commands::Worker(cmd) => worker::run(cmd, &GrootContext::load()?).await,
+        Commands::Daemon(cmd) => daemon::run(cmd, &GrootContext::load()?).await,
+        Commands::Config(cmd) => config::run(cmd).await,
+        Commands::Gitignore(cmd) => gitignore::run(cmd, &GrootContext::load()?).await,
+        Commands::Migrate { from, state_backend } => migrate::run(from, state_backend).await,
+        Commands::Export { archive } => archive::export(&archive).await,
+        Commands::Import { archive } => archive::import(&archive).await,
+        Commands::Containerize { template, dockerfile, yes } => {
+            containerize::run(template, dockerfile, yes).await
+        }
+        Commands::Commit { commit_type, scope, message, ai } => {
+            commit::run(commit_type, scope, message, ai).await
+        }
+        Commands::Statusline => statusline::run().await,
+        Commands::Stats { weeks } => stats::run(weeks).await,
+        Commands::Locks { clear } => locks::run(clear).await,
+        Commands::Doctor => doctor::run().await,
+        Commands::GenDocs { dir } => gen_docs::run(&dir).await,
     }
 }
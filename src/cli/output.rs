@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::compose::ports::AllocatedPorts;
+use crate::error::Result;
+use crate::git::repo::GitRepo;
+use crate::git::worktree;
+use crate::logfile;
+use crate::orchestrator::activity::AgentActivity;
+use crate::orchestrator::run::RunRecord;
+use crate::orchestrator::state::{GroveState, NoteRecord};
+use crate::progress::{self, StderrWriter};
+use crate::terminal::TerminalBackend;
+use crate::tmux::session;
+
+/// Environment variable that selects the output format (`json` or `text`)
+/// when neither `--json` nor `--output` is passed.
+pub const ENV_VAR: &str = "GROOT_OUTPUT";
+
+static JSON: OnceLock<bool> = OnceLock::new();
+
+/// How commands print their results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Styled text for people
+    Text,
+    /// Machine-readable JSON
+    Json,
+}
+
+/// Record the output format for this invocation: `--output` or `--json`,
+/// else `$GROOT_OUTPUT`.
+pub fn init(format: Option<Format>) {
+    let json = match format {
+        Some(format) => format == Format::Json,
+        None => std::env::var(ENV_VAR).is_ok_and(|v| v.eq_ignore_ascii_case("json")),
+    };
+    let _ = JSON.set(json);
+}
+
+/// Whether commands should print JSON instead of styled text.
+pub fn is_json() -> bool {
+    JSON.get().copied().unwrap_or(false)
+}
+
+/// Send `tracing` events from groot to stderr: progress messages (info) by
+/// default, subprocess command lines (debug) with `-v`, their output (trace)
+/// with `-vv`, and only warnings with `--quiet`. Slow steps also get spinners
+/// unless the output is quiet or JSON.
+///
+/// Inside a groot project, every event down to trace level also goes to
+/// `.groot/logs/groot.log` as JSON, whatever the console shows.
+pub fn init_logging(verbose: u8, quiet: bool) {
+    progress::init(!quiet && !is_json(), verbose > 0);
+    let level = match (quiet, verbose) {
+        (true, _) => LevelFilter::WARN,
+        (_, 0) => LevelFilter::INFO,
+        (_, 1) => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let console = tracing_subscriber::fmt::layer()
+        .with_writer(StderrWriter)
+        .with_ansi(console::colors_enabled_stderr())
+        .without_time()
+        .with_target(false)
+        .with_level(verbose > 0)
+        .with_filter(
+            Targets::new()
+                .with_target(env!("CARGO_CRATE_NAME"), level)
+                .with_target(logfile::FILE_ONLY, LevelFilter::OFF),
+        );
+    let file = GitRepo::discover()
+        .ok()
+        .map(|git| git.groot_dir())
+        .filter(|dir| dir.join("config.yml").exists())
+        .and_then(|dir| logfile::open(&dir).ok())
+        .map(|file| {
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(Mutex::new(file))
+                .with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), LevelFilter::TRACE))
+        });
+    let _ = tracing_subscriber::registry().with(console).with(file).try_init();
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print `value` as JSON on a single line, for streams of JSON documents.
+pub fn print_json_line<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+/// A grove or tree as the list and status commands report it with `--json`.
+#[derive(Debug, Serialize)]
+pub struct WorkerInfo {
+    pub name: String,
+    /// `grove` (own compose stack) or `tree`
+    pub kind: &'static str,
+    pub branch: String,
+    pub worktree: PathBuf,
+    pub worktree_exists: bool,
+    pub task_type: Option<String>,
+    pub terminal_backend: TerminalBackend,
+    pub session: Option<String>,
+    pub session_active: bool,
+    pub compose_ports: Option<AllocatedPorts>,
+    pub shared_grove: Option<String>,
+    pub shared_compose_ports: Option<AllocatedPorts>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub expired: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// The most recent headless agent run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<RunRecord>,
+    /// What the agent pane is doing (`worker monitor --agents` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentActivity>,
+    /// Commits ahead of / behind the base branch (status commands only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<BaseDistance>,
+    /// Tasks the worker was planted to build on
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Of those, the ones that haven't finished (list and show commands only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<String>,
+    /// The worker this one is a subtask of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Workers planted as subtasks of this one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<String>,
+    /// When the task was marked complete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Notes added with `groot worker note`, oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<NoteRecord>,
+    /// The branch's newest commit, or when the worker was planted if later
+    /// (list and monitor only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_activity: Option<DateTime<Utc>>,
+    /// Seconds the task has been planted in all (list and monitor with `--time` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_spent_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaseDistance {
+    pub branch: String,
+    pub ahead: u64,
+    pub behind: u64,
+}
+
+impl WorkerInfo {
+    pub fn new(state: &GroveState) -> Self {
+        Self::with_sessions(state, &session::list_sessions())
+    }
+
+    /// `new`, given the running tmux sessions, so a list of workers needs one
+    /// `tmux list-sessions` rather than a check per worker.
+    pub fn with_sessions(state: &GroveState, sessions: &[String]) -> Self {
+        Self {
+            name: state.task_name.clone(),
+            kind: state.kind(),
+            branch: state.branch.clone(),
+            worktree: state.worktree_path.clone(),
+            worktree_exists: state.worktree_path.exists(),
+            task_type: state.task_type.clone(),
+            terminal_backend: state.terminal_backend,
+            session: state.tmux_session.clone(),
+            session_active: state.tmux_session.as_ref().is_some_and(|ws| sessions.contains(ws)),
+            compose_ports: state.compose_ports.clone(),
+            shared_grove: state.shared_grove.clone(),
+            shared_compose_ports: state.shared_compose_ports.clone(),
+            created_at: state.created_at,
+            expires_at: state.expires_at,
+            expired: state.is_expired(),
+            labels: state.labels.clone(),
+            last_run: state.last_run.clone(),
+            agent: None,
+            base: None,
+            depends_on: state.depends_on.clone(),
+            blocked_by: Vec::new(),
+            parent: state.parent.clone(),
+            subtasks: state.subtasks.clone(),
+            completed_at: state.completed_at,
+            notes: state.notes.clone(),
+            last_activity: None,
+            time_spent_secs: None,
+        }
+    }
+
+    /// Also report how far the worker's branch is from `base`.
+    pub fn with_base(self, repo_root: &Path, base: &str) -> Self {
+        let distance = worktree::ahead_behind(repo_root, &self.branch, base);
+        self.with_distance(base, distance)
+    }
+
+    /// Report an already computed `(ahead, behind)` distance from `base`.
+    pub fn with_distance(mut self, base: &str, distance: Option<(u64, u64)>) -> Self {
+        self.base = distance.map(|(ahead, behind)| BaseDistance {
+            branch: base.to_string(),
+            ahead,
+            behind,
+        });
+        self
+    }
+}
+
+/// Distances of the workers' branches from `base`, computed in process.
+pub fn base_distances(git: &GitRepo, workers: &[&GroveState], base: &str) -> HashMap<String, (u64, u64)> {
+    let branches: Vec<&str> = workers.iter().map(|w| w.branch.as_str()).collect();
+    progress::timed("comparing branches with the base", || {
+        worktree::ahead_behind_all(&git.repo, &branches, base)
+    })
+}
+
+/// `WorkerInfo`s for a list of workers, with tmux asked once for all of them
+/// and, with `base`, their distances from it computed in process.
+pub fn worker_infos(git: &GitRepo, workers: &[&GroveState], base: Option<&str>) -> Vec<WorkerInfo> {
+    let sessions = progress::timed("listing tmux sessions", session::list_sessions);
+    let distances = base.map(|base| base_distances(git, workers, base)).unwrap_or_default();
+    workers
+        .iter()
+        .map(|w| {
+            let info = WorkerInfo::with_sessions(w, &sessions);
+            match base {
+                Some(base) => info.with_distance(base, distances.get(&w.branch).copied()),
+                None => info,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scripts read these fields; renaming or dropping one breaks them.
+    #[test]
+    fn test_worker_info_json_shape() {
+        let state = GroveState::parse(
+            r#"{"task_name":"fix-login","branch":"app/bugfix/fix-login","worktree_path":"/nonexistent/fix-login",
+            "container_id":null,"created_at":"2026-01-02T03:04:05Z","pid":null,"task_type":"bugfix",
+            "tmux_session":"groot-fix-login","labels":["api"],"depends_on":["schema"],"parent":"auth"}"#,
+        )
+        .unwrap();
+        let info = WorkerInfo::with_sessions(&state, &["groot-fix-login".to_string()]);
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            serde_json::json!({
+                "name": "fix-login",
+                "kind": "tree",
+                "branch": "app/bugfix/fix-login",
+                "worktree": "/nonexistent/fix-login",
+                "worktree_exists": false,
+                "task_type": "bugfix",
+                "terminal_backend": "tmux",
+                "session": "groot-fix-login",
+                "session_active": true,
+                "compose_ports": null,
+                "shared_grove": null,
+                "shared_compose_ports": null,
+                "created_at": "2026-01-02T03:04:05Z",
+                "expires_at": null,
+                "expired": false,
+                "labels": ["api"],
+                "depends_on": ["schema"],
+                "parent": "auth",
+            })
+        );
+    }
+}
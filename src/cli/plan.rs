@@ -0,0 +1,352 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use console::style;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::grove::GroveOptions;
+use crate::cli::output;
+use crate::cli::prompt::PromptArgs;
+use crate::cli::task::{self, TaskArgs};
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::git::branch;
+use crate::orchestrator::deps::DepStatus;
+
+#[derive(Subcommand)]
+pub enum PlanCommands {
+    /// Plant a worker for every task in a plan file: YAML with a `tasks` list,
+    /// or Markdown with a `## <name>` section per task
+    Apply {
+        /// The plan file (.yml/.yaml or .md)
+        file: PathBuf,
+        /// Check the plan and show what would be planted, without planting
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip tasks that have a worker or had one, instead of refusing the
+        /// plan; re-apply a plan like this once blocked tasks can be planted
+        #[arg(long)]
+        skip_existing: bool,
+    },
+}
+
+pub async fn run(cmd: PlanCommands, ctx: &GrootContext) -> Result<()> {
+    match cmd {
+        PlanCommands::Apply { file, dry_run, skip_existing } => apply(ctx, &file, dry_run, skip_existing).await,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Plan {
+    tasks: Vec<PlanTask>,
+}
+
+/// One task of a plan: what `worker spawn` would be given for it.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanTask {
+    pub name: String,
+    #[serde(rename = "type", default = "default_task_type")]
+    pub task_type: String,
+    /// The agent's prompt, or with `prompt` its `{{DESCRIPTION}}`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// A prompt template from `.groot/prompts/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Plant a grove (true) or a tree (false) regardless of the profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compose: Option<bool>,
+}
+
+fn default_task_type() -> String {
+    "feature".to_string()
+}
+
+impl PlanTask {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            task_type: default_task_type(),
+            description: String::new(),
+            prompt: None,
+            labels: Vec::new(),
+            depends_on: Vec::new(),
+            parent: None,
+            compose: None,
+        }
+    }
+}
+
+/// Read a plan: Markdown by its extension, YAML otherwise.
+pub fn load(path: &Path) -> Result<Vec<PlanTask>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| GrootError::Other(format!("Failed to read {}: {e}", path.display())))?;
+    let markdown = path.extension().is_some_and(|ext| ext == "md" || ext == "markdown");
+    let tasks = if markdown {
+        parse_markdown(&contents)?
+    } else {
+        serde_yml::from_str::<Plan>(&contents)
+            .map_err(|e| GrootError::Other(format!("Invalid plan {}: {e}", path.display())))?
+            .tasks
+    };
+    if tasks.is_empty() {
+        return Err(GrootError::Other(format!("{} has no tasks", path.display())));
+    }
+    Ok(tasks)
+}
+
+/// A Markdown plan: each `## <name>` heading starts a task, its first lines
+/// may set fields (`type: bugfix`, `labels: a, b`), and the rest of the
+/// section is its description. Anything before the first task is ignored.
+fn parse_markdown(text: &str) -> Result<Vec<PlanTask>> {
+    let mut tasks: Vec<PlanTask> = Vec::new();
+    let mut in_fields = false;
+    let mut description: Vec<&str> = Vec::new();
+    let finish = |tasks: &mut Vec<PlanTask>, description: &mut Vec<&str>| {
+        if let Some(task) = tasks.last_mut() {
+            task.description = description.join("\n").trim().to_string();
+        }
+        description.clear();
+    };
+
+    for line in text.lines() {
+        if let Some(name) = line.strip_prefix("## ") {
+            finish(&mut tasks, &mut description);
+            tasks.push(PlanTask::named(name.trim()));
+            in_fields = true;
+            continue;
+        }
+        let Some(task) = tasks.last_mut() else {
+            continue;
+        };
+        if in_fields {
+            if line.trim().is_empty() && description.is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':').filter(|(key, _)| FIELDS.contains(key)) {
+                set_field(task, key, value.trim())?;
+                continue;
+            }
+            in_fields = false;
+        }
+        description.push(line);
+    }
+    finish(&mut tasks, &mut description);
+    Ok(tasks)
+}
+
+/// The fields a Markdown task can set, as `<field>: <value>` lines.
+const FIELDS: &[&str] = &["type", "prompt", "labels", "depends_on", "parent", "compose"];
+
+fn set_field(task: &mut PlanTask, key: &str, value: &str) -> Result<()> {
+    let list = || value.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect();
+    match key {
+        "type" => task.task_type = value.to_string(),
+        "prompt" => task.prompt = Some(value.to_string()),
+        "labels" => task.labels = list(),
+        "depends_on" => task.depends_on = list(),
+        "parent" => task.parent = Some(value.to_string()),
+        "compose" => {
+            let compose = value.parse().map_err(|_| {
+                GrootError::Other(format!("'{}': compose must be true or false, not '{value}'", task.name))
+            })?;
+            task.compose = Some(compose);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// What's wrong with the plan, one message per problem: bad or repeated
+/// names and branches, references to tasks the plan lists later, and (unless
+/// they're to be skipped) tasks that already exist.
+fn problems(tasks: &[PlanTask], branches: &[String], existing: &HashSet<&str>, skip_existing: bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    let position: HashMap<&str, usize> = tasks.iter().enumerate().map(|(i, t)| (t.name.as_str(), i)).collect();
+    let mut seen_names = HashSet::new();
+    let mut seen_branches = HashSet::new();
+
+    for (i, (task, branch)) in tasks.iter().zip(branches).enumerate() {
+        let name = task.name.as_str();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            problems.push(format!("'{name}': names may only use letters, digits, '-', '_', and '.'"));
+        }
+        if !seen_names.insert(name) {
+            problems.push(format!("'{name}' is in the plan more than once"));
+        } else if !seen_branches.insert(branch.as_str()) {
+            problems.push(format!("'{name}' would use branch {branch}, like a task before it"));
+        }
+        if existing.contains(name) && !skip_existing {
+            problems.push(format!(
+                "'{name}' already has a worker or a branch, or had one; rename it or pass --skip-existing"
+            ));
+        }
+        let references = task.depends_on.iter().chain(&task.parent);
+        for other in references {
+            if other == name {
+                problems.push(format!("'{name}' can't depend on itself or be its own subtask"));
+            } else if position.get(other.as_str()).is_some_and(|&j| j > i) {
+                problems.push(format!("'{name}' refers to '{other}', which the plan lists after it"));
+            }
+        }
+    }
+    problems
+}
+
+async fn apply(ctx: &GrootContext, file: &Path, dry_run: bool, skip_existing: bool) -> Result<()> {
+    let tasks = load(file)?;
+    let project = &ctx.project.project_name;
+    let branches: Vec<String> =
+        tasks.iter().map(|t| branch::format_branch_name(project, &t.task_type, &t.name)).collect();
+    let progress = task::load_progress(ctx)?;
+    let existing: HashSet<&str> = tasks
+        .iter()
+        .zip(&branches)
+        .filter(|(t, b)| progress.status(&t.name) != DepStatus::Unknown || branch::branch_exists(&ctx.git, b))
+        .map(|(t, _)| t.name.as_str())
+        .collect();
+
+    let problems = problems(&tasks, &branches, &existing, skip_existing);
+    if !problems.is_empty() {
+        return Err(GrootError::Other(format!(
+            "{} isn't a valid plan:\n  {}",
+            file.display(),
+            problems.join("\n  ")
+        )));
+    }
+
+    let pending: Vec<(&PlanTask, &String)> =
+        tasks.iter().zip(&branches).filter(|(t, _)| !existing.contains(t.name.as_str())).collect();
+    if !existing.is_empty() && !output::is_json() {
+        let mut skipped: Vec<&str> = existing.iter().copied().collect();
+        skipped.sort_unstable();
+        println!("Skipping {} existing task(s): {}", skipped.len(), skipped.join(", "));
+    }
+    if dry_run {
+        if output::is_json() {
+            return output::print_json(&pending.iter().map(|(t, _)| t).collect::<Vec<_>>());
+        }
+        let name_width = pending.iter().map(|(t, _)| t.name.len()).max().unwrap_or(0);
+        for (t, branch) in &pending {
+            let depends = if t.depends_on.is_empty() {
+                String::new()
+            } else {
+                format!("  after {}", t.depends_on.join(", "))
+            };
+            println!("  {:<name_width$}  {branch}{}", t.name, style(depends).dim());
+        }
+        println!("{} task(s) would be planted; nothing was changed.", pending.len());
+        return Ok(());
+    }
+
+    let mut planted = 0;
+    let mut blocked = Vec::new();
+    let mut failed = Vec::new();
+    for (t, _) in &pending {
+        // Dependencies planted a moment ago from this plan are unfinished too
+        let blockers = task::load_progress(ctx)?.blockers(&t.depends_on);
+        if !blockers.is_empty() {
+            println!("{} {}: blocked by {}", style("–").yellow().bold(), t.name, blockers.join(", "));
+            blocked.push(t.name.clone());
+            continue;
+        }
+        let prompt = PromptArgs {
+            prompt: Some(t.description.clone()).filter(|d| !d.is_empty()),
+            prompt_template: t.prompt.clone(),
+            ..PromptArgs::default()
+        };
+        let task_args = TaskArgs {
+            labels: t.labels.clone(),
+            depends_on: t.depends_on.clone(),
+            parent: t.parent.clone(),
+            ..TaskArgs::default()
+        };
+        let result = if super::worker::wants_compose(ctx, &t.task_type, t.compose)? {
+            super::grove::plant(ctx, &t.name, &t.task_type, prompt, &task_args, GroveOptions::default(), None).await
+        } else {
+            super::tree::plant(ctx, &t.name, &t.task_type, prompt, &task_args, None, None).await
+        };
+        match result {
+            Ok(()) => planted += 1,
+            Err(e) => {
+                eprintln!("{} {}: {e}", style("✗").red().bold(), t.name);
+                failed.push(t.name.clone());
+            }
+        }
+    }
+
+    println!(
+        "{} Planted {planted} of {} task(s) from {}",
+        style("✓").green().bold(),
+        pending.len(),
+        file.display()
+    );
+    if !blocked.is_empty() {
+        println!(
+            "Plant the blocked ones once their dependencies finish with {}",
+            style(format!("groot worker plan apply {} --skip-existing", file.display())).cyan()
+        );
+    }
+    if !failed.is_empty() {
+        return Err(GrootError::Other(format!(
+            "{} of {} worker(s) failed to plant: {}",
+            failed.len(),
+            pending.len(),
+            failed.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown_plan() {
+        let text = "# Checkout\n\nSplit from the design doc.\n\n## api-schema\ntype: chore\nlabels: backend, db\n\nAdd the orders table.\n\nKeep it reversible.\n\n## api-client\ndepends_on: api-schema\ncompose: false\nCall the new endpoint.\n";
+        let tasks = parse_markdown(text).unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].task_type, "chore");
+        assert_eq!(tasks[0].labels, ["backend", "db"]);
+        assert_eq!(tasks[0].description, "Add the orders table.\n\nKeep it reversible.");
+        assert_eq!(tasks[1].task_type, "feature");
+        assert_eq!(tasks[1].depends_on, ["api-schema"]);
+        assert_eq!(tasks[1].compose, Some(false));
+        assert_eq!(tasks[1].description, "Call the new endpoint.");
+
+        assert!(parse_markdown("## x\ncompose: maybe\n").is_err());
+    }
+
+    #[test]
+    fn test_plan_problems() {
+        let plan: Plan = serde_yml::from_str(
+            "tasks:\n  - name: api\n    depends_on: [web]\n  - name: web\n  - name: api\n  - name: Web\n  - name: has space\n",
+        )
+        .unwrap();
+        let branches: Vec<String> =
+            plan.tasks.iter().map(|t| branch::format_branch_name("p", &t.task_type, &t.name)).collect();
+        let existing = HashSet::from(["web"]);
+
+        let found = problems(&plan.tasks, &branches, &existing, false);
+        assert_eq!(found.len(), 5, "{found:?}");
+        assert!(found[0].contains("'api' refers to 'web', which the plan lists after it"));
+        assert!(found[1].contains("'web' already has a worker"));
+        assert!(found[2].contains("'api' is in the plan more than once"));
+        assert!(found[3].contains("'Web' would use branch p/feature/web"));
+        assert!(found[4].contains("'has space': names may only use"));
+
+        assert!(problems(&plan.tasks[1..2], &branches[1..2], &existing, true).is_empty());
+        assert!(serde_yml::from_str::<Plan>("tasks:\n  - name: a\n    titel: x\n").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::claude_md::{self, InitialPrompt};
+use crate::config::profile::Profile;
+use crate::context::GrootContext;
+use crate::error::{self, GrootError, Result};
+
+/// The prompt flags shared by every command that plants a worker.
+#[derive(Args, Clone, Debug, Default)]
+pub struct PromptArgs {
+    /// Launch claude with this prompt in the worker's tmux window (with
+    /// --prompt-template: the task description filled in as {{DESCRIPTION}})
+    #[arg(long)]
+    pub prompt: Option<String>,
+    /// Launch claude with the prompt read from this file
+    #[arg(long, conflicts_with = "prompt")]
+    pub prompt_file: Option<PathBuf>,
+    /// Launch claude with .groot/prompts/<name>.md, rendered with the CLAUDE.local.md variables
+    #[arg(long, conflicts_with = "prompt_file")]
+    pub prompt_template: Option<String>,
+    /// The model claude is launched with (set per variant by `worker fan-out --models`)
+    #[arg(skip)]
+    pub model: Option<String>,
+}
+
+impl PromptArgs {
+    /// The prompt claude is launched with in the worker's tmux window: the
+    /// `--prompt-template` (with `--prompt` as its description), the prompt
+    /// text or file, falling back to the profile's `prompt_file`. `None` when
+    /// no prompt was given anywhere.
+    pub fn initial_prompt(self, ctx: &GrootContext, profile: &Profile) -> Result<Option<InitialPrompt>> {
+        if let Some(name) = self.prompt_template {
+            return Ok(Some(InitialPrompt {
+                template: load_template(ctx, &name)?,
+                description: self.prompt.unwrap_or_default(),
+                model: self.model,
+            }));
+        }
+
+        let prompt_file = self
+            .prompt_file
+            .or_else(|| profile.prompt_file.as_ref().map(|f| ctx.git.root.join(f)));
+
+        let prompt_text = match (self.prompt, prompt_file) {
+            (Some(p), _) => Some(p),
+            (_, Some(path)) => {
+                let text = std::fs::read_to_string(&path).map_err(|e| {
+                    GrootError::Other(format!("Failed to read prompt file '{}': {e}", path.display()))
+                })?;
+                Some(text)
+            }
+            _ => None,
+        };
+
+        // A plain prompt is its own task description
+        Ok(prompt_text.map(|template| InitialPrompt {
+            description: template.clone(),
+            template,
+            model: self.model,
+        }))
+    }
+}
+
+/// Read `.groot/prompts/<name>.md`, suggesting close names when it's missing.
+fn load_template(ctx: &GrootContext, name: &str) -> Result<String> {
+    let path = claude_md::prompt_template_path(&ctx.groot_dir, name);
+    if path.exists() {
+        let template = std::fs::read_to_string(&path)?;
+        return claude_md::expand_partials(&template, &ctx.groot_dir);
+    }
+    let available = claude_md::list_prompt_templates(&ctx.groot_dir);
+    let suggestions = error::similar_names(name, &available);
+    let hint = match (suggestions.as_slice(), available.as_slice()) {
+        ([first, ..], _) => format!(" Did you mean '{first}'?"),
+        (_, []) => " Create it to add the first one.".to_string(),
+        _ => format!(" Available: {}.", available.join(", ")),
+    };
+    Err(GrootError::TemplateNotFound(format!("{}.{hint}", path.display())))
+}
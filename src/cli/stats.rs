@@ -0,0 +1,68 @@
+use console::style;
+
+use crate::cli::output;
+use crate::context::GrootContext;
+use crate::error::Result;
+use crate::notify::events;
+use crate::orchestrator::stats::{self, Timing};
+
+/// `4.2s`, `1m 05s`
+fn short(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs < 60 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// `45m`, `3h 20m`, `2d 4h`
+fn long(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    match (days, hours) {
+        (0, 0) => format!("{mins}m"),
+        (0, _) => format!("{hours}h {mins}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+fn timing(t: &Timing) -> String {
+    match t.mean_ms {
+        Some(ms) => format!("{} on average over {}", short(ms), t.count),
+        None => "-".to_string(),
+    }
+}
+
+pub async fn run(weeks: u32) -> Result<()> {
+    let ctx = GrootContext::load()?;
+    let events = events::read(&ctx.groot_dir);
+    let stats = stats::compute(&events, chrono::Utc::now(), weeks);
+    if output::is_json() {
+        return output::print_json(&stats);
+    }
+    let Some(since) = stats.since else {
+        println!("No events recorded yet; they are logged to {}.", events::path(&ctx.groot_dir).display());
+        return Ok(());
+    };
+
+    println!("{} (since {})", style("Workers spawned per week").bold(), since.format("%Y-%m-%d"));
+    for w in &stats.weeks {
+        let took = w.mean_plant_ms.map(|ms| format!("  plant {}", short(ms))).unwrap_or_default();
+        println!("  {}  {:>3}{took}", w.week, w.spawned);
+    }
+    println!();
+    println!("{:<12}{}", "Plant", timing(&stats.plant));
+    if stats.failed_plants > 0 {
+        println!("{:<12}{}", "  failed", stats.failed_plants);
+    }
+    println!("{:<12}{}", "DB clone", timing(&stats.db_clone));
+
+    if !stats.lifetime_by_type.is_empty() {
+        println!();
+        println!("{}", style("Lifetime by task type").bold());
+        for l in &stats.lifetime_by_type {
+            println!("  {:<12}{:>8} on average over {}", l.task_type, long(l.mean_secs), l.stopped);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::error::Result;
+use crate::git::repo::GitRepo;
+use crate::orchestrator::daemon;
+use crate::orchestrator::grove as orch_grove;
+use crate::orchestrator::state::GroveState;
+
+/// The counts `statusline` prints.
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Summary {
+    active: usize,
+    /// Workers with uncommitted changes (`None` without a daemon)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dirty: Option<usize>,
+    /// Groves whose compose stack isn't all ready (`None` without a daemon)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failing: Option<usize>,
+}
+
+impl Summary {
+    /// Count the planted workers and, when a daemon keeps it up to date, what
+    /// its last round saw of them.
+    fn of(workers: &[GroveState], daemon_running: bool) -> Self {
+        let count = |seen: fn(&GroveState) -> bool| daemon_running.then(|| workers.iter().filter(|w| seen(w)).count());
+        Self {
+            active: workers.len(),
+            dirty: count(|w| w.health.as_ref().is_some_and(|h| h.dirty)),
+            failing: count(|w| w.health.as_ref().is_some_and(|h| h.unhealthy.is_some())),
+        }
+    }
+
+    fn line(&self) -> String {
+        let mut parts = vec![format!("{} active", self.active)];
+        if let Some(dirty) = self.dirty.filter(|n| *n > 0) {
+            parts.push(format!("{dirty} dirty"));
+        }
+        if let Some(failing) = self.failing.filter(|n| *n > 0) {
+            parts.push(format!("{failing} failing"));
+        }
+        format!("groot: {}", parts.join(", "))
+    }
+}
+
+/// Print a one-line worker summary for tmux `status-right` or a shell prompt.
+///
+/// Reads only the state files, so it is cheap enough to run on every refresh:
+/// the dirty and failing counts are what the daemon's last round recorded,
+/// and are left out when no daemon is running. Prints nothing outside an
+/// initialized repo or when no workers exist.
+pub async fn run() -> Result<()> {
+    let Ok(git) = GitRepo::discover() else {
+        return Ok(());
+    };
+    let groot_dir = git.groot_dir();
+    if !groot_dir.join("config.yml").exists() {
+        return Ok(());
+    }
+
+    let workers = orch_grove::list_groves(&groot_dir)?;
+    // A socket left by a killed daemon refuses connections at once, so this
+    // stays cheap, and stale counts aren't shown as current
+    let summary = Summary::of(&workers, daemon::is_running(&groot_dir));
+    if output::is_json() {
+        return output::print_json(&summary);
+    }
+    if !workers.is_empty() {
+        println!("{}", summary.line());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::state::WorkerHealth;
+
+    fn worker(name: &str, dirty: bool, unhealthy: Option<&str>) -> GroveState {
+        let mut state = GroveState::parse(&format!(
+            r#"{{"task_name":"{name}","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null}}"#
+        ))
+        .unwrap();
+        state.health = Some(WorkerHealth {
+            since: state.created_at,
+            dirty,
+            unhealthy: unhealthy.map(str::to_string),
+        });
+        state
+    }
+
+    #[test]
+    fn test_statusline_from_recorded_health() {
+        let workers = [
+            worker("a", true, None),
+            worker("b", true, Some("db starting")),
+            worker("c", false, None),
+        ];
+        let summary = Summary::of(&workers, true);
+        assert_eq!(summary, Summary { active: 3, dirty: Some(2), failing: Some(1) });
+        assert_eq!(summary.line(), "groot: 3 active, 2 dirty, 1 failing");
+
+        assert_eq!(Summary::of(&workers[2..], true).line(), "groot: 1 active");
+        // Without a daemon the recorded health may be stale, so it isn't shown
+        assert_eq!(Summary::of(&workers, false).line(), "groot: 3 active");
+        assert_eq!(serde_json::to_string(&Summary::of(&workers, false)).unwrap(), r#"{"active":3}"#);
+    }
+}
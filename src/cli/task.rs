@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, ValueEnum};
+use console::style;
+use dialoguer::Confirm;
+use serde_json::json;
+
+use crate::cli::interactive;
+use crate::config::profile;
+use crate::config::webhook::WebhookEvent;
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::git::worktree;
+use crate::notify;
+use crate::orchestrator::deps::{self, Progress};
+use crate::orchestrator::grove as orch_grove;
+use crate::orchestrator::state::GroveState;
+use crate::orchestrator::timesheet::Timesheet;
+use crate::progress;
+use crate::store;
+use crate::tmux::session;
+
+/// The flags describing the task a worker is planted for, shared by every
+/// command that plants one.
+#[derive(Args, Clone, Debug, Default)]
+pub struct TaskArgs {
+    /// Label the worker, to filter `worker list` by (repeatable, or comma-separated)
+    #[arg(long = "label", value_name = "LABEL", value_delimiter = ',')]
+    pub labels: Vec<String>,
+    /// A task this one builds on; the worker isn't planted until it has
+    /// finished (repeatable)
+    #[arg(long = "depends-on", value_name = "TASK")]
+    pub depends_on: Vec<String>,
+    /// Plant even if a --depends-on task hasn't finished, with a warning
+    #[arg(long, requires = "depends_on")]
+    pub force: bool,
+    /// Plant the worker as a subtask of this planted worker, splitting a big
+    /// task into pieces with their own branches
+    #[arg(long, value_name = "TASK")]
+    pub parent: Option<String>,
+}
+
+impl TaskArgs {
+    /// Refuse to plant `task` while a dependency is unfinished (with
+    /// `--force`, warn).
+    pub fn check(&self, ctx: &GrootContext, task: &str) -> Result<()> {
+        if let Some(parent) = &self.parent {
+            if parent == task {
+                return Err(GrootError::Other(format!("'{task}' can't be a subtask of itself")));
+            }
+            orch_grove::get_grove_by_name(&ctx.groot_dir, parent)?;
+        }
+        if self.depends_on.is_empty() {
+            return Ok(());
+        }
+        let progress = load_progress(ctx)?;
+        deps::check(&progress, task, &self.depends_on, self.force)
+    }
+
+    /// Keep the labels, dependencies, and parent in the planted worker's
+    /// state, and add it to its parent's subtasks.
+    pub fn record(&self, ctx: &GrootContext, state: GroveState) -> Result<GroveState> {
+        if self.depends_on.is_empty() && self.labels.is_empty() && self.parent.is_none() {
+            return Ok(state);
+        }
+        if let Some(parent) = &self.parent {
+            store::update_worker(&ctx.groot_dir, parent, |p| {
+                if !p.subtasks.contains(&state.task_name) {
+                    p.subtasks.push(state.task_name.clone());
+                }
+            })?;
+        }
+        let mut labels: Vec<String> = self.labels.iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+        labels.sort();
+        labels.dedup();
+        let updated = store::update_worker(&ctx.groot_dir, &state.task_name, |s| {
+            s.depends_on = self.depends_on.clone();
+            s.labels = labels;
+            s.parent = self.parent.clone();
+        })?;
+        Ok(updated.unwrap_or(state))
+    }
+}
+
+/// Once the last unfinished subtask of `parent` has finished, say so and
+/// offer to mark the parent complete.
+pub fn offer_parent_complete(ctx: &GrootContext, parent: &str) -> Result<()> {
+    let Ok(state) = orch_grove::get_grove_by_name(&ctx.groot_dir, parent) else {
+        return Ok(());
+    };
+    if !load_progress(ctx)?.ready_to_complete(&state) {
+        return Ok(());
+    }
+    println!(
+        "{} All {} subtasks of '{parent}' have finished",
+        style("✓").green().bold(),
+        state.subtasks.len()
+    );
+    if !interactive::is_interactive() {
+        println!("  Mark it complete with: {}", style(format!("groot worker complete {parent}")).cyan());
+        return Ok(());
+    }
+    let complete = Confirm::new()
+        .with_prompt(format!("Mark '{parent}' complete?"))
+        .default(true)
+        .interact()
+        .map_err(|e| GrootError::Other(format!("Prompt failed: {e}")))?;
+    if complete {
+        mark_complete(ctx, parent)?;
+    }
+    Ok(())
+}
+
+/// Mark a planted task complete, so tasks depending on it are unblocked, and
+/// send the `task_completed` event. Its worktree and branch are left alone.
+pub fn mark_complete(ctx: &GrootContext, task: &str) -> Result<()> {
+    let state = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    if state.completed_at.is_some() {
+        println!("'{task}' is already complete");
+        return Ok(());
+    }
+    store::update_worker(&ctx.groot_dir, task, |s| s.completed_at = Some(Utc::now()))?;
+    notify::event(&ctx.groot_dir, WebhookEvent::TaskCompleted, task, json!({ "subtasks": state.subtasks }));
+    println!("{} '{task}' marked complete", style("✓").green().bold());
+    Ok(())
+}
+
+/// How far along every task is, for checking and showing dependencies.
+pub fn load_progress(ctx: &GrootContext) -> Result<Progress> {
+    let workers = orch_grove::list_groves(&ctx.groot_dir)?;
+    Ok(Progress::load(&ctx.git, &ctx.groot_dir, ctx.project.base_branch(), &workers))
+}
+
+/// The unfinished dependencies of each of `workers` that is blocked.
+pub fn blocked(ctx: &GrootContext, workers: &[GroveState]) -> HashMap<String, Vec<String>> {
+    if workers.iter().all(|w| w.depends_on.is_empty()) {
+        return HashMap::new();
+    }
+    let progress = Progress::load(&ctx.git, &ctx.groot_dir, ctx.project.base_branch(), workers);
+    workers
+        .iter()
+        .map(|w| (w.task_name.clone(), progress.blockers(&w.depends_on)))
+        .filter(|(_, blockers)| !blockers.is_empty())
+        .collect()
+}
+
+/// What a worker is up to, for `worker list --state`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum WorkerStatus {
+    /// Its tmux session is running
+    Active,
+    /// Its session is gone: stopped, closed, or never opened
+    Inactive,
+    /// Planted with --force while a --depends-on task hasn't finished
+    Blocked,
+    /// Past its profile's TTL
+    Expired,
+}
+
+/// The workers a listing shows, sorted by name, the unfinished dependencies
+/// of the blocked ones, when each last saw work, and with `--time` how long
+/// each task has been planted in all.
+pub struct Listing {
+    pub workers: Vec<GroveState>,
+    pub blocked: HashMap<String, Vec<String>>,
+    pub last_activity: HashMap<String, DateTime<Utc>>,
+    pub time_spent: HashMap<String, Duration>,
+}
+
+impl Listing {
+    /// The workers with a running session whose last activity is more than
+    /// `after` ago, with how long ago that was, longest idle first.
+    pub fn stale(&self, sessions: &[String], after: Duration) -> Vec<(&GroveState, Duration)> {
+        let now = Utc::now();
+        let mut stale: Vec<_> = self
+            .workers
+            .iter()
+            .filter(|w| w.tmux_session.as_ref().is_some_and(|ws| sessions.contains(ws)))
+            .filter_map(|w| Some((w, now - *self.last_activity.get(&w.task_name)?)))
+            .filter(|(_, idle)| *idle >= after)
+            .collect();
+        stale.sort_by_key(|(_, idle)| std::cmp::Reverse(*idle));
+        stale
+    }
+}
+
+/// When each worker last saw work: its branch's newest commit, or when it
+/// was planted if that's later (a new branch points at an old base commit).
+pub fn last_activity(ctx: &GrootContext, workers: &[GroveState]) -> HashMap<String, DateTime<Utc>> {
+    let branches: Vec<&str> = workers.iter().map(|w| w.branch.as_str()).collect();
+    let commits = progress::timed("reading branch tips", || worktree::tip_times(&ctx.git.repo, &branches));
+    workers
+        .iter()
+        .map(|w| {
+            let committed = commits.get(&w.branch).copied().unwrap_or(w.created_at);
+            (w.task_name.clone(), committed.max(w.created_at))
+        })
+        .collect()
+}
+
+/// How long each worker's task has been planted in all, from the event log.
+pub fn time_spent(ctx: &GrootContext, workers: &[GroveState]) -> HashMap<String, Duration> {
+    let timesheet = Timesheet::load(&ctx.groot_dir, workers);
+    let now = Utc::now();
+    workers
+        .iter()
+        .map(|w| (w.task_name.clone(), timesheet.total(&w.task_name, now)))
+        .collect()
+}
+
+/// Parse `--stale`'s age, like `14d`.
+fn parse_age(age: &str) -> std::result::Result<Duration, String> {
+    profile::parse_ttl(age).map_err(|_| "expected a number followed by m, h, d, or w (e.g. 14d)".to_string())
+}
+
+/// Which workers a listing shows.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ListFilter {
+    /// Only workers with this label (repeatable; a worker needs all of them)
+    #[arg(long = "label", value_name = "LABEL", value_delimiter = ',')]
+    pub labels: Vec<String>,
+    /// Only workers in this state
+    #[arg(long, value_enum)]
+    pub state: Option<WorkerStatus>,
+    /// Only workers without a commit for this long (e.g. 14d, 12h)
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub stale: Option<Duration>,
+    /// Add a column with how long each task has been planted in all, across
+    /// stops and re-plants
+    #[arg(long)]
+    pub time: bool,
+}
+
+impl ListFilter {
+    /// The planted workers that match.
+    pub fn list(&self, ctx: &GrootContext) -> Result<Listing> {
+        let mut workers = orch_grove::list_groves(&ctx.groot_dir)?;
+        workers.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+        let blocked = blocked(ctx, &workers);
+        let last_activity = last_activity(ctx, &workers);
+        let time_spent = if self.time { time_spent(ctx, &workers) } else { HashMap::new() };
+        let mut listing = Listing { workers, blocked, last_activity, time_spent };
+        if self.state.is_some() || !self.labels.is_empty() || self.stale.is_some() {
+            self.retain(&mut listing, &session::list_sessions());
+        }
+        Ok(listing)
+    }
+
+    /// Keep the workers that match, given the running tmux sessions.
+    pub fn retain(&self, listing: &mut Listing, sessions: &[String]) {
+        let now = Utc::now();
+        let Listing { workers, blocked, last_activity, .. } = listing;
+        workers.retain(|w| {
+            let labelled = self.labels.iter().all(|l| w.labels.contains(l));
+            let active = || w.tmux_session.as_ref().is_some_and(|ws| sessions.contains(ws));
+            let in_state = match self.state {
+                None => true,
+                Some(WorkerStatus::Active) => active(),
+                Some(WorkerStatus::Inactive) => !active(),
+                Some(WorkerStatus::Blocked) => blocked.contains_key(&w.task_name),
+                Some(WorkerStatus::Expired) => w.is_expired(),
+            };
+            let stale = self
+                .stale
+                .is_none_or(|after| last_activity.get(&w.task_name).is_some_and(|at| now - *at >= after));
+            labelled && in_state && stale
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str, labels: &[&str], session: &str) -> GroveState {
+        let mut state = GroveState::parse(&format!(
+            r#"{{"task_name":"{name}","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null,"tmux_session":"{session}"}}"#
+        ))
+        .unwrap();
+        state.labels = labels.iter().map(|l| l.to_string()).collect();
+        state
+    }
+
+    #[test]
+    fn test_list_filter() {
+        let workers = vec![
+            worker("a", &["backend", "urgent"], "groot-a"),
+            worker("b", &["backend"], "groot-b"),
+            worker("c", &[], "groot-c"),
+        ];
+        let sessions = ["groot-a".to_string(), "groot-c".to_string()];
+        let blocked = HashMap::from([("c".to_string(), vec!["a".to_string()])]);
+        let now = Utc::now();
+        let last_activity = HashMap::from([
+            ("a".to_string(), now - Duration::days(20)),
+            ("b".to_string(), now - Duration::days(20)),
+            ("c".to_string(), now - Duration::hours(2)),
+        ]);
+        let listing = || Listing {
+            workers: workers.clone(),
+            blocked: blocked.clone(),
+            last_activity: last_activity.clone(),
+            time_spent: HashMap::new(),
+        };
+        let names = |filter: ListFilter| {
+            let mut kept = listing();
+            filter.retain(&mut kept, &sessions);
+            kept.workers.into_iter().map(|w| w.task_name).collect::<Vec<_>>()
+        };
+
+        let backend = vec!["backend".to_string()];
+        let filter = |labels: &[String], state| ListFilter { labels: labels.to_vec(), state, stale: None, time: false };
+        assert_eq!(names(filter(&backend, None)), ["a", "b"]);
+        assert_eq!(names(filter(&backend, Some(WorkerStatus::Active))), ["a"]);
+        assert_eq!(names(filter(&[], Some(WorkerStatus::Inactive))), ["b"]);
+        assert_eq!(names(filter(&[], Some(WorkerStatus::Blocked))), ["c"]);
+        assert_eq!(names(ListFilter { stale: Some(Duration::days(14)), ..filter(&[], None) }), ["a", "b"]);
+
+        // Only workers with a running session are flagged in the monitor
+        let stale: Vec<_> = listing().stale(&sessions, Duration::days(14)).into_iter().map(|(w, _)| w.task_name.clone()).collect();
+        assert_eq!(stale, ["a"]);
+    }
+}
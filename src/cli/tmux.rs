@@ -0,0 +1,457 @@
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use console::style;
+use dialoguer::FuzzySelect;
+
+use crate::claude_md;
+use crate::cli::interactive;
+use crate::detector::{linter, package_manager, test_framework};
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::git::{branch, worktree};
+use crate::orchestrator::grove as orch_grove;
+use crate::tmux::{hub as tmux_hub, session, workspace};
+
+#[derive(Subcommand)]
+pub enum TmuxCommands {
+    /// Validate tmux-layout.json, tmux-layouts/*.json, and claude-md.template and print the rendered result
+    ValidateTemplate {
+        /// Template variable for the dry run (e.g. --vars task=foo --vars APP_PORT=3005)
+        #[arg(long = "vars", value_name = "KEY=VALUE", value_parser = parse_var)]
+        vars: Vec<(String, String)>,
+    },
+    /// Create or refresh the hub session: a dashboard plus one window per active worker
+    Hub {
+        /// Attach to (or switch the client to) the hub afterwards
+        #[arg(short, long)]
+        attach: bool,
+    },
+    /// Fuzzy-pick an active worker session and switch to it (opens a popup inside tmux)
+    Switch {
+        /// Show the picker in this terminal instead of a tmux popup
+        #[arg(long, hide = true)]
+        inline: bool,
+    },
+    /// Re-render a running worker's layout and add missing windows and panes
+    Reload {
+        /// Grove or tree name (picked from the running sessions if omitted)
+        task: Option<String>,
+        /// Also re-send commands to panes that already exist
+        #[arg(long)]
+        resend: bool,
+    },
+}
+
+pub async fn run(cmd: TmuxCommands, ctx: &GrootContext) -> Result<()> {
+    match cmd {
+        TmuxCommands::ValidateTemplate { vars } => validate_template(ctx, &vars).await,
+        TmuxCommands::Hub { attach } => hub(ctx, attach).await,
+        TmuxCommands::Reload { task, resend } => {
+            let task = interactive::pick_worker(ctx, task, "tmux reload", "workers with a running session", |w| {
+                w.tmux_session.as_ref().is_some_and(|ws| session::session_exists(ws))
+            })?;
+            reload(ctx, &task, resend).await
+        }
+        TmuxCommands::Switch { inline } => switch(ctx, inline).await,
+    }
+}
+
+/// Parse a `KEY=VALUE` pair. Keys are upper-cased to match template placeholders;
+/// `task` is accepted as shorthand for `WORKER_NAME`.
+fn parse_var(raw: &str) -> std::result::Result<(String, String), String> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{raw}'"))?;
+    let key = match key.trim().to_uppercase().as_str() {
+        "TASK" => "WORKER_NAME".to_string(),
+        other => other.to_string(),
+    };
+    Ok((key, value.to_string()))
+}
+
+fn print_problems(problems: &[String]) {
+    if problems.is_empty() {
+        println!("  {} valid", style("✓").green().bold());
+    }
+    for p in problems {
+        println!("  {} {p}", style("✗").red().bold());
+    }
+}
+
+async fn validate_template(ctx: &GrootContext, vars: &[(String, String)]) -> Result<()> {
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
+    let config = &ctx.project;
+
+    let lookup = |key: &str| {
+        vars.iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    let port = |key: &str| -> Result<Option<u16>> {
+        lookup(key)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| GrootError::Other(format!("{key} must be a port number, got '{v}'")))
+            })
+            .transpose()
+    };
+
+    let worker_name = lookup("WORKER_NAME").unwrap_or_else(|| "example-task".to_string());
+    let task_type = lookup("TASK_TYPE").unwrap_or_else(|| "feature".to_string());
+    let worktree_path = lookup("WORKTREE_PATH").unwrap_or_else(|| {
+        ctx.local
+            .worktrees_root(groot_dir)
+            .join(&worker_name)
+            .to_string_lossy()
+            .to_string()
+    });
+    let compose_file = lookup("COMPOSE_FILE").map(PathBuf::from);
+    let app_port = port("APP_PORT")?;
+    let db_port = port("DB_PORT")?;
+    let redis_port = port("REDIS_PORT")?;
+
+    let mut problem_count = 0;
+
+    // tmux-layout.json plus every named layout in tmux-layouts/
+    let mut layouts = vec![(
+        groot_dir.join("tmux-layout.json"),
+        workspace::load_template(groot_dir),
+    )];
+    for name in workspace::list_named_templates(groot_dir) {
+        layouts.push((
+            workspace::named_template_path(groot_dir, &name)?,
+            workspace::load_named_template(groot_dir, &name).map(Some),
+        ));
+    }
+
+    let ws_vars = workspace::WorkspaceVars {
+        worktree_path: &worktree_path,
+        worker_name: &worker_name,
+        app_port,
+        db_port,
+        redis_port,
+        compose_file: compose_file.as_deref(),
+    };
+
+    for (path, loaded) in layouts {
+        println!("{}", style(path.display()).bold());
+        let template = match loaded {
+            Ok(Some(t)) => Some(t),
+            Ok(None) => {
+                println!("  {} not found, checking the built-in default", style("!").yellow());
+                Some(workspace::default_template())
+            }
+            Err(e) => {
+                println!("  {} failed to parse: {e}", style("✗").red().bold());
+                problem_count += 1;
+                None
+            }
+        };
+
+        if let Some(template) = template {
+            let problems = workspace::validate_template(&template);
+            print_problems(&problems);
+            problem_count += problems.len();
+
+            let rendered = workspace::render_template(&template, &ws_vars);
+            println!();
+            println!("{}", serde_json::to_string_pretty(&rendered)?);
+        }
+        println!();
+    }
+
+    // claude-md.template
+    let claude_path = groot_dir.join("claude-md.template");
+    println!("{}", style(claude_path.display()).bold());
+    let (claude_template, is_custom) = claude_md::load_or_default(groot_dir)?;
+    if !is_custom {
+        println!("  {} not found, checking the built-in default", style("!").yellow());
+    }
+    let problems = claude_md::validate(&claude_template);
+    print_problems(&problems);
+    problem_count += problems.len();
+
+    let branch_name = lookup("BRANCH_NAME").unwrap_or_else(|| {
+        branch::format_branch_name(&config.project_name, &task_type, &worker_name)
+    });
+    let detected_types = lookup("DETECTED_TYPES").unwrap_or_else(|| config.detected_types.join(", "));
+    let primary_type = lookup("PRIMARY_TYPE")
+        .or_else(|| config.primary())
+        .unwrap_or_default();
+    let compose_file_str = compose_file
+        .as_ref()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let compose_project = lookup("COMPOSE_PROJECT").unwrap_or_else(|| {
+        compose_file
+            .as_deref()
+            .map(crate::compose::manager::project_name)
+            .unwrap_or_default()
+    });
+    let shared_grove = lookup("SHARED_GROVE").unwrap_or_default();
+    let compose_enabled = lookup("COMPOSE_ENABLED")
+        .map(|v| v == "true")
+        .unwrap_or(compose_file.is_some());
+
+    let detected_managers = package_manager::detect(&git.root);
+    let pkg_manager = lookup("PKG_MANAGER").unwrap_or_else(|| detected_managers.join(", "));
+    let install_command = lookup("INSTALL_COMMAND").unwrap_or_else(|| {
+        detected_managers
+            .first()
+            .and_then(|pm| package_manager::install_command(&git.root, pm))
+            .unwrap_or("bundle install")
+            .to_string()
+    });
+    let tests = test_framework::detect(&git.root);
+    let test_frameworks = lookup("TEST_FRAMEWORKS")
+        .unwrap_or_else(|| tests.iter().map(|t| t.name).collect::<Vec<_>>().join(", "));
+    let test_commands = lookup("TEST_COMMANDS").unwrap_or_else(|| {
+        tests
+            .iter()
+            .map(|t| t.command.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+
+    let lints = linter::detect(&git.root);
+    let linters = lookup("LINTERS")
+        .unwrap_or_else(|| lints.iter().map(|l| l.name).collect::<Vec<_>>().join(", "));
+    let lint_commands = lookup("LINT_COMMANDS").unwrap_or_else(|| {
+        lints
+            .iter()
+            .map(|l| l.command.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    let db_engine = lookup("DB_ENGINE")
+        .or_else(|| config.database.map(|e| e.name().to_string()))
+        .unwrap_or_default();
+
+    let description = lookup("DESCRIPTION").unwrap_or_default();
+    let notes = lookup("NOTES").unwrap_or_default();
+
+    let claude_vars = claude_md::ClaudeMdVars {
+        worktree_path: &worktree_path,
+        worker_name: &worker_name,
+        branch_name: &branch_name,
+        project_name: &config.project_name,
+        task_type: &task_type,
+        detected_types: &detected_types,
+        primary_type: &primary_type,
+        compose_enabled,
+        compose_file: &compose_file_str,
+        compose_project: &compose_project,
+        app_port: app_port.unwrap_or(3000),
+        db_port: db_port.unwrap_or(5432),
+        redis_port: redis_port.unwrap_or(6379),
+        shared_compose: !shared_grove.is_empty(),
+        shared_grove_name: &shared_grove,
+        pkg_manager: &pkg_manager,
+        install_command: &install_command,
+        test_frameworks: &test_frameworks,
+        test_commands: &test_commands,
+        linters: &linters,
+        lint_commands: &lint_commands,
+        db_engine: &db_engine,
+        description: &description,
+        notes: &notes,
+    };
+    println!();
+    print!("{}", claude_md::render(&claude_template, &claude_vars));
+
+    if problem_count > 0 {
+        return Err(GrootError::Other(format!(
+            "{problem_count} template problem(s) found"
+        )));
+    }
+
+    Ok(())
+}
+
+async fn hub(ctx: &GrootContext, attach: bool) -> Result<()> {
+    if !ctx.probes.tmux() {
+        return Err(GrootError::TmuxNotAvailable);
+    }
+
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
+    let local = &ctx.local;
+    let hub_session = &local.tmux_session_name;
+
+    let mut workers = orch_grove::list_groves(groot_dir)?;
+    workers.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+    let worker_sessions: Vec<String> = workers
+        .iter()
+        .filter_map(|w| w.tmux_session.clone())
+        .filter(|ws| session::session_exists(ws))
+        .collect();
+
+    let linked = tmux_hub::refresh(hub_session, &worker_sessions, &git.root)?;
+
+    println!(
+        "{} Hub session '{}' ready with {} worker window(s)",
+        style("✓").green().bold(),
+        hub_session,
+        linked
+    );
+
+    if attach {
+        if std::env::var_os("TMUX").is_some() {
+            session::switch_client(hub_session)?;
+        } else {
+            session::attach_session(hub_session)?;
+        }
+    } else {
+        println!(
+            "  Attach: {}",
+            style(format!("tmux attach -t {hub_session}")).cyan()
+        );
+    }
+
+    Ok(())
+}
+
+async fn reload(ctx: &GrootContext, task_name: &str, resend: bool) -> Result<()> {
+    if !ctx.probes.tmux() {
+        return Err(GrootError::TmuxNotAvailable);
+    }
+
+    let groot_dir = &ctx.groot_dir;
+    let state = orch_grove::get_grove_by_name(groot_dir, task_name)?;
+    let ws_name = state
+        .tmux_session
+        .clone()
+        .ok_or_else(|| GrootError::Other(format!("'{task_name}' has no tmux session")))?;
+
+    // State files written before task types were recorded: recover it from the branch
+    let task_type = state
+        .task_type
+        .clone()
+        .or_else(|| state.branch.split('/').nth(1).map(|s| s.to_string()))
+        .unwrap_or_else(|| "feature".to_string());
+    let template =
+        workspace::resolve_template(groot_dir, state.tmux_layout.as_deref(), &task_type)?;
+
+    let problems = workspace::validate_template(&template);
+    if !problems.is_empty() {
+        print_problems(&problems);
+        return Err(GrootError::Other(format!(
+            "{} template problem(s) found",
+            problems.len()
+        )));
+    }
+
+    let effective_ports = state.shared_compose_ports.as_ref().or(state.compose_ports.as_ref());
+    let effective_compose_file = if state.shared_grove.is_some() {
+        None
+    } else {
+        state.compose_file.as_deref()
+    };
+    let worktree_path = state.worktree_path.to_string_lossy().to_string();
+    let vars = workspace::WorkspaceVars {
+        worktree_path: &worktree_path,
+        worker_name: &state.task_name,
+        app_port: effective_ports.map(|p| p.app),
+        db_port: effective_ports.map(|p| p.db),
+        redis_port: effective_ports.map(|p| p.redis),
+        compose_file: effective_compose_file,
+    };
+    let mut rendered = workspace::render_template(&template, &vars);
+    if state.compose_file.is_none() && state.remote.is_none() && ctx.project.uses_nix(&state.worktree_path) {
+        workspace::wrap_in_nix(&mut rendered);
+    }
+    if let Some(remote) = &state.remote {
+        remote.wrap_panes(&mut rendered);
+    }
+    let env = workspace::session_environment(&vars);
+
+    let summary = workspace::reload_worker_session(
+        &ws_name,
+        &rendered,
+        &state.worktree_path,
+        effective_compose_file,
+        &env,
+        resend,
+    )?;
+
+    println!(
+        "{} Reloaded '{}': {} window(s) and {} pane(s) created, {} command(s) sent",
+        style("✓").green().bold(),
+        ws_name,
+        summary.windows_created.len(),
+        summary.panes_created,
+        summary.commands_sent
+    );
+    for name in &summary.windows_created {
+        println!("  + window {}", style(name).cyan());
+    }
+
+    Ok(())
+}
+
+async fn switch(ctx: &GrootContext, inline: bool) -> Result<()> {
+    if !ctx.probes.tmux() {
+        return Err(GrootError::TmuxNotAvailable);
+    }
+
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
+    let in_tmux = std::env::var_os("TMUX").is_some();
+
+    // From a key binding: re-run ourselves inside a popup that shows the picker
+    if in_tmux && !inline {
+        let exe = std::env::current_exe()?;
+        let command = format!(
+            "{} tmux switch --inline",
+            session::shell_quote(&exe.to_string_lossy())
+        );
+        return session::display_popup(&command, &git.root, " groot workers ");
+    }
+
+    let sessions = session::list_sessions();
+    let mut workers: Vec<_> = orch_grove::list_groves(groot_dir)?
+        .into_iter()
+        .filter(|w| w.tmux_session.as_ref().is_some_and(|ws| sessions.contains(ws)))
+        .collect();
+    workers.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+
+    if workers.is_empty() {
+        println!("No active worker sessions.");
+        return Ok(());
+    }
+
+    let name_width = workers.iter().map(|w| w.task_name.len()).max().unwrap_or(0);
+    let worktrees: Vec<&Path> = workers.iter().map(|w| w.worktree_path.as_path()).collect();
+    let dirty = worktree::dirty_worktrees(&worktrees);
+    let items: Vec<String> = workers
+        .iter()
+        .zip(dirty)
+        .map(|(w, dirty)| {
+            let dirty = if dirty { "*" } else { " " };
+            format!("{:<name_width$}  {dirty} {}", w.task_name, w.branch)
+        })
+        .collect();
+
+    let Some(selection) = FuzzySelect::new()
+        .with_prompt("Switch to worker")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| GrootError::Other(format!("Picker failed: {e}")))?
+    else {
+        return Ok(());
+    };
+
+    let target = workers[selection]
+        .tmux_session
+        .as_deref()
+        .unwrap_or_default();
+    if in_tmux {
+        session::switch_client(target)
+    } else {
+        session::attach_session(target)
+    }
+}
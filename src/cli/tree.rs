@@ -1,13 +1,19 @@
-use std::path::PathBuf;
+use std::time::Instant;
 
 use clap::Subcommand;
 use console::style;
+use tracing::{info, warn};
 
-use crate::config::local::LocalConfig;
-use crate::config::project::ProjectConfig;
+use crate::cli::interactive;
+use crate::cli::output::{self, WorkerInfo};
+use crate::cli::prompt::PromptArgs;
+use crate::cli::task::{self, TaskArgs};
+use crate::context::GrootContext;
 use crate::error::{GrootError, Result};
-use crate::git::{branch, repo::GitRepo, worktree as wt};
-use crate::orchestrator::grove as orch_grove;
+use crate::git::{branch, worktree as wt};
+use crate::orchestrator::{cleanup, grove as orch_grove, state::GroveState};
+use crate::progress::{self, Event};
+use crate::terminal::TerminalBackend;
 use crate::tmux::session;
 
 #[derive(Subcommand)]
@@ -19,15 +25,19 @@ pub enum TreeCommands {
         /// Task type (feature, bugfix, refactor, chore)
         #[arg(short = 't', long = "type", default_value = "feature")]
         task_type: String,
-        /// Launch claude with this prompt in the tree's tmux window
-        #[arg(long)]
-        prompt: Option<String>,
-        /// Launch claude with the prompt read from this file
-        #[arg(long, conflicts_with = "prompt")]
-        prompt_file: Option<PathBuf>,
+        #[command(flatten)]
+        prompt: PromptArgs,
+        #[command(flatten)]
+        task_args: Box<TaskArgs>,
         /// Share a running grove's compose stack (db, redis) instead of running bare
         #[arg(short = 'g', long)]
         grove: Option<String>,
+        /// Workspace layout from .groot/tmux-layouts/<name>.json (default: per task type)
+        #[arg(long)]
+        layout: Option<String>,
+        /// Print progress as JSON lines (step started/finished/failed) for editor plugins and wrappers
+        #[arg(long)]
+        porcelain: bool,
     },
     /// List all trees
     List,
@@ -35,16 +45,19 @@ pub enum TreeCommands {
     Status,
     /// Stop a tree (tear down tmux but keep worktree and branch)
     Stop {
-        /// Task name of the tree to stop
-        task: String,
+        /// Task name of the tree to stop (picked from a list if omitted)
+        task: Option<String>,
     },
     /// Uproot a tree and clean up all resources (worktree, branch, tmux)
     Uproot {
-        /// Task name of the tree to uproot
-        task: String,
+        /// Task name of the tree to uproot (picked from a list if omitted)
+        task: Option<String>,
         /// Force uproot even if the worktree has uncommitted changes or unpushed commits
         #[arg(long)]
         force: bool,
+        /// With --force, skip the prompt to type the task name (for scripts)
+        #[arg(short, long)]
+        yes: bool,
     },
     /// Clean up stale worktrees
     Prune,
@@ -52,38 +65,45 @@ pub enum TreeCommands {
     Health,
     /// Attach to a tree's tmux session
     Attach {
-        /// Task name of the tree to attach to (optional — attaches to first tree if omitted)
+        /// Task name of the tree to attach to (picked from the running sessions if omitted)
         task: Option<String>,
     },
 }
 
-pub async fn run(cmd: TreeCommands) -> Result<()> {
+pub async fn run(cmd: TreeCommands, ctx: &GrootContext) -> Result<()> {
     match cmd {
-        TreeCommands::Plant { task, task_type, prompt, prompt_file, grove } => {
-            plant(&task, &task_type, prompt, prompt_file, grove).await
+        TreeCommands::Plant { task, task_type, prompt, task_args, grove, layout, porcelain } => {
+            if porcelain {
+                progress::enable_porcelain();
+            }
+            plant(ctx, &task, &task_type, prompt, &task_args, grove, layout.as_deref()).await
+        }
+        TreeCommands::List => list(ctx).await,
+        TreeCommands::Status => status(ctx).await,
+        TreeCommands::Stop { task } => {
+            let task = interactive::pick_worker(ctx, task, "tree stop", "trees", is_tree)?;
+            stop(ctx, &task).await
+        }
+        TreeCommands::Uproot { task, force, yes } => {
+            let task = interactive::pick_worker(ctx, task, "tree uproot", "trees", is_tree)?;
+            uproot(ctx, &task, force, yes).await
+        }
+        TreeCommands::Prune => prune(ctx).await,
+        TreeCommands::Health => health(ctx).await,
+        TreeCommands::Attach { task } => {
+            let task = interactive::pick_worker(ctx, task, "tree attach", "trees with a running session", |t| {
+                is_tree(t) && t.tmux_session.as_ref().is_some_and(|ws| session::session_exists(ws))
+            })?;
+            attach(ctx, &task).await
         }
-        TreeCommands::List => list().await,
-        TreeCommands::Status => status().await,
-        TreeCommands::Stop { task } => stop(&task).await,
-        TreeCommands::Uproot { task, force } => uproot(&task, force).await,
-        TreeCommands::Prune => prune().await,
-        TreeCommands::Health => health().await,
-        TreeCommands::Attach { task } => attach(task.as_deref()).await,
-    }
-}
-
-fn ensure_groot(git: &GitRepo) -> Result<std::path::PathBuf> {
-    let groot_dir = git.groot_dir();
-    if !groot_dir.join("config.yml").exists() {
-        return Err(GrootError::NotInitialized);
     }
-    Ok(groot_dir)
 }
 
 /// If the cwd is inside a grove's worktree, return that grove's task name.
-fn detect_grove_from_cwd(groot_dir: &std::path::Path) -> Option<String> {
+fn detect_grove_from_cwd(ctx: &GrootContext) -> Option<String> {
+    let groot_dir = &ctx.groot_dir;
     let cwd = std::env::current_dir().ok()?;
-    let worktrees_dir = groot_dir.join("worktrees");
+    let worktrees_dir = ctx.local.worktrees_root(groot_dir);
     let relative = cwd.strip_prefix(&worktrees_dir).ok()?;
     // First component of the relative path is the task name
     let task_name = relative.components().next()?.as_os_str().to_str()?;
@@ -96,29 +116,36 @@ fn detect_grove_from_cwd(groot_dir: &std::path::Path) -> Option<String> {
     }
 }
 
-async fn plant(
+pub(crate) async fn plant(
+    ctx: &GrootContext,
     task_name: &str,
     task_type: &str,
-    prompt: Option<String>,
-    prompt_file: Option<PathBuf>,
+    prompt: PromptArgs,
+    task_args: &TaskArgs,
     grove: Option<String>,
+    layout: Option<&str>,
 ) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
 
-    let local = LocalConfig::load(&groot_dir.join("local.yml"))?;
+    let local = &ctx.local;
+    task_args.check(ctx, task_name)?;
 
     // Generate branch name from project config
-    let config = ProjectConfig::load(&groot_dir.join("config.yml"))?;
+    let config = &ctx.project;
     let branch_name = branch::format_branch_name(&config.project_name, task_type, task_name);
 
+    // Fill in whatever the flags left unset from the task type's profile
+    let profile = config.profile(task_type);
+    let layout = layout.or(profile.layout.as_deref());
+
     // Resolve grove: explicit --grove flag, or auto-detect from cwd inside a grove worktree
     let auto_detected = grove.is_none();
-    let grove = grove.or_else(|| detect_grove_from_cwd(&groot_dir));
+    let grove = grove.or_else(|| detect_grove_from_cwd(ctx));
 
     // If grove is set, validate it exists and has a running compose stack
     let (shared_grove_name, shared_ports) = if let Some(ref grove_name) = grove {
-        let grove_state = orch_grove::get_grove_by_name(&groot_dir, grove_name)?;
+        let grove_state = orch_grove::get_grove_by_name(groot_dir, grove_name)?;
 
         if grove_state.compose_file.is_none() {
             return Err(GrootError::Other(format!(
@@ -144,10 +171,7 @@ async fn plant(
         })?;
 
         if auto_detected {
-            println!(
-                "Auto-detected grove '{}' from current worktree",
-                grove_name
-            );
+            info!("Auto-detected grove '{grove_name}' from current worktree");
         }
 
         (Some(grove_name.as_str()), Some(ports))
@@ -155,41 +179,36 @@ async fn plant(
         (None, None)
     };
 
-    // Resolve prompt text
-    let prompt_text = match (prompt, prompt_file) {
-        (Some(p), _) => Some(p),
-        (_, Some(path)) => {
-            let text = std::fs::read_to_string(&path).map_err(|e| {
-                GrootError::Other(format!("Failed to read prompt file '{}': {e}", path.display()))
-            })?;
-            Some(text)
-        }
-        _ => None,
-    };
-
-    let initial_command = prompt_text.as_ref().map(|text: &String| {
-        let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
-        format!("claude --prompt \"{escaped}\"")
-    });
+    let initial_prompt = prompt.initial_prompt(ctx, &profile)?;
 
     // Plant the tree (no compose)
-    let state = orch_grove::plant(
-        &git,
-        &groot_dir,
+    let started = Instant::now();
+    let planted = orch_grove::plant(
+        ctx,
         task_name,
         &branch_name,
         task_type,
-        &local.tmux_session_name,
-        local.min_disk_space_mb,
-        initial_command.as_deref(),
-        false, // never compose for tree
-        0,
-        &[],
+        initial_prompt.as_ref(),
+        orch_grove::Stack::None, // never compose for tree
         false,
         None,
         shared_grove_name,
         shared_ports.as_ref(),
-    )?;
+        layout,
+    );
+    orch_grove::notify_planted(groot_dir, task_name, &branch_name, &planted, started.elapsed());
+    let state = task_args.record(ctx, planted?)?;
+
+    if local.tmux_auto_stop && local.terminal_backend == TerminalBackend::Tmux {
+        cleanup::register_auto_stop_hook(&git.root).unwrap_or_else(|e| {
+            warn!("Warning: failed to register tmux session-closed hook: {e}");
+        });
+    }
+
+    if progress::is_porcelain() {
+        progress::emit(&Event::Planted { worker: &WorkerInfo::new(&state) });
+        return Ok(());
+    }
 
     println!(
         "{} Tree planted for task '{}'",
@@ -198,6 +217,15 @@ async fn plant(
     );
     println!("  Branch:   {}", state.branch);
     println!("  Worktree: {}", state.worktree_path.display());
+    if !state.labels.is_empty() {
+        println!("  Labels:   {}", state.labels.join(", "));
+    }
+    if !state.depends_on.is_empty() {
+        println!("  Depends:  {}", state.depends_on.join(", "));
+    }
+    if let Some(parent) = &state.parent {
+        println!("  Parent:   {parent}");
+    }
 
     if let Some(ref grove_name) = grove {
         println!("  Shared:   grove '{grove_name}'");
@@ -217,12 +245,15 @@ async fn plant(
     Ok(())
 }
 
-async fn list() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn list(ctx: &GrootContext) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
 
-    let groves = orch_grove::list_groves(&groot_dir)?;
-    let trees: Vec<_> = groves.iter().filter(|g| g.compose_file.is_none()).collect();
+    let groves = orch_grove::list_groves(groot_dir)?;
+    let trees: Vec<_> = groves.iter().filter(|g| !g.is_grove()).collect();
+
+    if output::is_json() {
+        return output::print_json(&output::worker_infos(&ctx.git, &trees, None));
+    }
 
     if trees.is_empty() {
         println!("No active trees.");
@@ -264,13 +295,21 @@ async fn list() -> Result<()> {
     Ok(())
 }
 
-async fn status() -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn status(ctx: &GrootContext) -> Result<()> {
+    let git = &ctx.git;
+    let groot_dir = &ctx.groot_dir;
+    let config = &ctx.project;
+    let base = config.base_branch();
 
-    let groves = orch_grove::list_groves(&groot_dir)?;
-    let trees: Vec<_> = groves.iter().filter(|g| g.compose_file.is_none()).collect();
+    let groves = orch_grove::list_groves(groot_dir)?;
+    let trees: Vec<_> = groves.iter().filter(|g| !g.is_grove()).collect();
 
+    if output::is_json() {
+        return output::print_json(&output::worker_infos(git, &trees, Some(base)));
+    }
+
+    let sessions = progress::timed("listing tmux sessions", session::list_sessions);
+    let distances = output::base_distances(git, &trees, base);
     println!("{}", style("Tree Status").bold());
     println!("Active trees: {}", trees.len());
     println!();
@@ -290,9 +329,12 @@ async fn status() -> Result<()> {
                 mins
             );
             println!("    Branch:   {}", t.branch);
+            if let Some((ahead, behind)) = distances.get(&t.branch) {
+                println!("    Base:     {base} ({ahead} ahead, {behind} behind)");
+            }
             println!("    Worktree: {}", t.worktree_path.display());
             if let Some(ref ws) = t.tmux_session {
-                let active = if session::session_exists(ws) {
+                let active = if sessions.contains(ws) {
                     style("active").green()
                 } else {
                     style("inactive").red()
@@ -314,11 +356,10 @@ async fn status() -> Result<()> {
     Ok(())
 }
 
-async fn stop(task_name: &str) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn stop(ctx: &GrootContext, task_name: &str) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
 
-    orch_grove::stop(&groot_dir, task_name, false)?;
+    orch_grove::stop(groot_dir, task_name, false)?;
 
     println!(
         "{} Tree '{}' stopped (tmux removed, worktree and branch preserved)",
@@ -333,30 +374,35 @@ async fn stop(task_name: &str) -> Result<()> {
     Ok(())
 }
 
-async fn uproot(task_name: &str, force: bool) -> Result<()> {
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
+async fn uproot(ctx: &GrootContext, task_name: &str, force: bool, yes: bool) -> Result<()> {
+    if force {
+        interactive::confirm_uproot(ctx, task_name, yes)?;
+    }
 
-    orch_grove::uproot(&git, &groot_dir, task_name, force)?;
+    let parent = orch_grove::get_grove_by_name(&ctx.groot_dir, task_name).ok().and_then(|s| s.parent);
+    orch_grove::uproot(ctx, task_name, force)?;
 
     println!(
         "{} Tree '{}' uprooted and resources cleaned up",
         style("✓").green().bold(),
         task_name
     );
+    if let Some(parent) = parent {
+        task::offer_parent_complete(ctx, &parent)?;
+    }
 
     Ok(())
 }
 
-async fn prune() -> Result<()> {
-    let git = GitRepo::discover()?;
+async fn prune(ctx: &GrootContext) -> Result<()> {
+    let git = &ctx.git;
     wt::prune_worktrees(&git.root)?;
     println!("{} Pruned stale worktree entries", style("✓").green().bold());
     Ok(())
 }
 
-async fn health() -> Result<()> {
-    let git = GitRepo::discover()?;
+async fn health(ctx: &GrootContext) -> Result<()> {
+    let git = &ctx.git;
     let worktrees = wt::list_worktrees(&git.root)?;
 
     let mut healthy = 0;
@@ -389,49 +435,24 @@ async fn health() -> Result<()> {
     Ok(())
 }
 
-async fn attach(task_name: Option<&str>) -> Result<()> {
-    if !session::is_available() {
+async fn attach(ctx: &GrootContext, name: &str) -> Result<()> {
+    if !ctx.probes.tmux() {
         return Err(GrootError::TmuxNotAvailable);
     }
 
-    let git = GitRepo::discover()?;
-    let groot_dir = ensure_groot(&git)?;
-    let groves = orch_grove::list_groves(&groot_dir)?;
-    let trees: Vec<_> = groves.iter().filter(|g| g.compose_file.is_none()).collect();
-
-    if trees.is_empty() {
-        println!("No active trees. Plant a tree first.");
-        return Ok(());
-    }
-
-    if let Some(name) = task_name {
-        let tree = trees
-            .iter()
-            .find(|t| t.task_name == name)
-            .ok_or_else(|| GrootError::GroveNotFound(name.to_string()))?;
+    let tree = orch_grove::get_grove_by_name(&ctx.groot_dir, name)?;
+    let ws_name = tree.tmux_session.as_ref().ok_or_else(|| {
+        GrootError::Other(format!("Tree '{name}' has no tmux session"))
+    })?;
 
-        let ws_name = tree.tmux_session.as_ref().ok_or_else(|| {
-            GrootError::Other(format!("Tree '{name}' has no tmux session"))
-        })?;
-
-        if session::session_exists(ws_name) {
-            session::attach_session(ws_name)?;
-        } else {
-            println!("Session '{ws_name}' no longer exists.");
-        }
-        return Ok(());
-    }
-
-    // No task specified — attach to first tree's session
-    for t in &trees {
-        if let Some(ref ws_name) = t.tmux_session {
-            if session::session_exists(ws_name) {
-                session::attach_session(ws_name)?;
-                return Ok(());
-            }
-        }
+    if session::session_exists(ws_name) {
+        session::attach_session(ws_name)?;
+    } else {
+        println!("Session '{ws_name}' no longer exists.");
     }
-
-    println!("No active tree sessions found.");
     Ok(())
 }
+
+fn is_tree(state: &GroveState) -> bool {
+    !state.is_grove()
+}
@@ -0,0 +1,1546 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use clap::{Subcommand, ValueEnum};
+use console::{Term, style};
+use dialoguer::MultiSelect;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::cli::grove::GroveOptions;
+use crate::cli::interactive;
+use crate::cli::output::{self, WorkerInfo};
+use crate::cli::plan::{self, PlanCommands};
+use crate::cli::prompt::PromptArgs;
+use crate::cli::task::{self, ListFilter, Listing, TaskArgs};
+use crate::compose::manager as compose_mgr;
+use crate::config::lock::FileLock;
+use crate::config::profile::DbStrategy;
+use crate::config::secret;
+use crate::context::GrootContext;
+use crate::error::{self, GrootError, Result};
+use crate::git::worktree as git_worktree;
+use crate::importer::{self, Provider, TaskDraft};
+use crate::notify;
+use crate::orchestrator::activity::{self, AgentActivity};
+use crate::orchestrator::journal::{self, PlantJournal};
+use crate::orchestrator::{
+    cleanup, grove as orch_grove, run as orch_run,
+    state::{FanOut, GroveState, NoteRecord},
+    timesheet::{Span, Timesheet},
+    transcript as orch_transcript,
+};
+use crate::process::CommandExt;
+use crate::progress;
+use crate::store;
+use crate::tmux::{session, workspace};
+
+#[derive(Subcommand)]
+pub enum WorkerCommands {
+    /// Plant a grove or a tree for a task, as the task type's profile says
+    Spawn {
+        /// Task name
+        task: String,
+        /// Task type (feature, bugfix, refactor, chore); selects the profile
+        #[arg(short = 't', long = "type", default_value = "feature")]
+        task_type: String,
+        #[command(flatten)]
+        prompt: PromptArgs,
+        #[command(flatten)]
+        task_args: TaskArgs,
+        /// Plant a grove with a compose stack regardless of the profile
+        #[arg(long, conflicts_with = "no_compose")]
+        compose: bool,
+        /// Plant a bare tree regardless of the profile
+        #[arg(long)]
+        no_compose: bool,
+        /// Clone the host's development database into the grove (implies --compose)
+        #[arg(long, conflicts_with = "no_compose")]
+        transplant: bool,
+        /// Source database URL for --transplant (default: auto-detect from config/database.yml)
+        #[arg(long, requires = "transplant")]
+        db_source: Option<String>,
+        /// Run the agent in its own container behind an allowlisting proxy (implies --compose)
+        #[arg(long, conflicts_with = "no_compose")]
+        sandbox: bool,
+        /// Workspace layout from .groot/tmux-layouts/<name>.json
+        #[arg(long)]
+        layout: Option<String>,
+        /// Print progress as JSON lines (step started/finished/failed) for editor plugins and wrappers
+        #[arg(long)]
+        porcelain: bool,
+        /// Skip pruning groves whose session is gone before planting one (`grove prune` does it later)
+        #[arg(long)]
+        no_cleanup: bool,
+    },
+    /// Spawn N variants of a task (<task>-v1 ... <task>-vN) to race agent attempts
+    FanOut {
+        /// Task name; the variants are named <task>-v1, <task>-v2, ...
+        task: String,
+        /// Number of variants (default: one per --prompts file or --models name)
+        #[arg(short, required_unless_present_any = ["prompts", "models"])]
+        n: Option<usize>,
+        /// One prompt file per variant, in order
+        #[arg(long, num_args = 1.., conflicts_with_all = ["prompt", "prompt_file", "prompt_template"])]
+        prompts: Vec<PathBuf>,
+        /// One model per variant for its agent, in order, e.g. --models opus sonnet
+        #[arg(long, num_args = 1..)]
+        models: Vec<String>,
+        /// Task type (feature, bugfix, refactor, chore); selects the profile
+        #[arg(short = 't', long = "type", default_value = "feature")]
+        task_type: String,
+        /// The same prompt for every variant
+        #[command(flatten)]
+        prompt: PromptArgs,
+        /// The same dependencies and labels for every variant
+        #[command(flatten)]
+        task_args: TaskArgs,
+        /// Plant groves with a compose stack regardless of the profile
+        #[arg(long, conflicts_with = "no_compose")]
+        compose: bool,
+        /// Plant bare trees regardless of the profile
+        #[arg(long)]
+        no_compose: bool,
+    },
+    /// List the open issues of an issue tracker that have no worker yet, or
+    /// with --spawn plant workers for them (set up under `importers:` in local.yml)
+    Import {
+        #[arg(value_enum)]
+        provider: Provider,
+        /// Plant a worker for each of these issue keys (e.g. ENG-123); picked from a list if none are given
+        #[arg(long, num_args = 0.., value_name = "KEY")]
+        spawn: Option<Vec<String>>,
+        /// Most issues to fetch
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Plant many workers at once from a plan file, e.g. an agent's work breakdown
+    #[command(subcommand)]
+    Plan(PlanCommands),
+    /// Compare the variants of a fanned-out task: commits and changed lines of each
+    Compare {
+        /// Task name given to fan-out
+        task: String,
+        /// Show the diff between the committed work of two variants, e.g. --diff 1 2
+        #[arg(long, num_args = 2, value_names = ["A", "B"])]
+        diff: Vec<usize>,
+    },
+    /// List every grove and tree with its session and compose status
+    List {
+        #[command(flatten)]
+        filter: ListFilter,
+    },
+    /// Show one worker's details
+    Show {
+        /// Task name
+        task: String,
+        /// Print every prompt given to the worker's agent, with when and how it was sent
+        #[arg(long)]
+        prompts: bool,
+    },
+    /// Show every grove and tree with its session and compose status
+    Monitor {
+        /// Keep refreshing the view until interrupted
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between refreshes with --watch
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Also show what each agent is doing, flagging idle agents and permission prompts
+        #[arg(long)]
+        agents: bool,
+        /// Minutes without output before an agent is flagged as idle
+        #[arg(long, value_name = "MINUTES", default_value_t = 10, requires = "agents")]
+        idle: u64,
+        /// With --watch, send a desktop notification when an agent gets flagged
+        #[arg(long, requires = "agents")]
+        notify: bool,
+        #[command(flatten)]
+        filter: ListFilter,
+    },
+    /// Stop workers whose tmux session has closed or whose TTL has passed
+    /// (run by the session-closed hook)
+    Reap,
+    /// Continue a plant that was interrupted (crash, Ctrl-C, kill), or with
+    /// --rollback undo what it created; lists interrupted plants if no task is given
+    Resume {
+        /// Task name
+        task: Option<String>,
+        /// Remove the branch, worktree, ports, and compose files the plant created instead
+        #[arg(long, requires = "task")]
+        rollback: bool,
+    },
+    /// Print a worker's worktree path
+    Path {
+        /// Task name
+        task: String,
+    },
+    /// Print a worker's app URL (its own compose stack's, or the shared grove's)
+    Url {
+        /// Task name
+        task: String,
+    },
+    /// Open a worker's app in the browser once its app container is ready
+    Browse {
+        /// Task name (picked from the workers with compose ports if omitted)
+        task: Option<String>,
+    },
+    /// Give a worker's agent a prompt: typed into its workspace, or with
+    /// --headless run non-interactively with output captured under .groot/runs/<task>/
+    Run {
+        /// Task name
+        task: String,
+        #[command(flatten)]
+        prompt: PromptArgs,
+        /// Run `claude -p` in the worktree instead of typing into the workspace
+        #[arg(long)]
+        headless: bool,
+        /// Stop the worker (compose stack and session) once the headless run ends
+        #[arg(long, requires = "headless")]
+        stop: bool,
+    },
+    /// Send a follow-up instruction to the agent already running in a worker's session
+    Prompt {
+        /// Task name
+        task: String,
+        /// The instruction
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        text: Option<String>,
+        /// Read the instruction from this file
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Add a timestamped note to a worker, shown by `worker show` and in its
+    /// CLAUDE.local.md
+    Note {
+        /// Task name
+        task: String,
+        /// The note
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        text: Option<String>,
+        /// Read the note from this file
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Leave the worktree's CLAUDE.local.md as it is
+        #[arg(long)]
+        no_claude_md: bool,
+    },
+    /// Mark a task complete, e.g. a parent whose subtasks have all finished;
+    /// tasks depending on it are no longer blocked
+    Complete {
+        /// Task name
+        task: String,
+    },
+    /// Show how long a task has been planted: each plant until its stop or
+    /// uproot, and the total
+    Time {
+        /// Task name (planted, or stopped and uprooted ones from the event log)
+        task: String,
+    },
+    /// Follow the output of a worker's agent pane, read-only, until interrupted
+    Tail {
+        /// Task name
+        task: String,
+        /// Redraw the pane's screen every second instead of streaming its recording
+        #[arg(long)]
+        poll: bool,
+    },
+    /// Print the recorded agent pane of a worker, including stopped and uprooted ones
+    Transcript {
+        /// Task name
+        task: String,
+        /// Print the transcripts directory (pane log and agent session JSONL) instead
+        #[arg(long)]
+        path: bool,
+    },
+    /// Assemble a pull request description from the worker's prompt, commits, and diff stat
+    Describe {
+        /// Task name
+        task: String,
+        /// Open with a summary of the changes written by the agent
+        #[arg(long)]
+        summarize: bool,
+        /// Write the description to this file instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Open the pull request with `gh pr create --body-file`
+        #[arg(long)]
+        create: bool,
+    },
+    /// Have the agent review the worker's branch diff, saving it to .groot/reviews/<task>.md
+    Review {
+        /// Task name
+        task: String,
+        /// Post the review as a comment on the branch's pull request with `gh pr comment`
+        #[arg(long)]
+        post: bool,
+    },
+    /// Print the host port of one of a worker's compose services
+    Port {
+        /// Task name
+        task: String,
+        #[arg(value_enum)]
+        service: Service,
+    },
+}
+
+/// A compose service with a host port.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Service {
+    App,
+    Db,
+    Redis,
+}
+
+pub async fn run(cmd: WorkerCommands, ctx: &GrootContext) -> Result<()> {
+    match cmd {
+        WorkerCommands::Spawn {
+            task,
+            task_type,
+            prompt,
+            task_args,
+            compose,
+            no_compose,
+            transplant,
+            db_source,
+            sandbox,
+            layout,
+            porcelain,
+            no_cleanup,
+        } => {
+            if porcelain {
+                progress::enable_porcelain();
+            }
+            let compose_flag = match (compose || transplant || sandbox, no_compose) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            if wants_compose(ctx, &task_type, compose_flag)? {
+                let options = GroveOptions { transplant, db_source, sandbox, no_cleanup, ..Default::default() };
+                super::grove::plant(ctx, &task, &task_type, prompt, &task_args, options, layout.as_deref()).await
+            } else {
+                super::tree::plant(ctx, &task, &task_type, prompt, &task_args, None, layout.as_deref()).await
+            }
+        }
+        WorkerCommands::FanOut {
+            task,
+            n,
+            prompts,
+            models,
+            task_type,
+            prompt,
+            task_args,
+            compose,
+            no_compose,
+        } => {
+            let n = n.unwrap_or(prompts.len().max(models.len()));
+            if n == 0 {
+                return Err(GrootError::Other("fan-out needs at least one variant".to_string()));
+            }
+            for (given, what) in [(prompts.len(), "--prompts files; give one file"), (models.len(), "--models; give one model")] {
+                if given != 0 && given != n {
+                    return Err(GrootError::Other(format!("-n {n} doesn't match the {given} {what} per variant")));
+                }
+            }
+            let compose_flag = match (compose, no_compose) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            let grove = wants_compose(ctx, &task_type, compose_flag)?;
+
+            let mut failed = Vec::new();
+            for i in 1..=n {
+                let name = variant_name(&task, i);
+                let model = models.get(i - 1).cloned();
+                let prompt = match prompts.get(i - 1) {
+                    Some(file) => PromptArgs {
+                        prompt_file: Some(file.clone()),
+                        model: model.clone(),
+                        ..PromptArgs::default()
+                    },
+                    None => PromptArgs { model: model.clone(), ..prompt.clone() },
+                };
+                let planted = if grove {
+                    super::grove::plant(ctx, &name, &task_type, prompt, &task_args, GroveOptions::default(), None).await
+                } else {
+                    super::tree::plant(ctx, &name, &task_type, prompt, &task_args, None, None).await
+                };
+                // Record which variant it is, and its model even when it was
+                // planted without a prompt to launch the agent with
+                let planted = planted.and_then(|()| {
+                    store::update_worker(&ctx.groot_dir, &name, |s| {
+                        s.fan_out = Some(FanOut { task: task.clone(), variant: i });
+                        s.model = model;
+                    })
+                    .map(drop)
+                });
+                if let Err(e) = planted {
+                    eprintln!("{} {name}: {e}", style("✗").red().bold());
+                    failed.push(name);
+                }
+            }
+            if !failed.is_empty() {
+                return Err(GrootError::Other(format!(
+                    "{} of {n} variant(s) failed to plant: {}",
+                    failed.len(),
+                    failed.join(", ")
+                )));
+            }
+            println!(
+                "{} Planted {n} variant(s) of '{task}'; compare them with `groot worker compare {task}`",
+                style("✓").green().bold()
+            );
+            Ok(())
+        }
+        WorkerCommands::Import { provider, spawn, limit } => import(ctx, provider, spawn, limit).await,
+        WorkerCommands::Plan(cmd) => plan::run(cmd, ctx).await,
+        WorkerCommands::Compare { task, diff } => compare(ctx, &task, &diff),
+        WorkerCommands::List { filter } => monitor(ctx, false, 0, None, &filter).await,
+        WorkerCommands::Show { task, prompts } => show(ctx, &task, prompts),
+        WorkerCommands::Monitor { watch, interval, agents, idle, notify, filter } => {
+            let agents = agents.then_some(AgentWatch {
+                idle_after: Duration::from_secs(idle * 60),
+                notify,
+            });
+            monitor(ctx, watch, interval, agents, &filter).await
+        }
+        WorkerCommands::Reap => reap(ctx).await,
+        WorkerCommands::Resume { task, rollback } => resume(ctx, task.as_deref(), rollback),
+        WorkerCommands::Path { task } => {
+            let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, &task)?;
+            println!("{}", worker.worktree_path.display());
+            Ok(())
+        }
+        WorkerCommands::Url { task } => {
+            let port = port(ctx, &task, Service::App)?;
+            println!("http://localhost:{port}");
+            Ok(())
+        }
+        WorkerCommands::Browse { task } => {
+            let task = interactive::pick_worker(ctx, task, "worker browse", "workers with compose ports", |w| {
+                w.compose_ports.is_some() || w.shared_compose_ports.is_some()
+            })?;
+            browse(ctx, &task)
+        }
+        WorkerCommands::Run { task, prompt, headless, stop } => run_agent(ctx, &task, prompt, headless, stop),
+        WorkerCommands::Prompt { task, text, file } => follow_up(ctx, &task, text, file),
+        WorkerCommands::Note { task, text, file, no_claude_md } => note(ctx, &task, text, file, !no_claude_md),
+        WorkerCommands::Complete { task } => task::mark_complete(ctx, &task),
+        WorkerCommands::Time { task } => time(ctx, &task),
+        WorkerCommands::Tail { task, poll } => tail(ctx, &task, poll).await,
+        WorkerCommands::Transcript { task, path } => transcript(ctx, &task, path),
+        WorkerCommands::Describe { task, summarize, output, create } => {
+            describe(ctx, &task, summarize, output, create)
+        }
+        WorkerCommands::Review { task, post } => review(ctx, &task, post),
+        WorkerCommands::Port { task, service } => {
+            println!("{}", port(ctx, &task, service)?);
+            Ok(())
+        }
+    }
+}
+
+/// The host port of `service` for a worker: its own compose stack's, else the
+/// grove it shares.
+fn port(ctx: &GrootContext, task: &str, service: Service) -> Result<u16> {
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let ports = worker
+        .compose_ports
+        .or(worker.shared_compose_ports)
+        .ok_or_else(|| GrootError::Other(format!("'{task}' has no compose stack, so it has no ports")))?;
+    Ok(match service {
+        Service::App => ports.app,
+        Service::Db => ports.db,
+        Service::Redis => ports.redis,
+    })
+}
+
+/// `<task>-v<i>`, the name of a fan-out variant.
+fn variant_name(task: &str, i: usize) -> String {
+    format!("{task}-v{i}")
+}
+
+/// The planted variants of a fanned-out task, by number.
+fn variants(workers: Vec<GroveState>, task: &str) -> Vec<(usize, GroveState)> {
+    let mut variants: Vec<(usize, GroveState)> = workers
+        .into_iter()
+        .filter_map(|w| {
+            let i = w.fan_out.as_ref().filter(|f| f.task == task)?.variant;
+            Some((i, w))
+        })
+        .collect();
+    variants.sort_by_key(|(i, _)| *i);
+    variants
+}
+
+/// The branches of variants `a` and `b`, for `compare --diff`.
+fn variant_branches<'a>(variants: &'a [(usize, GroveState)], task: &str, a: usize, b: usize) -> Result<[&'a str; 2]> {
+    let branch = |i: usize| {
+        variants
+            .iter()
+            .find(|(v, _)| *v == i)
+            .map(|(_, w)| w.branch.as_str())
+            .ok_or_else(|| GrootError::Other(format!("'{task}' has no variant {i}")))
+    };
+    Ok([branch(a)?, branch(b)?])
+}
+
+#[derive(Serialize)]
+struct VariantSummary<'a> {
+    variant: usize,
+    name: &'a str,
+    branch: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    commits: u64,
+    /// `git diff --shortstat` since the fork, uncommitted changes included
+    changes: String,
+    last_run_exit_code: Option<i32>,
+}
+
+/// Print each variant's commits and changes, or with `--diff A B` the diff
+/// between two variants' branches.
+fn compare(ctx: &GrootContext, task: &str, diff: &[usize]) -> Result<()> {
+    let variants = variants(orch_grove::list_groves(&ctx.groot_dir)?, task);
+    if variants.is_empty() {
+        return Err(GrootError::Other(format!(
+            "No variants of '{task}'; create them with `groot worker fan-out {task} -n 3`"
+        )));
+    }
+
+    if let [a, b] = diff {
+        let [a, b] = variant_branches(&variants, task, *a, *b)?;
+        let status = Command::new("git")
+            .args(["diff", a, b])
+            .current_dir(&ctx.git.root)
+            .logged_status()?;
+        if !status.success() {
+            return Err(GrootError::GitCommand("git diff failed".to_string()));
+        }
+        return Ok(());
+    }
+
+    let base = ctx.project.base_branch();
+    let branches: Vec<&str> = variants.iter().map(|(_, w)| w.branch.as_str()).collect();
+    let distances = git_worktree::ahead_behind_all(&ctx.git.repo, &branches, base);
+    let summaries: Vec<VariantSummary> = variants
+        .iter()
+        .map(|(i, w)| VariantSummary {
+            variant: *i,
+            name: &w.task_name,
+            branch: &w.branch,
+            model: w.model.as_deref(),
+            commits: distances.get(&w.branch).map_or(0, |(ahead, _)| *ahead),
+            changes: git_worktree::changes_since_fork(&w.worktree_path, base).unwrap_or_default(),
+            last_run_exit_code: w.last_run.as_ref().and_then(|r| r.exit_code),
+        })
+        .collect();
+    if output::is_json() {
+        return output::print_json(&summaries);
+    }
+
+    let name_width = summaries.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    println!("{}", style(format!("Variants of '{task}' against {base}")).bold());
+    for s in &summaries {
+        let changes = if s.changes.is_empty() { "no changes" } else { &s.changes };
+        let model = s.model.map(|m| format!("  {}", style(m).cyan())).unwrap_or_default();
+        let run = match s.last_run_exit_code {
+            Some(0) => format!("  {}", style("run ok").green()),
+            Some(code) => format!("  {}", style(format!("run exited {code}")).red()),
+            None => String::new(),
+        };
+        println!(
+            "  {:<name_width$}  {:>2} commit(s)  {changes}{run}{model}",
+            s.name, s.commits
+        );
+    }
+    if let [first, .., last] = summaries.as_slice() {
+        println!(
+            "\nDiff two variants with `groot worker compare {task} --diff {} {}`",
+            first.variant, last.variant
+        );
+    }
+    Ok(())
+}
+
+/// Whether `worker spawn` plants a grove: the `--compose`/`--no-compose` flag,
+/// else the profile's `compose`, else `container_enabled`.
+/// List an issue tracker's open issues without a worker, or plant workers for
+/// the issues in `spawn` (picked from the list when it's empty).
+async fn import(ctx: &GrootContext, provider: Provider, spawn: Option<Vec<String>>, limit: usize) -> Result<()> {
+    let importer = importer::importer(provider, &ctx.local.importers)?;
+    let fetched = progress::timed("fetching issues", || importer.fetch_candidates(limit))?;
+    let fetched_count = fetched.len();
+    let candidates = importer::new_candidates(fetched, &orch_grove::list_groves(&ctx.groot_dir)?);
+
+    let Some(keys) = spawn else {
+        if output::is_json() {
+            return output::print_json(&candidates);
+        }
+        if candidates.is_empty() {
+            println!("No open {} issues without a worker ({fetched_count} fetched).", provider.name());
+            return Ok(());
+        }
+        let key_width = candidates.iter().map(|d| d.key.len()).max().unwrap_or(0);
+        for d in &candidates {
+            let labels = if d.labels.is_empty() { String::new() } else { format!("  [{}]", d.labels.join(", ")) };
+            println!("  {:<key_width$}  {}{}", d.key, d.title, style(labels).dim());
+        }
+        println!(
+            "
+Plant workers for them with {}",
+            style(format!("groot worker import {} --spawn <KEY>...", provider.name())).cyan()
+        );
+        return Ok(());
+    };
+
+    let chosen: Vec<&TaskDraft> = if keys.is_empty() {
+        interactive::require_terminal(&format!("worker import {}", provider.name()), "--spawn <KEY>...")?;
+        if candidates.is_empty() {
+            return Err(GrootError::Other(format!("No open {} issues without a worker.", provider.name())));
+        }
+        let items: Vec<String> = candidates.iter().map(|d| format!("{}  {}", d.key, d.title)).collect();
+        let picked = MultiSelect::new()
+            .with_prompt("Issues to plant workers for")
+            .items(&items)
+            .interact_opt()
+            .map_err(|e| GrootError::Other(format!("Picker failed: {e}")))?
+            .ok_or_else(|| GrootError::Other("Cancelled.".to_string()))?;
+        picked.into_iter().map(|i| &candidates[i]).collect()
+    } else {
+        keys.iter()
+            .map(|key| {
+                candidates.iter().find(|d| d.key.eq_ignore_ascii_case(key)).ok_or_else(|| {
+                    GrootError::Other(format!(
+                        "{key} isn't among the open {} issues without a worker",
+                        provider.name()
+                    ))
+                })
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut failed = Vec::new();
+    for draft in &chosen {
+        let name = draft.task_name();
+        let prompt = PromptArgs { prompt: Some(draft.prompt()), ..PromptArgs::default() };
+        let task_args = TaskArgs { labels: draft.labels.clone(), ..TaskArgs::default() };
+        let planted = if wants_compose(ctx, draft.task_type(), None)? {
+            super::grove::plant(ctx, &name, draft.task_type(), prompt, &task_args, GroveOptions::default(), None).await
+        } else {
+            super::tree::plant(ctx, &name, draft.task_type(), prompt, &task_args, None, None).await
+        };
+        if let Err(e) = planted {
+            eprintln!("{} {name}: {e}", style("✗").red().bold());
+            failed.push(name);
+        }
+    }
+    if !failed.is_empty() {
+        return Err(GrootError::Other(format!(
+            "{} of {} worker(s) failed to plant: {}",
+            failed.len(),
+            chosen.len(),
+            failed.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn wants_compose(ctx: &GrootContext, task_type: &str, flag: Option<bool>) -> Result<bool> {
+    if let Some(compose) = flag {
+        return Ok(compose);
+    }
+    let config = &ctx.project;
+    let profile = config.profile(task_type);
+    let implied = config.container_enabled
+        || profile.db == Some(DbStrategy::Transplant)
+        || profile.sandbox == Some(true);
+    Ok(profile.compose.unwrap_or(implied))
+}
+
+/// Workers with a running session and no commits for this long are listed
+/// as stale under `worker list` and `worker monitor`, unless `--stale` says otherwise.
+const STALE_AFTER_DAYS: i64 = 14;
+
+fn print_workers(listing: &Listing, activity: &HashMap<String, AgentActivity>, stale_after: Option<chrono::Duration>) {
+    let Listing { workers, blocked, .. } = listing;
+    println!(
+        "{} {}",
+        style(format!("Workers ({})", workers.len())).bold(),
+        style(chrono::Local::now().format("%H:%M:%S")).dim()
+    );
+
+    if workers.is_empty() {
+        println!("  No active groves or trees.");
+        return;
+    }
+
+    let name_width = workers.iter().map(|w| w.task_name.len()).max().unwrap_or(0);
+    let sessions = session::list_sessions();
+    for w in workers {
+        let kind = w.kind();
+        let session_alive = w.tmux_session.as_ref().is_some_and(|ws| sessions.contains(ws));
+        let (marker, session_status) = if session_alive {
+            (style("●").green(), style("active  ").green())
+        } else {
+            (style("●").red(), style("inactive").red())
+        };
+
+        let age = chrono::Utc::now() - w.created_at;
+        let ports = w
+            .compose_ports
+            .as_ref()
+            .or(w.shared_compose_ports.as_ref())
+            .map(|p| format!("  app:{} db:{} redis:{}", p.app, p.db, p.redis))
+            .unwrap_or_default();
+        let expiry = match w.expires_at {
+            Some(_) if w.is_expired() => format!("  {}", style("expired").red()),
+            Some(at) => {
+                let left = at - chrono::Utc::now();
+                format!("  expires in {}h {}m", left.num_hours(), left.num_minutes() % 60)
+            }
+            None => String::new(),
+        };
+
+        let agent = match activity.get(&w.task_name) {
+            Some(state) if state.is_stuck() => format!("  {}", style(format!("⚠ {}", state.describe())).yellow()),
+            Some(AgentActivity::Working) => format!("  {}", style("agent working").green()),
+            Some(state) => format!("  {}", style(format!("agent: {}", state.describe())).dim()),
+            None => String::new(),
+        };
+        let labels = if w.labels.is_empty() {
+            String::new()
+        } else {
+            format!("  {}", style(format!("[{}]", w.labels.join(", "))).cyan())
+        };
+        let parent = match &w.parent {
+            Some(parent) => format!("  {}", style(format!("↳ {parent}")).dim()),
+            None => String::new(),
+        };
+        let time_spent = match listing.time_spent.get(&w.task_name) {
+            Some(spent) => format!("  {}", style(format!("{} in all", hours_minutes(*spent))).cyan()),
+            None => String::new(),
+        };
+        let blockers = match blocked.get(&w.task_name) {
+            Some(blockers) => format!("  {}", style(format!("blocked by {}", blockers.join(", "))).magenta()),
+            None => String::new(),
+        };
+
+        println!(
+            "  {marker} {:<name_width$}  {kind:<5}  {session_status}  {:>3}h {:>2}m{time_spent}  {}{parent}{labels}{ports}{expiry}{agent}{blockers}",
+            w.task_name,
+            age.num_hours(),
+            age.num_minutes() % 60,
+            w.branch,
+        );
+    }
+
+    let stale_after = stale_after.unwrap_or(chrono::Duration::days(STALE_AFTER_DAYS));
+    let stale = listing.stale(&sessions, stale_after);
+    if !stale.is_empty() {
+        let days_or_hours = |d: chrono::Duration| match d.num_days() {
+            0 => format!("{}h", d.num_hours()),
+            days => format!("{days}d"),
+        };
+        println!(
+            "\n{}",
+            style(format!("Stale: running with no commits for {} or more", days_or_hours(stale_after))).yellow().bold()
+        );
+        for (w, idle) in stale {
+            println!(
+                "  {} {:<name_width$}  last work {} ago",
+                style("!").yellow().bold(),
+                w.task_name,
+                days_or_hours(idle)
+            );
+        }
+    }
+}
+
+/// `worker monitor --agents`: how long an agent may stay silent before it's
+/// flagged, and whether to notify when it is.
+struct AgentWatch {
+    idle_after: Duration,
+    notify: bool,
+}
+
+/// The listed workers as `--json` reports them.
+fn listing_infos(listing: &Listing, activity: &HashMap<String, AgentActivity>) -> Vec<WorkerInfo> {
+    let sessions = progress::timed("listing tmux sessions", session::list_sessions);
+    listing
+        .workers
+        .iter()
+        .map(|w| WorkerInfo {
+            agent: activity.get(&w.task_name).copied(),
+            blocked_by: listing.blocked.get(&w.task_name).cloned().unwrap_or_default(),
+            last_activity: listing.last_activity.get(&w.task_name).copied(),
+            time_spent_secs: listing.time_spent.get(&w.task_name).map(|d| d.num_seconds()),
+            ..WorkerInfo::with_sessions(w, &sessions)
+        })
+        .collect()
+}
+
+async fn monitor(
+    ctx: &GrootContext,
+    watch: bool,
+    interval: u64,
+    agents: Option<AgentWatch>,
+    filter: &ListFilter,
+) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
+    let check_agents = |workers: &[GroveState]| -> HashMap<String, AgentActivity> {
+        let Some(agents) = &agents else {
+            return HashMap::new();
+        };
+        workers
+            .iter()
+            .map(|w| (w.task_name.clone(), activity::check(groot_dir, w, agents.idle_after)))
+            .collect()
+    };
+
+    if !watch {
+        let listing = filter.list(ctx)?;
+        let activity = check_agents(&listing.workers);
+        if output::is_json() {
+            return output::print_json(&listing_infos(&listing, &activity));
+        }
+        print_workers(&listing, &activity, filter.stale);
+        return Ok(());
+    }
+
+    let term = Term::stdout();
+    // Workers already notified about, so each gets one notification per episode
+    let mut flagged: HashSet<String> = HashSet::new();
+    loop {
+        let listing = filter.list(ctx)?;
+        let activity = check_agents(&listing.workers);
+        // With --json, one array of workers per line for each refresh
+        if output::is_json() {
+            output::print_json_line(&listing_infos(&listing, &activity))?;
+        } else {
+            let _ = term.clear_screen();
+            print_workers(&listing, &activity, filter.stale);
+        }
+
+        if agents.as_ref().is_some_and(|a| a.notify) {
+            for (task, state) in &activity {
+                if !state.is_stuck() {
+                    flagged.remove(task);
+                } else if flagged.insert(task.clone()) {
+                    notify::desktop(&format!("groot: {task}"), &format!("Agent {}", state.describe()));
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+    }
+}
+
+/// Print one worker's details, or with `prompts` its prompt history.
+fn show(ctx: &GrootContext, task: &str, prompts: bool) -> Result<()> {
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    if prompts {
+        if output::is_json() {
+            return output::print_json(&worker.prompts);
+        }
+        if worker.prompts.is_empty() {
+            println!("No prompts have been given to '{task}'.");
+        }
+        for (i, p) in worker.prompts.iter().enumerate() {
+            let sent_at = p.sent_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S");
+            println!("{} {sent_at}  {}", style(format!("#{}", i + 1)).bold(), style(p.via.as_str()).cyan());
+            for line in p.text.lines() {
+                println!("    {line}");
+            }
+            println!();
+        }
+        return Ok(());
+    }
+
+    let base = ctx.project.base_branch();
+    let mut info = WorkerInfo::new(&worker).with_base(&ctx.git.root, base);
+    let deps = if worker.depends_on.is_empty() && worker.subtasks.is_empty() {
+        None
+    } else {
+        Some(task::load_progress(ctx)?)
+    };
+    if let Some(deps) = &deps {
+        info.blocked_by = deps.blockers(&worker.depends_on);
+    }
+    if output::is_json() {
+        return output::print_json(&info);
+    }
+    let row = |label: &str, value: String| println!("  {:<10}{value}", style(format!("{label}:")).dim());
+    println!("{} ({})", style(&info.name).bold(), info.kind);
+    let distance = info
+        .base
+        .as_ref()
+        .map(|d| format!(" ({} ahead, {} behind {})", d.ahead, d.behind, d.branch))
+        .unwrap_or_default();
+    row("Branch", format!("{}{distance}", info.branch));
+    row("Worktree", info.worktree.display().to_string());
+    if let Some(task_type) = &info.task_type {
+        row("Type", task_type.clone());
+    }
+    if !info.labels.is_empty() {
+        row("Labels", info.labels.join(", "));
+    }
+    if let Some(ws) = &info.session {
+        let status = if info.session_active { style("active").green() } else { style("inactive").red() };
+        row("Session", format!("{ws} ({status})"));
+    }
+    if let Some(grove) = &info.shared_grove {
+        row("Shares", grove.clone());
+    }
+    if let Some(p) = info.compose_ports.as_ref().or(info.shared_compose_ports.as_ref()) {
+        row("Ports", format!("app:{} db:{} redis:{}", p.app, p.db, p.redis));
+    }
+    row("Created", info.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+    if let Some(at) = info.expires_at {
+        let expired = if info.expired { format!(" ({})", style("expired").red()) } else { String::new() };
+        row("Expires", format!("{}{expired}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")));
+    }
+    if let Some(run) = &info.last_run {
+        let status = run.exit_code.map_or("killed".to_string(), |c| format!("exit {c}"));
+        let seconds = run.duration_ms as f64 / 1000.0;
+        row("Last run", format!("{status} after {seconds:.1}s, {}", run.log_dir.display()));
+    }
+    if let Some(parent) = &info.parent {
+        row("Parent", parent.clone());
+    }
+    if let Some(at) = info.completed_at {
+        row("Complete", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+    }
+    if let Some(deps) = deps.as_ref().filter(|_| !info.subtasks.is_empty()) {
+        let finished = info.subtasks.len() - deps.blockers(&info.subtasks).len();
+        row("Subtasks", format!("{finished} of {} finished", info.subtasks.len()));
+        for subtask in &info.subtasks {
+            println!("  {:<10}  {subtask} ({})", "", deps.status(subtask).describe());
+        }
+    }
+    if let Some(deps) = deps.as_ref().filter(|_| !worker.depends_on.is_empty()) {
+        let status = if info.blocked_by.is_empty() {
+            style("ready".to_string()).green()
+        } else {
+            style(format!("blocked by {}", info.blocked_by.join(", "))).magenta()
+        };
+        row("Depends", status.to_string());
+        for (depth, dep, dep_status) in deps.chain(task) {
+            println!("  {:<10}{}{dep} ({})", "", "  ".repeat(depth + 1), dep_status.describe());
+        }
+    }
+    if !info.notes.is_empty() {
+        row("Notes", info.notes.len().to_string());
+        for n in &info.notes {
+            let at = n.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+            for (i, line) in n.text.lines().enumerate() {
+                let at = if i == 0 { at.to_string() } else { String::new() };
+                println!("  {:<10}{:<18}{line}", "", style(at).dim());
+            }
+        }
+    }
+    if !worker.prompts.is_empty() {
+        row("Prompts", format!("{} (groot worker show {task} --prompts)", worker.prompts.len()));
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct TaskTime<'a> {
+    task: &'a str,
+    total_secs: i64,
+    spans: &'a [Span],
+}
+
+fn hours_minutes(d: chrono::Duration) -> String {
+    format!("{}h {:02}m", d.num_hours(), d.num_minutes() % 60)
+}
+
+/// Print each plant of `task` with how long it lasted, and the total.
+fn time(ctx: &GrootContext, task: &str) -> Result<()> {
+    let workers = orch_grove::list_groves(&ctx.groot_dir)?;
+    let timesheet = Timesheet::load(&ctx.groot_dir, &workers);
+    let spans = timesheet.spans(task);
+    if spans.is_empty() {
+        return Err(GrootError::Other(format!("No time recorded for '{task}'")));
+    }
+    let now = chrono::Utc::now();
+    let total = timesheet.total(task, now);
+    if output::is_json() {
+        return output::print_json(&TaskTime { task, total_secs: total.num_seconds(), spans });
+    }
+
+    let local = |at: chrono::DateTime<chrono::Utc>| {
+        at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()
+    };
+    println!("{} {}", style(task).bold(), style(format!("{} in all", hours_minutes(total))).cyan());
+    for span in spans {
+        let stopped = span.stopped.map_or_else(|| "running".to_string(), local);
+        println!("  {} – {stopped:<16}  {:>8}", local(span.started), hours_minutes(span.duration(now)));
+    }
+    Ok(())
+}
+
+/// Append a note to the worker's journal and, unless told not to, render it
+/// into the worktree's CLAUDE.local.md so the agent sees it.
+fn note(ctx: &GrootContext, task: &str, text: Option<String>, file: Option<PathBuf>, claude_md: bool) -> Result<()> {
+    let text = match file {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| GrootError::Other(format!("Failed to read {}: {e}", path.display())))?,
+        None => text.unwrap_or_default(),
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(GrootError::Other("The note is empty".to_string()));
+    }
+
+    orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let worker = store::update_worker(&ctx.groot_dir, task, |w| {
+        w.notes.push(NoteRecord {
+            at: chrono::Utc::now(),
+            text: text.to_string(),
+        });
+    })?
+    .ok_or_else(|| GrootError::Other(format!("Worker '{task}' not found")))?;
+    println!("{} Added note {} to '{task}'", style("✓").green().bold(), worker.notes.len());
+
+    if claude_md && worker.worktree_path.exists() {
+        match orch_grove::refresh_claude_md(ctx, &worker) {
+            Ok(()) => println!("{} Updated CLAUDE.local.md", style("✓").green().bold()),
+            Err(e) => warn!("Warning: failed to update CLAUDE.local.md: {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Render the prompt with the worker's CLAUDE.local.md variables and hand it to
+/// its agent.
+fn run_agent(ctx: &GrootContext, task: &str, prompt: PromptArgs, headless: bool, stop: bool) -> Result<()> {
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let profile = ctx.project.profile(worker.task_type.as_deref().unwrap_or("feature"));
+    let Some(prompt) = prompt.initial_prompt(ctx, &profile)? else {
+        return Err(GrootError::Other(
+            "No prompt given; pass --prompt, --prompt-file, or --prompt-template".to_string(),
+        ));
+    };
+    let prompt = orch_grove::with_claude_vars(ctx, &worker, &prompt.description, |vars| prompt.render(vars));
+
+    if !headless {
+        let Some(ws) = worker.tmux_session.as_ref().filter(|ws| session::session_exists(ws)) else {
+            return Err(GrootError::Other(format!(
+                "'{task}' has no running tmux session to type into; use --headless"
+            )));
+        };
+        let command = format!("{} --prompt {}", orch_grove::agent_command(&worker), session::shell_quote(&prompt));
+        session::send_keys_to_pane(&workspace::first_pane_target(ws), &command)?;
+        orch_run::record_typed_prompt(&ctx.groot_dir, task, &prompt)?;
+        println!("{} Sent the prompt to '{task}'", style("✓").green().bold());
+        return Ok(());
+    }
+
+    let step = progress::Step::start("agent_run", format!("Running the agent in '{task}'"));
+    let record = orch_run::run_headless(&ctx.groot_dir, &worker, &prompt)?;
+    step.done();
+
+    if stop {
+        orch_grove::stop(&ctx.groot_dir, task, false)?;
+    }
+
+    let seconds = record.duration_ms as f64 / 1000.0;
+    let stopped = if stop { ", worker stopped" } else { "" };
+    if output::is_json() {
+        output::print_json(&record)?;
+    } else if record.succeeded() {
+        println!(
+            "{} Agent finished in {seconds:.1}s{stopped}; output in {}",
+            style("✓").green().bold(),
+            record.log_dir.display()
+        );
+    }
+    if !record.succeeded() {
+        let status = record.exit_code.map_or("was killed".to_string(), |c| format!("exited with {c}"));
+        return Err(GrootError::Other(format!(
+            "The agent {status} after {seconds:.1}s{stopped}; see {}",
+            record.log_dir.join("stderr.log").display()
+        )));
+    }
+    Ok(())
+}
+
+/// Type a follow-up instruction into the pane the worker's agent is running in.
+fn follow_up(ctx: &GrootContext, task: &str, text: Option<String>, file: Option<PathBuf>) -> Result<()> {
+    let text = match file {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| GrootError::Other(format!("Failed to read {}: {e}", path.display())))?,
+        None => text.unwrap_or_default(),
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(GrootError::Other("The instruction is empty".to_string()));
+    }
+
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let Some(ws) = worker.tmux_session.as_ref().filter(|ws| session::session_exists(ws)) else {
+        return Err(GrootError::Other(format!(
+            "'{task}' has no running tmux session; use `groot worker run {task} --headless`"
+        )));
+    };
+    let Some((pane, command)) = workspace::agent_pane(ws, orch_run::AGENT) else {
+        return Err(GrootError::Other(format!("'{task}' has no panes")));
+    };
+    // Typed into a shell, the instruction would run as a command
+    if activity::SHELLS.contains(&command.as_str()) {
+        return Err(GrootError::Other(format!(
+            "No agent is running in '{task}' (its first pane is at a {command} prompt); \
+             start one with `groot worker run {task}`"
+        )));
+    }
+
+    if text.contains('\n') {
+        session::paste_into_pane(&pane, text)?;
+    } else {
+        session::send_literal_keys(&pane, text)?;
+    }
+    orch_run::record_typed_prompt(&ctx.groot_dir, task, text)?;
+    println!("{} Sent to the agent in '{task}'", style("✓").green().bold());
+    Ok(())
+}
+
+/// Stream what the worker's agent pane prints: new bytes of its recording as
+/// they arrive, or with `poll` (or no recording) its screen redrawn every
+/// second. Nothing is ever sent to the pane. Ends when the session does.
+async fn tail(ctx: &GrootContext, task: &str, poll: bool) -> Result<()> {
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let Some(ws) = worker.tmux_session.clone().filter(|ws| session::session_exists(ws)) else {
+        return Err(GrootError::Other(format!(
+            "'{task}' has no running tmux session; see `groot worker transcript {task}` for what it recorded"
+        )));
+    };
+    let log = orch_transcript::transcripts_dir(&ctx.groot_dir, task).join(orch_transcript::PANE_LOG);
+    let mut stdout = std::io::stdout();
+
+    if poll || !log.exists() {
+        let Some((pane, _)) = workspace::agent_pane(&ws, orch_run::AGENT) else {
+            return Err(GrootError::Other(format!("'{task}' has no panes")));
+        };
+        let term = Term::stdout();
+        let mut last = String::new();
+        while let Some(screen) = session::capture_pane(&pane) {
+            if screen != last {
+                let _ = term.clear_screen();
+                stdout.write_all(screen.as_bytes())?;
+                stdout.flush()?;
+                last = screen;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    } else {
+        let mut file = std::fs::File::open(&log)?;
+        file.seek(SeekFrom::End(0))?;
+        let mut buf = Vec::new();
+        let mut checked = Instant::now();
+        loop {
+            buf.clear();
+            file.read_to_end(&mut buf)?;
+            if !buf.is_empty() {
+                stdout.write_all(&buf)?;
+                stdout.flush()?;
+            }
+            if checked.elapsed() >= Duration::from_secs(5) {
+                if !session::session_exists(&ws) {
+                    break;
+                }
+                checked = Instant::now();
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+    eprintln!("\n{} '{task}' session ended", style("!").yellow());
+    Ok(())
+}
+
+/// Print a worker's pane log, raw on a terminal (so it replays with colors)
+/// and without escape codes when piped.
+fn transcript(ctx: &GrootContext, task: &str, path: bool) -> Result<()> {
+    let dir = orch_transcript::transcripts_dir(&ctx.groot_dir, task);
+    if !dir.is_dir() {
+        let recorded = orch_transcript::list(&ctx.groot_dir);
+        let hint = match error::similar_names(task, &recorded).first() {
+            Some(name) => format!(" Did you mean '{name}'?"),
+            None => " Only tmux workspaces are recorded.".to_string(),
+        };
+        return Err(GrootError::Other(format!("No transcript for '{task}'.{hint}")));
+    }
+    if path {
+        println!("{}", dir.display());
+        return Ok(());
+    }
+
+    let log = dir.join(orch_transcript::PANE_LOG);
+    let text = std::fs::read(&log)
+        .map_err(|e| GrootError::Other(format!("Failed to read {}: {e}", log.display())))?;
+    let text = String::from_utf8_lossy(&text);
+    if Term::stdout().is_term() {
+        print!("{text}");
+    } else {
+        print!("{}", console::strip_ansi_codes(&text));
+    }
+    Ok(())
+}
+
+/// Write, print, or open a pull request with the worker's description.
+fn describe(ctx: &GrootContext, task: &str, summarize: bool, output: Option<PathBuf>, create: bool) -> Result<()> {
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let base = ctx.project.base_branch();
+    let commits = git_worktree::commit_subjects(&ctx.git.root, &worker.branch, base);
+    if commits.is_empty() {
+        return Err(GrootError::Other(format!(
+            "'{}' has no commits that {base} lacks; commit the work first",
+            worker.branch
+        )));
+    }
+    let diff_stat = git_worktree::diff_stat(&ctx.git.root, &worker.branch, base).unwrap_or_default();
+    let summary = if summarize {
+        let step = progress::Step::start("agent_summary", "Asking the agent for a summary");
+        let summary = orch_run::ask(
+            &worker.worktree_path,
+            &format!(
+                "Summarize the changes on this branch since it forked from {base} for a pull request \
+                 description: a short paragraph, then a few bullet points. Reply with the summary only."
+            ),
+        )?;
+        step.done();
+        Some(summary)
+    } else {
+        None
+    };
+
+    let body = pr_body(summary.as_deref(), worker.initial_prompt(), &commits, &diff_stat);
+    // One commit names the change best; otherwise fall back to the task name
+    let title = match commits.as_slice() {
+        [only] => only.clone(),
+        _ => task.replace(['-', '_'], " "),
+    };
+
+    let body_file = match (output, create) {
+        (Some(path), _) => path,
+        (None, true) => ctx.groot_dir.join("describe").join(format!("{task}.md")),
+        (None, false) => {
+            print!("{body}");
+            return Ok(());
+        }
+    };
+    if let Some(parent) = body_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&body_file, &body)?;
+    println!("{} Wrote {}", style("✓").green().bold(), body_file.display());
+
+    if create {
+        let status = Command::new("gh")
+            .args(["pr", "create", "--base", base, "--title", &title, "--body-file"])
+            .arg(&body_file)
+            .current_dir(&worker.worktree_path)
+            .logged_status()
+            .map_err(|e| GrootError::Other(format!("Could not run `gh` (https://cli.github.com): {e}")))?;
+        if !status.success() {
+            return Err(GrootError::Other("`gh pr create` failed".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Have the agent review the worker's committed changes, save the review, and
+/// optionally post it on the pull request.
+fn review(ctx: &GrootContext, task: &str, post: bool) -> Result<()> {
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let base = ctx.project.base_branch();
+    let diff = git_worktree::branch_diff(&ctx.git.root, &worker.branch, base).unwrap_or_default();
+    if diff.is_empty() {
+        return Err(GrootError::Other(format!(
+            "'{}' has no committed changes that {base} lacks; commit the work first",
+            worker.branch
+        )));
+    }
+
+    let step = progress::Step::start("agent_review", "Asking the agent for a review");
+    let review = orch_run::ask_about(
+        &worker.worktree_path,
+        &format!(
+            "Review the diff on stdin: the changes on branch {} since it forked from {base}. \
+             Point out bugs, risky or unclear code, and missing tests, citing file and line, \
+             most important first. Skip praise and restating the diff. Reply in Markdown.",
+            worker.branch
+        ),
+        &diff,
+    )?;
+    step.done();
+
+    let path = ctx.groot_dir.join("reviews").join(format!("{task}.md"));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, format!("## Review of `{}`\n\n{review}\n", worker.branch))?;
+    println!("{review}\n");
+    println!("{} Wrote {}", style("✓").green().bold(), path.display());
+
+    if post {
+        let status = Command::new("gh")
+            .args(["pr", "comment", &worker.branch, "--body-file"])
+            .arg(&path)
+            .current_dir(&worker.worktree_path)
+            .logged_status()
+            .map_err(|e| GrootError::Other(format!("Could not run `gh` (https://cli.github.com): {e}")))?;
+        if !status.success() {
+            return Err(GrootError::Other(format!(
+                "`gh pr comment` failed; is there a pull request for '{}'?",
+                worker.branch
+            )));
+        }
+        println!("{} Posted the review on the pull request for '{}'", style("✓").green().bold(), worker.branch);
+    }
+    Ok(())
+}
+
+/// Markdown pull request body: the agent's summary, the task prompt, the
+/// commits, and the diff stat, skipping what's missing.
+fn pr_body(summary: Option<&str>, prompt: Option<&str>, commits: &[String], diff_stat: &str) -> String {
+    let mut body = String::new();
+    if let Some(summary) = summary {
+        body.push_str(&format!("## Summary\n\n{}\n\n", summary.trim()));
+    }
+    if let Some(prompt) = prompt.map(str::trim).filter(|p| !p.is_empty()) {
+        let quoted: Vec<String> = prompt.lines().map(|l| format!("> {l}").trim_end().to_string()).collect();
+        body.push_str(&format!("## Task\n\n{}\n\n", quoted.join("\n")));
+    }
+    body.push_str("## Commits\n\n");
+    for subject in commits {
+        body.push_str(&format!("- {subject}\n"));
+    }
+    if !diff_stat.is_empty() {
+        body.push_str(&format!("\n## Changes\n\n```\n{diff_stat}\n```\n"));
+    }
+    body
+}
+
+/// Check the app container serving `task` is ready, then open its URL.
+fn browse(ctx: &GrootContext, task: &str) -> Result<()> {
+    let url = format!("http://localhost:{}", port(ctx, task, Service::App)?);
+
+    // A tree sharing a grove is served by the grove's app container
+    let worker = orch_grove::get_grove_by_name(&ctx.groot_dir, task)?;
+    let compose_file = match &worker.shared_grove {
+        Some(grove) => orch_grove::get_grove_by_name(&ctx.groot_dir, grove)?.compose_file,
+        None => worker.compose_file,
+    };
+    if let Some(cf) = compose_file {
+        let containers = compose_mgr::ps(&cf)?.unwrap_or_default();
+        let logs = format!("docker compose -p {} logs app", compose_mgr::project_name(&cf));
+        match containers.iter().find(|c| c.service == "app") {
+            Some(app) if app.is_ready() => {}
+            Some(app) => {
+                let health = if app.health.is_empty() { String::new() } else { format!(" ({})", app.health) };
+                return Err(GrootError::Other(format!(
+                    "The app container for '{task}' is {}{health}, not ready yet. Check `{logs}`",
+                    app.state
+                )));
+            }
+            None => {
+                return Err(GrootError::Other(format!(
+                    "No app container is running for '{task}'. Check `{logs}`"
+                )));
+            }
+        }
+    }
+
+    open_url(&url)?;
+    println!("{} Opened {url}", style("✓").green().bold());
+    Ok(())
+}
+
+/// Open `url` with `$BROWSER`, else the platform's default handler.
+fn open_url(url: &str) -> Result<()> {
+    let opener = std::env::var("BROWSER").ok().filter(|b| !b.is_empty()).unwrap_or_else(|| {
+        if cfg!(target_os = "macos") {
+            "open".to_string()
+        } else if cfg!(target_os = "windows") {
+            "explorer".to_string()
+        } else {
+            "xdg-open".to_string()
+        }
+    });
+    let status = Command::new(&opener)
+        .arg(url)
+        .logged_status()
+        .map_err(|e| GrootError::Other(format!("Could not run '{opener}' to open {url}: {e}")))?;
+    if !status.success() {
+        return Err(GrootError::Other(format!("'{opener}' failed to open {url}")));
+    }
+    Ok(())
+}
+
+async fn reap(ctx: &GrootContext) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
+
+    let reaped = cleanup::reap_closed_sessions(groot_dir)?;
+    let expired = cleanup::reap_expired(groot_dir)?;
+    if reaped.is_empty() && expired.is_empty() {
+        println!("No closed worker sessions.");
+    }
+    if !reaped.is_empty() {
+        println!(
+            "{} Stopped {} worker(s) with closed sessions: {}",
+            style("✓").green().bold(),
+            reaped.len(),
+            reaped.join(", ")
+        );
+    }
+    if !expired.is_empty() {
+        println!(
+            "{} Stopped {} worker(s) past their TTL: {}",
+            style("✓").green().bold(),
+            expired.len(),
+            expired.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn resume(ctx: &GrootContext, task: Option<&str>, rollback: bool) -> Result<()> {
+    let groot_dir = &ctx.groot_dir;
+    let Some(task) = task else {
+        let interrupted = PlantJournal::list(groot_dir);
+        if interrupted.is_empty() {
+            println!("No interrupted plants.");
+        }
+        for j in &interrupted {
+            let steps: Vec<String> = j.steps.iter().map(journal::describe).collect();
+            println!(
+                "{}  {}  started {}  [{}]",
+                style(&j.task_name).bold(),
+                j.branch,
+                j.started_at.format("%Y-%m-%d %H:%M"),
+                steps.join(", ")
+            );
+        }
+        return Ok(());
+    };
+
+    let journal = PlantJournal::load(groot_dir, task)?
+        .ok_or_else(|| GrootError::Other(format!("No interrupted plant of '{task}'")))?;
+    // The plant holds the task's lock until it finishes
+    let lock = FileLock::acquire(&groot_dir.join("locks").join(format!("{task}.lock"))).map_err(|_| {
+        GrootError::Other(format!(
+            "The plant of '{task}' is still running (pid {}); wait for it to finish",
+            journal.pid
+        ))
+    })?;
+
+    if rollback {
+        journal.roll_back(&ctx.git, groot_dir);
+        println!("{} Rolled back the interrupted plant of '{task}'", style("✓").green().bold());
+        return Ok(());
+    }
+    drop(lock);
+
+    // Plant again with the same options; it picks up the journal
+    let request = &journal.request;
+    let shared_ports = match &request.shared_grove {
+        Some(grove) => orch_grove::get_grove_by_name(groot_dir, grove)?.compose_ports,
+        None => None,
+    };
+    let db_source = ctx
+        .project
+        .profile(&request.task_type)
+        .db_source
+        .or(ctx.local.compose_db_source.clone())
+        .map(|src| secret::resolve(&src))
+        .transpose()?;
+    let started = Instant::now();
+    let planted = orch_grove::plant(
+        ctx,
+        task,
+        &journal.branch,
+        &request.task_type,
+        request.prompt.as_ref(),
+        request.stack,
+        request.db_clone,
+        db_source.as_deref(),
+        request.shared_grove.as_deref(),
+        shared_ports.as_ref(),
+        request.layout.as_deref(),
+    );
+    orch_grove::notify_planted(groot_dir, task, &journal.branch, &planted, started.elapsed());
+    let state = planted?;
+    println!("{} Resumed the plant of '{task}'", style("✓").green().bold());
+    println!("  Branch:   {}", state.branch);
+    println!("  Worktree: {}", state.worktree_path.display());
+    if let Some(ref ws) = state.tmux_session {
+        println!("  Session:  {ws}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str, fan_out: Option<(&str, usize)>) -> GroveState {
+        let mut state = GroveState::parse(&format!(
+            r#"{{"task_name":"{name}","branch":"groot/{name}","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null}}"#
+        ))
+        .unwrap();
+        state.fan_out = fan_out.map(|(task, variant)| FanOut { task: task.to_string(), variant });
+        state
+    }
+
+    #[test]
+    fn test_fan_out_variants() {
+        assert_eq!(variant_name("fix-login", 2), "fix-login-v2");
+
+        let workers = vec![
+            worker("fix-login-v2", Some(("fix-login", 2))),
+            worker("fix-login-v1", Some(("fix-login", 1))),
+            // Named like a variant, but spawned on its own
+            worker("fix-login-v3", None),
+            worker("fix-login-v-next-v1", Some(("fix-login-v-next", 1))),
+        ];
+        let variants = variants(workers, "fix-login");
+        let names: Vec<(usize, &str)> = variants.iter().map(|(i, w)| (*i, w.task_name.as_str())).collect();
+        assert_eq!(names, [(1, "fix-login-v1"), (2, "fix-login-v2")]);
+
+        assert_eq!(
+            variant_branches(&variants, "fix-login", 2, 1).unwrap(),
+            ["groot/fix-login-v2", "groot/fix-login-v1"]
+        );
+        let err = variant_branches(&variants, "fix-login", 1, 3).unwrap_err();
+        assert_eq!(err.to_string(), "'fix-login' has no variant 3");
+    }
+
+    #[test]
+    fn test_variant_model_reaches_the_agent() {
+        let mut state = worker("fix-login-v1", Some(("fix-login", 1)));
+        assert_eq!(orch_grove::agent_command(&state), "claude");
+        state.model = Some("opus".to_string());
+        assert_eq!(orch_grove::agent_command(&state), "claude --model 'opus'");
+    }
+
+    #[test]
+    fn test_pr_body_sections() {
+        let commits = vec!["Add login form".to_string(), "Validate email".to_string()];
+        let body = pr_body(None, Some("Fix the login\n\nUse the new API"), &commits, " 2 files changed");
+        assert_eq!(
+            body,
+            "## Task\n\n> Fix the login\n>\n> Use the new API\n\n## Commits\n\n- Add login form\n- Validate email\n\n## Changes\n\n```\n 2 files changed\n```\n"
+        );
+
+        let body = pr_body(Some("Adds login.\n"), None, &commits[..1], "");
+        assert_eq!(body, "## Summary\n\nAdds login.\n\n## Commits\n\n- Add login form\n");
+    }
+}
@@ -0,0 +1,311 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde_yml::{Mapping, Value};
+use tracing::{info, warn};
+
+use crate::config::caches::NodeModules;
+use crate::config::project::ProjectConfig;
+use crate::error::Result;
+use crate::process::CommandExt;
+
+use super::manager;
+
+/// Where the shared copy of `node_modules` is mounted with `node_modules: seeded`.
+const NODE_MODULES_SEED: &str = "/groot-cache/node_modules";
+
+/// Detected types that install into `node_modules`.
+const NODE_TYPES: &[&str] = &["node", "react", "react-native", "nextjs", "vite", "vue", "svelte"];
+
+/// A volume mounted into the app service for dependencies.
+#[derive(Debug, PartialEq)]
+struct CacheMount {
+    volume: String,
+    target: String,
+    /// Shared by every worker of the project (an external volume, so `down
+    /// -v` leaves it), rather than the worker's own
+    shared: bool,
+    /// Variable pointing the package manager at the mount
+    env: Option<(&'static str, &'static str)>,
+}
+
+/// `groot-cache-<project>`, restricted to what Docker allows in volume names.
+fn shared_prefix(project_name: &str) -> String {
+    let project: String = project_name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-' { c } else { '-' })
+        .collect();
+    format!("groot-cache-{project}")
+}
+
+/// The caches a worker's app service gets for the project's detected types,
+/// with the worktree mounted at `app_dir`.
+fn mounts(config: &ProjectConfig, worker_name: &str, app_dir: &str) -> Vec<CacheMount> {
+    let caches = &config.caches;
+    let has = |types: &[&str]| config.detected_types.iter().any(|t| types.contains(&t.as_str()));
+    let prefix = shared_prefix(&config.project_name);
+    let shared = |name: &str, target: &str, env| CacheMount {
+        volume: format!("{prefix}-{name}"),
+        target: target.to_string(),
+        shared: true,
+        env,
+    };
+
+    let mut mounts = Vec::new();
+    if caches.wants("bundle") && has(&["rails", "ruby"]) {
+        // The official Ruby images install gems into /usr/local/bundle
+        mounts.push(shared("bundle", "/usr/local/bundle", None));
+    }
+    if caches.wants("node_modules") && has(NODE_TYPES) {
+        mounts.push(CacheMount {
+            volume: format!("groot-{worker_name}-node-modules"),
+            target: format!("{}/node_modules", app_dir.trim_end_matches('/')),
+            shared: false,
+            env: None,
+        });
+        match caches.node_modules {
+            NodeModules::Volume => {}
+            NodeModules::PnpmStore => mounts.push(shared(
+                "pnpm-store",
+                "/groot-cache/pnpm-store",
+                Some(("npm_config_store_dir", "/groot-cache/pnpm-store")),
+            )),
+            NodeModules::Seeded => mounts.push(shared("node-modules", NODE_MODULES_SEED, None)),
+        }
+    }
+    if caches.wants("cargo") && has(&["rust"]) {
+        mounts.push(shared("cargo", "/groot-cache/cargo", Some(("CARGO_HOME", "/groot-cache/cargo"))));
+    }
+    if caches.wants("go") && has(&["go"]) {
+        mounts.push(shared("go-mod", "/groot-cache/go-mod", Some(("GOMODCACHE", "/groot-cache/go-mod"))));
+    }
+    mounts
+}
+
+/// Source and target of a service volume, in short (`src:target[:mode]`) or
+/// long (`source:` / `target:`) syntax.
+fn mount_parts(entry: &Value) -> Option<(String, String)> {
+    if let Some(long) = entry.as_mapping() {
+        let source = long.get("source").and_then(Value::as_str).unwrap_or_default();
+        return Some((source.to_string(), long.get("target")?.as_str()?.to_string()));
+    }
+    let (head, tail) = entry.as_str()?.rsplit_once(':')?;
+    if tail.starts_with('/') {
+        return Some((head.to_string(), tail.to_string()));
+    }
+    let (source, target) = head.rsplit_once(':')?;
+    Some((source.to_string(), target.to_string()))
+}
+
+fn key(name: &str) -> Value {
+    Value::String(name.to_string())
+}
+
+/// Mount the project's dependency caches into the `app` service of a rendered
+/// compose file. A volume the template already mounts at a cache's path (the
+/// built-in Rails template's per-worker bundle) is swapped for the cache.
+pub fn inject(compose: &str, config: &ProjectConfig, worker_name: &str, worktree_path: &str) -> Result<String> {
+    if !config.caches.enabled {
+        return Ok(compose.to_string());
+    }
+    let mut doc: Value = serde_yml::from_str(compose)?;
+    let Some(app) = doc
+        .get_mut("services")
+        .and_then(|s| s.get_mut("app"))
+        .and_then(Value::as_mapping_mut)
+    else {
+        return Ok(compose.to_string());
+    };
+
+    let app_dir = app
+        .get("volumes")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(mount_parts)
+        .find(|(source, _)| source == worktree_path)
+        .map_or("/app".to_string(), |(_, target)| target);
+    let mounts = mounts(config, worker_name, &app_dir);
+    if mounts.is_empty() {
+        return Ok(compose.to_string());
+    }
+
+    let volumes = app
+        .entry(key("volumes"))
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    let Some(volumes) = volumes.as_sequence_mut() else {
+        warn!("Warning: the app service's volumes aren't a list; dependency caches not mounted");
+        return Ok(compose.to_string());
+    };
+    let mut replaced = Vec::new();
+    for mount in &mounts {
+        volumes.retain(|v| match mount_parts(v) {
+            Some((source, target)) if target == mount.target => {
+                replaced.push(source);
+                false
+            }
+            _ => true,
+        });
+        volumes.push(Value::String(format!("{}:{}", mount.volume, mount.target)));
+    }
+
+    let environment = app
+        .entry(key("environment"))
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    for (name, value) in mounts.iter().filter_map(|m| m.env) {
+        match environment {
+            Value::Sequence(list) => {
+                let prefix = format!("{name}=");
+                if !list.iter().any(|v| v.as_str().is_some_and(|v| v == name || v.starts_with(&prefix))) {
+                    list.push(Value::String(format!("{name}={value}")));
+                }
+            }
+            Value::Mapping(map) => {
+                map.entry(key(name)).or_insert_with(|| key(value));
+            }
+            _ => {}
+        }
+    }
+
+    let root = doc.as_mapping_mut().expect("a document with services is a mapping");
+    let declared = root
+        .entry(key("volumes"))
+        .or_insert_with(|| Value::Mapping(Mapping::new()));
+    if declared.is_null() {
+        *declared = Value::Mapping(Mapping::new());
+    }
+    if let Some(declared) = declared.as_mapping_mut() {
+        for name in &replaced {
+            declared.remove(name.as_str());
+        }
+        for mount in &mounts {
+            let spec = if mount.shared {
+                Value::Mapping(Mapping::from_iter([(key("external"), Value::Bool(true))]))
+            } else {
+                Value::Null
+            };
+            declared.insert(key(&mount.volume), spec);
+        }
+    }
+    info!(
+        "  Mounting dependency caches: {}",
+        mounts.iter().map(|m| m.target.as_str()).collect::<Vec<_>>().join(", ")
+    );
+    Ok(serde_yml::to_string(&doc)?)
+}
+
+/// Create the external volumes a compose file declares, which compose
+/// refuses to start without. Creating one that exists is a no-op.
+pub fn create_external_volumes(compose_file: &Path) -> Result<()> {
+    let doc: Value = serde_yml::from_str(&std::fs::read_to_string(compose_file)?)?;
+    let Some(declared) = doc.get("volumes").and_then(Value::as_mapping) else {
+        return Ok(());
+    };
+    for (name, spec) in declared {
+        if spec.get("external").and_then(Value::as_bool) != Some(true) {
+            continue;
+        }
+        let Some(name) = spec.get("name").and_then(Value::as_str).or(name.as_str()) else {
+            continue;
+        };
+        Command::new("docker")
+            .args(["volume", "create", name])
+            .checked_output(&format!("Creating volume {name}"))?;
+    }
+    Ok(())
+}
+
+/// With `node_modules: seeded`, the worker's `node_modules` directory, as
+/// mounted in the compose file's app service.
+fn seeded_node_modules(compose_file: &Path) -> Option<String> {
+    let doc: Value = serde_yml::from_str(&std::fs::read_to_string(compose_file).ok()?).ok()?;
+    let targets: Vec<String> = doc
+        .get("services")?
+        .get("app")?
+        .get("volumes")?
+        .as_sequence()?
+        .iter()
+        .filter_map(mount_parts)
+        .map(|(_, target)| target)
+        .collect();
+    if !targets.iter().any(|t| t == NODE_MODULES_SEED) {
+        return None;
+    }
+    targets.into_iter().find(|t| t.ends_with("/node_modules"))
+}
+
+/// Copy the shared `node_modules` into a new worker's empty one, before
+/// anything installs. Failures only warn: the install just runs cold.
+pub fn seed_node_modules(compose_file: &Path) {
+    let Some(dir) = seeded_node_modules(compose_file) else {
+        return;
+    };
+    let cmd = format!(
+        "if [ -z \"$(ls -A {dir})\" ] && [ -n \"$(ls -A {NODE_MODULES_SEED})\" ]; then cp -a {NODE_MODULES_SEED}/. {dir}/; fi"
+    );
+    if let Err(e) = manager::exec(compose_file, "app", &cmd) {
+        warn!("Warning: could not seed node_modules: {e}");
+    }
+}
+
+/// After the post-start hooks installed packages, fill the shared
+/// `node_modules` from this worker's if it's still empty.
+pub fn save_node_modules_seed(compose_file: &Path) {
+    let Some(dir) = seeded_node_modules(compose_file) else {
+        return;
+    };
+    let cmd = format!(
+        "if [ -z \"$(ls -A {NODE_MODULES_SEED})\" ] && [ -n \"$(ls -A {dir})\" ]; then cp -a {dir}/. {NODE_MODULES_SEED}/; fi"
+    );
+    if let Err(e) = manager::exec(compose_file, "app", &cmd) {
+        warn!("Warning: could not save node_modules for later workers: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compose::template;
+    use crate::detector::database::DbEngine;
+
+    #[test]
+    fn test_inject_swaps_bundle_and_adds_caches() {
+        let mut config = ProjectConfig {
+            project_name: "My App".to_string(),
+            detected_types: vec!["rails".to_string(), "node".to_string()],
+            ..Default::default()
+        };
+        config.caches.node_modules = NodeModules::PnpmStore;
+        let rendered = template::default_template_for(Some("rails"), DbEngine::Postgres)
+            .replace("{{WORKER_NAME}}", "w1")
+            .replace("{{WORKTREE_PATH}}", "/wt/w1");
+        let doc: Value = serde_yml::from_str(&inject(&rendered, &config, "w1", "/wt/w1").unwrap()).unwrap();
+
+        let volumes: Vec<&str> = doc["services"]["app"]["volumes"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert_eq!(
+            volumes,
+            [
+                "/wt/w1:/app",
+                "groot-cache-my-app-bundle:/usr/local/bundle",
+                "groot-w1-node-modules:/app/node_modules",
+                "groot-cache-my-app-pnpm-store:/groot-cache/pnpm-store",
+            ]
+        );
+        let env = doc["services"]["app"]["environment"].as_sequence().unwrap();
+        assert!(env.contains(&key("npm_config_store_dir=/groot-cache/pnpm-store")));
+
+        let declared = doc["volumes"].as_mapping().unwrap();
+        assert!(!declared.contains_key("groot-w1-bundle"));
+        assert!(declared.contains_key("groot-w1-node-modules"));
+        assert_eq!(declared["groot-cache-my-app-bundle"]["external"], Value::Bool(true));
+
+        config.caches.enabled = false;
+        assert_eq!(inject(&rendered, &config, "w1", "/wt/w1").unwrap(), rendered);
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::Utc;
+use tracing::warn;
+
+use crate::error::Result;
+use crate::process::CommandExt;
+
+use super::manager::{parse_ps, project_name};
+
+/// Log lines kept from each failing service.
+const LOG_LINES: &str = "100";
+
+/// Directory holding every diagnostics dump of this project.
+pub fn diagnostics_dir(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("diagnostics")
+}
+
+fn compose(compose_file: &Path) -> Command {
+    let mut cmd = Command::new("docker");
+    cmd.args(["compose", "-f"])
+        .arg(compose_file)
+        .args(["-p", &project_name(compose_file)]);
+    cmd
+}
+
+/// Stdout and stderr of a command, or why it couldn't run.
+fn output_of(cmd: &mut Command) -> String {
+    match cmd.logged_output() {
+        Ok(o) => format!("{}{}", String::from_utf8_lossy(&o.stdout), String::from_utf8_lossy(&o.stderr)),
+        Err(e) => format!("failed to run: {e}\n"),
+    }
+}
+
+/// Save what's needed to tell why a worker's stack didn't come up: `docker
+/// compose ps`, the last lines of each service that isn't ready (every
+/// service's if that can't be told), and the compose file itself, into
+/// `.groot/diagnostics/<task>-<timestamp>/`. Returns that directory.
+pub fn capture(groot_dir: &Path, task_name: &str, compose_file: &Path) -> Result<PathBuf> {
+    let dir = diagnostics_dir(groot_dir).join(format!("{task_name}-{}", Utc::now().format("%Y%m%d-%H%M%S")));
+    std::fs::create_dir_all(&dir)?;
+
+    std::fs::write(dir.join("ps.txt"), output_of(compose(compose_file).args(["ps", "--all"])))?;
+    if let Err(e) = std::fs::copy(compose_file, dir.join("docker-compose.yml")) {
+        warn!("Warning: could not copy {}: {e}", compose_file.display());
+    }
+
+    let failing: Vec<String> = compose(compose_file)
+        .args(["ps", "--all", "--format", "json"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_ps(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| !c.is_ready())
+        .map(|c| c.service)
+        .collect();
+    if failing.is_empty() {
+        let logs = output_of(compose(compose_file).args(["logs", "--no-color", "--tail", LOG_LINES]));
+        std::fs::write(dir.join("all-services.log"), logs)?;
+    }
+    for service in failing {
+        let logs = output_of(compose(compose_file).args(["logs", "--no-color", "--tail", LOG_LINES, &service]));
+        std::fs::write(dir.join(format!("{service}.log")), logs)?;
+    }
+    Ok(dir)
+}
@@ -2,16 +2,23 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, Instant};
 
-use crate::error::{GrootError, Result};
+use tracing::{info, warn};
 
+use crate::config::project::ProjectConfig;
+use crate::error::{GrootError, Result, Subprocess};
+use crate::process::CommandExt;
+use crate::wsl;
+
+use super::caches;
 use super::ports::AllocatedPorts;
+use super::sandbox;
 use super::template::{self, TemplateVars};
 
 /// Check that `docker compose` is available on the system.
 pub fn check_available() -> Result<()> {
     let output = Command::new("docker")
         .args(["compose", "version"])
-        .output()
+        .logged_output()
         .map_err(|_| GrootError::ComposeNotAvailable)?;
 
     if !output.status.success() {
@@ -20,17 +27,24 @@ pub fn check_available() -> Result<()> {
     Ok(())
 }
 
-/// Generate a docker-compose.yml for a worker from the template.
+/// Generate a docker-compose.yml for a worker from the template, with the
+/// agent sandbox added when `sandbox` is set.
 pub fn generate_compose_file(
     groot_dir: &Path,
+    config: &ProjectConfig,
     worker_name: &str,
     worktree_path: &Path,
     ports: &AllocatedPorts,
+    sandbox: bool,
 ) -> Result<PathBuf> {
-    let (tmpl, is_custom) = template::load_or_default(groot_dir)?;
+    let (tmpl, is_custom) = template::load_or_default(
+        groot_dir,
+        config.primary().as_deref(),
+        config.database.unwrap_or_default(),
+    )?;
 
     if is_custom {
-        println!(
+        info!(
             "  Using custom compose template: {}",
             groot_dir.join("compose-template.yml").display()
         );
@@ -38,7 +52,7 @@ pub fn generate_compose_file(
 
     let vars = TemplateVars {
         worker_name,
-        worktree_path: &worktree_path.to_string_lossy(),
+        worktree_path: &wsl::docker_path(worktree_path),
         ports,
     };
     let rendered = template::render(&tmpl, &vars);
@@ -49,13 +63,13 @@ pub fn generate_compose_file(
     let build_args = template::extract_dockerfile_args(&dockerfile_path, &env_path);
 
     if !build_args.is_empty() {
-        println!("  Injecting build args from Dockerfile + .env: {}", build_args.join(", "));
+        info!("  Injecting build args from Dockerfile + .env: {}", build_args.join(", "));
     }
 
     let (rendered, injected) = template::inject_build_args(&rendered, &build_args);
 
     if !build_args.is_empty() && !injected {
-        eprintln!(
+        warn!(
             "Warning: Found build args {:?} but could not inject into compose template \
              (no 'dockerfile:' or 'context:' line found). \
              Add them manually to your compose-template.yml under build: args:",
@@ -66,7 +80,7 @@ pub fn generate_compose_file(
     // Auto-extract build secrets (RUN --mount=type=secret) from Dockerfile
     let build_secrets = template::extract_dockerfile_secrets(&dockerfile_path, &env_path);
     let rendered = if !build_secrets.is_empty() {
-        println!(
+        info!(
             "  Injecting build secrets from Dockerfile + .env: {}",
             build_secrets.join(", ")
         );
@@ -75,9 +89,20 @@ pub fn generate_compose_file(
         rendered
     };
 
+    let rendered = caches::inject(&rendered, config, worker_name, vars.worktree_path)?;
+
     let compose_dir = groot_dir.join("compose").join(worker_name);
     std::fs::create_dir_all(&compose_dir)?;
 
+    let rendered = if sandbox {
+        let proxy_conf = compose_dir.join("squid.conf");
+        std::fs::write(&proxy_conf, sandbox::proxy_conf(&config.sandbox.hosts()))?;
+        info!("  Sandboxing the agent; allowed hosts: {}", config.sandbox.hosts().join(", "));
+        sandbox::inject(&rendered, worktree_path, &config.sandbox, &proxy_conf)?
+    } else {
+        rendered
+    };
+
     let compose_file = compose_dir.join("docker-compose.yml");
     std::fs::write(&compose_file, &rendered)?;
 
@@ -86,11 +111,11 @@ pub fn generate_compose_file(
     let worktree_env = worktree_path.join(".env");
     if worktree_env.exists() {
         match normalize_env_file(&worktree_env, &compose_dir.join(".env")) {
-            Ok(_) => println!("  Copied .env to compose directory (normalized)"),
-            Err(e) => eprintln!("Warning: failed to copy .env to compose directory: {e}"),
+            Ok(_) => info!("  Copied .env to compose directory (normalized)"),
+            Err(e) => warn!("Warning: failed to copy .env to compose directory: {e}"),
         }
     } else {
-        eprintln!(
+        warn!(
             "Warning: no .env found at {} — build args may not resolve",
             worktree_env.display()
         );
@@ -101,6 +126,7 @@ pub fn generate_compose_file(
 
 /// Start the compose stack in detached mode.
 pub fn up(compose_file: &Path) -> Result<()> {
+    caches::create_external_volumes(compose_file)?;
     let project = project_name(compose_file);
     let compose_dir = compose_file.parent().unwrap_or(Path::new("."));
     let env_file = compose_dir.join(".env");
@@ -145,21 +171,14 @@ pub fn up(compose_file: &Path) -> Result<()> {
         }
     }
 
-    let output = cmd.output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::ComposeOperationFailed(format!(
-            "compose up failed: {stderr}"
-        )));
-    }
+    cmd.checked_output("compose up failed")?;
     Ok(())
 }
 
 /// Tear down the compose stack and remove volumes.
 pub fn down(compose_file: &Path) -> Result<()> {
     let project = project_name(compose_file);
-    let output = Command::new("docker")
+    Command::new("docker")
         .args([
             "compose",
             "-f",
@@ -169,84 +188,97 @@ pub fn down(compose_file: &Path) -> Result<()> {
             "down",
             "-v",
         ])
-        .output()?;
+        .checked_output("compose down failed")?;
+    Ok(())
+}
+
+/// One container of a compose project, from `docker compose ps`.
+#[derive(Debug)]
+pub struct ContainerStatus {
+    pub name: String,
+    pub service: String,
+    /// `running`, `exited`, `restarting`, ...
+    pub state: String,
+    /// `healthy`, `unhealthy`, `starting`, or empty without a healthcheck
+    pub health: String,
+}
 
+impl ContainerStatus {
+    /// Running with no healthcheck, or running and healthy.
+    pub fn is_ready(&self) -> bool {
+        self.state == "running" && (self.health.is_empty() || self.health == "healthy")
+    }
+}
+
+/// The compose project's containers, or `None` when `docker compose ps` fails.
+pub fn ps(compose_file: &Path) -> Result<Option<Vec<ContainerStatus>>> {
+    let project = project_name(compose_file);
+    let output = Command::new("docker")
+        .args([
+            "compose",
+            "-f",
+            &compose_file.to_string_lossy(),
+            "-p",
+            &project,
+            "ps",
+            "--format",
+            "json",
+        ])
+        .logged_output()?;
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::ComposeOperationFailed(format!(
-            "compose down failed: {stderr}"
-        )));
+        return Ok(None);
     }
-    Ok(())
+    Ok(Some(parse_ps(&String::from_utf8_lossy(&output.stdout))))
+}
+
+/// Parse `docker compose ps --format json`: one object per line.
+pub fn parse_ps(stdout: &str) -> Vec<ContainerStatus> {
+    let field = |svc: &serde_json::Value, key: &str| {
+        svc.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string()
+    };
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+        .map(|svc| ContainerStatus {
+            name: Some(field(&svc, "Name")).filter(|n| !n.is_empty()).unwrap_or_else(|| "unknown".into()),
+            service: field(&svc, "Service"),
+            state: field(&svc, "State"),
+            health: field(&svc, "Health"),
+        })
+        .collect()
 }
 
 /// Wait for all services in the compose stack to be running (and healthy, if
 /// a healthcheck is defined). Polls `docker compose ps --format json` every 2s.
 pub fn wait_healthy(compose_file: &Path, timeout: Duration) -> Result<()> {
-    let project = project_name(compose_file);
     let start = Instant::now();
 
-    println!("Waiting for containers to be ready...");
+    info!("Waiting for containers to be ready...");
 
     loop {
-        let output = Command::new("docker")
-            .args([
-                "compose",
-                "-f",
-                &compose_file.to_string_lossy(),
-                "-p",
-                &project,
-                "ps",
-                "--format",
-                "json",
-            ])
-            .output()?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let mut total = 0u32;
-            let mut ready = 0u32;
-
-            for line in stdout.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
+        if let Some(containers) = ps(compose_file)? {
+            let total = containers.len();
+            let mut ready = 0;
+
+            for container in &containers {
+                // Fail fast if a container has exited or died
+                if container.state == "exited" || container.state == "dead" {
+                    return Err(GrootError::ComposeOperationFailed(format!(
+                        "container '{}' {} unexpectedly",
+                        container.name, container.state
+                    )));
                 }
-                if let Ok(svc) = serde_json::from_str::<serde_json::Value>(line) {
-                    total += 1;
-                    let state = svc
-                        .get("State")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
-                    let health = svc
-                        .get("Health")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
-                    let name = svc
-                        .get("Name")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-
-                    // Fail fast if a container has exited or died
-                    if state == "exited" || state == "dead" {
-                        return Err(GrootError::ComposeOperationFailed(format!(
-                            "container '{name}' {state} unexpectedly"
-                        )));
-                    }
-
-                    // Running with no healthcheck, or running+healthy
-                    if state == "running" && (health.is_empty() || health == "healthy") {
-                        ready += 1;
-                    }
+                if container.is_ready() {
+                    ready += 1;
                 }
             }
 
             if total > 0 && ready == total {
-                println!("  All {total} container(s) ready.");
+                info!("  All {total} container(s) ready.");
                 return Ok(());
             }
 
-            println!("  {ready}/{total} container(s) ready...");
+            info!("  {ready}/{total} container(s) ready...");
         }
 
         if start.elapsed() >= timeout {
@@ -284,9 +316,9 @@ pub fn exec_as_user(compose_file: &Path, service: &str, cmd: &str, user: Option<
     }
     args.extend([service, "sh", "-c", cmd]);
 
-    let output = Command::new("docker")
-        .args(&args)
-        .output()?;
+    let mut docker = Command::new("docker");
+    docker.args(&args);
+    let output = docker.logged_output()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     if !stdout.is_empty() {
@@ -294,10 +326,10 @@ pub fn exec_as_user(compose_file: &Path, service: &str, cmd: &str, user: Option<
     }
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::ComposeOperationFailed(format!(
-            "exec '{cmd}' failed: {stderr}"
-        )));
+        return Err(GrootError::CommandFailed {
+            what: format!("exec '{cmd}' failed"),
+            process: Subprocess::new(&docker, &output),
+        });
     }
     Ok(())
 }
@@ -374,3 +406,20 @@ pub fn project_name(compose_file: &Path) -> String {
         .map(|n| format!("groot-{}", n.to_string_lossy()))
         .unwrap_or_else(|| "groot".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ps() {
+        let stdout = r#"{"Name":"groot-a-app-1","Service":"app","State":"running","Health":""}
+{"Name":"groot-a-db-1","Service":"db","State":"running","Health":"starting"}
+"#;
+        let containers = parse_ps(stdout);
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].service, "app");
+        assert!(containers[0].is_ready());
+        assert!(!containers[1].is_ready());
+    }
+}
@@ -1,4 +1,7 @@
+pub mod caches;
 pub mod db;
+pub mod diagnostics;
 pub mod manager;
 pub mod ports;
+pub mod sandbox;
 pub mod template;
@@ -7,6 +7,7 @@ use std::net::TcpListener;
 
 use crate::config::lock::FileLock;
 use crate::error::{GrootError, Result};
+use crate::store;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AllocatedPorts {
@@ -15,31 +16,45 @@ pub struct AllocatedPorts {
     pub redis: u16,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
-struct PortRegistry {
-    allocations: HashMap<String, AllocatedPorts>,
-}
-
 const APP_BASE: u16 = 3001;
 const DB_BASE: u16 = 5433;
 const REDIS_BASE: u16 = 6380;
 
 /// Allocate ports for a worker, using gap-filling to reuse freed slots.
 pub fn allocate(groot_dir: &Path, worker_name: &str) -> Result<AllocatedPorts> {
-    let registry_path = groot_dir.join("ports.json");
     let lock_path = groot_dir.join("ports.json.lock");
-    let _lock = FileLock::acquire(&lock_path)?;
+    let _lock = FileLock::acquire_within(&lock_path, store::LOCK_WAIT)?;
 
-    let mut registry = load_registry(&registry_path);
+    let store = store::open(groot_dir)?;
+    let mut allocations = store.load_ports()?;
 
     // If already allocated, return existing
-    if let Some(existing) = registry.allocations.get(worker_name) {
+    if let Some(existing) = allocations.get(worker_name) {
         return Ok(existing.clone());
     }
 
-    // Find lowest available index (gap-filling)
-    let used_indices: Vec<u16> = registry
-        .allocations
+    let ports = next_slot(&allocations);
+    allocations.insert(worker_name.to_string(), ports.clone());
+    store.save_ports(&allocations)?;
+
+    Ok(ports)
+}
+
+/// The ports the next worker would get, without reserving them.
+pub fn next_free(groot_dir: &Path) -> AllocatedPorts {
+    next_slot(&allocations(groot_dir))
+}
+
+/// Every worker's ports as recorded in the state store (none if it's unreadable).
+pub fn allocations(groot_dir: &Path) -> HashMap<String, AllocatedPorts> {
+    store::open(groot_dir)
+        .and_then(|store| store.load_ports())
+        .unwrap_or_default()
+}
+
+/// Lowest unused slot (gap-filling, so freed slots are reused).
+fn next_slot(allocations: &HashMap<String, AllocatedPorts>) -> AllocatedPorts {
+    let used_indices: Vec<u16> = allocations
         .values()
         .map(|p| p.app - APP_BASE)
         .collect();
@@ -49,29 +64,22 @@ pub fn allocate(groot_dir: &Path, worker_name: &str) -> Result<AllocatedPorts> {
         index += 1;
     }
 
-    let ports = AllocatedPorts {
+    AllocatedPorts {
         app: APP_BASE + index,
         db: DB_BASE + index,
         redis: REDIS_BASE + index,
-    };
-
-    registry
-        .allocations
-        .insert(worker_name.to_string(), ports.clone());
-    save_registry(&registry_path, &registry)?;
-
-    Ok(ports)
+    }
 }
 
 /// Release ports for a worker.
 pub fn release(groot_dir: &Path, worker_name: &str) -> Result<()> {
-    let registry_path = groot_dir.join("ports.json");
     let lock_path = groot_dir.join("ports.json.lock");
-    let _lock = FileLock::acquire(&lock_path)?;
+    let _lock = FileLock::acquire_within(&lock_path, store::LOCK_WAIT)?;
 
-    let mut registry = load_registry(&registry_path);
-    registry.allocations.remove(worker_name);
-    save_registry(&registry_path, &registry)?;
+    let store = store::open(groot_dir)?;
+    let mut allocations = store.load_ports()?;
+    allocations.remove(worker_name);
+    store.save_ports(&allocations)?;
 
     Ok(())
 }
@@ -96,15 +104,3 @@ pub fn check_ports_available(ports: &AllocatedPorts) -> Result<()> {
     Ok(())
 }
 
-fn load_registry(path: &Path) -> PortRegistry {
-    std::fs::read_to_string(path)
-        .ok()
-        .and_then(|s| serde_json::from_str(&s).ok())
-        .unwrap_or_default()
-}
-
-fn save_registry(path: &Path, registry: &PortRegistry) -> Result<()> {
-    let contents = serde_json::to_string_pretty(registry)?;
-    std::fs::write(path, contents)?;
-    Ok(())
-}
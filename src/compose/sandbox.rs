@@ -0,0 +1,258 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde_yml::{Mapping, Value};
+
+use crate::config::sandbox::SandboxConfig;
+use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
+
+/// Service the sandboxed agent runs in.
+pub const AGENT_SERVICE: &str = "agent";
+/// The proxy sidecar: the agent's only way off the internal network.
+const PROXY_SERVICE: &str = "agent-proxy";
+/// Internal network shared by the agent, the proxy, and the other services.
+const SANDBOX_NETWORK: &str = "groot-sandbox";
+const PROXY_PORT: u16 = 3128;
+
+/// Keys of the app service the agent doesn't inherit: it publishes no ports,
+/// gets no `.env` (host credentials), and keeps running on its own.
+const NOT_INHERITED: &[&str] = &[
+    "container_name",
+    "ports",
+    "env_file",
+    "command",
+    "entrypoint",
+    "depends_on",
+    "healthcheck",
+    "networks",
+    "network_mode",
+    "restart",
+    "profiles",
+];
+
+fn key(name: &str) -> Value {
+    Value::String(name.to_string())
+}
+
+fn seq(items: &[&str]) -> Value {
+    Value::Sequence(items.iter().map(|s| key(s)).collect())
+}
+
+/// Add the sandbox to a rendered compose file: an `agent` service cloned from
+/// `app` (same build or image, volumes, and environment, minus `.env` and
+/// ports) on an internal network, and a proxy that allows only the configured
+/// hosts. Every other service joins the internal network so the agent can
+/// still reach the database and the app.
+pub fn inject(
+    compose: &str,
+    worktree_path: &Path,
+    config: &SandboxConfig,
+    proxy_conf: &Path,
+) -> Result<String> {
+    let mut doc: Value = serde_yml::from_str(compose)?;
+    let services = doc
+        .get_mut("services")
+        .and_then(Value::as_mapping_mut)
+        .ok_or_else(|| GrootError::Other("compose template has no services".to_string()))?;
+    let app = services
+        .get("app")
+        .and_then(Value::as_mapping)
+        .cloned()
+        .ok_or_else(|| {
+            GrootError::Other("--sandbox needs an `app` service in the compose template".to_string())
+        })?;
+
+    let mut names: Vec<String> = Vec::new();
+    for (name, service) in services.iter_mut() {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        if service.contains_key("network_mode") {
+            continue;
+        }
+        names.extend(name.as_str().map(str::to_string));
+        join_sandbox_network(service);
+    }
+
+    let mut agent: Mapping = app
+        .into_iter()
+        .filter(|(k, _)| !k.as_str().is_some_and(|k| NOT_INHERITED.contains(&k)))
+        .collect();
+    if let Some(image) = &config.image {
+        agent.remove("build");
+        agent.insert(key("image"), key(image));
+    }
+    let mut environment = env_entries(agent.get("environment"));
+    let no_proxy: Vec<&str> = names.iter().map(String::as_str).chain(["localhost", "127.0.0.1"]).collect();
+    let no_proxy = no_proxy.join(",");
+    let proxy_url = format!("http://{PROXY_SERVICE}:{PROXY_PORT}");
+    for var in ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"] {
+        environment.push(format!("{var}={proxy_url}"));
+    }
+    environment.push(format!("NO_PROXY={no_proxy}"));
+    environment.push(format!("no_proxy={no_proxy}"));
+    // The one credential passed through, from the environment `compose up` runs in
+    environment.push("ANTHROPIC_API_KEY".to_string());
+    let environment = environment.into_iter().map(Value::String).collect();
+    agent.insert(key("environment"), Value::Sequence(environment));
+    agent.insert(key("command"), seq(&["sleep", "infinity"]));
+    agent.insert(key("networks"), seq(&[SANDBOX_NETWORK]));
+    agent.insert(key("depends_on"), seq(&[PROXY_SERVICE]));
+    agent.insert(key("stdin_open"), Value::Bool(true));
+    agent.insert(key("tty"), Value::Bool(true));
+    // git in the agent follows the worktree's `.git` file to the main repository
+    if let Some(git_dir) = git_common_dir(worktree_path) {
+        let volumes = agent
+            .entry(key("volumes"))
+            .or_insert_with(|| Value::Sequence(Vec::new()));
+        if let Some(volumes) = volumes.as_sequence_mut() {
+            volumes.push(key(&format!("{git_dir}:{git_dir}")));
+        }
+    }
+
+    let mut proxy = Mapping::new();
+    let proxy_image = config.proxy_image.as_deref().unwrap_or("ubuntu/squid:latest");
+    proxy.insert(key("image"), key(proxy_image));
+    proxy.insert(
+        key("volumes"),
+        seq(&[&format!("{}:/etc/squid/squid.conf:ro", proxy_conf.display())]),
+    );
+    proxy.insert(key("networks"), seq(&["default", SANDBOX_NETWORK]));
+
+    services.insert(key(AGENT_SERVICE), Value::Mapping(agent));
+    services.insert(key(PROXY_SERVICE), Value::Mapping(proxy));
+
+    let root = doc
+        .as_mapping_mut()
+        .ok_or_else(|| GrootError::Other("compose template is not a mapping".to_string()))?;
+    let networks = root
+        .entry(key("networks"))
+        .or_insert_with(|| Value::Mapping(Mapping::new()));
+    if let Some(networks) = networks.as_mapping_mut() {
+        let mut internal = Mapping::new();
+        internal.insert(key("internal"), Value::Bool(true));
+        networks.insert(key(SANDBOX_NETWORK), Value::Mapping(internal));
+    }
+
+    Ok(serde_yml::to_string(&doc)?)
+}
+
+/// Add the internal network to a service, keeping the networks it had (or
+/// `default`).
+fn join_sandbox_network(service: &mut Mapping) {
+    match service.get_mut("networks") {
+        Some(Value::Sequence(networks)) => networks.push(key(SANDBOX_NETWORK)),
+        Some(Value::Mapping(networks)) => {
+            networks.insert(key(SANDBOX_NETWORK), Value::Null);
+        }
+        _ => {
+            service.insert(key("networks"), seq(&["default", SANDBOX_NETWORK]));
+        }
+    }
+}
+
+/// `environment:` as `KEY=value` entries, from either its list or map form.
+fn env_entries(environment: Option<&Value>) -> Vec<String> {
+    match environment {
+        Some(Value::Sequence(entries)) => {
+            entries.iter().filter_map(|e| e.as_str().map(str::to_string)).collect()
+        }
+        Some(Value::Mapping(entries)) => entries
+            .iter()
+            .filter_map(|(k, v)| {
+                let k = k.as_str()?;
+                Some(match v {
+                    Value::Null => k.to_string(),
+                    Value::String(s) => format!("{k}={s}"),
+                    other => format!("{k}={}", serde_yml::to_string(other).ok()?.trim()),
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Absolute path of the repository's `.git` directory, shared by its worktrees.
+fn git_common_dir(worktree_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .current_dir(worktree_path)
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Squid configuration allowing only `hosts` and their subdomains.
+pub fn proxy_conf(hosts: &[&str]) -> String {
+    let mut domains: Vec<String> = hosts
+        .iter()
+        .map(|h| format!(".{}", h.trim().trim_start_matches('.')))
+        .collect();
+    domains.sort();
+    domains.dedup();
+    // squid rejects a domain listed alongside one of its parents
+    let domains: Vec<&str> = domains
+        .iter()
+        .filter(|d| !domains.iter().any(|parent| *d != parent && d.ends_with(parent.as_str())))
+        .map(String::as_str)
+        .collect();
+    format!(
+        "http_port {PROXY_PORT}\n\
+         acl allowed dstdomain {}\n\
+         http_access allow allowed\n\
+         http_access deny all\n",
+        domains.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_conf_drops_covered_subdomains() {
+        let conf = proxy_conf(&["anthropic.com", "api.anthropic.com", "github.com", ".github.com"]);
+        assert!(conf.contains("acl allowed dstdomain .anthropic.com .github.com\n"));
+    }
+
+    #[test]
+    fn test_inject_adds_agent_and_proxy() {
+        let compose = r#"services:
+  app:
+    build:
+      context: /wt
+    container_name: groot-x-app
+    ports:
+      - "3001:3000"
+    env_file:
+      - path: /wt/.env
+    environment:
+      DATABASE_URL: postgres://db:5432/x
+    volumes:
+      - /wt:/app
+  db:
+    image: postgres:16-alpine
+    networks: [backend]
+"#;
+        let config = SandboxConfig::default();
+        let rendered = inject(compose, Path::new("/nonexistent"), &config, Path::new("/c/squid.conf")).unwrap();
+        let doc: Value = serde_yml::from_str(&rendered).unwrap();
+        let services = &doc["services"];
+
+        let agent = &services["agent"];
+        assert_eq!(agent["build"]["context"], key("/wt"));
+        assert!(agent.get("ports").is_none() && agent.get("env_file").is_none());
+        assert_eq!(agent["networks"], seq(&["groot-sandbox"]));
+        let env: Vec<&str> = agent["environment"].as_sequence().unwrap().iter().filter_map(Value::as_str).collect();
+        assert!(env.contains(&"DATABASE_URL=postgres://db:5432/x"));
+        assert!(env.contains(&"HTTPS_PROXY=http://agent-proxy:3128"));
+        assert!(env.contains(&"NO_PROXY=app,db,localhost,127.0.0.1"));
+
+        assert_eq!(services["app"]["networks"], seq(&["default", "groot-sandbox"]));
+        assert_eq!(services["db"]["networks"], seq(&["backend", "groot-sandbox"]));
+        assert_eq!(services["agent-proxy"]["networks"], seq(&["default", "groot-sandbox"]));
+        assert_eq!(doc["networks"]["groot-sandbox"]["internal"], Value::Bool(true));
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::detector::database::DbEngine;
 use crate::error::Result;
 
 use super::ports::AllocatedPorts;
@@ -10,17 +11,90 @@ pub struct TemplateVars<'a> {
     pub ports: &'a AllocatedPorts,
 }
 
-/// Load a user-provided compose template, or fall back to the built-in default.
+/// Load a user-provided compose template, or fall back to the built-in default
+/// for the primary project type and database engine.
 /// Returns (template_content, is_custom).
-pub fn load_or_default(groot_dir: &Path) -> Result<(String, bool)> {
+pub fn load_or_default(
+    groot_dir: &Path,
+    primary_type: Option<&str>,
+    engine: DbEngine,
+) -> Result<(String, bool)> {
     let custom_path = groot_dir.join("compose-template.yml");
     if custom_path.exists() {
         let contents = std::fs::read_to_string(&custom_path)?;
         return Ok((contents, true));
     }
-    Ok((default_rails_template().to_string(), false))
+    Ok((default_template_for(primary_type, engine), false))
 }
 
+/// Pick the built-in compose template for the primary project type (Rails unless
+/// it is Django) and swap its PostgreSQL service for the project's database engine.
+pub fn default_template_for(primary_type: Option<&str>, engine: DbEngine) -> String {
+    let django = primary_type == Some("django");
+    let template = if django {
+        default_django_template()
+    } else {
+        default_rails_template()
+    };
+
+    match engine {
+        DbEngine::Postgres => template.to_string(),
+        DbEngine::Mysql => {
+            // Rails picks its adapter from the URL scheme
+            let scheme = if django { "mysql" } else { "mysql2" };
+            template
+                .replace(POSTGRES_SERVICE, MYSQL_SERVICE)
+                .replace(POSTGRES_URL_PREFIX, &format!("{scheme}://root:root@db:3306/"))
+        }
+        // SQLite lives in the worktree: no db service and no DATABASE_URL override
+        DbEngine::Sqlite => template
+            .replace(POSTGRES_SERVICE, "")
+            .replace(DB_DEPENDENCY, "")
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("- DATABASE_URL"))
+            .map(|line| format!("{line}\n"))
+            .collect(),
+    }
+}
+
+/// Pieces of the built-in templates that `default_template_for` swaps per engine.
+const POSTGRES_URL_PREFIX: &str = "postgres://postgres:postgres@db:5432/";
+
+const DB_DEPENDENCY: &str = "      db:\n        condition: service_healthy\n";
+
+const POSTGRES_SERVICE: &str = r#"  db:
+    image: postgres:16-alpine
+    container_name: groot-{{WORKER_NAME}}-db
+    ports:
+      - "{{DB_PORT}}:5432"
+    environment:
+      - POSTGRES_USER=postgres
+      - POSTGRES_PASSWORD=postgres
+      - POSTGRES_DB={{WORKER_NAME}}_dev
+    healthcheck:
+      test: ["CMD-SHELL", "pg_isready -U postgres"]
+      interval: 5s
+      timeout: 5s
+      retries: 5
+
+"#;
+
+const MYSQL_SERVICE: &str = r#"  db:
+    image: mysql:8.4
+    container_name: groot-{{WORKER_NAME}}-db
+    ports:
+      - "{{DB_PORT}}:3306"
+    environment:
+      - MYSQL_ROOT_PASSWORD=root
+      - MYSQL_DATABASE={{WORKER_NAME}}_dev
+    healthcheck:
+      test: ["CMD", "mysqladmin", "ping", "-h", "127.0.0.1", "-uroot", "-proot"]
+      interval: 5s
+      timeout: 5s
+      retries: 10
+
+"#;
+
 /// Render template variables using simple string replacement.
 pub fn render(template: &str, vars: &TemplateVars) -> String {
     template
@@ -319,3 +393,88 @@ volumes:
   groot-{{WORKER_NAME}}-node-modules:
 "#
 }
+
+/// Built-in Django compose template: app on port 8000 plus PostgreSQL and Redis.
+pub fn default_django_template() -> &'static str {
+    r#"services:
+  app:
+    build:
+      context: "{{WORKTREE_PATH}}"
+      dockerfile: Dockerfile.dev
+    container_name: groot-{{WORKER_NAME}}-app
+    command: ["sleep", "infinity"]
+    ports:
+      - "{{APP_PORT}}:8000"
+    volumes:
+      - "{{WORKTREE_PATH}}:/app"
+    env_file:
+      - path: "{{WORKTREE_PATH}}/.env"
+        required: false
+    environment:
+      - DATABASE_URL=postgres://postgres:postgres@db:5432/{{WORKER_NAME}}_dev
+      - REDIS_URL=redis://redis:6379/0
+      - PYTHONUNBUFFERED=1
+    depends_on:
+      db:
+        condition: service_healthy
+      redis:
+        condition: service_healthy
+    stdin_open: true
+    tty: true
+
+  db:
+    image: postgres:16-alpine
+    container_name: groot-{{WORKER_NAME}}-db
+    ports:
+      - "{{DB_PORT}}:5432"
+    environment:
+      - POSTGRES_USER=postgres
+      - POSTGRES_PASSWORD=postgres
+      - POSTGRES_DB={{WORKER_NAME}}_dev
+    healthcheck:
+      test: ["CMD-SHELL", "pg_isready -U postgres"]
+      interval: 5s
+      timeout: 5s
+      retries: 5
+
+  redis:
+    image: redis:7-alpine
+    container_name: groot-{{WORKER_NAME}}-redis
+    ports:
+      - "{{REDIS_PORT}}:6379"
+    healthcheck:
+      test: ["CMD", "redis-cli", "ping"]
+      interval: 5s
+      timeout: 5s
+      retries: 5
+"#
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_template_for_engine() {
+        let rails = Some("rails");
+        assert_eq!(
+            default_template_for(rails, DbEngine::Postgres),
+            default_rails_template()
+        );
+        assert_eq!(
+            default_template_for(Some("django"), DbEngine::Postgres),
+            default_django_template()
+        );
+
+        let mysql = default_template_for(rails, DbEngine::Mysql);
+        assert!(mysql.contains("image: mysql:8.4"));
+        assert!(mysql.contains("DATABASE_URL=mysql2://root:root@db:3306/{{WORKER_NAME}}_dev"));
+        assert!(!mysql.contains("postgres"));
+
+        let sqlite = default_template_for(rails, DbEngine::Sqlite);
+        assert!(!sqlite.contains("  db:"));
+        assert!(!sqlite.contains("DATABASE_URL"));
+        assert!(sqlite.contains("REDIS_URL=redis://redis:6379/0"));
+        assert!(serde_yml::from_str::<serde_yml::Value>(&sqlite).is_ok());
+    }
+}
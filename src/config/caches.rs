@@ -0,0 +1,59 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Dependency caches mounted into every grove's `app` service (`caches:` in
+/// `.groot/config.yml`), chosen by the project's detected types, so a new
+/// worker doesn't start with a cold `bundle install` or `npm install`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CacheConfig {
+    /// Mount the caches at all
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// How each worker's `node_modules` is kept
+    #[serde(default)]
+    pub node_modules: NodeModules,
+    /// Caches to leave out: `bundle`, `node_modules`, `cargo`, or `go`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+/// Strategies for `node_modules`, which can't be shared outright: packages
+/// with native builds and different lockfiles per branch would clash.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodeModules {
+    /// A volume per worker, installed from scratch
+    #[default]
+    Volume,
+    /// A volume per worker, with pnpm's content-addressed store shared, so
+    /// installs link packages instead of downloading them
+    PnpmStore,
+    /// A volume per worker, copied from a shared one on first start; the first
+    /// worker to install fills the shared one
+    Seeded,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            node_modules: NodeModules::default(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the cache named `kind` is mounted.
+    pub fn wants(&self, kind: &str) -> bool {
+        self.enabled && !self.exclude.iter().any(|k| k == kind)
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
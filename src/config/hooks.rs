@@ -0,0 +1,81 @@
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::webhook::WebhookEvent;
+use crate::error::Result;
+
+/// Shell commands run when a worker's task changes state (`.groot/hooks.yml`).
+/// Each runs with `sh -c` from the repository root, in order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HooksConfig {
+    /// After a worker was planted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_create: Vec<String>,
+    /// When the agent is given a prompt: at plant, by `worker run`, or by `worker prompt`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_active: Vec<String>,
+    /// After a headless agent run succeeded, or the task was marked complete
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_complete: Vec<String>,
+    /// After a worker was stopped or uprooted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_close: Vec<String>,
+}
+
+/// A state change hooks can run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifecycle {
+    Create,
+    Active,
+    Complete,
+    Close,
+}
+
+impl Lifecycle {
+    /// The key the hook is configured under.
+    pub fn key(self) -> &'static str {
+        match self {
+            Lifecycle::Create => "on_create",
+            Lifecycle::Active => "on_active",
+            Lifecycle::Complete => "on_complete",
+            Lifecycle::Close => "on_close",
+        }
+    }
+
+    /// The state change a lifecycle event stands for, if any.
+    pub fn of(event: WebhookEvent) -> Option<Self> {
+        match event {
+            WebhookEvent::WorkerSpawned => Some(Lifecycle::Create),
+            WebhookEvent::TaskCompleted => Some(Lifecycle::Complete),
+            WebhookEvent::WorkerStopped => Some(Lifecycle::Close),
+            WebhookEvent::WorkerFailed | WebhookEvent::DbCloneFinished => None,
+        }
+    }
+}
+
+pub fn path(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("hooks.yml")
+}
+
+impl HooksConfig {
+    /// Load `.groot/hooks.yml`; no hooks when it doesn't exist.
+    pub fn load(groot_dir: &Path) -> Result<Self> {
+        let path = path(groot_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(serde_yml::from_str::<Option<Self>>(&contents)?.unwrap_or_default())
+    }
+
+    pub fn commands(&self, lifecycle: Lifecycle) -> &[String] {
+        match lifecycle {
+            Lifecycle::Create => &self.on_create,
+            Lifecycle::Active => &self.on_active,
+            Lifecycle::Complete => &self.on_complete,
+            Lifecycle::Close => &self.on_close,
+        }
+    }
+}
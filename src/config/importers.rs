@@ -0,0 +1,44 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Issue trackers `groot worker import` reads from (`importers:` in
+/// `.groot/local.yml`, since the tokens are personal).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImporterConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linear: Option<LinearConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jira: Option<JiraConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LinearConfig {
+    /// Personal API key; may be a `keyring:<name>` reference
+    pub api_key: String,
+    /// Only issues of the team with this key (e.g. `ENG`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct JiraConfig {
+    /// The Jira site, e.g. `https://acme.atlassian.net`
+    pub url: String,
+    /// The account the API token belongs to
+    pub email: String,
+    /// API token; may be a `keyring:<name>` reference
+    pub api_token: String,
+    /// The issues to offer; open issues assigned to you by default
+    #[serde(default = "default_jql")]
+    pub jql: String,
+}
+
+impl ImporterConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_jql() -> String {
+    "assignee = currentUser() AND statusCategory != Done ORDER BY priority DESC, updated DESC".to_string()
+}
@@ -1,37 +1,208 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::config::importers::ImporterConfig;
+use crate::detector::package_manager;
 use crate::error::Result;
+use crate::terminal::TerminalBackend;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// Missing keys (or a missing local.yml) get the same values `groot init` writes.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LocalConfig {
-    #[serde(default)]
+    /// Name of the shared tmux session and prefix of per-worker sessions
+    #[serde(default = "default_tmux_session_name")]
     pub tmux_session_name: String,
-    #[serde(default)]
+    /// How many workers this machine is meant to run in parallel
+    #[serde(default = "default_max_workers")]
     pub max_workers: usize,
-    #[serde(default)]
+    /// Free disk space (MB) required before planting a worker
+    #[serde(default = "default_min_disk_space_mb")]
     pub min_disk_space_mb: u64,
+    /// Seconds to wait for compose services to become healthy
     #[serde(default = "default_compose_health_timeout_secs")]
     pub compose_health_timeout_secs: u64,
+    /// Commands run in the compose app service after the stack is healthy
     #[serde(default)]
-    pub compose_post_start: Vec<String>,
+    pub compose_post_start: PostStartHooks,
     /// Default source database URL for `--db-clone` (e.g., postgres://localhost:5432/Reportal_development)
     #[serde(default)]
     pub compose_db_source: Option<String>,
+    /// Commands run in a new tree's worktree after planting (one list, or lists
+    /// by task type like `compose_post_start`); when unset, the install
+    /// commands for the package managers found in the worktree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_post_plant: Option<PostStartHooks>,
+    /// Where `tree_post_plant` runs: `worktree` (before the workspace opens)
+    /// or `pane` (typed into the first tmux pane, ahead of the agent)
+    #[serde(default)]
+    pub tree_post_plant_in: PostPlantIn,
+    /// Stop a worker's compose stack when its tmux session is closed by hand
+    #[serde(default = "default_true")]
+    pub tmux_auto_stop: bool,
+    /// Seconds a successful check for tmux or `docker compose` is trusted by
+    /// later commands; 0 re-checks on every command
+    #[serde(default = "default_probe_cache_secs")]
+    pub probe_cache_secs: u64,
+    /// Where worktrees are created instead of `.groot/worktrees/` (`~` is the
+    /// home directory), e.g. on the Linux filesystem when the repo is on a
+    /// Windows drive under WSL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktrees_dir: Option<PathBuf>,
+    /// DevPod provider for groves planted with `--remote devpod`; DevPod's
+    /// default provider when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub devpod_provider: Option<String>,
+    /// Where worker workspaces open: `tmux` (default), `kitty`, or `wezterm`
+    #[serde(default)]
+    pub terminal_backend: TerminalBackend,
+    /// Desktop notifications when slow operations finish or fail
+    #[serde(default, skip_serializing_if = "Notifications::is_default")]
+    pub notifications: Notifications,
+    /// Issue trackers to plant workers from with `groot worker import`
+    #[serde(default, skip_serializing_if = "ImporterConfig::is_default")]
+    pub importers: ImporterConfig,
+}
+
+/// Which finished operations show a desktop notification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Notifications {
+    /// Show notifications at all
+    #[serde(default)]
+    pub enabled: bool,
+    /// Only for operations that took at least this many seconds
+    #[serde(default = "default_notify_after_secs")]
+    pub min_duration_secs: u64,
+    /// Operations to notify about; every one when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Operation>,
+}
+
+/// A slow operation groot can notify about when it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Planting a worker
+    Plant,
+    /// Starting a grove's compose stack until it is healthy
+    ComposeHealthy,
+    /// Cloning a database into a grove
+    DbClone,
+    /// A headless agent run
+    AgentDone,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_duration_secs: default_notify_after_secs(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Notifications {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `op` ending after `took` is worth a notification.
+    pub fn wants(&self, op: Operation, took: std::time::Duration) -> bool {
+        self.enabled
+            && took.as_secs() >= self.min_duration_secs
+            && (self.events.is_empty() || self.events.contains(&op))
+    }
+}
+
+/// `compose_post_start` is either one list for every worker, or lists keyed by
+/// task type with an optional `default` entry for the rest:
+///
+/// ```yaml
+/// compose_post_start:
+///   feature: ["bin/rails db:prepare", "bin/rails assets:precompile"]
+///   default: ["bin/rails db:prepare"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PostStartHooks {
+    All(Vec<String>),
+    ByTaskType(BTreeMap<String, Vec<String>>),
+}
+
+impl Default for PostStartHooks {
+    fn default() -> Self {
+        Self::All(Vec::new())
+    }
+}
+
+impl PostStartHooks {
+    /// The commands for a task type.
+    pub fn for_task_type(&self, task_type: &str) -> &[String] {
+        match self {
+            Self::All(hooks) => hooks,
+            Self::ByTaskType(by_type) => by_type
+                .get(task_type)
+                .or_else(|| by_type.get("default"))
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Where a tree's post-plant commands run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PostPlantIn {
+    /// In the worktree, before the workspace opens; output goes to the log
+    #[default]
+    Worktree,
+    /// Typed into the first pane, where the install can be watched
+    Pane,
+}
+
+fn default_tmux_session_name() -> String {
+    "groot".to_string()
+}
+
+fn default_max_workers() -> usize {
+    4
+}
+
+fn default_min_disk_space_mb() -> u64 {
+    500
 }
 
 fn default_compose_health_timeout_secs() -> u64 {
     60
 }
 
+fn default_probe_cache_secs() -> u64 {
+    30
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_notify_after_secs() -> u64 {
+    30
+}
+
 impl LocalConfig {
+    /// Load `path` with this machine's `local.<hostname>.yml` overlay (see
+    /// `overlay_path`) merged over it.
     pub fn load(path: &Path) -> Result<Self> {
-        if !path.exists() {
+        let mut value = read_yaml(path)?;
+        if let Some(overlay) = overlay_path(path) {
+            merge(&mut value, read_yaml(&overlay)?);
+        }
+        if value.is_null() {
             return Ok(Self::default());
         }
-        let contents = std::fs::read_to_string(path)?;
-        let config: Self = serde_yml::from_str(&contents)?;
+        let config: Self = serde_yml::from_value(value)?;
         Ok(config)
     }
 
@@ -41,14 +212,150 @@ impl LocalConfig {
         Ok(())
     }
 
+    /// The directory worktrees are created in: `worktrees_dir`, or
+    /// `.groot/worktrees/`.
+    pub fn worktrees_root(&self, groot_dir: &Path) -> PathBuf {
+        match &self.worktrees_dir {
+            Some(dir) => match (dir.strip_prefix("~"), dirs::home_dir()) {
+                (Ok(rest), Some(home)) => home.join(rest),
+                _ => groot_dir.join(dir),
+            },
+            None => groot_dir.join("worktrees"),
+        }
+    }
+
+    /// The commands run after planting a tree of `task_type` in `worktree`.
+    pub fn tree_post_plant_for(&self, task_type: &str, worktree: &Path) -> Vec<String> {
+        match &self.tree_post_plant {
+            Some(hooks) => hooks.for_task_type(task_type).to_vec(),
+            None => package_manager::install_commands(worktree),
+        }
+    }
+
     pub fn with_defaults() -> Self {
         Self {
-            tmux_session_name: "groot".to_string(),
-            max_workers: 4,
-            min_disk_space_mb: 500,
+            tmux_session_name: default_tmux_session_name(),
+            max_workers: default_max_workers(),
+            min_disk_space_mb: default_min_disk_space_mb(),
             compose_health_timeout_secs: default_compose_health_timeout_secs(),
-            compose_post_start: Vec::new(),
+            compose_post_start: PostStartHooks::default(),
             compose_db_source: None,
+            tree_post_plant: None,
+            tree_post_plant_in: PostPlantIn::Worktree,
+            tmux_auto_stop: true,
+            probe_cache_secs: default_probe_cache_secs(),
+            worktrees_dir: None,
+            devpod_provider: None,
+            terminal_backend: TerminalBackend::Tmux,
+            notifications: Notifications::default(),
+            importers: ImporterConfig::default(),
+        }
+    }
+}
+
+/// The short hostname overlays are named after: `$GROOT_HOSTNAME`, else the
+/// system hostname up to its first dot.
+pub fn hostname() -> Option<String> {
+    std::env::var("GROOT_HOSTNAME")
+        .ok()
+        .or_else(sysinfo::System::host_name)
+        .map(|h| h.split('.').next().unwrap_or_default().to_lowercase())
+        .filter(|h| !h.is_empty())
+}
+
+/// This machine's overlay for a config file: `local.yml` → `local.<hostname>.yml`
+/// next to it.
+pub fn overlay_path(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    Some(path.with_file_name(format!("{stem}.{}.yml", hostname()?)))
+}
+
+/// A YAML file's contents, or null when it doesn't exist.
+fn read_yaml(path: &Path) -> Result<serde_yml::Value> {
+    if !path.exists() {
+        return Ok(serde_yml::Value::Null);
+    }
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_yml::from_str(&contents)?)
+}
+
+/// Merge `overlay` into `base`: maps merge key by key, anything else
+/// (scalars, lists) is replaced.
+pub fn merge(base: &mut serde_yml::Value, overlay: serde_yml::Value) {
+    match (base, overlay) {
+        (_, serde_yml::Value::Null) => {}
+        (serde_yml::Value::Mapping(base), serde_yml::Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
         }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+impl Default for LocalConfig {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let config: LocalConfig = serde_yml::from_str("max_workers: 2\n").unwrap();
+        assert_eq!(config.max_workers, 2);
+        assert_eq!(config.tmux_session_name, "groot");
+        assert_eq!(config.compose_health_timeout_secs, 60);
+        assert!(config.tmux_auto_stop);
+    }
+
+    #[test]
+    fn test_host_overlay_merges_over_local() {
+        let mut base: serde_yml::Value = serde_yml::from_str(
+            "max_workers: 2\ncompose_post_start:\n  feature: [a]\n  default: [b]\n",
+        )
+        .unwrap();
+        let overlay: serde_yml::Value = serde_yml::from_str(
+            "max_workers: 8\ncompose_post_start:\n  default: [c]\n",
+        )
+        .unwrap();
+        merge(&mut base, overlay);
+
+        let config: LocalConfig = serde_yml::from_value(base).unwrap();
+        assert_eq!(config.max_workers, 8);
+        assert_eq!(config.compose_post_start.for_task_type("feature"), ["a"]);
+        assert_eq!(config.compose_post_start.for_task_type("chore"), ["c"]);
+    }
+
+    #[test]
+    fn test_post_start_hooks_by_task_type() {
+        let config: LocalConfig = serde_yml::from_str(
+            "compose_post_start:\n  feature: [\"db:prepare\", \"assets\"]\n  default: [\"db:prepare\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.compose_post_start.for_task_type("feature"), ["db:prepare", "assets"]);
+        assert_eq!(config.compose_post_start.for_task_type("fix"), ["db:prepare"]);
+
+        let config: LocalConfig = serde_yml::from_str("compose_post_start: [\"seed\"]\n").unwrap();
+        assert_eq!(config.compose_post_start.for_task_type("chore"), ["seed"]);
+    }
+
+    #[test]
+    fn test_notifications_filter_by_event_and_duration() {
+        let config: LocalConfig =
+            serde_yml::from_str("notifications:\n  enabled: true\n  events: [db_clone]\n").unwrap();
+        let long = std::time::Duration::from_secs(90);
+        assert!(config.notifications.wants(Operation::DbClone, long));
+        assert!(!config.notifications.wants(Operation::Plant, long));
+        assert!(!config.notifications.wants(Operation::DbClone, std::time::Duration::from_secs(5)));
+        assert!(!LocalConfig::default().notifications.wants(Operation::DbClone, long));
     }
 }
@@ -1,7 +1,13 @@
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use tracing::warn;
 
 use crate::error::{GrootError, Result};
 
@@ -10,27 +16,196 @@ pub struct FileLock {
     path: PathBuf,
 }
 
+/// Who holds a lock, written into the lock file once it's acquired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub acquired_at: DateTime<Utc>,
+    /// The groot command line that took it
+    pub command: String,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Self {
+            pid: std::process::id(),
+            acquired_at: Utc::now(),
+            command: format!("groot {}", args.join(" ")),
+        }
+    }
+
+    /// The owner recorded in a lock file, if it has one.
+    pub fn read(path: &Path) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Whether the owning process is still running.
+    pub fn is_alive(&self) -> bool {
+        let pid = Pid::from_u32(self.pid);
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        system.process(pid).is_some()
+    }
+}
+
+/// What a lock file on disk amounts to.
+#[derive(Debug, Clone)]
+pub enum LockState {
+    /// Nobody holds it; the file was merely left behind
+    Free,
+    /// Held by a running process (its owner is unknown if it didn't record one)
+    Held(Option<LockOwner>),
+    /// Held according to the file, but its owner is no longer running
+    Stale(LockOwner),
+}
+
 impl FileLock {
     /// Acquire an exclusive lock on a file. Creates parent dirs and file if needed.
+    /// A lock whose recorded owner is no longer running is broken and taken over.
     pub fn acquire(path: &Path) -> Result<Self> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let file = File::create(path)?;
-        file.try_lock_exclusive().map_err(|e| {
-            GrootError::LockFailed(format!("Could not acquire lock on {}: {e}", path.display()))
-        })?;
+        if let Some(lock) = Self::try_acquire(path)? {
+            return Ok(lock);
+        }
+        match LockOwner::read(path) {
+            Some(owner) if !owner.is_alive() => {
+                warn!(
+                    "Breaking stale lock {} held by pid {} ({}), which is no longer running",
+                    path.display(),
+                    owner.pid,
+                    owner.command
+                );
+                let _ = fs::remove_file(path);
+                Self::try_acquire(path)?.ok_or_else(|| held(path, None))
+            }
+            owner => Err(held(path, owner.as_ref())),
+        }
+    }
+
+    /// Like [`acquire`](Self::acquire), but waits up to `timeout` for the
+    /// holder to let go. For locks held only across a quick read-modify-write,
+    /// where a concurrent command should wait its turn rather than fail.
+    pub fn acquire_within(path: &Path, timeout: Duration) -> Result<Self> {
+        let start = Instant::now();
+        loop {
+            match Self::acquire(path) {
+                Err(GrootError::LockFailed(_)) if start.elapsed() < timeout => {
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                result => return result,
+            }
+        }
+    }
 
-        Ok(Self {
-            _file: file,
-            path: path.to_path_buf(),
-        })
+    /// Take the lock if nobody holds it, recording this process as its owner.
+    fn try_acquire(path: &Path) -> Result<Option<Self>> {
+        // A holder that is done removes the file, so a lock taken on a file
+        // that's no longer at `path` is worthless: open it afresh
+        for _ in 0..3 {
+            let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+            if let Err(e) = file.try_lock_exclusive() {
+                if e.kind() == fs2::lock_contended_error().kind() {
+                    return Ok(None);
+                }
+                return Err(GrootError::LockFailed(format!("Could not acquire lock on {}: {e}", path.display())));
+            }
+            if !is_at(&file, path) {
+                continue;
+            }
+            file.set_len(0)?;
+            file.write_all(serde_json::to_string(&LockOwner::current())?.as_bytes())?;
+            return Ok(Some(Self {
+                _file: file,
+                path: path.to_path_buf(),
+            }));
+        }
+        Ok(None)
     }
+}
 
-    pub fn path(&self) -> &Path {
-        &self.path
+// Remove the file while still holding the lock; the lock itself is released
+// when the File is dropped (fs2 behavior)
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
     }
 }
 
-// Lock is released automatically when File is dropped (fs2 behavior)
+fn held(path: &Path, owner: Option<&LockOwner>) -> GrootError {
+    let by = owner.map_or(String::new(), |o| {
+        format!(" (held by pid {}, `{}`, since {})", o.pid, o.command, o.acquired_at.format("%Y-%m-%d %H:%M:%S"))
+    });
+    GrootError::LockFailed(format!("Could not acquire lock on {}{by}", path.display()))
+}
+
+/// Whether `file` is still the file at `path`, not one removed from under it.
+#[cfg(unix)]
+fn is_at(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_at(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+/// Look at a lock file without disturbing whoever holds it.
+pub fn inspect(path: &Path) -> Result<LockState> {
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    if file.try_lock_exclusive().is_ok() {
+        return Ok(LockState::Free);
+    }
+    Ok(match LockOwner::read(path) {
+        Some(owner) if !owner.is_alive() => LockState::Stale(owner),
+        owner => LockState::Held(owner),
+    })
+}
+
+/// Remove a lock file unless a running process holds it. Returns whether it
+/// was removed.
+pub fn clear(path: &Path) -> Result<bool> {
+    match inspect(path)? {
+        LockState::Held(_) => Ok(false),
+        // Take it first, so nobody else gets it between the check and the
+        // removal; dropping the lock removes the file
+        LockState::Free => Ok(FileLock::try_acquire(path)?.is_some()),
+        LockState::Stale(_) => {
+            fs::remove_file(path)?;
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_records_owner_and_breaks_stale_locks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locks").join("task.lock");
+
+        let lock = FileLock::acquire(&path).unwrap();
+        let owner = LockOwner::read(&path).unwrap();
+        assert_eq!(owner.pid, std::process::id());
+        assert!(matches!(inspect(&path).unwrap(), LockState::Held(Some(_))));
+        drop(lock);
+        assert!(!path.exists());
+
+        // Left behind by a process that is gone, still locked (as on NFS)
+        let dead = LockOwner { pid: u32::MAX - 1, ..owner };
+        std::fs::write(&path, serde_json::to_string(&dead).unwrap()).unwrap();
+        let file = File::open(&path).unwrap();
+        file.try_lock_exclusive().unwrap();
+        let lock = FileLock::acquire(&path).unwrap();
+        assert_eq!(LockOwner::read(&lock.path).unwrap().pid, std::process::id());
+    }
+}
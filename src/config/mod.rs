@@ -1,3 +1,14 @@
+pub mod caches;
+pub mod hooks;
+pub mod importers;
 pub mod local;
 pub mod lock;
+pub mod options;
+pub mod preset;
+pub mod profile;
 pub mod project;
+pub mod sandbox;
+pub mod secret;
+pub mod state_dir;
+pub mod validate;
+pub mod webhook;
@@ -0,0 +1,179 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+/// One documented config key, generated from the config structs' schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigOption {
+    /// Dotted path: `profiles.<name>.ttl`, `detection[].glob`
+    pub key: String,
+    /// Human-readable type, e.g. `string`, `list of string`, `tmux | kitty | wezterm`
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub default: Option<String>,
+    pub required: bool,
+    pub description: String,
+}
+
+/// Every key `T` accepts, in declaration order, with nested structs flattened
+/// into dotted keys. Descriptions come from the fields' doc comments.
+pub fn options<T: JsonSchema>() -> Vec<ConfigOption> {
+    let schema = schemars::schema_for!(T);
+    let root = schema.as_value();
+    let mut out = Vec::new();
+    collect(root, root, "", &mut out);
+    out
+}
+
+fn collect(root: &Value, schema: &Value, prefix: &str, out: &mut Vec<ConfigOption>) {
+    let schema = resolve(root, schema);
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    for (name, property) in properties {
+        let key = format!("{prefix}{name}");
+        out.push(ConfigOption {
+            key: key.clone(),
+            ty: type_name(root, property),
+            default: property.get("default").and_then(format_default),
+            required: required.contains(&name.as_str()),
+            description: description(root, property),
+        });
+
+        // Descend into nested structs, map values, and list items
+        let inner = non_null(root, property);
+        if inner.get("properties").is_some() {
+            collect(root, inner, &format!("{key}."), out);
+        } else if let Some(values) = inner.get("additionalProperties").filter(|v| v.is_object()) {
+            collect(root, values, &format!("{key}.<name>."), out);
+        } else if let Some(items) = inner.get("items") {
+            collect(root, items, &format!("{key}[]."), out);
+        }
+    }
+}
+
+/// Follow a `$ref` into `$defs`.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => reference
+            .strip_prefix("#/$defs/")
+            .and_then(|name| root.get("$defs")?.get(name))
+            .unwrap_or(schema),
+        None => schema,
+    }
+}
+
+/// The schema of an optional value without its `null` alternative.
+fn non_null<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    let schema = resolve(root, schema);
+    let variants = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array);
+    match variants.map(|v| v.iter().filter(|s| !is_null(s)).collect::<Vec<_>>()) {
+        Some(rest) if rest.len() == 1 => resolve(root, rest[0]),
+        _ => schema,
+    }
+}
+
+fn is_null(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+fn type_name(root: &Value, schema: &Value) -> String {
+    let schema = resolve(root, schema);
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .filter(|v| !v.is_null())
+            .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(value) = schema.get("const") {
+        return value.as_str().map_or_else(|| value.to_string(), str::to_string);
+    }
+    if let Some(variants) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        return variants
+            .iter()
+            .filter(|v| !is_null(v))
+            .map(|v| type_name(root, v))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).filter(|t| *t != "null").collect(),
+        _ => return "any".to_string(),
+    };
+    types
+        .iter()
+        .map(|t| match *t {
+            "array" => match schema.get("items") {
+                Some(items) => format!("list of {}", type_name(root, items)),
+                None => "list".to_string(),
+            },
+            "object" => match schema.get("additionalProperties").filter(|v| v.is_object()) {
+                Some(values) => format!("map of {}", type_name(root, values)),
+                None => "object".to_string(),
+            },
+            t => t.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// The field's own doc comment, else the doc comment of the type it refers to.
+fn description(root: &Value, schema: &Value) -> String {
+    schema
+        .get("description")
+        .or_else(|| non_null(root, schema).get("description"))
+        .and_then(Value::as_str)
+        .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
+}
+
+fn format_default(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) if s.is_empty() => Some("\"\"".to_string()),
+        Value::String(s) => Some(s.clone()),
+        v => Some(v.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::local::LocalConfig;
+    use crate::config::project::ProjectConfig;
+
+    #[test]
+    fn test_options_come_from_doc_comments() {
+        let local = options::<LocalConfig>();
+        let max_workers = local.iter().find(|o| o.key == "max_workers").unwrap();
+        assert_eq!(max_workers.ty, "integer");
+        assert_eq!(max_workers.default.as_deref(), Some("4"));
+        assert!(!max_workers.description.is_empty());
+
+        let backend = local.iter().find(|o| o.key == "terminal_backend").unwrap();
+        assert_eq!(backend.ty, "tmux | kitty | wezterm | iterm");
+
+        let project = options::<ProjectConfig>();
+        assert!(project.iter().any(|o| o.key == "project_name" && o.required));
+        assert!(project.iter().any(|o| o.key == "profiles.<name>.ttl"));
+        assert!(project.iter().any(|o| o.key == "detection[].file_contains.text"));
+        assert!(project.iter().all(|o| !o.description.is_empty()), "undocumented config key");
+    }
+}
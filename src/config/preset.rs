@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::claude_md;
+use crate::compose::template;
+use crate::config::local;
+use crate::config::project::ProjectConfig;
+use crate::detector::database::DbEngine;
+use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
+use crate::tmux::workspace;
+
+/// Everything a project needs to start with groot, written by `groot init
+/// --preset` in one step: config defaults plus the compose, tmux, CLAUDE.md,
+/// and prompt templates. Team-shared presets (`--from-url`) are YAML documents
+/// of this shape.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Keys merged over the detected `config.yml`, maps key by key
+    #[serde(default)]
+    pub config: serde_yml::Mapping,
+    /// Templates to write, by path under `.groot/` (e.g. `compose-template.yml`,
+    /// `tmux-layouts/review.json`, `prompts/bugfix.md`)
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+/// The presets built into groot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BuiltinPreset {
+    /// Rails with PostgreSQL and Redis in a compose stack
+    RailsPostgres,
+    /// A Node app installed with pnpm, sharing pnpm's store between workers
+    NodePnpm,
+    /// Django with PostgreSQL and Redis in a compose stack
+    Django,
+}
+
+const FEATURE_PROMPT: &str = "Implement the following in {{PROJECT_NAME}} on branch {{BRANCH_NAME}}.
+
+{{DESCRIPTION}}
+
+Work in small commits. Add tests for the new behaviour{{#if TESTS_DETECTED}} and run them with:
+
+{{TEST_COMMANDS}}{{/if}}
+";
+
+const BUGFIX_PROMPT: &str = "Fix a bug in {{PROJECT_NAME}} on branch {{BRANCH_NAME}}.
+
+{{DESCRIPTION}}
+
+Reproduce it with a failing test first, then fix it{{#if TESTS_DETECTED}}. Run the tests with:
+
+{{TEST_COMMANDS}}{{/if}}
+";
+
+const NODE_COMPOSE: &str = r#"services:
+  app:
+    image: node:22
+    container_name: groot-{{WORKER_NAME}}-app
+    working_dir: /app
+    command: ["sh", "-c", "corepack enable && sleep infinity"]
+    ports:
+      - "{{APP_PORT}}:3000"
+    volumes:
+      - "{{WORKTREE_PATH}}:/app"
+    env_file:
+      - path: "{{WORKTREE_PATH}}/.env"
+        required: false
+    environment:
+      - REDIS_URL=redis://redis:6379/0
+      - COREPACK_ENABLE_DOWNLOAD_PROMPT=0
+    depends_on:
+      redis:
+        condition: service_healthy
+    stdin_open: true
+    tty: true
+
+  redis:
+    image: redis:7-alpine
+    container_name: groot-{{WORKER_NAME}}-redis
+    ports:
+      - "{{REDIS_PORT}}:6379"
+    healthcheck:
+      test: ["CMD", "redis-cli", "ping"]
+      interval: 5s
+      timeout: 5s
+      retries: 5
+"#;
+
+const NODE_LAYOUT: &str = r#"{
+  "windows": [
+    {
+      "name": "dev",
+      "layout": "main-vertical",
+      "focus": true,
+      "panes": [
+        { "command": "claude", "host": true, "focus": true },
+        { "command": "pnpm install && pnpm dev" },
+        { "command": null }
+      ]
+    },
+    {
+      "name": "test",
+      "layout": "tiled",
+      "panes": [{ "command": "pnpm test --watch" }]
+    }
+  ]
+}
+"#;
+
+const DJANGO_LAYOUT: &str = r#"{
+  "windows": [
+    {
+      "name": "server",
+      "layout": "tiled",
+      "panes": [
+        { "command": "python manage.py runserver 0.0.0.0:8000" },
+        { "command": "python manage.py shell" }
+      ]
+    },
+    {
+      "name": "editor",
+      "layout": "main-vertical",
+      "focus": true,
+      "panes": [
+        { "command": "claude", "host": true, "focus": true },
+        { "command": null }
+      ]
+    }
+  ]
+}
+"#;
+
+impl BuiltinPreset {
+    pub fn preset(self) -> Preset {
+        let (name, description, config, files) = match self {
+            Self::RailsPostgres => (
+                "rails-postgres",
+                "Rails with PostgreSQL and Redis in a compose stack",
+                "container_enabled: true\ndatabase: postgres\n",
+                vec![
+                    ("compose-template.yml", template::default_template_for(Some("rails"), DbEngine::Postgres)),
+                    (
+                        "tmux-layout.json",
+                        serde_json::to_string_pretty(&workspace::default_template()).unwrap_or_default() + "\n",
+                    ),
+                ],
+            ),
+            Self::NodePnpm => (
+                "node-pnpm",
+                "A Node app installed with pnpm, sharing pnpm's store between workers",
+                "caches:\n  node_modules: pnpm-store\n",
+                vec![
+                    ("compose-template.yml", NODE_COMPOSE.to_string()),
+                    ("tmux-layout.json", NODE_LAYOUT.to_string()),
+                ],
+            ),
+            Self::Django => (
+                "django",
+                "Django with PostgreSQL and Redis in a compose stack",
+                "container_enabled: true\ndatabase: postgres\n",
+                vec![
+                    ("compose-template.yml", template::default_template_for(Some("django"), DbEngine::Postgres)),
+                    ("tmux-layout.json", DJANGO_LAYOUT.to_string()),
+                ],
+            ),
+        };
+        let mut files: BTreeMap<String, String> = files.into_iter().map(|(p, c)| (p.to_string(), c)).collect();
+        files.insert("claude-md.template".to_string(), claude_md::default_template().to_string());
+        files.insert("prompts/feature.md".to_string(), FEATURE_PROMPT.to_string());
+        files.insert("prompts/bugfix.md".to_string(), BUGFIX_PROMPT.to_string());
+        Preset {
+            name: name.to_string(),
+            description: description.to_string(),
+            config: serde_yml::from_str(config).unwrap_or_default(),
+            files,
+        }
+    }
+}
+
+impl Preset {
+    /// Parse a preset document, checking where its files would go.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let preset: Preset = serde_yml::from_str(contents)?;
+        for path in preset.files.keys() {
+            check_path(path)?;
+        }
+        Ok(preset)
+    }
+
+    /// Download a team-shared preset with `curl`.
+    pub fn fetch(url: &str) -> Result<Self> {
+        let output = Command::new("curl")
+            .args(["--fail", "--silent", "--show-error", "--location", "--max-time", "30", url])
+            .checked_output(&format!("Downloading the preset from {url}"))?;
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+            .map_err(|e| GrootError::Other(format!("Invalid preset at {url}: {e}")))
+    }
+
+    /// `config` with the preset's settings merged over it.
+    pub fn apply_config(&self, config: &ProjectConfig) -> Result<ProjectConfig> {
+        let mut merged = serde_yml::to_value(config)?;
+        local::merge(&mut merged, serde_yml::Value::Mapping(self.config.clone()));
+        serde_yml::from_value(merged)
+            .map_err(|e| GrootError::Other(format!("Preset '{}' has invalid config: {e}", self.name)))
+    }
+
+    /// Write the preset's files under `groot_dir`, leaving any that exist
+    /// alone. Returns the paths written.
+    pub fn write_files(&self, groot_dir: &Path) -> Result<Vec<String>> {
+        let mut written = Vec::new();
+        for (rel, contents) in &self.files {
+            check_path(rel)?;
+            let path = groot_dir.join(rel);
+            if path.exists() {
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, contents)?;
+            written.push(rel.clone());
+        }
+        Ok(written)
+    }
+}
+
+/// A preset may only write templates inside the state directory, and not the
+/// config files `init` writes itself.
+fn check_path(rel: &str) -> Result<()> {
+    let path = Path::new(rel);
+    let inside = !rel.is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)));
+    let reserved = ["config.yml", "local.yml"].contains(&rel) || rel.starts_with("local.");
+    if !inside || reserved {
+        return Err(GrootError::Other(format!(
+            "Preset file '{rel}' must be a relative path under the state directory other than config.yml and local.yml"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_presets_apply() {
+        for builtin in [BuiltinPreset::RailsPostgres, BuiltinPreset::NodePnpm, BuiltinPreset::Django] {
+            let preset = builtin.preset();
+            let config = preset.apply_config(&ProjectConfig::default()).unwrap();
+            let layout: workspace::WorkspaceTemplate =
+                serde_json::from_str(&preset.files["tmux-layout.json"]).unwrap();
+            assert!(!layout.windows.is_empty(), "{}", preset.name);
+            assert!(preset.files["compose-template.yml"].contains("{{WORKTREE_PATH}}:/app"));
+            if builtin == BuiltinPreset::NodePnpm {
+                assert_eq!(config.caches.node_modules, crate::config::caches::NodeModules::PnpmStore);
+            } else {
+                assert!(config.container_enabled);
+                assert_eq!(config.database, Some(DbEngine::Postgres));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_paths_outside() {
+        let preset = Preset::parse("name: team\nfiles:\n  prompts/review.md: Review it\n").unwrap();
+        assert_eq!(preset.files["prompts/review.md"], "Review it");
+        assert!(Preset::parse("name: team\nfiles:\n  ../.bashrc: x\n").is_err());
+        assert!(Preset::parse("name: team\nfiles:\n  /etc/passwd: x\n").is_err());
+        assert!(Preset::parse("name: team\nfiles:\n  local.yml: x\n").is_err());
+    }
+}
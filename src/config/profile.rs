@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GrootError, Result};
+
+/// Settings applied automatically when planting a worker of one task type
+/// (`profiles.<type>` in `.groot/config.yml`). Command-line flags win over
+/// anything set here.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Profile {
+    /// Whether `groot worker spawn` plants a grove (with compose) or a bare tree;
+    /// `container_enabled` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compose: Option<bool>,
+    /// How a grove's database is populated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db: Option<DbStrategy>,
+    /// Source database URL for `db: transplant`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_source: Option<String>,
+    /// Workspace layout from `.groot/tmux-layouts/<name>.json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    /// Prompt template (relative to the repo root) claude is launched with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_file: Option<PathBuf>,
+    /// Run the agent in its own container behind an allowlisting proxy
+    /// (implies `compose`); see `sandbox` in config.yml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<bool>,
+    /// How long the worker is expected to live, e.g. `30m`, `12h`, `3d`;
+    /// expired workers are stopped by `groot worker reap`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<String>,
+}
+
+/// How a grove's database is populated at plant time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DbStrategy {
+    /// An empty database prepared by the project's setup command (the default)
+    #[default]
+    Fresh,
+    /// A clone of the host's development database (like `--transplant`)
+    Transplant,
+}
+
+impl Profile {
+    /// The profile's TTL as a duration, if set.
+    pub fn ttl(&self) -> Result<Option<chrono::Duration>> {
+        self.ttl.as_deref().map(parse_ttl).transpose()
+    }
+}
+
+/// Parse a TTL like `45m`, `12h`, `3d`, or `1w`.
+pub fn parse_ttl(ttl: &str) -> Result<chrono::Duration> {
+    let ttl = ttl.trim();
+    let split = ttl.find(|c: char| !c.is_ascii_digit()).unwrap_or(ttl.len());
+    let (amount, unit) = ttl.split_at(split);
+    let invalid = || {
+        GrootError::Other(format!(
+            "Invalid ttl '{ttl}': expected a number followed by m, h, d, or w (e.g. 12h)"
+        ))
+    };
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "m" => Ok(chrono::Duration::minutes(amount)),
+        "h" => Ok(chrono::Duration::hours(amount)),
+        "d" => Ok(chrono::Duration::days(amount)),
+        "w" => Ok(chrono::Duration::weeks(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_from_config() {
+        let profile: Profile = serde_yml::from_str(
+            "compose: false\ndb: transplant\nlayout: review\nttl: 2d\n",
+        )
+        .unwrap();
+        assert_eq!(profile.compose, Some(false));
+        assert_eq!(profile.db, Some(DbStrategy::Transplant));
+        assert_eq!(profile.ttl().unwrap(), Some(chrono::Duration::days(2)));
+
+        assert_eq!(parse_ttl("90m").unwrap(), chrono::Duration::minutes(90));
+        assert!(parse_ttl("2 days").is_err());
+        assert!(parse_ttl("h").is_err());
+    }
+}
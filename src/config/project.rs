@@ -1,20 +1,85 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::config::caches::CacheConfig;
+use crate::config::profile::Profile;
+use crate::config::sandbox::SandboxConfig;
+use crate::config::webhook::Webhook;
+use crate::detector::PackageDetection;
+use crate::detector::database::DbEngine;
+use crate::detector::primary::{self, PrimaryType};
+use crate::detector::rules::{self, DetectionRule};
 use crate::error::Result;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, JsonSchema)]
 pub struct ProjectConfig {
+    /// Prefix for worker branch names (`<project>/<type>/<task>`)
     pub project_name: String,
+    /// Project types found by `groot detect` (e.g. `rails`, `node`)
     #[serde(default)]
     pub detected_types: Vec<String>,
+    /// The type templates and CLAUDE.md are chosen by, with its confidence score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_type: Option<PrimaryType>,
+    /// Whether workers get a compose stack by default
     #[serde(default)]
     pub container_enabled: bool,
+    /// The repository's main branch, recorded by `groot init`
     #[serde(default)]
     pub default_branch: String,
+    /// Branch new workers are created from and compared against;
+    /// `default_branch` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+    /// Branches workers may never be planted on (globs such as `release/*`).
+    /// The base and default branches are always protected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_branches: Vec<String>,
+    /// Database engine the project uses; built-in compose templates and
+    /// database cloning assume Postgres when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<DbEngine>,
+    /// Monorepo tooling found at the root (e.g. `pnpm-workspaces`, `turborepo`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspace_tools: Vec<String>,
+    /// Per-package detections for monorepos
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageDetection>,
+    /// User-defined detection rules applied on top of the built-in patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detection: Vec<DetectionRule>,
+    /// Untracked files copied from the repo root into new worktrees (globs);
+    /// `Dockerfile.dev`, `Dockerfile.groot`, `.env`, and `config/master.key` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_copy_files: Option<Vec<String>>,
+    /// Directories created in new worktrees (e.g. `tmp/pids`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worktree_create_dirs: Vec<String>,
+    /// Settings applied when planting a worker, keyed by task type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Run compose-less workers' pane commands and post-plant hooks inside
+    /// the flake's devshell (`nix develop -c ...`); set by `groot init` when
+    /// it finds a `flake.nix`
+    #[serde(default)]
+    pub nix_develop: bool,
+    /// Dependency caches mounted into groves' app service
+    #[serde(default, skip_serializing_if = "CacheConfig::is_default")]
+    pub caches: CacheConfig,
+    /// How sandboxed workers (`--sandbox`, or `sandbox: true` in a profile) run their agent
+    #[serde(default, skip_serializing_if = "SandboxConfig::is_default")]
+    pub sandbox: SandboxConfig,
+    /// URLs sent a JSON payload on worker lifecycle events
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<Webhook>,
 }
 
+/// Files every worktree needs but git doesn't carry.
+pub const DEFAULT_COPY_FILES: &[&str] = &["Dockerfile.dev", "Dockerfile.groot", ".env", "config/master.key"];
+
 impl ProjectConfig {
     pub fn load(path: &Path) -> Result<Self> {
         let contents = std::fs::read_to_string(path)?;
@@ -22,9 +87,78 @@ impl ProjectConfig {
         Ok(config)
     }
 
+    /// The primary type name: the recorded one, or one scored from
+    /// `detected_types` for configs written before it was recorded.
+    pub fn primary(&self) -> Option<String> {
+        self.primary_type
+            .clone()
+            .or_else(|| primary::from_names(&self.detected_types))
+            .map(|p| p.name)
+    }
+
+    /// Whether compose-less workers in `worktree` run in its flake's devshell.
+    pub fn uses_nix(&self, worktree: &Path) -> bool {
+        self.nix_develop && worktree.join("flake.nix").exists()
+    }
+
+    /// The branch workers start from: `base_branch`, else `default_branch`, else `main`.
+    pub fn base_branch(&self) -> &str {
+        self.base_branch
+            .as_deref()
+            .filter(|b| !b.is_empty())
+            .or(Some(self.default_branch.as_str()).filter(|b| !b.is_empty()))
+            .unwrap_or("main")
+    }
+
+    /// Whether a worker must not be planted on `branch`.
+    pub fn is_protected(&self, branch: &str) -> bool {
+        branch == self.base_branch()
+            || branch == self.default_branch
+            || self
+                .protected_branches
+                .iter()
+                .any(|pattern| rules::wildcard_match(pattern, branch))
+    }
+
+    /// Globs of files to copy into new worktrees.
+    pub fn copy_files(&self) -> Vec<&str> {
+        match &self.worktree_copy_files {
+            Some(files) => files.iter().map(String::as_str).collect(),
+            None => DEFAULT_COPY_FILES.to_vec(),
+        }
+    }
+
+    /// The profile for a task type, or an empty one when none is configured.
+    pub fn profile(&self, task_type: &str) -> Profile {
+        self.profiles.get(task_type).cloned().unwrap_or_default()
+    }
+
     pub fn save(&self, path: &Path) -> Result<()> {
         let contents = serde_yml::to_string(self)?;
         std::fs::write(path, contents)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_and_protected_branches() {
+        let mut config = ProjectConfig {
+            default_branch: "master".to_string(),
+            protected_branches: vec!["release/*".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.base_branch(), "master");
+        assert!(config.is_protected("master"));
+        assert!(config.is_protected("release/2.1"));
+        assert!(!config.is_protected("app/feature/login"));
+
+        config.base_branch = Some("develop".to_string());
+        assert_eq!(config.base_branch(), "develop");
+        assert!(config.is_protected("develop"));
+        assert_eq!(ProjectConfig::default().base_branch(), "main");
+    }
+}
@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Hosts a sandboxed agent can always reach: the Anthropic API.
+pub const DEFAULT_ALLOWED_HOSTS: &[&str] = &["anthropic.com"];
+
+/// How sandboxed workers run their agent (`sandbox:` in `.groot/config.yml`):
+/// in an `agent` container next to the app, whose only way out is a proxy
+/// that allows the listed hosts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SandboxConfig {
+    /// Hosts the agent may reach (subdomains included), besides anthropic.com,
+    /// e.g. `github.com`, `registry.npmjs.org`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// Image the agent runs in, which must have `claude` installed;
+    /// the app service's image when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Image of the allowlisting proxy; `ubuntu/squid:latest` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_image: Option<String>,
+}
+
+impl SandboxConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Every host the proxy lets through.
+    pub fn hosts(&self) -> Vec<&str> {
+        DEFAULT_ALLOWED_HOSTS
+            .iter()
+            .copied()
+            .chain(self.allowed_hosts.iter().map(String::as_str))
+            .collect()
+    }
+}
@@ -0,0 +1,61 @@
+use crate::error::{GrootError, Result};
+
+/// Prefix marking a config value as a reference to an OS keyring entry.
+pub const KEYRING_PREFIX: &str = "keyring:";
+
+/// Keyring service every groot secret is stored under.
+const SERVICE: &str = "groot";
+
+/// The keyring entry name a config value refers to, if it is a `keyring:<name>` reference.
+pub fn keyring_name(value: &str) -> Option<&str> {
+    value.strip_prefix(KEYRING_PREFIX).map(str::trim)
+}
+
+/// Resolve a config value: `keyring:<name>` is looked up in the OS keyring,
+/// anything else is returned as-is.
+pub fn resolve(value: &str) -> Result<String> {
+    let Some(name) = keyring_name(value) else {
+        return Ok(value.to_string());
+    };
+    entry(name)?.get_password().map_err(|e| match e {
+        keyring::Error::NoEntry => GrootError::Other(format!(
+            "Secret '{name}' is not in the keyring. Store it with: groot config secret set {name}"
+        )),
+        e => GrootError::Other(format!("Failed to read secret '{name}' from the keyring: {e}")),
+    })
+}
+
+/// Store a secret in the OS keyring under `name`.
+pub fn store(name: &str, secret: &str) -> Result<()> {
+    entry(name)?
+        .set_password(secret)
+        .map_err(|e| GrootError::Other(format!("Failed to store secret '{name}' in the keyring: {e}")))
+}
+
+/// Remove a secret from the OS keyring.
+pub fn delete(name: &str) -> Result<()> {
+    entry(name)?.delete_credential().map_err(|e| match e {
+        keyring::Error::NoEntry => GrootError::Other(format!("Secret '{name}' is not in the keyring")),
+        e => GrootError::Other(format!("Failed to delete secret '{name}' from the keyring: {e}")),
+    })
+}
+
+fn entry(name: &str) -> Result<keyring::Entry> {
+    if name.is_empty() {
+        return Err(GrootError::Other("Secret name is empty".to_string()));
+    }
+    keyring::Entry::new(SERVICE, name)
+        .map_err(|e| GrootError::Other(format!("Keyring is not available: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_values_pass_through() {
+        assert_eq!(keyring_name("keyring:staging-db"), Some("staging-db"));
+        assert_eq!(keyring_name("postgres://localhost/app"), None);
+        assert_eq!(resolve("postgres://localhost/app").unwrap(), "postgres://localhost/app");
+    }
+}
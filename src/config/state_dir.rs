@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
+use crate::store;
+
+/// State directory name compiled in: `GROOT_STATE_DIR` at build time, else `.groot`.
+pub const DEFAULT_NAME: &str = match option_env!("GROOT_STATE_DIR") {
+    Some(name) => name,
+    None => ".groot",
+};
+
+/// Names earlier builds used for the state directory; `groot migrate` moves
+/// any of these to the current name.
+pub const LEGACY_NAMES: &[&str] = &[".groot", ".devflow", ".treehouse"];
+
+/// Environment variable that overrides the state directory name at runtime.
+pub const ENV_VAR: &str = "GROOT_STATE_DIR";
+
+/// The state directory name: `$GROOT_STATE_DIR`, else the compiled-in default.
+/// Only a single path component is accepted.
+pub fn name() -> String {
+    std::env::var(ENV_VAR)
+        .ok()
+        .filter(|n| is_valid_name(n))
+        .unwrap_or_else(|| DEFAULT_NAME.to_string())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+/// Initialized state directories under `root` that use another name than the
+/// current one.
+pub fn legacy_dirs(root: &Path) -> Vec<String> {
+    let current = name();
+    LEGACY_NAMES
+        .iter()
+        .filter(|n| **n != current && root.join(n).join("config.yml").exists())
+        .map(|n| n.to_string())
+        .collect()
+}
+
+/// What `migrate` did.
+pub struct Migration {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    /// Worktrees whose git metadata was repaired after the move
+    pub worktrees: usize,
+    /// `.gitignore` lines rewritten to the new name
+    pub gitignore_lines: usize,
+}
+
+/// Move the state directory `from` (a name under `root`) to the current name,
+/// repair the worktrees inside it, and rewrite `.gitignore` entries.
+///
+/// Refuses while workers are running, since their compose files and state
+/// hold absolute paths. If the target already exists (a mixed install), the
+/// old directory's entries are merged in as long as none of them collide.
+pub fn migrate(root: &Path, from: &str) -> Result<Migration> {
+    let to_name = name();
+    if from == to_name {
+        return Err(GrootError::Other(format!("'{from}' is already the state directory")));
+    }
+    let source = root.join(from);
+    let target = root.join(&to_name);
+    if !source.join("config.yml").exists() {
+        return Err(GrootError::Other(format!(
+            "{} is not a state directory (no config.yml)",
+            source.display()
+        )));
+    }
+
+    let active: Vec<String> = store::open(&source)?
+        .list_workers()?
+        .into_iter()
+        .map(|w| w.task_name)
+        .collect();
+    if !active.is_empty() {
+        return Err(GrootError::Other(format!(
+            "Workers are still planted in {from}: {}. Stop them first (worktrees and branches are kept).",
+            active.join(", ")
+        )));
+    }
+
+    if target.exists() {
+        let entries: Vec<_> = std::fs::read_dir(&source)?.flatten().collect();
+        let collisions: Vec<String> = entries
+            .iter()
+            .map(|e| e.file_name())
+            .filter(|n| target.join(n).exists() && !is_empty_dir(&target.join(n)))
+            .map(|n| n.to_string_lossy().to_string())
+            .collect();
+        if !collisions.is_empty() {
+            return Err(GrootError::Other(format!(
+                "Both {from} and {to_name} contain: {}. Merge them by hand, then remove {from}.",
+                collisions.join(", ")
+            )));
+        }
+        for entry in entries {
+            let dest = target.join(entry.file_name());
+            if dest.exists() {
+                std::fs::remove_dir(&dest)?;
+            }
+            std::fs::rename(entry.path(), dest)?;
+        }
+        std::fs::remove_dir(&source)?;
+    } else {
+        std::fs::rename(&source, &target)?;
+    }
+
+    let worktrees: Vec<PathBuf> = std::fs::read_dir(target.join("worktrees"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    if !worktrees.is_empty() {
+        let output = Command::new("git")
+            .arg("worktree")
+            .arg("repair")
+            .args(&worktrees)
+            .current_dir(root)
+            .logged_output()?;
+        if !output.status.success() {
+            return Err(GrootError::GitCommand(format!(
+                "git worktree repair failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+
+    let gitignore_lines = rename_gitignore_entries(&root.join(".gitignore"), from, &to_name)?;
+
+    Ok(Migration {
+        from: source,
+        to: target,
+        worktrees: worktrees.len(),
+        gitignore_lines,
+    })
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    std::fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+}
+
+/// Point `.gitignore` entries for `from` (and paths under it) at `to`.
+/// Returns how many lines changed.
+fn rename_gitignore_entries(path: &Path, from: &str, to: &str) -> Result<usize> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Ok(0);
+    };
+
+    let mut changed = 0;
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        let (prefix, rest) = line.split_at(line.starts_with('/') as usize);
+        let renamed = rest
+            .strip_prefix(from)
+            .filter(|tail| tail.is_empty() || tail.starts_with('/'))
+            .map(|tail| format!("{prefix}{to}{tail}"));
+        match renamed {
+            Some(new_line) => {
+                changed += 1;
+                // Drop entries the new name already has
+                if !contents.lines().any(|l| l == new_line) && !lines.contains(&new_line) {
+                    lines.push(new_line);
+                }
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    if changed > 0 {
+        let mut new_contents = lines.join("\n");
+        new_contents.push('\n');
+        std::fs::write(path, new_contents)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_gitignore_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+        std::fs::write(
+            &path,
+            "target/\n.devflow/worktrees/\n/.devflow/local.yml\n.groot/local.yml\n.devflowrc\n",
+        )
+        .unwrap();
+
+        assert_eq!(rename_gitignore_entries(&path, ".devflow", ".groot").unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "target/\n.groot/worktrees/\n/.groot/local.yml\n.groot/local.yml\n.devflowrc\n"
+        );
+        assert!(!is_valid_name("a/b"));
+    }
+}
@@ -0,0 +1,385 @@
+use std::path::Path;
+
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+
+use crate::compose::{ports::AllocatedPorts, template as compose_template};
+use crate::config::hooks::{HooksConfig, Lifecycle};
+use crate::config::local::LocalConfig;
+use crate::config::profile::Profile;
+use crate::config::project::ProjectConfig;
+use crate::config::caches::CacheConfig;
+use crate::config::sandbox::SandboxConfig;
+use crate::config::secret;
+use crate::config::webhook::Webhook;
+use crate::detector::rules::DetectionRule;
+use crate::tmux::workspace::{self, PaneTemplate, WindowTemplate, WorkspaceTemplate};
+
+/// A deserializer that only records the field names a struct asks for.
+struct FieldProbe<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldProbe<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("probed"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// The keys a config struct accepts — what `deny_unknown_fields` would check against.
+pub fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldProbe(&mut fields));
+    fields
+}
+
+/// Report keys of a YAML mapping that `T` doesn't know, with a suggestion for typos.
+pub fn unknown_keys<T: DeserializeOwned>(value: &serde_yml::Value, context: &str) -> Vec<String> {
+    let known = struct_fields::<T>();
+    let Some(map) = value.as_mapping() else {
+        return Vec::new();
+    };
+    map.keys()
+        .filter_map(|k| k.as_str())
+        .filter(|k| !known.contains(k))
+        .map(|key| {
+            let suggestion = known
+                .iter()
+                .map(|k| (k, strsim::jaro_winkler(key, k)))
+                .filter(|(_, score)| *score > 0.8)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(k, _)| format!(" (did you mean '{k}'?)"))
+                .unwrap_or_default();
+            format!("{context}unknown key '{key}'{suggestion}")
+        })
+        .collect()
+}
+
+/// Parse a YAML config file, reporting syntax errors, unknown keys, and values
+/// of the wrong type. Returns the typed config when it parses.
+fn check_yaml<T: DeserializeOwned>(path: &Path, problems: &mut Vec<String>) -> Option<T> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            problems.push(format!("cannot read: {e}"));
+            return None;
+        }
+    };
+    let value: serde_yml::Value = match serde_yml::from_str(&contents) {
+        Ok(v) => v,
+        Err(e) => {
+            problems.push(format!("invalid YAML: {e}"));
+            return None;
+        }
+    };
+    problems.extend(unknown_keys::<T>(&value, ""));
+    match serde_yml::from_value(value) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            problems.push(format!("bad value: {e}"));
+            None
+        }
+    }
+}
+
+/// Validate `.groot/config.yml`.
+pub fn validate_project(path: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(config) = check_yaml::<ProjectConfig>(path, &mut problems) else {
+        return problems;
+    };
+
+    if config.project_name.trim().is_empty() {
+        problems.push("project_name is empty".to_string());
+    }
+
+    // Detection rules are nested structs: check their keys too
+    let raw: serde_yml::Value = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_yml::from_str(&c).ok())
+        .unwrap_or_default();
+    if let Some(rules) = raw.get("detection").and_then(|d| d.as_sequence()) {
+        for (i, rule) in rules.iter().enumerate() {
+            problems.extend(unknown_keys::<DetectionRule>(rule, &format!("detection[{i}]: ")));
+        }
+    }
+    for (i, rule) in config.detection.iter().enumerate() {
+        if rule.file_exists.is_none() && rule.file_contains.is_none() && rule.glob.is_none() {
+            problems.push(format!(
+                "detection[{i}]: rule '{}' has no conditions and never matches",
+                rule.project_type
+            ));
+        }
+    }
+
+    if let Some(caches) = raw.get("caches") {
+        problems.extend(unknown_keys::<CacheConfig>(caches, "caches: "));
+    }
+    for kind in &config.caches.exclude {
+        if !["bundle", "node_modules", "cargo", "go"].contains(&kind.as_str()) {
+            problems.push(format!("caches.exclude: unknown cache '{kind}' (bundle, node_modules, cargo, or go)"));
+        }
+    }
+    if let Some(sandbox) = raw.get("sandbox") {
+        problems.extend(unknown_keys::<SandboxConfig>(sandbox, "sandbox: "));
+    }
+    if let Some(hooks) = raw.get("webhooks").and_then(|h| h.as_sequence()) {
+        for (i, hook) in hooks.iter().enumerate() {
+            problems.extend(unknown_keys::<Webhook>(hook, &format!("webhooks[{i}]: ")));
+        }
+    }
+    for (i, hook) in config.webhooks.iter().enumerate() {
+        problems.extend(check_url(&format!("webhooks[{i}].url"), &hook.url));
+    }
+    if let Some(profiles) = raw.get("profiles").and_then(|p| p.as_mapping()) {
+        for (name, profile) in profiles {
+            let name = name.as_str().unwrap_or_default();
+            problems.extend(unknown_keys::<Profile>(profile, &format!("profiles.{name}: ")));
+        }
+    }
+    let groot_dir = path.parent().unwrap_or(Path::new("."));
+    let repo_root = groot_dir.parent().unwrap_or(Path::new("."));
+    for (name, profile) in &config.profiles {
+        if let Some(source) = &profile.db_source {
+            problems.extend(check_url(&format!("profiles.{name}.db_source"), source));
+        }
+        if let Err(e) = profile.ttl() {
+            problems.push(format!("profiles.{name}: {e}"));
+        }
+        if let Some(file) = profile.prompt_file.as_ref().filter(|f| !repo_root.join(f).exists()) {
+            problems.push(format!("profiles.{name}: prompt_file '{}' does not exist", file.display()));
+        }
+        if let Some(layout) = profile
+            .layout
+            .as_ref()
+            .filter(|l| !workspace::named_template_path(groot_dir, l).is_ok_and(|p| p.exists()))
+        {
+            problems.push(format!("profiles.{name}: layout '{layout}' not found in .groot/tmux-layouts/"));
+        }
+    }
+
+    problems
+}
+
+/// Check a URL setting, resolving `keyring:<name>` references first.
+fn check_url(key: &str, value: &str) -> Option<String> {
+    match secret::resolve(value) {
+        Err(e) => Some(format!("{key}: {e}")),
+        Ok(url) if url::Url::parse(&url).is_err() => Some(match secret::keyring_name(value) {
+            // Don't echo a secret's value back
+            Some(name) => format!("{key}: keyring secret '{name}' is not a URL"),
+            None => format!("{key} is not a URL: '{value}'"),
+        }),
+        Ok(_) => None,
+    }
+}
+
+/// Check that a secret setting's `keyring:<name>` reference resolves.
+fn check_secret(key: &str, value: &str) -> Option<String> {
+    secret::resolve(value).err().map(|e| format!("{key}: {e}"))
+}
+
+/// Validate `.groot/local.yml`.
+pub fn validate_local(path: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(config) = check_yaml::<LocalConfig>(path, &mut problems) else {
+        return problems;
+    };
+
+    if config.tmux_session_name.trim().is_empty() {
+        problems.push("tmux_session_name is empty".to_string());
+    }
+    if config.max_workers == 0 {
+        problems.push("max_workers is 0: no worker could be planted".to_string());
+    }
+    if config.compose_health_timeout_secs == 0 {
+        problems.push("compose_health_timeout_secs is 0".to_string());
+    }
+    if let Some(source) = &config.compose_db_source {
+        problems.extend(check_url("compose_db_source", source));
+    }
+    if let Some(linear) = &config.importers.linear {
+        problems.extend(check_secret("importers.linear.api_key", &linear.api_key));
+    }
+    if let Some(jira) = &config.importers.jira {
+        problems.extend(check_url("importers.jira.url", &jira.url));
+        problems.extend(check_secret("importers.jira.api_token", &jira.api_token));
+    }
+    if !config.terminal_backend.is_available() {
+        problems.push(format!(
+            "terminal_backend '{}' is not installed",
+            config.terminal_backend.name()
+        ));
+    }
+
+    problems
+}
+
+/// Validate `.groot/hooks.yml`.
+pub fn validate_hooks(path: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(hooks) = check_yaml::<HooksConfig>(path, &mut problems) else {
+        return problems;
+    };
+    for lifecycle in [Lifecycle::Create, Lifecycle::Active, Lifecycle::Complete, Lifecycle::Close] {
+        if hooks.commands(lifecycle).iter().any(|c| c.trim().is_empty()) {
+            problems.push(format!("{} has an empty command", lifecycle.key()));
+        }
+    }
+    problems
+}
+
+/// Validate `.groot/compose-template.yml`: it must render to YAML with a
+/// `services` mapping, and the Dockerfiles it builds from must exist.
+pub fn validate_compose_template(path: &Path, repo_root: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+    let template = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) => return vec![format!("cannot read: {e}")],
+    };
+
+    let ports = AllocatedPorts {
+        app: 3001,
+        db: 5433,
+        redis: 6380,
+    };
+    let root = repo_root.to_string_lossy();
+    let rendered = compose_template::render(
+        &template,
+        &compose_template::TemplateVars {
+            worker_name: "example-task",
+            worktree_path: &root,
+            ports: &ports,
+        },
+    );
+
+    let leftover: Vec<&str> = rendered
+        .match_indices("{{")
+        .filter_map(|(i, _)| rendered[i + 2..].split_once("}}").map(|(name, _)| name.trim()))
+        .collect();
+    for name in leftover {
+        problems.push(format!(
+            "unknown placeholder {{{{{name}}}}} (known: WORKER_NAME, WORKTREE_PATH, APP_PORT, DB_PORT, REDIS_PORT)"
+        ));
+    }
+
+    let doc: serde_yml::Value = match serde_yml::from_str(&rendered) {
+        Ok(v) => v,
+        Err(e) => {
+            problems.push(format!("invalid YAML after rendering: {e}"));
+            return problems;
+        }
+    };
+    let Some(services) = doc.get("services").and_then(|s| s.as_mapping()) else {
+        problems.push("no 'services' mapping".to_string());
+        return problems;
+    };
+
+    for (name, service) in services {
+        let name = name.as_str().unwrap_or("?");
+        let Some(build) = service.get("build") else {
+            continue;
+        };
+        let (context, dockerfile) = match build {
+            serde_yml::Value::String(context) => (context.as_str(), "Dockerfile"),
+            _ => (
+                build.get("context").and_then(|c| c.as_str()).unwrap_or("."),
+                build
+                    .get("dockerfile")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or("Dockerfile"),
+            ),
+        };
+        let context = repo_root.join(context);
+        if !context.join(dockerfile).exists() {
+            problems.push(format!(
+                "service '{name}' builds from {} which does not exist",
+                context.join(dockerfile).display()
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Validate a tmux layout JSON file: unknown keys at every level, then the
+/// same structural checks `groot tmux validate-template` runs.
+pub fn validate_layout(path: &Path) -> Vec<String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => return vec![format!("cannot read: {e}")],
+    };
+    let value: serde_yml::Value = match serde_json::from_str(&contents) {
+        Ok(v) => v,
+        Err(e) => return vec![format!("invalid JSON: {e}")],
+    };
+
+    let mut problems = unknown_keys::<WorkspaceTemplate>(&value, "");
+    let windows = value.get("windows").and_then(|w| w.as_sequence());
+    for (i, window) in windows.into_iter().flatten().enumerate() {
+        problems.extend(unknown_keys::<WindowTemplate>(window, &format!("windows[{i}]: ")));
+        let panes = window.get("panes").and_then(|p| p.as_sequence());
+        for (j, pane) in panes.into_iter().flatten().enumerate() {
+            problems.extend(unknown_keys::<PaneTemplate>(
+                pane,
+                &format!("windows[{i}].panes[{j}]: "),
+            ));
+        }
+    }
+
+    match serde_yml::from_value::<WorkspaceTemplate>(value) {
+        Ok(template) => problems.extend(workspace::validate_template(&template)),
+        Err(e) => problems.push(format!("bad value: {e}")),
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_keys_suggest_fixes() {
+        let value: serde_yml::Value =
+            serde_yml::from_str("max_wokers: 4\ntmux_session_name: groot\nfoo: 1\n").unwrap();
+        let problems = unknown_keys::<LocalConfig>(&value, "");
+        assert_eq!(
+            problems,
+            [
+                "unknown key 'max_wokers' (did you mean 'max_workers'?)",
+                "unknown key 'foo'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_compose_template_reports_missing_dockerfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("compose-template.yml");
+        std::fs::write(
+            &path,
+            "services:\n  app:\n    build:\n      context: \"{{WORKTREE_PATH}}\"\n      dockerfile: Dockerfile.dev\n    container_name: \"{{WORKER_NAM}}\"\n",
+        )
+        .unwrap();
+
+        let problems = validate_compose_template(&path, dir.path());
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("{{WORKER_NAM}}"));
+        assert!(problems[1].contains("Dockerfile.dev"));
+    }
+}
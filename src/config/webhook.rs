@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A URL groot POSTs lifecycle events to as JSON (`webhooks:` in
+/// `.groot/config.yml`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Webhook {
+    /// Where to POST; may be a `keyring:<name>` reference
+    pub url: String,
+    /// What the endpoint expects: the JSON payload, or a Slack or Discord message
+    #[serde(default, skip_serializing_if = "WebhookKind::is_json")]
+    pub kind: WebhookKind,
+    /// Events to send; every event when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<WebhookEvent>,
+}
+
+/// The body a webhook is sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookKind {
+    /// The event's JSON payload
+    #[default]
+    Json,
+    /// A Slack incoming webhook message
+    Slack,
+    /// A Discord webhook message
+    Discord,
+}
+
+impl WebhookKind {
+    pub fn is_json(&self) -> bool {
+        *self == WebhookKind::Json
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookKind::Json => "json",
+            WebhookKind::Slack => "slack",
+            WebhookKind::Discord => "discord",
+        }
+    }
+}
+
+/// Something that happened to a worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A worker was planted
+    WorkerSpawned,
+    /// Planting a worker, or its headless agent run, failed
+    WorkerFailed,
+    /// A worker was stopped or uprooted
+    WorkerStopped,
+    /// A headless agent run finished successfully
+    TaskCompleted,
+    /// Cloning a database into a grove finished, successfully or not
+    DbCloneFinished,
+}
+
+impl WebhookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::WorkerSpawned => "worker_spawned",
+            WebhookEvent::WorkerFailed => "worker_failed",
+            WebhookEvent::WorkerStopped => "worker_stopped",
+            WebhookEvent::TaskCompleted => "task_completed",
+            WebhookEvent::DbCloneFinished => "db_clone_finished",
+        }
+    }
+}
+
+impl Webhook {
+    /// Whether this hook wants `event`.
+    pub fn wants(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
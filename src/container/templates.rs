@@ -64,3 +64,18 @@ WORKDIR /app
 CMD ["sleep", "infinity"]
 "#
 }
+
+/// Built-in Django Dockerfile template
+pub fn django_template() -> &'static str {
+    r#"FROM python:3.12-slim
+
+RUN apt-get update -qq && \
+    apt-get install -y --no-install-recommends \
+    build-essential libpq-dev git curl && \
+    rm -rf /var/lib/apt/lists/*
+
+WORKDIR /app
+
+CMD ["sleep", "infinity"]
+"#
+}
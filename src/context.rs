@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use crate::config::local::LocalConfig;
+use crate::config::project::ProjectConfig;
+use crate::error::{GrootError, Result};
+use crate::git::repo::GitRepo;
+use crate::probe::Probes;
+
+/// The repository a command runs in and its configuration, loaded once per
+/// invocation and passed down instead of every function re-discovering the
+/// repo and re-reading `config.yml` / `local.yml`.
+pub struct GrootContext {
+    pub git: GitRepo,
+    /// The state directory (`.groot/`)
+    pub groot_dir: PathBuf,
+    pub project: ProjectConfig,
+    pub local: LocalConfig,
+    /// Cached checks for tmux, `docker compose`, and the terminal backend
+    pub probes: Probes,
+}
+
+impl GrootContext {
+    /// Discover the repo from the working directory and load its config.
+    /// Fails with `NotInitialized` when `groot init` hasn't been run.
+    pub fn load() -> Result<Self> {
+        Self::from_repo(GitRepo::discover()?)
+    }
+
+    fn from_repo(git: GitRepo) -> Result<Self> {
+        let groot_dir = git.groot_dir();
+        let config_path = groot_dir.join("config.yml");
+        if !config_path.exists() {
+            return Err(GrootError::NotInitialized);
+        }
+        let project = ProjectConfig::load(&config_path)?;
+        let local = LocalConfig::load(&groot_dir.join("local.yml"))?;
+        let probes = Probes::new(&groot_dir, local.probe_cache_secs);
+        Ok(Self {
+            git,
+            groot_dir,
+            project,
+            local,
+            probes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_requires_init() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        assert!(matches!(
+            GrootContext::from_repo(GitRepo::open(dir.path()).unwrap()),
+            Err(GrootError::NotInitialized)
+        ));
+
+        let groot_dir = dir.path().join(".groot");
+        std::fs::create_dir_all(&groot_dir).unwrap();
+        std::fs::write(groot_dir.join("config.yml"), "project_name: demo\n").unwrap();
+        std::fs::write(groot_dir.join("local.yml"), "max_workers: 2\n").unwrap();
+
+        let ctx = GrootContext::from_repo(GitRepo::open(dir.path()).unwrap()).unwrap();
+        assert_eq!(ctx.project.project_name, "demo");
+        assert_eq!(ctx.local.max_workers, 2);
+        assert_eq!(ctx.groot_dir, ctx.git.root.join(".groot"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The database engine a project talks to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DbEngine {
+    #[default]
+    Postgres,
+    Mysql,
+    Sqlite,
+}
+
+impl DbEngine {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Postgres => "postgres",
+            Self::Mysql => "mysql",
+            Self::Sqlite => "sqlite",
+        }
+    }
+}
+
+fn read(root: &Path, file: &str) -> String {
+    std::fs::read_to_string(root.join(file)).unwrap_or_default()
+}
+
+/// Check a Gemfile for a `gem "name"` line.
+fn has_gem(gemfile: &str, name: &str) -> bool {
+    gemfile.lines().any(|line| {
+        let line = line.trim();
+        line.strip_prefix("gem ")
+            .map(|rest| rest.trim_start().trim_start_matches(['"', '\'']))
+            .is_some_and(|rest| {
+                rest.strip_prefix(name)
+                    .is_some_and(|after| after.starts_with(['"', '\'']))
+            })
+    })
+}
+
+/// `provider = "..."` from the datasource block of a Prisma schema.
+fn prisma_provider(root: &Path) -> Option<DbEngine> {
+    let schema = ["prisma/schema.prisma", "schema.prisma"]
+        .iter()
+        .map(|f| read(root, f))
+        .find(|s| !s.is_empty())?;
+    let datasource = schema.split("datasource").nth(1)?;
+    let block = datasource.split('}').next()?;
+    let provider = block
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("provider"))?
+        .trim_start_matches([' ', '='])
+        .trim()
+        .trim_matches('"');
+    match provider {
+        "postgresql" | "postgres" | "cockroachdb" => Some(DbEngine::Postgres),
+        "mysql" => Some(DbEngine::Mysql),
+        "sqlite" => Some(DbEngine::Sqlite),
+        _ => None,
+    }
+}
+
+/// Detect the database engine from driver dependencies (pg/mysql2/sqlite3 gems,
+/// psycopg/mysqlclient, Prisma's datasource provider). The first match wins, so a
+/// Gemfile with both `pg` and `sqlite3` reports Postgres.
+pub fn detect(root: &Path) -> Option<DbEngine> {
+    let gemfile = read(root, "Gemfile");
+    if has_gem(&gemfile, "pg") {
+        return Some(DbEngine::Postgres);
+    }
+    if has_gem(&gemfile, "mysql2") || has_gem(&gemfile, "trilogy") {
+        return Some(DbEngine::Mysql);
+    }
+
+    let python_deps = ["requirements.txt", "pyproject.toml", "Pipfile"]
+        .iter()
+        .map(|f| read(root, f).to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if python_deps.contains("psycopg") || python_deps.contains("asyncpg") {
+        return Some(DbEngine::Postgres);
+    }
+    if python_deps.contains("mysqlclient") || python_deps.contains("pymysql") {
+        return Some(DbEngine::Mysql);
+    }
+
+    if let Some(engine) = prisma_provider(root) {
+        return Some(engine);
+    }
+
+    // SQLite last: Rails apps often keep the gem around for tests
+    if has_gem(&gemfile, "sqlite3") {
+        return Some(DbEngine::Sqlite);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_engine() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(detect(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("Gemfile"),
+            "gem \"rails\"\ngem 'sqlite3', group: :test\ngem \"mysql2\", \"~> 0.5\"\n",
+        )
+        .unwrap();
+        assert_eq!(detect(dir.path()), Some(DbEngine::Mysql));
+
+        std::fs::remove_file(dir.path().join("Gemfile")).unwrap();
+        std::fs::create_dir(dir.path().join("prisma")).unwrap();
+        std::fs::write(
+            dir.path().join("prisma/schema.prisma"),
+            "generator client {\n  provider = \"prisma-client-js\"\n}\n\ndatasource db {\n  provider = \"sqlite\"\n  url      = env(\"DATABASE_URL\")\n}\n",
+        )
+        .unwrap();
+        assert_eq!(detect(dir.path()), Some(DbEngine::Sqlite));
+    }
+}
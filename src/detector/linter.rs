@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::package_manager;
+
+/// A detected linter and the command that checks the project with it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Linter {
+    pub name: &'static str,
+    pub command: String,
+}
+
+fn file_contains(root: &Path, file: &str, text: &str) -> bool {
+    std::fs::read_to_string(root.join(file)).is_ok_and(|c| c.contains(text))
+}
+
+fn any_exists(root: &Path, files: &[&str]) -> bool {
+    files.iter().any(|f| root.join(f).exists())
+}
+
+/// Detect the linters configured in a project.
+pub fn detect(root: &Path) -> Vec<Linter> {
+    let mut found = Vec::new();
+    let mut add = |name: &'static str, command: String| found.push(Linter { name, command });
+
+    // Ruby
+    if root.join(".rubocop.yml").exists() || file_contains(root, "Gemfile", "rubocop") {
+        add("rubocop", "bundle exec rubocop".to_string());
+    }
+
+    // JavaScript
+    let eslint_configs = [
+        "eslint.config.js",
+        "eslint.config.mjs",
+        "eslint.config.cjs",
+        "eslint.config.ts",
+        ".eslintrc",
+        ".eslintrc.js",
+        ".eslintrc.cjs",
+        ".eslintrc.json",
+        ".eslintrc.yml",
+    ];
+    if any_exists(root, &eslint_configs) || file_contains(root, "package.json", "\"eslint\"") {
+        add("eslint", format!("{} eslint .", package_manager::js_runner(root)));
+    }
+    if any_exists(root, &["biome.json", "biome.jsonc"]) {
+        add("biome", format!("{} biome check .", package_manager::js_runner(root)));
+    }
+
+    // Python
+    if any_exists(root, &["ruff.toml", ".ruff.toml"]) || file_contains(root, "pyproject.toml", "[tool.ruff") {
+        add("ruff", format!("{}ruff check .", package_manager::python_runner(root)));
+    } else if root.join(".flake8").exists() || file_contains(root, "setup.cfg", "[flake8]") {
+        add("flake8", format!("{}flake8", package_manager::python_runner(root)));
+    }
+
+    // Rust / Go
+    if root.join("Cargo.toml").exists() {
+        add("clippy", "cargo clippy --all-targets".to_string());
+    }
+    if any_exists(root, &[".golangci.yml", ".golangci.yaml", ".golangci.toml"]) {
+        add("golangci-lint", "golangci-lint run".to_string());
+    } else if root.join("go.mod").exists() {
+        add("go-vet", "go vet ./...".to_string());
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_linters() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Gemfile"), "gem \"rubocop-rails\", require: false\n").unwrap();
+        std::fs::write(dir.path().join("eslint.config.mjs"), "export default [];\n").unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("yarn.lock"), "").unwrap();
+        std::fs::write(dir.path().join("pyproject.toml"), "[tool.ruff.lint]\nselect = [\"E\"]\n").unwrap();
+        std::fs::write(dir.path().join("uv.lock"), "").unwrap();
+
+        let found = detect(dir.path());
+        let commands: Vec<_> = found.iter().map(|l| l.command.as_str()).collect();
+        assert_eq!(commands, ["bundle exec rubocop", "yarn eslint .", "uv run ruff check ."]);
+    }
+}
@@ -1,9 +1,53 @@
+pub mod database;
+pub mod linter;
+pub mod package_manager;
 pub mod patterns;
+pub mod primary;
+pub mod rules;
+pub mod test_framework;
+pub mod versions;
+pub mod workspace;
 
 use std::path::Path;
 
-/// Detect all project types present in a directory
-pub fn detect_project_types(root: &Path) -> Vec<String> {
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Project types detected for one package of a monorepo.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PackageDetection {
+    /// Path relative to the repo root (e.g. `apps/api`)
+    pub path: String,
+    /// Project types detected in the package
+    pub types: Vec<String>,
+}
+
+/// A detected project type and the files that matched.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeEvidence {
+    pub name: String,
+    pub evidence: Vec<String>,
+}
+
+/// Detect project types along with the evidence for each. Types added by a
+/// user rule report the rule's matched paths.
+pub fn detect_with_evidence(root: &Path, user_rules: &[rules::DetectionRule]) -> Vec<TypeEvidence> {
+    detect_project_types(root, user_rules)
+        .into_iter()
+        .map(|name| {
+            let evidence = user_rules
+                .iter()
+                .find(|r| r.project_type == name && r.matches(root))
+                .map(|r| r.evidence(root))
+                .unwrap_or_else(|| patterns::evidence(&name, root));
+            TypeEvidence { name, evidence }
+        })
+        .collect()
+}
+
+/// Detect all project types present in a directory: the built-in patterns,
+/// then the user's `detection` rules from config.yml on top.
+pub fn detect_project_types(root: &Path, user_rules: &[rules::DetectionRule]) -> Vec<String> {
     let mut types = Vec::new();
 
     for (name, check_fn) in patterns::all_patterns() {
@@ -12,5 +56,18 @@ pub fn detect_project_types(root: &Path) -> Vec<String> {
         }
     }
 
+    rules::apply(root, user_rules, &mut types);
     types
 }
+
+/// Detect project types for each workspace package of a monorepo.
+/// Returns an empty list for single-project repos.
+pub fn detect_packages(root: &Path, user_rules: &[rules::DetectionRule]) -> Vec<PackageDetection> {
+    workspace::package_dirs(root)
+        .into_iter()
+        .map(|path| PackageDetection {
+            types: detect_project_types(&root.join(&path), user_rules),
+            path,
+        })
+        .collect()
+}
@@ -0,0 +1,167 @@
+use std::path::Path;
+
+/// Detect the package managers in use, one per ecosystem, in the order
+/// Ruby, JavaScript, Python.
+pub fn detect(root: &Path) -> Vec<String> {
+    [detect_ruby(root), detect_js(root), detect_python(root)]
+        .into_iter()
+        .flatten()
+        .map(|pm| pm.to_string())
+        .collect()
+}
+
+/// Prefix for running a JS binary with the project's package manager.
+pub fn js_runner(root: &Path) -> &'static str {
+    match detect(root)
+        .iter()
+        .find(|pm| matches!(pm.as_str(), "npm" | "yarn" | "pnpm" | "bun"))
+        .map(String::as_str)
+    {
+        Some("yarn") => "yarn",
+        Some("pnpm") => "pnpm exec",
+        Some("bun") => "bunx",
+        _ => "npx",
+    }
+}
+
+/// Prefix for running a Python tool with the project's package manager.
+pub fn python_runner(root: &Path) -> &'static str {
+    match detect(root)
+        .iter()
+        .map(String::as_str)
+        .find(|pm| matches!(*pm, "uv" | "poetry"))
+    {
+        Some("uv") => "uv run ",
+        Some("poetry") => "poetry run ",
+        _ => "",
+    }
+}
+
+fn detect_ruby(root: &Path) -> Option<&'static str> {
+    root.join("Gemfile").exists().then_some("bundler")
+}
+
+fn detect_js(root: &Path) -> Option<&'static str> {
+    if !root.join("package.json").exists() {
+        return None;
+    }
+
+    // The `packageManager` field (corepack) is authoritative when present
+    let declared = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .and_then(|json| {
+            json.get("packageManager")
+                .and_then(|v| v.as_str())
+                .map(|s| s.split('@').next().unwrap_or_default().to_string())
+        });
+    match declared.as_deref() {
+        Some("pnpm") => return Some("pnpm"),
+        Some("yarn") => return Some("yarn"),
+        Some("bun") => return Some("bun"),
+        Some("npm") => return Some("npm"),
+        _ => {}
+    }
+
+    if root.join("bun.lockb").exists() || root.join("bun.lock").exists() {
+        Some("bun")
+    } else if root.join("pnpm-lock.yaml").exists() {
+        Some("pnpm")
+    } else if root.join("yarn.lock").exists() {
+        Some("yarn")
+    } else {
+        Some("npm")
+    }
+}
+
+fn detect_python(root: &Path) -> Option<&'static str> {
+    let pyproject = std::fs::read_to_string(root.join("pyproject.toml")).unwrap_or_default();
+    if root.join("uv.lock").exists() || pyproject.contains("[tool.uv]") {
+        Some("uv")
+    } else if root.join("poetry.lock").exists() || pyproject.contains("[tool.poetry]") {
+        Some("poetry")
+    } else if root.join("requirements.txt").exists()
+        || root.join("setup.py").exists()
+        || !pyproject.is_empty()
+    {
+        Some("pip")
+    } else {
+        None
+    }
+}
+
+/// The command that installs the dependencies of the checkout at `root` with
+/// a package manager, preferring lockfile-respecting variants. pip installs
+/// requirements.txt when there is one, and otherwise the project itself
+/// (from setup.py or pyproject.toml).
+pub fn install_command(root: &Path, package_manager: &str) -> Option<&'static str> {
+    match package_manager {
+        "bundler" => Some("bundle install"),
+        "npm" => Some("npm ci"),
+        "yarn" => Some("yarn install --frozen-lockfile"),
+        "pnpm" => Some("pnpm install --frozen-lockfile"),
+        "bun" => Some("bun install --frozen-lockfile"),
+        "uv" => Some("uv sync"),
+        "poetry" => Some("poetry install"),
+        "pip" if root.join("requirements.txt").exists() => Some("pip install -r requirements.txt"),
+        "pip" => Some("pip install -e ."),
+        _ => None,
+    }
+}
+
+/// Commands that install a checkout's dependencies on the host: one per
+/// detected package manager, then `cargo fetch` and `go mod download`.
+/// Plain pip is left out, since without a virtualenv it would install into
+/// the system Python.
+pub fn install_commands(root: &Path) -> Vec<String> {
+    detect(root)
+        .iter()
+        .filter(|pm| pm.as_str() != "pip")
+        .filter_map(|pm| install_command(root, pm))
+        .chain(root.join("Cargo.toml").exists().then_some("cargo fetch"))
+        .chain(root.join("go.mod").exists().then_some("go mod download"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_package_managers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Gemfile"), "").unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(detect(dir.path()), ["bundler", "pnpm"]);
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"packageManager": "yarn@4.1.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("pyproject.toml"), "[tool.poetry]\nname = \"x\"\n").unwrap();
+        assert_eq!(detect(dir.path()), ["bundler", "yarn", "poetry"]);
+        assert_eq!(install_command(dir.path(), "pnpm"), Some("pnpm install --frozen-lockfile"));
+
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(
+            install_commands(dir.path()),
+            ["bundle install", "yarn install --frozen-lockfile", "poetry install", "cargo fetch"]
+        );
+        std::fs::write(dir.path().join("pyproject.toml"), "").unwrap();
+        std::fs::write(dir.path().join("requirements.txt"), "").unwrap();
+        assert!(!install_commands(dir.path()).iter().any(|c| c.starts_with("pip")));
+    }
+
+    #[test]
+    fn test_pip_installs_the_project_without_requirements() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("setup.py"), "from setuptools import setup\nsetup()\n").unwrap();
+        assert_eq!(detect(dir.path()), ["pip"]);
+        assert_eq!(install_command(dir.path(), "pip"), Some("pip install -e ."));
+        std::fs::write(dir.path().join("requirements.txt"), "requests\n").unwrap();
+        assert_eq!(install_command(dir.path(), "pip"), Some("pip install -r requirements.txt"));
+    }
+}
@@ -7,12 +7,60 @@ pub fn all_patterns() -> Vec<(&'static str, PatternFn)> {
         ("rails", is_rails),
         ("node", is_node),
         ("react-native", is_react_native),
+        ("nextjs", is_nextjs),
+        ("vite", is_vite),
+        ("vue", is_vue),
+        ("svelte", is_svelte),
+        ("react", is_react),
         ("python", is_python),
+        ("django", is_django),
+        ("php", is_php),
+        ("laravel", is_laravel),
+        ("maven", is_maven),
+        ("gradle", is_gradle),
+        ("java", is_java),
+        ("kotlin", is_kotlin),
+        ("dotnet", is_dotnet),
         ("rust", is_rust),
         ("go", is_go),
+        ("nix", is_nix),
     ]
 }
 
+/// Globs for the files each pattern inspects. The ones that exist are reported
+/// as evidence by `groot detect --json`.
+fn markers(name: &str) -> &'static [&'static str] {
+    match name {
+        "rails" => &["Gemfile", "config/routes.rb"],
+        "node" | "react" | "react-native" => &["package.json"],
+        "nextjs" => &["next.config.*", "package.json"],
+        "vite" => &["vite.config.*", "package.json"],
+        "vue" => &["vue.config.*", "package.json"],
+        "svelte" => &["svelte.config.*", "package.json"],
+        "python" => &["pyproject.toml", "setup.py", "requirements.txt"],
+        "django" => &["manage.py", "pyproject.toml", "requirements.txt", "setup.py", "Pipfile"],
+        "php" => &["composer.json", "index.php"],
+        "laravel" => &["artisan", "composer.json"],
+        "maven" => &["pom.xml"],
+        "gradle" => GRADLE_FILES,
+        "java" => &["pom.xml", "build.gradle*", "src/main/java", "*/src/main/java"],
+        "kotlin" => &["pom.xml", "build.gradle*", "src/main/kotlin", "*/src/main/kotlin"],
+        "dotnet" => &["*.sln", "*.csproj", "*.fsproj", "*/*.sln", "*/*.csproj", "*/*.fsproj"],
+        "rust" => &["Cargo.toml"],
+        "go" => &["go.mod"],
+        "nix" => &["flake.nix"],
+        _ => &[],
+    }
+}
+
+/// Files that back a built-in detection, relative to `root`.
+pub fn evidence(name: &str, root: &Path) -> Vec<String> {
+    markers(name)
+        .iter()
+        .flat_map(|glob| super::rules::glob_paths(root, glob))
+        .collect()
+}
+
 fn is_rails(root: &Path) -> bool {
     root.join("Gemfile").exists() && root.join("config/routes.rb").exists()
 }
@@ -32,12 +80,183 @@ fn is_react_native(root: &Path) -> bool {
     false
 }
 
+/// Names of all `dependencies` and `devDependencies` in package.json.
+fn package_json_deps(root: &Path) -> Vec<String> {
+    let Ok(contents) = std::fs::read_to_string(root.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) else {
+        return Vec::new();
+    };
+    ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|key| json.get(key).and_then(|v| v.as_object()))
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+fn has_dep(root: &Path, names: &[&str]) -> bool {
+    package_json_deps(root)
+        .iter()
+        .any(|dep| names.contains(&dep.as_str()))
+}
+
+fn has_config(root: &Path, stem: &str) -> bool {
+    ["js", "mjs", "cjs", "ts", "mts"]
+        .iter()
+        .any(|ext| root.join(format!("{stem}.{ext}")).exists())
+}
+
+fn is_nextjs(root: &Path) -> bool {
+    has_config(root, "next.config") || has_dep(root, &["next"])
+}
+
+fn is_vite(root: &Path) -> bool {
+    has_config(root, "vite.config") || has_dep(root, &["vite"])
+}
+
+fn is_vue(root: &Path) -> bool {
+    has_config(root, "vue.config") || has_dep(root, &["vue", "nuxt"])
+}
+
+fn is_svelte(root: &Path) -> bool {
+    has_config(root, "svelte.config") || has_dep(root, &["svelte", "@sveltejs/kit"])
+}
+
+fn is_react(root: &Path) -> bool {
+    // React Native projects depend on react too, but are reported separately
+    has_dep(root, &["react"]) && !is_react_native(root)
+}
+
 fn is_python(root: &Path) -> bool {
     root.join("pyproject.toml").exists()
         || root.join("setup.py").exists()
         || root.join("requirements.txt").exists()
 }
 
+fn is_django(root: &Path) -> bool {
+    // manage.py pointing at a settings module
+    let manage_py = std::fs::read_to_string(root.join("manage.py"))
+        .is_ok_and(|contents| contents.contains("DJANGO_SETTINGS_MODULE"));
+    if manage_py {
+        return true;
+    }
+    // Or a declared django dependency
+    ["pyproject.toml", "requirements.txt", "setup.py", "Pipfile"]
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(root.join(file)).ok())
+        .any(|contents| declares_django(&contents.to_lowercase()))
+}
+
+/// Whether `django` appears as a dependency name: at the start of a line
+/// (`Django>=5.0` in requirements, `django = "*"` in a Pipfile or Poetry
+/// table) or opening a quoted string (`"django>=5"` in a dependency array),
+/// and not as part of a longer name like `django-environ` or a comment.
+fn declares_django(contents: &str) -> bool {
+    contents.match_indices("django").any(|(at, name)| {
+        let before = contents[..at].rsplit('\n').next().unwrap_or_default();
+        let opens = before.trim().is_empty() || before.ends_with(['"', '\'']);
+        let ends = !contents[at + name.len()..]
+            .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+        opens && ends
+    })
+}
+
+fn is_php(root: &Path) -> bool {
+    root.join("composer.json").exists() || root.join("index.php").exists()
+}
+
+fn is_laravel(root: &Path) -> bool {
+    if !root.join("artisan").exists() {
+        return false;
+    }
+    std::fs::read_to_string(root.join("composer.json"))
+        .is_ok_and(|contents| contents.contains("\"laravel/framework\""))
+}
+
+fn is_maven(root: &Path) -> bool {
+    root.join("pom.xml").exists()
+}
+
+const GRADLE_FILES: &[&str] = &[
+    "build.gradle",
+    "build.gradle.kts",
+    "settings.gradle",
+    "settings.gradle.kts",
+];
+
+fn is_gradle(root: &Path) -> bool {
+    GRADLE_FILES.iter().any(|f| root.join(f).exists())
+}
+
+/// Check for `src/main/<lang>` at the root or in any module one level down
+/// (multi-module Maven/Gradle builds keep sources in the modules).
+fn has_jvm_sources(root: &Path, lang: &str) -> bool {
+    let source_dir = Path::new("src/main").join(lang);
+    if root.join(&source_dir).is_dir() {
+        return true;
+    }
+    std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().join(&source_dir).is_dir())
+        })
+        .unwrap_or(false)
+}
+
+fn mentions_kotlin(root: &Path) -> bool {
+    GRADLE_FILES
+        .iter()
+        .chain(["pom.xml"].iter())
+        .filter_map(|f| std::fs::read_to_string(root.join(f)).ok())
+        .any(|contents| contents.contains("kotlin"))
+}
+
+fn is_java(root: &Path) -> bool {
+    if !is_maven(root) && !is_gradle(root) {
+        return false;
+    }
+    has_jvm_sources(root, "java") || !(has_jvm_sources(root, "kotlin") || mentions_kotlin(root))
+}
+
+fn is_kotlin(root: &Path) -> bool {
+    if !is_maven(root) && !is_gradle(root) {
+        return false;
+    }
+    has_jvm_sources(root, "kotlin") || mentions_kotlin(root)
+}
+
+fn has_extension(dir: &Path, extensions: &[&str]) -> bool {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                entry
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn is_dotnet(root: &Path) -> bool {
+    const EXTENSIONS: &[&str] = &["sln", "csproj", "fsproj"];
+    if has_extension(root, EXTENSIONS) {
+        return true;
+    }
+    // Solutions often live in a subdirectory (e.g. src/MyApp.sln)
+    std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                .any(|entry| has_extension(&entry.path(), EXTENSIONS))
+        })
+        .unwrap_or(false)
+}
+
 fn is_rust(root: &Path) -> bool {
     root.join("Cargo.toml").exists()
 }
@@ -45,3 +264,104 @@ fn is_rust(root: &Path) -> bool {
 fn is_go(root: &Path) -> bool {
     root.join("go.mod").exists()
 }
+
+/// A Nix flake, whose devshell provides the toolchain.
+fn is_nix(root: &Path) -> bool {
+    root.join("flake.nix").exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_django() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("requirements.txt"), "requests==2.31\n").unwrap();
+        assert!(is_python(dir.path()));
+        assert!(!is_django(dir.path()));
+
+        std::fs::write(dir.path().join("requirements.txt"), "Django>=5.0\n").unwrap();
+        assert!(is_django(dir.path()));
+
+        // A django plugin, or a mention, isn't django itself
+        std::fs::write(dir.path().join("requirements.txt"), "django-environ==0.11\n# not django\n").unwrap();
+        assert!(!is_django(dir.path()));
+        assert!(declares_django("dependencies = [\n  \"django>=5\",\n]\n"));
+        assert!(declares_django("[packages]\ndjango = \"*\"\n"));
+        assert!(!declares_django("description = \"a django-like toolkit\"\n"));
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("manage.py"),
+            "os.environ.setdefault(\"DJANGO_SETTINGS_MODULE\", \"mysite.settings\")\n",
+        )
+        .unwrap();
+        assert!(is_django(dir.path()));
+    }
+
+    #[test]
+    fn test_frontend_frameworks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18", "react-dom": "^18"}, "devDependencies": {"vite": "^5"}}"#,
+        )
+        .unwrap();
+        assert!(is_react(dir.path()));
+        assert!(is_vite(dir.path()));
+        assert!(!is_nextjs(dir.path()));
+        assert!(!is_vue(dir.path()));
+
+        std::fs::write(dir.path().join("next.config.mjs"), "export default {}\n").unwrap();
+        assert!(is_nextjs(dir.path()));
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18", "react-native": "0.74"}}"#,
+        )
+        .unwrap();
+        assert!(!is_react(dir.path()));
+    }
+
+    #[test]
+    fn test_jvm_multi_module() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("settings.gradle.kts"), "include(\"api\")\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("api/src/main/java")).unwrap();
+        assert!(is_gradle(dir.path()));
+        assert!(!is_maven(dir.path()));
+        assert!(is_java(dir.path()));
+        assert!(!is_kotlin(dir.path()));
+        assert_eq!(evidence("java", dir.path()), ["api/src/main/java"]);
+
+        std::fs::create_dir_all(dir.path().join("web/src/main/kotlin")).unwrap();
+        assert!(is_kotlin(dir.path()));
+    }
+
+    #[test]
+    fn test_is_dotnet_one_level_deep() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_dotnet(dir.path()));
+
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/MyApp.sln"), "").unwrap();
+        assert!(is_dotnet(dir.path()));
+        assert_eq!(evidence("dotnet", dir.path()), ["src/MyApp.sln"]);
+    }
+
+    #[test]
+    fn test_is_laravel() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("composer.json"),
+            r#"{"require": {"laravel/framework": "^11.0"}}"#,
+        )
+        .unwrap();
+        assert!(is_php(dir.path()));
+        assert!(!is_laravel(dir.path()));
+
+        std::fs::write(dir.path().join("artisan"), "#!/usr/bin/env php\n").unwrap();
+        assert!(is_laravel(dir.path()));
+    }
+}
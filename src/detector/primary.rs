@@ -0,0 +1,115 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::TypeEvidence;
+
+/// The type that best describes a project, with a 0–1 confidence: its share
+/// of the total score across all detected types.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PrimaryType {
+    /// Project type name (e.g. `rails`)
+    pub name: String,
+    /// Share of the total detection score, 0–1
+    pub confidence: f32,
+}
+
+/// How strongly a type says "this is what the project is". Frameworks outrank
+/// languages, which outrank build tools; `node` is weakest because nearly every
+/// web app has a package.json, and `nix` hardly counts. Unknown (user rule) types count as frameworks.
+fn weight(name: &str) -> f32 {
+    match name {
+        "rails" | "django" | "laravel" | "nextjs" | "react-native" => 5.0,
+        "react" | "vue" | "svelte" | "vite" => 3.0,
+        "python" | "php" | "java" | "kotlin" | "go" | "rust" | "dotnet" => 2.5,
+        "maven" | "gradle" => 1.5,
+        "node" => 1.0,
+        // Says how the toolchain is installed, not what the project is
+        "nix" => 0.5,
+        _ => 5.0,
+    }
+}
+
+/// Types that make `name` redundant: a Django project is also "python".
+fn subsumed_by(name: &str) -> &'static [&'static str] {
+    match name {
+        "python" => &["django"],
+        "php" => &["laravel"],
+        "node" => &["nextjs", "vite", "vue", "svelte", "react", "react-native"],
+        "react" => &["nextjs", "react-native"],
+        "vite" => &["vue", "svelte", "react"],
+        "maven" | "gradle" => &["java", "kotlin"],
+        _ => &[],
+    }
+}
+
+/// Each matched file adds a little, up to this much.
+const MAX_EVIDENCE_BONUS: f32 = 2.0;
+
+fn score(name: &str, evidence_count: usize, all: &[&str]) -> f32 {
+    let mut score = weight(name) + (evidence_count as f32 * 0.5).min(MAX_EVIDENCE_BONUS);
+    if subsumed_by(name).iter().any(|s| all.contains(s)) {
+        score *= 0.25;
+    }
+    score
+}
+
+fn pick(scored: Vec<(&str, f32)>) -> Option<PrimaryType> {
+    let total: f32 = scored.iter().map(|(_, s)| s).sum();
+    // Strictly greater keeps detection order as the tie-breaker
+    let (name, best) = scored
+        .iter()
+        .fold(None::<(&str, f32)>, |best, &(name, s)| match best {
+            Some((_, b)) if b >= s => best,
+            _ => Some((name, s)),
+        })?;
+    Some(PrimaryType {
+        name: name.to_string(),
+        confidence: (best / total * 100.0).round() / 100.0,
+    })
+}
+
+/// Choose the primary type from detections with evidence.
+pub fn choose(types: &[TypeEvidence]) -> Option<PrimaryType> {
+    let names: Vec<&str> = types.iter().map(|t| t.name.as_str()).collect();
+    pick(
+        types
+            .iter()
+            .map(|t| (t.name.as_str(), score(&t.name, t.evidence.len(), &names)))
+            .collect(),
+    )
+}
+
+/// Choose the primary type from type names alone (configs written before
+/// `primary_type` was recorded).
+pub fn from_names(types: &[String]) -> Option<PrimaryType> {
+    let names: Vec<&str> = types.iter().map(String::as_str).collect();
+    pick(names.iter().map(|&n| (n, score(n, 0, &names))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(name: &str, files: &[&str]) -> TypeEvidence {
+        TypeEvidence {
+            name: name.to_string(),
+            evidence: files.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_choose_primary_type() {
+        let primary = choose(&[
+            detected("rails", &["Gemfile", "config/routes.rb"]),
+            detected("node", &["package.json"]),
+        ])
+        .unwrap();
+        assert_eq!(primary.name, "rails");
+        assert_eq!(primary.confidence, 0.8);
+
+        let names = ["node".to_string(), "react".to_string(), "python".to_string(), "django".to_string()];
+        assert_eq!(from_names(&names).unwrap().name, "django");
+        assert_eq!(from_names(&["go".to_string()]).unwrap().confidence, 1.0);
+        assert_eq!(from_names(&[]), None);
+    }
+}
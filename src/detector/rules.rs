@@ -0,0 +1,252 @@
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A user-defined detection rule from the `detection` list in `.groot/config.yml`.
+/// Every condition that is set must hold for the rule to match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DetectionRule {
+    /// Project type reported when the rule matches
+    #[serde(rename = "type")]
+    pub project_type: String,
+    /// Path (relative to the project root) that must exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_exists: Option<String>,
+    /// File that must contain some text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_contains: Option<FileContains>,
+    /// Glob that must match at least one path (`*`, `?`, and `**` segments)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glob: Option<String>,
+    /// Built-in types to drop when this rule matches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaces: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FileContains {
+    /// Path relative to the project root
+    pub file: String,
+    /// Text the file must contain
+    pub text: String,
+}
+
+impl DetectionRule {
+    /// Check the rule against a directory. A rule without conditions never matches.
+    pub fn matches(&self, root: &Path) -> bool {
+        if self.file_exists.is_none() && self.file_contains.is_none() && self.glob.is_none() {
+            return false;
+        }
+        let exists = self
+            .file_exists
+            .as_ref()
+            .is_none_or(|f| root.join(f).exists());
+        let contains = self.file_contains.as_ref().is_none_or(|fc| {
+            std::fs::read_to_string(root.join(&fc.file)).is_ok_and(|c| c.contains(&fc.text))
+        });
+        let globbed = self.glob.as_ref().is_none_or(|g| glob_matches(root, g));
+        exists && contains && globbed
+    }
+
+    /// The paths that satisfied the rule's conditions (for `groot detect --json`).
+    pub fn evidence(&self, root: &Path) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .file_exists
+            .iter()
+            .chain(self.file_contains.as_ref().map(|fc| &fc.file))
+            .cloned()
+            .collect();
+        if let Some(glob) = &self.glob {
+            paths.extend(glob_paths(root, glob).into_iter().take(MAX_EVIDENCE));
+        }
+        paths
+    }
+}
+
+/// Cap on glob matches reported as evidence for one rule.
+const MAX_EVIDENCE: usize = 5;
+
+/// Apply user rules on top of the built-in detections: matching rules add their
+/// type and drop the built-in types they replace.
+pub fn apply(root: &Path, rules: &[DetectionRule], types: &mut Vec<String>) {
+    for rule in rules.iter().filter(|r| r.matches(root)) {
+        types.retain(|t| !rule.replaces.contains(t));
+        if !types.contains(&rule.project_type) {
+            types.push(rule.project_type.clone());
+        }
+    }
+}
+
+/// Depth limit for `**` so a glob can't walk an entire node_modules tree.
+const MAX_GLOB_DEPTH: usize = 8;
+
+/// Check whether any path under `root` matches a `/`-separated glob.
+pub fn glob_matches(root: &Path, pattern: &str) -> bool {
+    let mut found = Vec::new();
+    collect_matches(root, "", &glob_segments(pattern), 0, true, &mut found);
+    !found.is_empty()
+}
+
+/// Every path under `root` (relative, `/`-separated) that matches a glob, sorted.
+pub fn glob_paths(root: &Path, pattern: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    collect_matches(root, "", &glob_segments(pattern), 0, false, &mut found);
+    found.sort();
+    found.dedup();
+    found
+}
+
+fn glob_segments(pattern: &str) -> Vec<&str> {
+    pattern
+        .trim_start_matches("./")
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Walk `dir` (at `rel` from the root) collecting matches for the remaining
+/// segments. Returns true once a match is found and `first_only` is set.
+fn collect_matches(
+    dir: &Path,
+    rel: &str,
+    segments: &[&str],
+    depth: usize,
+    first_only: bool,
+    found: &mut Vec<String>,
+) -> bool {
+    let Some((first, rest)) = segments.split_first() else {
+        found.push(rel.to_string());
+        return first_only;
+    };
+    if depth > MAX_GLOB_DEPTH {
+        return false;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    let entries: Vec<_> = entries.flatten().collect();
+
+    if *first == "**" {
+        // Zero directories, or descend one more and keep `**`
+        if collect_matches(dir, rel, rest, depth, first_only, found) {
+            return true;
+        }
+        for entry in entries.iter().filter(|e| e.path().is_dir()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            if collect_matches(&entry.path(), &join(rel, &name), segments, depth + 1, first_only, found) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    for entry in &entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !wildcard_match(first, &name) {
+            continue;
+        }
+        let path = join(rel, &name);
+        let done = if rest.is_empty() {
+            found.push(path);
+            first_only
+        } else {
+            entry.path().is_dir()
+                && collect_matches(&entry.path(), &path, rest, depth + 1, first_only, found)
+        };
+        if done {
+            return true;
+        }
+    }
+    false
+}
+
+fn join(rel: &str, name: &str) -> String {
+    if rel.is_empty() {
+        name.to_string()
+    } else {
+        format!("{rel}/{name}")
+    }
+}
+
+/// Match one path segment (or branch name) against a pattern with `*` and `?`.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    let (mut pi, mut ni) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some(pi);
+            mark = ni;
+            pi += 1;
+        } else if let Some(s) = star {
+            pi = s + 1;
+            mark += 1;
+            ni = mark;
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(project_type: &str) -> DetectionRule {
+        DetectionRule {
+            project_type: project_type.to_string(),
+            file_exists: None,
+            file_contains: None,
+            glob: None,
+            replaces: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.proto", "user.proto"));
+        assert!(wildcard_match("app?.yml", "app1.yml"));
+        assert!(!wildcard_match("*.proto", "user.proto.bak"));
+    }
+
+    #[test]
+    fn test_rules_add_and_replace_types() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("acme.toml"), "framework = \"acme\"\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("proto/v1")).unwrap();
+        std::fs::write(dir.path().join("proto/v1/user.proto"), "").unwrap();
+
+        let acme = DetectionRule {
+            file_contains: Some(FileContains {
+                file: "acme.toml".to_string(),
+                text: "acme".to_string(),
+            }),
+            replaces: vec!["node".to_string()],
+            ..rule("acme")
+        };
+        let protobuf = DetectionRule {
+            glob: Some("proto/**/*.proto".to_string()),
+            ..rule("protobuf")
+        };
+        let missing = DetectionRule {
+            file_exists: Some("nope.txt".to_string()),
+            ..rule("never")
+        };
+
+        let mut types = vec!["node".to_string()];
+        assert_eq!(protobuf.evidence(dir.path()), ["proto/v1/user.proto"]);
+        apply(dir.path(), &[acme, protobuf, missing, rule("empty")], &mut types);
+        assert_eq!(types, ["acme", "protobuf"]);
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::package_manager;
+
+/// A detected test framework and the command that runs its suite.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestFramework {
+    pub name: &'static str,
+    pub command: String,
+}
+
+fn file_contains(root: &Path, file: &str, text: &str) -> bool {
+    std::fs::read_to_string(root.join(file)).is_ok_and(|c| c.contains(text))
+}
+
+fn has_config(root: &Path, stem: &str) -> bool {
+    ["js", "mjs", "cjs", "ts", "mts", "json"]
+        .iter()
+        .any(|ext| root.join(format!("{stem}.{ext}")).exists())
+}
+
+/// Detect the test frameworks configured in a project.
+pub fn detect(root: &Path) -> Vec<TestFramework> {
+    let mut found = Vec::new();
+    let mut add = |name: &'static str, command: String| found.push(TestFramework { name, command });
+
+    // Ruby
+    if root.join(".rspec").exists()
+        || root.join("spec/spec_helper.rb").exists()
+        || file_contains(root, "Gemfile", "rspec")
+    {
+        add("rspec", "bundle exec rspec".to_string());
+    }
+    if root.join("test/test_helper.rb").exists() {
+        let command = if root.join("bin/rails").exists() {
+            "bin/rails test"
+        } else {
+            "bundle exec rake test"
+        };
+        add("minitest", command.to_string());
+    }
+
+    // JavaScript
+    if has_config(root, "vitest.config") || file_contains(root, "package.json", "\"vitest\"") {
+        add("vitest", format!("{} vitest run", package_manager::js_runner(root)));
+    }
+    if has_config(root, "jest.config") || file_contains(root, "package.json", "\"jest\"") {
+        add("jest", format!("{} jest", package_manager::js_runner(root)));
+    }
+
+    // Python
+    if root.join("pytest.ini").exists()
+        || root.join("conftest.py").exists()
+        || file_contains(root, "pyproject.toml", "[tool.pytest")
+        || file_contains(root, "setup.cfg", "[tool:pytest]")
+        || file_contains(root, "requirements.txt", "pytest")
+    {
+        add("pytest", format!("{}pytest", package_manager::python_runner(root)));
+    }
+
+    // Rust / Go
+    if root.join("Cargo.toml").exists() {
+        add("cargo-test", "cargo test".to_string());
+    }
+    if root.join("go.mod").exists() {
+        add("go-test", "go test ./...".to_string());
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_test_frameworks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".rspec"), "--require spec_helper\n").unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"devDependencies": {"vitest": "^1"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+
+        let found = detect(dir.path());
+        let commands: Vec<_> = found.iter().map(|f| f.command.as_str()).collect();
+        assert_eq!(commands, ["bundle exec rspec", "pnpm exec vitest run"]);
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+fn read(root: &Path, file: &str) -> Option<String> {
+    std::fs::read_to_string(root.join(file)).ok()
+}
+
+/// First non-empty, non-comment line of a version file like `.ruby-version`.
+fn version_file(root: &Path, files: &[&str]) -> Option<String> {
+    files.iter().filter_map(|f| read(root, f)).find_map(|contents| {
+        contents
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| l.trim_start_matches('v').to_string())
+    })
+}
+
+/// Tool versions from asdf/mise `.tool-versions` (`ruby 3.3.0`).
+fn tool_versions(root: &Path) -> BTreeMap<String, String> {
+    read(root, ".tool-versions")
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| !l.trim_start().starts_with('#'))
+        .filter_map(|l| {
+            let mut parts = l.split_whitespace();
+            let tool = match parts.next()? {
+                "nodejs" => "node",
+                "golang" => "go",
+                other => other,
+            };
+            Some((tool.to_string(), parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Locked gem version from Gemfile.lock (`    rails (7.1.3)`).
+fn locked_gem(root: &Path, gem: &str) -> Option<String> {
+    let needle = format!("    {gem} (");
+    read(root, "Gemfile.lock")?
+        .lines()
+        .find_map(|l| l.strip_prefix(&needle)?.strip_suffix(')').map(|v| v.to_string()))
+}
+
+/// `go 1.22` directive from go.mod.
+fn go_directive(root: &Path) -> Option<String> {
+    read(root, "go.mod")?
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("go ").map(|v| v.trim().to_string()))
+}
+
+/// `channel = "1.79"` from rust-toolchain.toml, or a bare rust-toolchain file.
+fn rust_toolchain(root: &Path) -> Option<String> {
+    if let Some(contents) = read(root, "rust-toolchain.toml") {
+        return contents.lines().find_map(|l| {
+            let value = l.trim().strip_prefix("channel")?.trim_start_matches([' ', '=']);
+            Some(value.trim().trim_matches('"').to_string())
+        });
+    }
+    version_file(root, &["rust-toolchain"])
+}
+
+/// Pinned language and framework versions, keyed by tool name. Dedicated
+/// version files win over `.tool-versions`.
+pub fn detect(root: &Path) -> BTreeMap<String, String> {
+    let mut versions = tool_versions(root);
+
+    let pinned = [
+        ("ruby", version_file(root, &[".ruby-version"])),
+        ("node", version_file(root, &[".nvmrc", ".node-version"])),
+        ("python", version_file(root, &[".python-version"])),
+        ("go", go_directive(root)),
+        ("rust", rust_toolchain(root)),
+        ("rails", locked_gem(root, "rails")),
+    ];
+    for (tool, version) in pinned {
+        if let Some(version) = version {
+            versions.insert(tool.to_string(), version);
+        }
+    }
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".tool-versions"), "ruby 3.2.0\nnodejs 20.11.0\n").unwrap();
+        std::fs::write(dir.path().join(".ruby-version"), "3.3.0\n").unwrap();
+        std::fs::write(
+            dir.path().join("Gemfile.lock"),
+            "GEM\n  specs:\n    rails (7.1.3)\n      actioncable (= 7.1.3)\n",
+        )
+        .unwrap();
+
+        let versions = detect(dir.path());
+        assert_eq!(versions["ruby"], "3.3.0");
+        assert_eq!(versions["node"], "20.11.0");
+        assert_eq!(versions["rails"], "7.1.3");
+        assert!(!versions.contains_key("python"));
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::Path;
+
+/// Package globs assumed when a tool (nx, turborepo) doesn't list its packages itself.
+const CONVENTIONAL_GLOBS: &[&str] = &["apps/*", "packages/*", "libs/*"];
+
+/// Detect monorepo workspace tooling at the repo root.
+pub fn detect_tools(root: &Path) -> Vec<String> {
+    let mut tools = Vec::new();
+
+    if root.join("pnpm-workspace.yaml").exists() {
+        tools.push("pnpm-workspaces".to_string());
+    } else if !package_json_workspaces(root).is_empty() {
+        if root.join("yarn.lock").exists() {
+            tools.push("yarn-workspaces".to_string());
+        } else {
+            tools.push("npm-workspaces".to_string());
+        }
+    }
+    if root.join("turbo.json").exists() {
+        tools.push("turborepo".to_string());
+    }
+    if root.join("nx.json").exists() {
+        tools.push("nx".to_string());
+    }
+    if !cargo_workspace_members(root).is_empty() {
+        tools.push("cargo-workspace".to_string());
+    }
+
+    tools
+}
+
+/// Relative paths of every workspace package, sorted and deduplicated.
+pub fn package_dirs(root: &Path) -> Vec<String> {
+    let mut globs = pnpm_workspace_packages(root);
+    globs.extend(package_json_workspaces(root));
+    globs.extend(cargo_workspace_members(root));
+    if globs.is_empty() && (root.join("turbo.json").exists() || root.join("nx.json").exists()) {
+        globs.extend(CONVENTIONAL_GLOBS.iter().map(|g| g.to_string()));
+    }
+
+    let mut dirs: Vec<String> = globs
+        .iter()
+        .filter(|g| !g.starts_with('!'))
+        .flat_map(|g| expand_glob(root, g))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Expand a workspace glob. Supports exact paths and a trailing `/*` or `/**`
+/// (one level of subdirectories), which covers how workspaces are declared in practice.
+fn expand_glob(root: &Path, glob: &str) -> Vec<String> {
+    let glob = glob.trim().trim_start_matches("./").trim_end_matches('/');
+    let prefix = glob
+        .strip_suffix("/**")
+        .or_else(|| glob.strip_suffix("/*"));
+
+    let Some(prefix) = prefix else {
+        return if root.join(glob).is_dir() {
+            vec![glob.to_string()]
+        } else {
+            Vec::new()
+        };
+    };
+
+    let Ok(entries) = std::fs::read_dir(root.join(prefix)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.') && name != "node_modules")
+        .map(|name| format!("{prefix}/{name}"))
+        .collect()
+}
+
+fn pnpm_workspace_packages(root: &Path) -> Vec<String> {
+    #[derive(serde::Deserialize)]
+    struct PnpmWorkspace {
+        #[serde(default)]
+        packages: Vec<String>,
+    }
+
+    std::fs::read_to_string(root.join("pnpm-workspace.yaml"))
+        .ok()
+        .and_then(|contents| serde_yml::from_str::<PnpmWorkspace>(&contents).ok())
+        .map(|ws| ws.packages)
+        .unwrap_or_default()
+}
+
+/// `workspaces` in package.json: either an array or `{ "packages": [...] }`.
+fn package_json_workspaces(root: &Path) -> Vec<String> {
+    let Some(json) = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+    else {
+        return Vec::new();
+    };
+
+    let workspaces = json.get("workspaces");
+    workspaces
+        .and_then(|w| w.as_array())
+        .or_else(|| workspaces.and_then(|w| w.get("packages")).and_then(|p| p.as_array()))
+        .map(|globs| {
+            globs
+                .iter()
+                .filter_map(|g| g.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `members = [...]` from the `[workspace]` table of a root Cargo.toml.
+fn cargo_workspace_members(root: &Path) -> Vec<String> {
+    let Ok(contents) = std::fs::read_to_string(root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = contents.split("[workspace]").nth(1) else {
+        return Vec::new();
+    };
+    // Stop at the next table header
+    let table = workspace.split("\n[").next().unwrap_or_default();
+    // The `members` key itself, not `default-members` or a comment
+    let start = table.match_indices("members").map(|(at, _)| at).find(|&at| {
+        let line_start = table[..at].rsplit('\n').next().unwrap_or_default();
+        line_start.trim().is_empty() && table[at + "members".len()..].trim_start().starts_with('=')
+    });
+    let Some(members) = start
+        .map(|at| &table[at..])
+        .and_then(|rest| rest.split_once('['))
+        .and_then(|(_, rest)| rest.split_once(']'))
+        .map(|(list, _)| list)
+    else {
+        return Vec::new();
+    };
+
+    members
+        .split(',')
+        .map(|m| m.trim().trim_matches('"').to_string())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pnpm_and_turbo_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pnpm-workspace.yaml"),
+            "packages:\n  - \"apps/*\"\n  - \"packages/ui\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("turbo.json"), "{}").unwrap();
+        for pkg in ["apps/api", "apps/web", "packages/ui", "packages/unused"] {
+            std::fs::create_dir_all(dir.path().join(pkg)).unwrap();
+        }
+
+        assert_eq!(detect_tools(dir.path()), ["pnpm-workspaces", "turborepo"]);
+        assert_eq!(package_dirs(dir.path()), ["apps/api", "apps/web", "packages/ui"]);
+    }
+
+    #[test]
+    fn test_cargo_workspace_members() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\n    \"crates/*\",\n    \"xtask\",\n]\n\n[workspace.dependencies]\nserde = \"1\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("crates/core")).unwrap();
+        std::fs::create_dir_all(dir.path().join("xtask")).unwrap();
+
+        assert_eq!(detect_tools(dir.path()), ["cargo-workspace"]);
+        assert_eq!(package_dirs(dir.path()), ["crates/core", "xtask"]);
+    }
+
+    #[test]
+    fn test_cargo_default_members_are_not_members() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace]\ndefault-members = [\"cli\"]\nmembers = [\"cli\", \"lib\"]\n",
+        )
+        .unwrap();
+        assert_eq!(cargo_workspace_members(dir.path()), ["cli", "lib"]);
+    }
+}
@@ -1,5 +1,12 @@
+use std::process::{Command, Output};
+
 use thiserror::Error;
 
+use crate::process::command_line;
+
+/// Lines of a failed command's stderr kept for the error report.
+const STDERR_LINES: usize = 12;
+
 #[derive(Error, Debug)]
 pub enum GrootError {
     #[error("IO error: {0}")]
@@ -17,6 +24,9 @@ pub enum GrootError {
     #[error("Docker error: {0}")]
     Docker(#[from] bollard::errors::Error),
 
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
     #[error("Not a groot project. Run 'groot init' first.")]
     NotInitialized,
 
@@ -32,8 +42,8 @@ pub enum GrootError {
     #[error("Grove already exists for task: {0}")]
     GroveAlreadyExists(String),
 
-    #[error("Grove not found: {0}")]
-    GroveNotFound(String),
+    #[error("No grove or tree named '{name}'{}", did_you_mean(.suggestions))]
+    GroveNotFound { name: String, suggestions: Vec<String> },
 
     #[error("Worktree already exists: {0}")]
     WorktreeAlreadyExists(String),
@@ -41,8 +51,14 @@ pub enum GrootError {
     #[error("Branch already exists: {0}")]
     BranchAlreadyExists(String),
 
-    #[error("Insufficient disk space: {available_mb}MB available, {required_mb}MB required")]
-    InsufficientDiskSpace { available_mb: u64, required_mb: u64 },
+    #[error("Insufficient disk space on {location}: {available_mb}MB available, {required_mb}MB required")]
+    InsufficientDiskSpace {
+        location: String,
+        available_mb: u64,
+        required_mb: u64,
+        /// What `docker system prune` could free, per `docker system df`
+        reclaimable: Option<String>,
+    },
 
     #[error("Lock acquisition failed: {0}")]
     LockFailed(String),
@@ -68,8 +84,192 @@ pub enum GrootError {
     #[error("Tmux is not available")]
     TmuxNotAvailable,
 
+    /// A git, docker, or other command exited unsuccessfully
+    #[error("{what}{}", exit_suffix(.process.exit_code))]
+    CommandFailed { what: String, process: Subprocess },
+
+    /// `source`, while doing `operation` (for `task`)
+    #[error("{operation}{}: {source}", task_suffix(.task))]
+    Context {
+        operation: String,
+        task: Option<String>,
+        source: Box<GrootError>,
+    },
+
     #[error("{0}")]
     Other(String),
 }
 
+/// A command groot ran that failed, for the error report: its command line
+/// and the end of what it printed to stderr.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subprocess {
+    pub command: String,
+    /// `None` when it was killed by a signal
+    pub exit_code: Option<i32>,
+    /// The last lines of its stderr
+    pub stderr: String,
+}
+
+impl Subprocess {
+    pub fn new(cmd: &Command, output: &Output) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+        Self {
+            command: command_line(cmd),
+            exit_code: output.status.code(),
+            stderr: lines[lines.len().saturating_sub(STDERR_LINES)..].join("\n"),
+        }
+    }
+}
+
+impl GrootError {
+    /// This error as having happened while doing `operation` (for `task`).
+    pub fn context(self, operation: &str, task: Option<&str>) -> Self {
+        GrootError::Context {
+            operation: operation.to_string(),
+            task: task.map(str::to_string),
+            source: Box::new(self),
+        }
+    }
+
+    /// The error under any context added to it.
+    pub fn root(&self) -> &GrootError {
+        match self {
+            GrootError::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// The failed command behind this error, if there was one.
+    pub fn subprocess(&self) -> Option<&Subprocess> {
+        match self.root() {
+            GrootError::CommandFailed { process, .. } => Some(process),
+            _ => None,
+        }
+    }
+
+    /// The command or step that usually fixes this error, shown under it.
+    pub fn hint(&self) -> Option<String> {
+        match self.root() {
+            Self::NotInitialized => Some(
+                "Run `groot init` in the repository root (or `groot migrate` if it has a .devflow/.treehouse directory)"
+                    .to_string(),
+            ),
+            Self::NotGitRepo => Some("Run groot inside a git repository, or create one with `git init`".to_string()),
+            Self::GroveNotFound { .. } => Some("List the planted workers with `groot worker list`".to_string()),
+            Self::GroveAlreadyExists(name) => Some(format!(
+                "Pick another task name, or attach to it with `groot tree attach {name}` (`groot grove attach {name}` for a grove)"
+            )),
+            Self::BranchAlreadyExists(name) => Some(format!(
+                "Pick another task name, or delete the branch with `git branch -D {name}` if it's no longer needed"
+            )),
+            Self::InsufficientDiskSpace { reclaimable, .. } => Some(format!(
+                "Free space with `docker system prune`{} and `groot grove prune`, or lower min_disk_space_mb in .groot/local.yml",
+                reclaimable.as_ref().map(|r| format!(" (reclaimable: {r})")).unwrap_or_default()
+            )),
+            Self::LockFailed(_) => Some(
+                "Another groot command is working on the same task; wait for it to finish and retry (`groot locks` shows who holds what)"
+                    .to_string(),
+            ),
+            Self::DockerNotAvailable => {
+                Some("Start the Docker daemon (Docker Desktop, or `sudo systemctl start docker`) and check `docker info`".to_string())
+            }
+            Self::ComposeNotAvailable => Some(
+                "Install the Compose plugin so `docker compose version` works, or plant without containers: `groot tree plant <task>`"
+                    .to_string(),
+            ),
+            Self::PortInUse { port, .. } => Some(format!(
+                "See what holds it with `lsof -i :{port}`; if it's another worker, stop it (`groot worker list` shows their ports)"
+            )),
+            Self::TmuxNotAvailable => Some(
+                "Install tmux (`brew install tmux` / `apt install tmux`), or set terminal_backend: kitty or wezterm in .groot/local.yml"
+                    .to_string(),
+            ),
+            Self::CommandFailed { .. } => Some(
+                "Run the command above by hand to see all of its output (`-vv` logs every command groot runs)".to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+fn exit_suffix(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!(" (exit code {code})"),
+        None => " (killed)".to_string(),
+    }
+}
+
+fn task_suffix(task: &Option<String>) -> String {
+    task.as_ref().map(|t| format!(" for '{t}'")).unwrap_or_default()
+}
+
+/// Names from `candidates` close to `name`: within a few edits, or containing
+/// it. Closest first, at most three.
+pub fn similar_names(name: &str, candidates: &[String]) -> Vec<String> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    let mut close: Vec<(usize, &String)> = candidates
+        .iter()
+        .filter(|c| c.as_str() != name)
+        .map(|c| (strsim::levenshtein(name, c), c))
+        .filter(|(distance, c)| *distance <= max_distance || (name.len() >= 3 && c.contains(name)))
+        .collect();
+    close.sort();
+    close.into_iter().take(3).map(|(_, c)| c.clone()).collect()
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [one] => format!(" (did you mean '{one}'?)"),
+        many => format!(
+            " (did you mean one of: {}?)",
+            many.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
 pub type Result<T> = std::result::Result<T, GrootError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::CommandExt;
+
+    #[test]
+    fn test_not_found_suggests_similar_names() {
+        let names = vec!["add-auth".to_string(), "fix-login".to_string(), "login-page".to_string()];
+        assert_eq!(similar_names("add-aut", &names), ["add-auth"]);
+        assert_eq!(similar_names("login", &names), ["fix-login", "login-page"]);
+        assert!(similar_names("refactor-db", &names).is_empty());
+
+        let err = GrootError::GroveNotFound {
+            name: "fix-logn".to_string(),
+            suggestions: similar_names("fix-logn", &names),
+        };
+        assert_eq!(err.to_string(), "No grove or tree named 'fix-logn' (did you mean 'fix-login'?)");
+    }
+
+    #[test]
+    fn test_context_keeps_the_failed_command_and_hint() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo noise >&2; echo 'port is already allocated' >&2; exit 3"]);
+        let err = cmd
+            .checked_output("compose up failed")
+            .unwrap_err()
+            .context("Starting the compose stack", Some("add-auth"));
+        assert_eq!(
+            err.to_string(),
+            "Starting the compose stack for 'add-auth': compose up failed (exit code 3)"
+        );
+        let process = err.subprocess().unwrap();
+        assert!(process.command.starts_with("sh -c "));
+        assert_eq!(process.stderr, "noise\nport is already allocated");
+        assert!(err.hint().unwrap().contains("by hand"));
+
+        let err = GrootError::TmuxNotAvailable.context("Opening the workspace", None);
+        assert!(matches!(err.root(), GrootError::TmuxNotAvailable));
+        assert!(err.hint().unwrap().contains("Install tmux"));
+    }
+}
@@ -11,15 +11,28 @@ pub fn format_branch_name(project: &str, task_type: &str, name: &str) -> String
     format!("{project}/{task_type}/{sanitized_name}")
 }
 
-/// Create a new branch from HEAD
-pub fn create_branch(git: &GitRepo, branch_name: &str) -> Result<()> {
-    let head = git.repo.head()?;
-    let commit = head.peel_to_commit()?;
-
+/// Create a new branch from `base` (a local branch, or `origin/<base>` when only
+/// the remote one exists)
+pub fn create_branch(git: &GitRepo, branch_name: &str, base: &str) -> Result<()> {
     if git.repo.find_branch(branch_name, git2::BranchType::Local).is_ok() {
         return Err(GrootError::BranchAlreadyExists(branch_name.to_string()));
     }
 
+    let commit = git
+        .repo
+        .find_branch(base, git2::BranchType::Local)
+        .or_else(|_| {
+            git.repo
+                .find_branch(&format!("origin/{base}"), git2::BranchType::Remote)
+        })
+        .map_err(|_| {
+            GrootError::Other(format!(
+                "Base branch '{base}' not found. Set base_branch in .groot/config.yml."
+            ))
+        })?
+        .get()
+        .peel_to_commit()?;
+
     git.repo.branch(branch_name, &commit, false)?;
     Ok(())
 }
@@ -0,0 +1,31 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::Result;
+use crate::process::CommandExt;
+
+/// A `git bundle` of `branch`'s commits that `base` lacks, or of its whole
+/// history when `base` is `None` (shells out to git CLI).
+pub fn create(repo_root: &Path, branch: &str, base: Option<&str>) -> Result<Vec<u8>> {
+    let revs = match base {
+        Some(base) => format!("{base}..{branch}"),
+        None => branch.to_string(),
+    };
+    let output = Command::new("git")
+        .args(["bundle", "create", "--quiet", "-", &revs])
+        .current_dir(repo_root)
+        .checked_output(&format!("Failed to bundle {branch}"))?;
+    Ok(output.stdout)
+}
+
+/// Create local `branch` from the same branch in `bundle` (shells out to git CLI)
+pub fn fetch(repo_root: &Path, bundle: &Path, branch: &str) -> Result<()> {
+    let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+    Command::new("git")
+        .args(["fetch", "--quiet"])
+        .arg(bundle)
+        .arg(&refspec)
+        .current_dir(repo_root)
+        .checked_output(&format!("Failed to fetch {branch} from bundle"))?;
+    Ok(())
+}
@@ -0,0 +1,174 @@
+//! The groot-managed block of ignore rules: the state directory's machine-local
+//! files, plus the untracked files copied into new worktrees (`.env` and
+//! friends). It's kept in `.gitignore` and in the repo's `info/exclude`, which
+//! applies to every worktree whatever its branch's `.gitignore` says.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::project::ProjectConfig;
+use crate::detector::rules;
+use crate::error::Result;
+use crate::process::CommandExt;
+
+use super::repo::GitRepo;
+
+pub const BEGIN: &str = "# BEGIN groot (managed by `groot gitignore sync`; edits between these lines are overwritten)";
+pub const END: &str = "# END groot";
+
+/// Machine-local files and directories under the state directory.
+const STATE_ENTRIES: &[&str] = &[
+    "worktrees/",
+    "groves/",
+    "compose/",
+    "locks/",
+    "journal/",
+    "logs/",
+    "diagnostics/",
+    "runs/",
+    "transcripts/",
+    "describe/",
+    "reviews/",
+    "events.jsonl",
+    "probes.json",
+    "daemon.sock",
+    "state.db",
+    "local.yml",
+    "local.*.yml",
+    "ports.json",
+    "ports.json.lock",
+];
+
+/// What `sync` changed.
+#[derive(Debug, Default)]
+pub struct Synced {
+    pub gitignore: bool,
+    pub exclude: bool,
+}
+
+/// The block's entries: the state directory's local files, `.env`, and each
+/// `worktree_copy_files` pattern that doesn't match a tracked file.
+pub fn entries(git: &GitRepo, state_dir: &str, config: &ProjectConfig) -> Vec<String> {
+    let mut entries: Vec<String> = STATE_ENTRIES.iter().map(|e| format!("{state_dir}/{e}")).collect();
+    let index = git.repo.index().ok();
+    let tracked = |rel: &str| index.as_ref().is_some_and(|i| i.get_path(Path::new(rel), 0).is_some());
+    for pattern in std::iter::once(".env").chain(config.copy_files()) {
+        let pattern = pattern.trim_start_matches("./");
+        if rules::glob_paths(&git.root, pattern).iter().any(|rel| tracked(rel)) {
+            continue;
+        }
+        if !entries.iter().any(|e| e == pattern) {
+            entries.push(pattern.to_string());
+        }
+    }
+    entries
+}
+
+/// `contents` with the managed block replaced by one holding `entries` (or
+/// appended), and lines elsewhere that repeat an entry dropped, as older
+/// versions of `groot init` added them one at a time.
+pub fn apply(contents: &str, entries: &[String]) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut block_at = None;
+    let mut in_block = false;
+    for line in contents.lines() {
+        if line == BEGIN {
+            in_block = true;
+            block_at.get_or_insert(kept.len());
+        } else if in_block {
+            in_block = line != END;
+        } else if !entries.iter().any(|e| e == line.trim()) {
+            kept.push(line);
+        }
+    }
+    // Leave a blank line before the block, without piling them up
+    let block_at = block_at.unwrap_or(kept.len());
+    let mut lines: Vec<&str> = kept[..block_at].to_vec();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    if !lines.is_empty() {
+        lines.push("");
+    }
+    lines.push(BEGIN);
+    lines.extend(entries.iter().map(String::as_str));
+    lines.push(END);
+    lines.extend_from_slice(&kept[block_at..]);
+
+    let mut new_contents = lines.join("\n");
+    new_contents.push('\n');
+    new_contents
+}
+
+/// Whether `path` already holds the block for `entries`.
+pub fn is_current(path: &Path, entries: &[String]) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|c| apply(&c, entries) == c)
+}
+
+/// Write the block into `path`. Returns whether the file changed.
+fn write_block(path: &Path, entries: &[String]) -> Result<bool> {
+    let contents = std::fs::read_to_string(path).unwrap_or_default();
+    let new_contents = apply(&contents, entries);
+    if new_contents == contents {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, new_contents)?;
+    Ok(true)
+}
+
+/// The repo's `info/exclude`, shared by all its worktrees.
+fn exclude_path(git: &GitRepo) -> PathBuf {
+    git.repo.commondir().join("info").join("exclude")
+}
+
+/// Write the block into `info/exclude` only, leaving the tracked `.gitignore`
+/// alone. Returns whether it changed.
+pub fn sync_exclude(git: &GitRepo, entries: &[String]) -> Result<bool> {
+    write_block(&exclude_path(git), entries)
+}
+
+/// Bring the block in `.gitignore` and `info/exclude` up to date.
+pub fn sync(git: &GitRepo, state_dir: &str, config: &ProjectConfig) -> Result<Synced> {
+    let entries = entries(git, state_dir, config);
+    Ok(Synced {
+        gitignore: write_block(&git.root.join(".gitignore"), &entries)?,
+        exclude: write_block(&exclude_path(git), &entries)?,
+    })
+}
+
+/// Of `paths` in a worktree, those git wouldn't ignore there.
+pub fn not_ignored(worktree: &Path, paths: &[String]) -> Vec<String> {
+    paths
+        .iter()
+        .filter(|rel| {
+            // check-ignore exits 0 when the path is ignored, 1 when it isn't
+            Command::new("git")
+                .arg("-C")
+                .arg(worktree)
+                .args(["check-ignore", "-q", "--", rel])
+                .logged_output()
+                .is_ok_and(|o| o.status.code() == Some(1))
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_replaces_block_and_folds_old_entries() {
+        let entries = vec![".groot/worktrees/".to_string(), ".env".to_string()];
+        let old = format!("target/\n.env\n.groot/worktrees/\n\n{BEGIN}\n.groot/stale/\n{END}\nnode_modules/\n");
+        let updated = apply(&old, &entries);
+        assert_eq!(updated, format!("target/\n\n{BEGIN}\n.groot/worktrees/\n.env\n{END}\nnode_modules/\n"));
+        assert_eq!(apply(&updated, &entries), updated);
+
+        assert_eq!(apply("", &entries), format!("{BEGIN}\n.groot/worktrees/\n.env\n{END}\n"));
+        assert_eq!(apply("target/", &entries), format!("target/\n\n{BEGIN}\n.groot/worktrees/\n.env\n{END}\n"));
+    }
+}
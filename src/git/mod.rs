@@ -1,3 +1,5 @@
 pub mod branch;
+pub mod bundle;
+pub mod gitignore;
 pub mod repo;
 pub mod worktree;
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 
 use git2::Repository;
 
+use crate::config::state_dir;
 use crate::error::{GrootError, Result};
 
 pub struct GitRepo {
@@ -41,6 +42,6 @@ impl GitRepo {
     }
 
     pub fn groot_dir(&self) -> PathBuf {
-        self.root.join(".groot")
+        self.root.join(state_dir::name())
     }
 }
@@ -1,40 +1,30 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use chrono::{DateTime, Utc};
+
 use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
 
 /// Create a new worktree at the given path for the given branch (shells out to git CLI)
 pub fn create_worktree(repo_root: &Path, worktree_path: &Path, branch: &str) -> Result<()> {
-    let output = Command::new("git")
+    Command::new("git")
         .args(["worktree", "add", "--quiet"])
         .arg(worktree_path)
         .arg(branch)
         .current_dir(repo_root)
-        .output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::GitCommand(format!(
-            "Failed to create worktree: {stderr}"
-        )));
-    }
+        .checked_output("Failed to create worktree")?;
     Ok(())
 }
 
 /// Remove a worktree (shells out to git CLI)
 pub fn remove_worktree(repo_root: &Path, worktree_path: &Path) -> Result<()> {
-    let output = Command::new("git")
+    Command::new("git")
         .args(["worktree", "remove", "--force"])
         .arg(worktree_path)
         .current_dir(repo_root)
-        .output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::GitCommand(format!(
-            "Failed to remove worktree: {stderr}"
-        )));
-    }
+        .checked_output("Failed to remove worktree")?;
     Ok(())
 }
 
@@ -43,14 +33,7 @@ pub fn list_worktrees(repo_root: &Path) -> Result<Vec<WorktreeInfo>> {
     let output = Command::new("git")
         .args(["worktree", "list", "--porcelain"])
         .current_dir(repo_root)
-        .output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::GitCommand(format!(
-            "Failed to list worktrees: {stderr}"
-        )));
-    }
+        .checked_output("Failed to list worktrees")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(parse_worktree_list(&stdout))
@@ -58,17 +41,10 @@ pub fn list_worktrees(repo_root: &Path) -> Result<Vec<WorktreeInfo>> {
 
 /// Prune stale worktree entries
 pub fn prune_worktrees(repo_root: &Path) -> Result<()> {
-    let output = Command::new("git")
+    Command::new("git")
         .args(["worktree", "prune"])
         .current_dir(repo_root)
-        .output()?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(GrootError::GitCommand(format!(
-            "Failed to prune worktrees: {stderr}"
-        )));
-    }
+        .checked_output("Failed to prune worktrees")?;
     Ok(())
 }
 
@@ -132,7 +108,7 @@ pub fn has_uncommitted_changes(worktree_path: &Path) -> bool {
     let output = Command::new("git")
         .args(["status", "--porcelain"])
         .current_dir(worktree_path)
-        .output();
+        .logged_output();
 
     match output {
         Ok(o) if o.status.success() => !o.stdout.is_empty(),
@@ -140,22 +116,195 @@ pub fn has_uncommitted_changes(worktree_path: &Path) -> bool {
     }
 }
 
+/// `has_uncommitted_changes` for several worktrees, checked concurrently.
+pub fn dirty_worktrees(paths: &[&Path]) -> Vec<bool> {
+    std::thread::scope(|scope| {
+        let checks: Vec<_> = paths
+            .iter()
+            .map(|path| scope.spawn(move || has_uncommitted_changes(path)))
+            .collect();
+        checks.into_iter().map(|c| c.join().unwrap_or(false)).collect()
+    })
+}
+
+/// Every uncommitted change in a worktree but those to `exclude`d paths,
+/// untracked files included, as a binary patch against HEAD (empty when there
+/// are none). Stages into `scratch_index` rather than the worktree's own
+/// index, which is left as is.
+pub fn uncommitted_patch(worktree_path: &Path, scratch_index: &Path, exclude: &[&str]) -> Result<Vec<u8>> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .env("GIT_INDEX_FILE", scratch_index)
+            .current_dir(worktree_path)
+            .logged_output()
+    };
+    let mut add = vec!["add".to_string(), "--all".to_string(), "--".to_string(), ".".to_string()];
+    add.extend(exclude.iter().map(|p| format!(":(exclude){p}")));
+    let add: Vec<&str> = add.iter().map(String::as_str).collect();
+    let staged = git(&["read-tree", "HEAD"]).and_then(|_| git(&add));
+    let output = staged.and_then(|_| git(&["diff", "--cached", "--binary", "HEAD"]));
+    let _ = std::fs::remove_file(scratch_index);
+    let output = output?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::GitCommand(format!(
+            "Failed to diff worktree: {stderr}"
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Apply a patch from [`uncommitted_patch`] to a worktree, leaving the
+/// changes uncommitted (shells out to git CLI)
+pub fn apply_patch(worktree_path: &Path, patch: &Path) -> Result<()> {
+    Command::new("git")
+        .arg("apply")
+        .arg(patch)
+        .current_dir(worktree_path)
+        .checked_output("Failed to apply patch")?;
+    Ok(())
+}
+
 /// Count commits on `branch` that are not on `base_branch`.
 /// Returns 0 on any error (non-fatal usage).
 pub fn commits_ahead_of(repo_root: &Path, branch: &str, base_branch: &str) -> u64 {
+    ahead_behind(repo_root, branch, base_branch).map_or(0, |(ahead, _)| ahead)
+}
+
+/// Commits `branch` has that `base_branch` lacks, and the reverse.
+pub fn ahead_behind(repo_root: &Path, branch: &str, base_branch: &str) -> Option<(u64, u64)> {
     let range = format!("{base_branch}...{branch}");
     let output = Command::new("git")
-        .args(["rev-list", "--count", &range])
+        .args(["rev-list", "--left-right", "--count", &range])
         .current_dir(repo_root)
-        .output();
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
 
-    match output {
-        Ok(o) if o.status.success() => {
-            String::from_utf8_lossy(&o.stdout)
-                .trim()
-                .parse()
-                .unwrap_or(0)
-        }
-        _ => 0,
+    // `--left-right` prints "<only on base>\t<only on branch>"
+    let counts = String::from_utf8_lossy(&output.stdout);
+    let mut counts = counts.split_whitespace().map(|n| n.parse::<u64>().ok());
+    let behind = counts.next()??;
+    let ahead = counts.next()??;
+    Some((ahead, behind))
+}
+
+/// `ahead_behind` for many branches at once, in process: the base branch is
+/// resolved once and no `git` is spawned per branch. Branches that don't
+/// resolve are left out.
+pub fn ahead_behind_all(repo: &git2::Repository, branches: &[&str], base_branch: &str) -> HashMap<String, (u64, u64)> {
+    let resolve = |name: &str| {
+        repo.revparse_single(name)
+            .and_then(|o| o.peel_to_commit())
+            .map(|c| c.id())
+            .ok()
+    };
+    let Some(base) = resolve(base_branch) else {
+        return HashMap::new();
+    };
+    branches
+        .iter()
+        .filter_map(|branch| {
+            let (ahead, behind) = repo.graph_ahead_behind(resolve(branch)?, base).ok()?;
+            Some((branch.to_string(), (ahead as u64, behind as u64)))
+        })
+        .collect()
+}
+
+/// When the commit at each branch's tip was made, read in process. Branches
+/// that don't resolve are left out.
+pub fn tip_times(repo: &git2::Repository, branches: &[&str]) -> HashMap<String, DateTime<Utc>> {
+    branches
+        .iter()
+        .filter_map(|branch| {
+            let commit = repo.revparse_single(branch).ok()?.peel_to_commit().ok()?;
+            Some((branch.to_string(), DateTime::from_timestamp(commit.time().seconds(), 0)?))
+        })
+        .collect()
+}
+
+/// Subjects of the commits on `branch` that are not on `base_branch`, oldest first.
+pub fn commit_subjects(repo_root: &Path, branch: &str, base_branch: &str) -> Vec<String> {
+    let range = format!("{base_branch}..{branch}");
+    Command::new("git")
+        .args(["log", "--reverse", "--format=%s", &range])
+        .current_dir(repo_root)
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// `git diff --stat` of `branch` against where it forked from `base_branch`.
+pub fn diff_stat(repo_root: &Path, branch: &str, base_branch: &str) -> Option<String> {
+    diff_since_fork(repo_root, branch, base_branch, &["--stat"])
+}
+
+/// The full diff of `branch` since it forked from `base_branch`, committed
+/// changes only.
+pub fn branch_diff(repo_root: &Path, branch: &str, base_branch: &str) -> Option<String> {
+    diff_since_fork(repo_root, branch, base_branch, &[])
+}
+
+fn diff_since_fork(repo_root: &Path, branch: &str, base_branch: &str, flags: &[&str]) -> Option<String> {
+    let range = format!("{base_branch}...{branch}");
+    let output = Command::new("git")
+        .arg("diff")
+        .args(flags)
+        .arg(&range)
+        .current_dir(repo_root)
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// `git diff --shortstat` of a worktree, uncommitted changes included, against
+/// where its branch forked from `base_branch`. Empty when nothing changed.
+pub fn changes_since_fork(worktree_path: &Path, base_branch: &str) -> Option<String> {
+    let fork = Command::new("git")
+        .args(["merge-base", base_branch, "HEAD"])
+        .current_dir(worktree_path)
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let fork = String::from_utf8_lossy(&fork.stdout).trim().to_string();
+    let output = Command::new("git")
+        .args(["diff", "--shortstat", &fork])
+        .current_dir(worktree_path)
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ahead_behind_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("t", "t@t").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let commit = |parent: Option<git2::Oid>, message: &str| {
+            let parents: Vec<git2::Commit> = parent.map(|p| repo.find_commit(p).unwrap()).into_iter().collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            repo.commit(None, &sig, &sig, message, &tree, &parents).unwrap()
+        };
+        let root = commit(None, "root");
+        let main = commit(Some(root), "main");
+        let feature = commit(Some(commit(Some(root), "one")), "two");
+        repo.branch("base", &repo.find_commit(main).unwrap(), true).unwrap();
+        repo.branch("feature", &repo.find_commit(feature).unwrap(), true).unwrap();
+
+        let distances = ahead_behind_all(&repo, &["feature", "missing"], "base");
+        assert_eq!(distances.get("feature"), Some(&(2, 1)));
+        assert_eq!(distances.get("missing"), None);
+        assert_eq!(ahead_behind(dir.path(), "feature", "base"), Some((2, 1)));
     }
 }
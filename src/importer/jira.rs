@@ -0,0 +1,109 @@
+use serde_json::Value;
+
+use crate::config::importers::JiraConfig;
+use crate::config::secret;
+use crate::error::{GrootError, Result};
+
+use super::{Importer, TaskDraft};
+
+/// The Jira issues the configured JQL finds, read with an API token.
+pub struct Jira {
+    config: JiraConfig,
+}
+
+impl Jira {
+    pub fn new(config: JiraConfig) -> Self {
+        Self { config }
+    }
+
+    fn site(&self) -> &str {
+        self.config.url.trim_end_matches('/')
+    }
+}
+
+impl Importer for Jira {
+    fn fetch_candidates(&self, limit: usize) -> Result<Vec<TaskDraft>> {
+        let token = secret::resolve(&self.config.api_token)?;
+        let url = url::Url::parse_with_params(
+            &format!("{}/rest/api/3/search/jql", self.site()),
+            [
+                ("jql", self.config.jql.as_str()),
+                ("maxResults", &limit.to_string()),
+                ("fields", "summary,description,labels,issuetype"),
+            ],
+        )
+        .map_err(|e| GrootError::Other(format!("Invalid Jira URL '{}': {e}", self.config.url)))?;
+        let response = super::request(
+            "Fetching issues from Jira",
+            url.as_str(),
+            &[("user", format!("{}:{token}", self.config.email))],
+            None,
+        )?;
+        Ok(parse(&response, self.site()))
+    }
+}
+
+fn parse(response: &Value, site: &str) -> Vec<TaskDraft> {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    response["issues"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|issue| {
+            let key = text(&issue["key"]);
+            let fields = &issue["fields"];
+            let mut description = String::new();
+            adf_text(&fields["description"], &mut description);
+            TaskDraft {
+                url: format!("{site}/browse/{key}"),
+                key,
+                title: text(&fields["summary"]),
+                description: description.trim().to_string(),
+                labels: fields["labels"].as_array().into_iter().flatten().map(text).collect(),
+                bug: fields["issuetype"]["name"].as_str().is_some_and(|t| t.eq_ignore_ascii_case("bug")),
+            }
+        })
+        .filter(|d| !d.key.is_empty())
+        .collect()
+}
+
+/// The plain text of an Atlassian Document Format node, a line per block.
+fn adf_text(node: &Value, out: &mut String) {
+    if let Some(text) = node["text"].as_str() {
+        out.push_str(text);
+    }
+    for child in node["content"].as_array().into_iter().flatten() {
+        adf_text(child, out);
+    }
+    let block = ["paragraph", "heading", "listItem", "codeBlock", "blockquote"];
+    if node["type"].as_str().is_some_and(|t| block.contains(&t) || t == "hardBreak") && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        let response = json!({ "issues": [{
+            "key": "OPS-12",
+            "fields": {
+                "summary": "Rotate the staging certs",
+                "labels": ["infra"],
+                "issuetype": { "name": "Task" },
+                "description": { "type": "doc", "content": [
+                    { "type": "paragraph", "content": [{ "type": "text", "text": "They expire " }, { "type": "text", "text": "Friday." }] },
+                    { "type": "paragraph", "content": [{ "type": "text", "text": "Use certbot." }] },
+                ] },
+            },
+        }] });
+        let drafts = parse(&response, "https://acme.atlassian.net");
+        assert_eq!(drafts[0].url, "https://acme.atlassian.net/browse/OPS-12");
+        assert_eq!(drafts[0].description, "They expire Friday.\nUse certbot.");
+        assert_eq!(drafts[0].labels, ["infra"]);
+        assert!(!drafts[0].bug);
+    }
+}
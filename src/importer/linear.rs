@@ -0,0 +1,105 @@
+use serde_json::{Value, json};
+
+use crate::config::importers::LinearConfig;
+use crate::config::secret;
+use crate::error::{GrootError, Result};
+
+use super::{Importer, TaskDraft};
+
+const API_URL: &str = "https://api.linear.app/graphql";
+
+const QUERY: &str = "query($filter: IssueFilter, $first: Int) {
+  viewer {
+    assignedIssues(filter: $filter, first: $first, orderBy: updatedAt) {
+      nodes { identifier title description url labels { nodes { name } } }
+    }
+  }
+}";
+
+/// The Linear issues assigned to the API key's user that aren't completed
+/// or canceled, optionally of one team.
+pub struct Linear {
+    config: LinearConfig,
+}
+
+impl Linear {
+    pub fn new(config: LinearConfig) -> Self {
+        Self { config }
+    }
+
+    fn filter(&self) -> Value {
+        let mut filter = json!({ "state": { "type": { "nin": ["completed", "canceled"] } } });
+        if let Some(team) = &self.config.team {
+            filter["team"] = json!({ "key": { "eq": team } });
+        }
+        filter
+    }
+}
+
+impl Importer for Linear {
+    fn fetch_candidates(&self, limit: usize) -> Result<Vec<TaskDraft>> {
+        let api_key = secret::resolve(&self.config.api_key)?;
+        let body = json!({ "query": QUERY, "variables": { "filter": self.filter(), "first": limit } });
+        let response = super::request(
+            "Fetching issues from Linear",
+            API_URL,
+            &[("header", format!("Authorization: {api_key}"))],
+            Some(&body.to_string()),
+        )?;
+        parse(&response)
+    }
+}
+
+fn parse(response: &Value) -> Result<Vec<TaskDraft>> {
+    if let Some(errors) = response.get("errors").and_then(Value::as_array) {
+        let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+        return Err(GrootError::Other(format!("Linear refused the query: {}", messages.join("; "))));
+    }
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    let nodes = response["data"]["viewer"]["assignedIssues"]["nodes"].as_array();
+    Ok(nodes
+        .into_iter()
+        .flatten()
+        .map(|issue| {
+            let labels: Vec<String> = issue["labels"]["nodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|l| text(&l["name"]))
+                .collect();
+            TaskDraft {
+                key: text(&issue["identifier"]),
+                title: text(&issue["title"]),
+                description: text(&issue["description"]),
+                bug: labels.iter().any(|l| l.eq_ignore_ascii_case("bug")),
+                labels,
+                url: text(&issue["url"]),
+            }
+        })
+        .filter(|d| !d.key.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let response = json!({ "data": { "viewer": { "assignedIssues": { "nodes": [{
+            "identifier": "ENG-7",
+            "title": "Login loops",
+            "description": null,
+            "url": "https://linear.app/acme/issue/ENG-7",
+            "labels": { "nodes": [{ "name": "Bug" }, { "name": "auth" }] },
+        }] } } } });
+        let drafts = parse(&response).unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].key, "ENG-7");
+        assert_eq!(drafts[0].description, "");
+        assert_eq!(drafts[0].labels, ["Bug", "auth"]);
+        assert!(drafts[0].bug);
+
+        assert!(parse(&json!({ "errors": [{ "message": "Authentication required" }] })).is_err());
+    }
+}
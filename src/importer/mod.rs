@@ -0,0 +1,171 @@
+//! Issue trackers `groot worker import` plants workers from. Each tracker
+//! offers its open issues as `TaskDraft`s; a draft becomes a worker named
+//! after the issue key, prompted with the issue.
+
+pub mod jira;
+pub mod linear;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::config::importers::ImporterConfig;
+use crate::error::{GrootError, Result, Subprocess};
+use crate::orchestrator::state::GroveState;
+use crate::process;
+
+/// The issue trackers there are importers for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Provider {
+    Linear,
+    Jira,
+}
+
+impl Provider {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::Jira => "jira",
+        }
+    }
+}
+
+/// An issue that could become a worker.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskDraft {
+    /// The tracker's key for the issue, e.g. `ENG-123`
+    pub key: String,
+    pub title: String,
+    pub description: String,
+    pub labels: Vec<String>,
+    pub url: String,
+    /// The tracker calls it a bug, so it's planted as a `bugfix`
+    pub bug: bool,
+}
+
+impl TaskDraft {
+    /// The worker's name: the issue key, lowercased (`ENG-123` is `eng-123`).
+    pub fn task_name(&self) -> String {
+        self.key.to_lowercase()
+    }
+
+    pub fn task_type(&self) -> &'static str {
+        if self.bug { "bugfix" } else { "feature" }
+    }
+
+    /// The prompt the worker's agent starts with.
+    pub fn prompt(&self) -> String {
+        let mut prompt = format!("{}: {}\n", self.key, self.title);
+        if !self.description.trim().is_empty() {
+            prompt.push('\n');
+            prompt.push_str(self.description.trim());
+            prompt.push('\n');
+        }
+        prompt.push_str(&format!("\n{}\n", self.url));
+        prompt
+    }
+}
+
+/// A tracker groot can read issues from.
+pub trait Importer {
+    /// Open issues worth planting a worker for, at most `limit` of them.
+    fn fetch_candidates(&self, limit: usize) -> Result<Vec<TaskDraft>>;
+}
+
+/// The importer for `provider`, as `local.yml` sets it up.
+pub fn importer(provider: Provider, config: &ImporterConfig) -> Result<Box<dyn Importer>> {
+    let missing = || {
+        GrootError::Other(format!(
+            "No {0} importer is configured; add `importers: {{ {0}: ... }}` to .groot/local.yml",
+            provider.name()
+        ))
+    };
+    Ok(match provider {
+        Provider::Linear => Box::new(linear::Linear::new(config.linear.clone().ok_or_else(missing)?)),
+        Provider::Jira => Box::new(jira::Jira::new(config.jira.clone().ok_or_else(missing)?)),
+    })
+}
+
+/// Of `drafts`, those without a planted worker of the same name.
+pub fn new_candidates(drafts: Vec<TaskDraft>, workers: &[GroveState]) -> Vec<TaskDraft> {
+    drafts
+        .into_iter()
+        .filter(|d| !workers.iter().any(|w| w.task_name == d.task_name()))
+        .collect()
+}
+
+/// Quote a value for a curl config file.
+fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Send a request with `curl` and parse the JSON response. `config` is curl
+/// config (headers, credentials) passed on stdin, so tokens don't show up in
+/// the process list or the logged command line.
+fn request(what: &str, url: &str, config: &[(&str, String)], body: Option<&str>) -> Result<Value> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["--fail-with-body", "--silent", "--show-error", "--max-time", "30", "--config", "-"]);
+    cmd.args(["-H", "Accept: application/json"]);
+    if let Some(body) = body {
+        cmd.args(["-H", "Content-Type: application/json", "--data-binary", body]);
+    }
+    cmd.arg(url);
+    debug!("$ {}", process::command_line(&cmd));
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        for (key, value) in config {
+            writeln!(stdin, "{key} = {}", curl_quote(value))?;
+        }
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let mut process = Subprocess::new(&cmd, &output);
+        // With --fail-with-body, the API's explanation is on stdout
+        let body = String::from_utf8_lossy(&output.stdout);
+        if !body.trim().is_empty() {
+            process.stderr = format!("{}\n{}", process.stderr, body.trim()).trim().to_string();
+        }
+        return Err(GrootError::CommandFailed { what: what.to_string(), process });
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| GrootError::Other(format!("{what}: unexpected response: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft_names_and_dedup() {
+        let draft = |key: &str| TaskDraft {
+            key: key.to_string(),
+            title: "Fix the login redirect".to_string(),
+            description: String::new(),
+            labels: Vec::new(),
+            url: format!("https://linear.app/acme/issue/{key}"),
+            bug: true,
+        };
+        assert_eq!(draft("ENG-1").task_name(), "eng-1");
+        assert_eq!(draft("ENG-1").task_type(), "bugfix");
+        assert_eq!(
+            draft("ENG-1").prompt(),
+            "ENG-1: Fix the login redirect\n\nhttps://linear.app/acme/issue/ENG-1\n"
+        );
+
+        let planted = GroveState::parse(
+            r#"{"task_name":"eng-1","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null}"#,
+        )
+        .unwrap();
+        let left = new_candidates(vec![draft("ENG-1"), draft("ENG-2")], &[planted]);
+        assert_eq!(left, [draft("ENG-2")]);
+    }
+}
@@ -0,0 +1,63 @@
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+
+/// Target of events meant for the log file only, such as the invocation's
+/// arguments and outcome, which the console already shows another way.
+pub const FILE_ONLY: &str = "groot::logfile";
+
+/// The log is rotated once it grows past this, when a command starts.
+const MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated logs kept: `groot.log.1` (newest) to `groot.log.3`.
+const KEEP: usize = 3;
+
+/// `.groot/logs/groot.log`: one JSON object per line, for every command run
+/// in the project.
+pub fn path(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("logs").join("groot.log")
+}
+
+/// Open the log for appending, rotating it first if it has grown too big.
+pub fn open(groot_dir: &Path) -> Result<File> {
+    let path = path(groot_dir);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_BYTES) {
+        rotate(&path)?;
+    }
+    Ok(OpenOptions::new().create(true).append(true).open(&path)?)
+}
+
+/// Shift `groot.log.N` to `.N+1`, dropping the oldest, then `groot.log` to `.1`.
+fn rotate(path: &Path) -> Result<()> {
+    let rotated = |n: usize| PathBuf::from(format!("{}.{n}", path.display()));
+    for n in (1..KEEP).rev() {
+        if rotated(n).exists() {
+            std::fs::rename(rotated(n), rotated(n + 1))?;
+        }
+    }
+    std::fs::rename(path, rotated(1))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_rotates_big_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = path(dir.path());
+        std::fs::create_dir_all(log.parent().unwrap()).unwrap();
+        std::fs::write(&log, vec![b'x'; MAX_BYTES as usize + 1]).unwrap();
+        std::fs::write(log.with_extension("log.1"), "older").unwrap();
+
+        let file = open(dir.path()).unwrap();
+        assert_eq!(file.metadata().unwrap().len(), 0);
+        assert_eq!(std::fs::metadata(log.with_extension("log.1")).unwrap().len(), MAX_BYTES + 1);
+        assert_eq!(std::fs::read_to_string(log.with_extension("log.2")).unwrap(), "older");
+    }
+}
@@ -1,19 +1,35 @@
+mod atomic;
 mod claude_md;
 mod cli;
 mod compose;
 mod config;
 mod container;
+mod context;
 mod detector;
 mod error;
 mod git;
+mod importer;
+mod logfile;
+mod notify;
 mod orchestrator;
+mod probe;
+mod process;
+mod progress;
+mod store;
+mod terminal;
 mod tmux;
+mod wsl;
 
 use clap::Parser;
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
     let cli = cli::Cli::parse();
+    cli::output::init(cli.output.or(cli.json.then_some(cli::output::Format::Json)));
+    cli::output::init_logging(cli.verbose, cli.quiet);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let started = std::time::Instant::now();
+    tracing::info!(target: logfile::FILE_ONLY, pid = std::process::id(), "groot {}", args.join(" "));
 
     let rt = match tokio::runtime::Runtime::new() {
         Ok(rt) => rt,
@@ -24,9 +40,36 @@ fn main() -> ExitCode {
     };
 
     match rt.block_on(cli::dispatch(cli.command)) {
-        Ok(()) => ExitCode::SUCCESS,
+        Ok(()) => {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            tracing::debug!("finished in {elapsed_ms} ms");
+            tracing::info!(target: logfile::FILE_ONLY, elapsed_ms, "succeeded");
+            ExitCode::SUCCESS
+        }
         Err(e) => {
+            let process = e.subprocess();
+            let command = process.map(|p| p.command.as_str());
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            tracing::error!(target: logfile::FILE_ONLY, command, elapsed_ms, "failed: {e}");
             eprintln!("Error: {e}");
+            if progress::is_porcelain() {
+                let message = e.to_string();
+                progress::emit(&progress::Event::Error {
+                    message: &message,
+                    hint: e.hint().as_deref(),
+                    command,
+                    stderr: process.map(|p| p.stderr.as_str()).filter(|s| !s.is_empty()),
+                });
+            }
+            if let Some(process) = process {
+                eprintln!("  {} {}", console::style("command:").dim(), process.command);
+                for line in process.stderr.lines() {
+                    eprintln!("  {} {line}", console::style("│").dim());
+                }
+            }
+            if let Some(hint) = e.hint() {
+                eprintln!("  {} {hint}", console::style("hint:").cyan());
+            }
             ExitCode::FAILURE
         }
     }
@@ -0,0 +1,91 @@
+use serde_json::{json, Value};
+
+use crate::config::webhook::{WebhookEvent, WebhookKind};
+
+/// Turns an event's JSON payload into the body one kind of endpoint expects.
+pub trait Notifier {
+    fn body(&self, event: WebhookEvent, payload: &Value) -> Value;
+}
+
+/// Any endpoint: the payload as is.
+pub struct Json;
+
+/// A Slack incoming webhook: a one-line `text` message.
+pub struct Slack;
+
+/// A Discord channel webhook: a one-line `content` message.
+pub struct Discord;
+
+/// Discord rejects messages longer than 2000 characters.
+const DISCORD_MAX_CHARS: usize = 2000;
+
+impl Notifier for Json {
+    fn body(&self, _event: WebhookEvent, payload: &Value) -> Value {
+        payload.clone()
+    }
+}
+
+impl Notifier for Slack {
+    fn body(&self, event: WebhookEvent, payload: &Value) -> Value {
+        json!({ "text": message(event, payload) })
+    }
+}
+
+impl Notifier for Discord {
+    fn body(&self, event: WebhookEvent, payload: &Value) -> Value {
+        let content: String = message(event, payload).chars().take(DISCORD_MAX_CHARS).collect();
+        json!({ "content": content })
+    }
+}
+
+pub fn notifier(kind: WebhookKind) -> Box<dyn Notifier> {
+    match kind {
+        WebhookKind::Json => Box::new(Json),
+        WebhookKind::Slack => Box::new(Slack),
+        WebhookKind::Discord => Box::new(Discord),
+    }
+}
+
+/// The event as a chat message, e.g. "[shop] `login` finished its task in 4m 10s".
+fn message(event: WebhookEvent, payload: &Value) -> String {
+    let text = |key: &str| payload.get(key).and_then(Value::as_str).unwrap_or_default();
+    let flag = |key: &str| payload.get(key).and_then(Value::as_bool).unwrap_or(false);
+    let worker = text("worker");
+    let what = match event {
+        WebhookEvent::WorkerSpawned => format!("`{worker}` planted on `{}`", text("branch")),
+        WebhookEvent::WorkerFailed if text("stage") == "run" => {
+            let exit = match payload.get("exit_code").and_then(Value::as_i64) {
+                Some(code) => format!("exit code {code}"),
+                None => "killed".to_string(),
+            };
+            format!("headless agent run in `{worker}` failed ({exit})")
+        }
+        WebhookEvent::WorkerFailed => format!("planting `{worker}` failed: {}", text("error")),
+        WebhookEvent::WorkerStopped if flag("uprooted") => format!("`{worker}` uprooted"),
+        WebhookEvent::WorkerStopped => format!("`{worker}` stopped"),
+        WebhookEvent::TaskCompleted if payload.get("duration_ms").is_none() => format!("`{worker}` marked complete"),
+        WebhookEvent::TaskCompleted => {
+            let secs = payload.get("duration_ms").and_then(Value::as_u64).unwrap_or(0) / 1000;
+            format!("`{worker}` finished its task in {}m {}s", secs / 60, secs % 60)
+        }
+        WebhookEvent::DbCloneFinished if flag("success") => format!("database cloned into `{worker}`"),
+        WebhookEvent::DbCloneFinished => format!("database clone into `{worker}` failed: {}", text("error")),
+    };
+    format!("[{}] {what}", text("project"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_messages() {
+        let payload = json!({ "project": "shop", "worker": "login", "stage": "run", "exit_code": 1 });
+        let slack = Slack.body(WebhookEvent::WorkerFailed, &payload);
+        assert_eq!(slack["text"], "[shop] headless agent run in `login` failed (exit code 1)");
+
+        let payload = json!({ "project": "shop", "worker": "login", "duration_ms": 250_000 });
+        let discord = Discord.body(WebhookEvent::TaskCompleted, &payload);
+        assert_eq!(discord["content"], "[shop] `login` finished its task in 4m 10s");
+    }
+}
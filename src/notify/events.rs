@@ -0,0 +1,55 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::debug;
+
+/// `.groot/events.jsonl`: every lifecycle event, one payload per line, kept
+/// whether or not any webhook wants it.
+pub fn path(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("events.jsonl")
+}
+
+/// Append `payload` to the event log. Best effort; failures are only logged.
+pub fn record(groot_dir: &Path, payload: &Value) {
+    let appended = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path(groot_dir))
+        .and_then(|mut f| writeln!(f, "{payload}"));
+    if let Err(e) = appended {
+        debug!("Could not record an event: {e}");
+    }
+}
+
+/// The fields of a logged event that history cares about.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoggedEvent {
+    pub event: String,
+    pub timestamp: DateTime<Utc>,
+    /// The task the event is about
+    pub worker: Option<String>,
+    pub task_type: Option<String>,
+    /// `worker_failed`: `plant` or `run`
+    pub stage: Option<String>,
+    /// How long the plant, clone, or run took
+    pub duration_ms: Option<u64>,
+    /// `worker_stopped`: how long the worker had been planted
+    pub lifetime_secs: Option<u64>,
+    /// `worker_stopped`: the worktree and branch went too
+    pub uprooted: Option<bool>,
+    /// `db_clone_finished`
+    pub success: Option<bool>,
+}
+
+/// Every event in the log, oldest first; unreadable lines are skipped.
+pub fn read(groot_dir: &Path) -> Vec<LoggedEvent> {
+    std::fs::read_to_string(path(groot_dir))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
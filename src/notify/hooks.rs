@@ -0,0 +1,81 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+use tracing::warn;
+
+use crate::config::hooks::{HooksConfig, Lifecycle};
+use crate::process::CommandExt;
+
+/// Run the `lifecycle` hooks from `.groot/hooks.yml`, in order, with each of
+/// `payload`'s fields as a `GROOT_<FIELD>` environment variable and the whole
+/// payload as `GROOT_PAYLOAD`. A failing hook is only warned about; it never
+/// fails the change it ran on.
+pub fn run(groot_dir: &Path, lifecycle: Lifecycle, payload: &Value) {
+    let hooks = match HooksConfig::load(groot_dir) {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            warn!("Warning: {} hooks not run, hooks.yml is invalid: {e}", lifecycle.key());
+            return;
+        }
+    };
+    let commands = hooks.commands(lifecycle);
+    if commands.is_empty() {
+        return;
+    }
+    let env = env(lifecycle, payload);
+    let repo_root = groot_dir.parent().unwrap_or(Path::new("."));
+    for command in commands {
+        let ran = Command::new("sh")
+            .args(["-c", command])
+            .current_dir(repo_root)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .checked_output(&format!("The {} hook `{command}`", lifecycle.key()));
+        if let Err(e) = ran {
+            warn!("Warning: {e}");
+        }
+    }
+}
+
+fn env(lifecycle: Lifecycle, payload: &Value) -> Vec<(String, String)> {
+    let mut env = vec![
+        ("GROOT_HOOK".to_string(), lifecycle.key().to_string()),
+        ("GROOT_PAYLOAD".to_string(), payload.to_string()),
+    ];
+    if let Value::Object(fields) = payload {
+        for (key, value) in fields {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            env.push((format!("GROOT_{}", key.to_uppercase()), value));
+        }
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_run_with_payload_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let groot_dir = dir.path().join(".groot");
+        std::fs::create_dir_all(&groot_dir).unwrap();
+        std::fs::write(
+            groot_dir.join("hooks.yml"),
+            "on_close:\n  - echo \"$GROOT_HOOK $GROOT_WORKER $GROOT_UPROOTED $GROOT_LABELS\" > closed.txt\n  - exit 3\n",
+        )
+        .unwrap();
+        let payload = serde_json::json!({ "worker": "fix-login", "uprooted": true, "labels": ["api"] });
+
+        run(&groot_dir, Lifecycle::Create, &payload);
+        assert!(!dir.path().join("closed.txt").exists());
+        run(&groot_dir, Lifecycle::Close, &payload);
+        let written = std::fs::read_to_string(dir.path().join("closed.txt")).unwrap();
+        assert_eq!(written, "on_close fix-login true [\"api\"]\n");
+    }
+}
@@ -0,0 +1,140 @@
+pub mod chat;
+pub mod events;
+pub mod hooks;
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::config::hooks::Lifecycle;
+use crate::config::local::{LocalConfig, Operation};
+use crate::config::project::ProjectConfig;
+use crate::config::secret;
+use crate::config::webhook::WebhookEvent;
+
+/// Show a native desktop notification. Best effort; without a
+/// notification service it is only logged.
+pub fn desktop(title: &str, body: &str) {
+    let result = notify_rust::Notification::new()
+        .appname("groot")
+        .summary(title)
+        .body(body)
+        .show();
+    if let Err(e) = result {
+        debug!("Could not show a desktop notification: {e}");
+    }
+}
+
+/// Notify that `op` finished for `worker` after `took`, failing with `error`
+/// if given, when this machine's `notifications` settings ask for it.
+pub fn finished(groot_dir: &Path, op: Operation, worker: &str, took: Duration, error: Option<&str>) {
+    let Ok(local) = LocalConfig::load(&groot_dir.join("local.yml")) else {
+        return;
+    };
+    if !local.notifications.wants(op, took) {
+        return;
+    }
+    let what = match op {
+        Operation::Plant => "Planting",
+        Operation::ComposeHealthy => "Starting the compose stack",
+        Operation::DbClone => "Cloning the database",
+        Operation::AgentDone => "The agent",
+    };
+    let secs = took.as_secs();
+    let body = match error {
+        None => format!("{what} finished after {}m {}s", secs / 60, secs % 60),
+        Some(e) => format!("{what} failed after {}m {}s: {e}", secs / 60, secs % 60),
+    };
+    desktop(&format!("groot: {worker}"), &body);
+}
+
+/// Record `event` for `worker` in the event log, and POST it to every
+/// webhook in the project config that wants it, with `details` merged into
+/// the payload (or, for Slack and Discord hooks, a message made from it).
+/// `curl` runs in the background so a slow endpoint never holds groot up;
+/// failures are only logged. Then run the lifecycle hooks the event stands for.
+pub fn event(groot_dir: &Path, event: WebhookEvent, worker: &str, details: Value) {
+    let Ok(config) = ProjectConfig::load(&groot_dir.join("config.yml")) else {
+        return;
+    };
+    let payload = payload(&config.project_name, event.as_str(), worker, details);
+    events::record(groot_dir, &payload);
+    for hook in config.webhooks.iter().filter(|h| h.wants(event)) {
+        let url = match secret::resolve(&hook.url) {
+            Ok(url) => url,
+            Err(e) => {
+                warn!("Warning: {} webhook not sent: {e}", event.as_str());
+                continue;
+            }
+        };
+        // The URL is often a secret itself, so it isn't logged
+        debug!("POST {} {} webhook for '{worker}'", event.as_str(), hook.kind.as_str());
+        let body = chat::notifier(hook.kind).body(event, &payload).to_string();
+        if let Err(e) = post_json(&url, &body) {
+            debug!("Could not send a webhook: {e}");
+        }
+    }
+    if let Some(lifecycle) = Lifecycle::of(event) {
+        hooks::run(groot_dir, lifecycle, &payload);
+    }
+}
+
+/// Run the `on_active` hooks: `worker`'s agent was given a prompt, `via` the
+/// plant, typed into its workspace, or for a headless run. Unlike `event`,
+/// this isn't logged or sent to webhooks.
+pub fn agent_prompted(groot_dir: &Path, worker: &str, via: &str) {
+    let Ok(config) = ProjectConfig::load(&groot_dir.join("config.yml")) else {
+        return;
+    };
+    let payload = payload(&config.project_name, "agent_prompted", worker, serde_json::json!({ "via": via }));
+    hooks::run(groot_dir, Lifecycle::Active, &payload);
+}
+
+fn payload(project: &str, event: &str, worker: &str, details: Value) -> Value {
+    let mut payload = serde_json::json!({
+        "event": event,
+        "project": project,
+        "worker": worker,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    });
+    if let (Some(payload), Value::Object(details)) = (payload.as_object_mut(), details) {
+        payload.extend(details);
+    }
+    payload
+}
+
+/// POST `body` to `url` with `curl`, without waiting for the response.
+fn post_json(url: &str, body: &str) -> std::io::Result<()> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--max-time", "10", "-X", "POST"])
+        .args(["-H", "Content-Type: application/json", "--data-binary", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes())?;
+    }
+    // Reap it if groot is still running when it finishes; if not, it finishes on its own
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_payload() {
+        let details = serde_json::json!({ "branch": "shop/feature/login" });
+        let payload = payload("shop", WebhookEvent::WorkerSpawned.as_str(), "login", details);
+        assert_eq!(payload["event"], "worker_spawned");
+        assert_eq!(payload["worker"], "login");
+        assert_eq!(payload["branch"], "shop/feature/login");
+        assert!(payload["timestamp"].is_string());
+    }
+}
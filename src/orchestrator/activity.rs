@@ -0,0 +1,110 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::orchestrator::run::AGENT;
+use crate::orchestrator::state::GroveState;
+use crate::orchestrator::transcript::{self, PANE_LOG};
+use crate::tmux::{session, workspace};
+
+/// Shells a pane sits at when no agent is running in it.
+pub const SHELLS: &[&str] = &["bash", "zsh", "sh", "dash", "fish", "ksh"];
+
+/// What a worker's agent pane is doing, judged from its last output and what
+/// it shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum AgentActivity {
+    /// Printed something recently
+    Working,
+    /// Nothing printed for at least the idle threshold
+    Idle { minutes: u64 },
+    /// Showing a permission prompt, waiting for a human
+    AwaitingPermission,
+    /// The pane is at a shell prompt
+    NoAgent,
+    /// No live tmux session to look at
+    NoSession,
+}
+
+impl AgentActivity {
+    /// Whether the agent needs a human to look at it.
+    pub fn is_stuck(self) -> bool {
+        matches!(self, AgentActivity::Idle { .. } | AgentActivity::AwaitingPermission)
+    }
+
+    pub fn describe(self) -> String {
+        match self {
+            AgentActivity::Working => "working".to_string(),
+            AgentActivity::Idle { minutes } => format!("idle for {minutes}m"),
+            AgentActivity::AwaitingPermission => "waiting for permission".to_string(),
+            AgentActivity::NoAgent => "no agent".to_string(),
+            AgentActivity::NoSession => "-".to_string(),
+        }
+    }
+}
+
+/// Check the worker's agent pane. It is idle once it has printed nothing for
+/// `idle_after`, going by the pane's transcript (else its window's activity).
+pub fn check(groot_dir: &Path, worker: &GroveState, idle_after: Duration) -> AgentActivity {
+    let Some(ws) = worker.tmux_session.as_ref().filter(|ws| session::session_exists(ws)) else {
+        return AgentActivity::NoSession;
+    };
+    let Some((pane, command)) = workspace::agent_pane(ws, AGENT) else {
+        return AgentActivity::NoSession;
+    };
+    if SHELLS.contains(&command.as_str()) {
+        return AgentActivity::NoAgent;
+    }
+    if session::capture_pane(&pane).is_some_and(|screen| is_permission_prompt(&screen)) {
+        return AgentActivity::AwaitingPermission;
+    }
+
+    let log = transcript::transcripts_dir(groot_dir, &worker.task_name).join(PANE_LOG);
+    let last_output = std::fs::metadata(&log)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .or_else(|| session::window_activity(&pane));
+    let Some(last_output) = last_output else {
+        return AgentActivity::Working;
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+    let silent = Duration::from_secs(now.saturating_sub(last_output).max(0) as u64);
+    if silent >= idle_after {
+        AgentActivity::Idle { minutes: silent.as_secs() / 60 }
+    } else {
+        AgentActivity::Working
+    }
+}
+
+/// Whether the bottom of the screen is one of the agent's "Do you want to ...?"
+/// prompts with numbered choices.
+fn is_permission_prompt(screen: &str) -> bool {
+    let lines: Vec<&str> = screen.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let tail = &lines[lines.len().saturating_sub(15)..];
+    tail.iter().any(|l| l.contains("Do you want to"))
+        && tail.iter().any(|l| l.trim_start_matches(['❯', '>', ' ']).starts_with("1. Yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_permission_prompt() {
+        let screen = "\
+ Bash command
+   rm -rf tmp/cache
+ Do you want to proceed?
+ ❯ 1. Yes
+   2. Yes, and don't ask again for rm commands
+   3. No, and tell Claude what to do differently (esc)
+
+";
+        assert!(is_permission_prompt(screen));
+        assert!(!is_permission_prompt("Do you want to see the diff?\n> add a test\n"));
+    }
+}
@@ -0,0 +1,349 @@
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::local::LocalConfig;
+use crate::context::GrootContext;
+use crate::error::{GrootError, Result};
+use crate::git::{branch, bundle, repo::GitRepo, worktree};
+use crate::orchestrator::transcript;
+use crate::store;
+
+/// Version of the archive layout, bumped when an older groot couldn't read it.
+pub const ARCHIVE_FORMAT: u32 = 1;
+
+/// `.groot/` files and directories that configure the project (as opposed to
+/// per-machine state), carried in an archive and restored where missing.
+const CONFIG_ENTRIES: &[&str] = &[
+    "config.yml",
+    "local.yml",
+    "hooks.yml",
+    "compose-template.yml",
+    "tmux-layout.json",
+    "tmux-layouts",
+    "prompts",
+    "claude-md.template",
+    "claude-partials",
+    "claude-settings.template",
+];
+
+/// Files planting writes into a worktree, left out of its uncommitted changes
+/// since planting it again rewrites them.
+const GENERATED: &[&str] = &["CLAUDE.local.md", ".claude/settings.local.json"];
+
+/// `manifest.json` at the root of an archive.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub project: String,
+    pub exported_at: DateTime<Utc>,
+    /// Branches without a bundle are recreated from here
+    pub base_branch: String,
+    pub workers: Vec<ExportedWorker>,
+}
+
+/// One worktree in an archive. Its commits are in `workers/<task>/branch.bundle`
+/// and its uncommitted changes in `workers/<task>/changes.patch`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedWorker {
+    pub task_name: String,
+    pub branch: String,
+    pub task_type: Option<String>,
+    /// Had a compose stack of its own
+    pub grove: bool,
+    /// Commits bundled; `None` when its base isn't in the repo and the whole
+    /// branch was bundled
+    pub commits: Option<u64>,
+    pub uncommitted: bool,
+}
+
+/// What [`import`] did with each part of an archive.
+#[derive(Debug)]
+pub struct Imported {
+    pub project: String,
+    pub exported_at: DateTime<Utc>,
+    pub config: Vec<String>,
+    /// Config files left alone because they already exist here
+    pub config_kept: Vec<String>,
+    pub workers: Vec<ExportedWorker>,
+    /// Workers skipped, with why
+    pub skipped: Vec<(String, String)>,
+}
+
+fn worker_dir(task_name: &str) -> PathBuf {
+    Path::new("workers").join(task_name)
+}
+
+/// Worktrees under `worktrees_dir`, by task name, with their branch.
+fn groot_worktrees(git: &GitRepo, worktrees_dir: &Path) -> Result<Vec<(String, worktree::WorktreeInfo)>> {
+    let worktrees_dir = worktrees_dir.to_path_buf();
+    let worktrees_dir = worktrees_dir.canonicalize().unwrap_or(worktrees_dir);
+    let mut found: Vec<_> = worktree::list_worktrees(&git.root)?
+        .into_iter()
+        .filter(|wt| wt.branch.is_some())
+        .filter_map(|wt| {
+            let path = wt.path.canonicalize().unwrap_or_else(|_| wt.path.clone());
+            let name = path.file_name()?.to_string_lossy().to_string();
+            (path.parent() == Some(worktrees_dir.as_path())).then_some((name, wt))
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(found)
+}
+
+/// Write every worktree under `.groot/worktrees/` (running or stopped), its
+/// transcripts, and the project's config to a `.tar.gz` at `archive`.
+pub fn export(ctx: &GrootContext, archive: &Path) -> Result<Manifest> {
+    let groot_dir = ctx.groot_dir.as_path();
+    let base = ctx.project.base_branch();
+    let store = store::open(groot_dir)?;
+
+    let file = File::create(archive)?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    for entry in CONFIG_ENTRIES {
+        let path = groot_dir.join(entry);
+        if path.is_dir() {
+            tar.append_dir_all(Path::new("groot").join(entry), &path)?;
+        } else if path.is_file() {
+            tar.append_path_with_name(&path, Path::new("groot").join(entry))?;
+        }
+    }
+
+    let mut workers = Vec::new();
+    for (task_name, wt) in groot_worktrees(&ctx.git, &ctx.local.worktrees_root(groot_dir))? {
+        let branch_name = wt.branch.clone().unwrap_or_default();
+        info!("Exporting {task_name} ({branch_name} at {})", wt.head.get(..8).unwrap_or(&wt.head));
+        let state = store.load_worker(&task_name)?;
+        let dir = worker_dir(&task_name);
+
+        // Commits since the base branch, or the whole branch if there's no base here
+        let commits = worktree::ahead_behind(&ctx.git.root, &branch_name, base).map(|(ahead, _)| ahead);
+        if commits != Some(0) {
+            let data = bundle::create(&ctx.git.root, &branch_name, commits.map(|_| base))?;
+            append_bytes(&mut tar, &dir.join("branch.bundle"), &data)?;
+        }
+
+        let scratch_index = groot_dir.join(format!(".export-{}.index", std::process::id()));
+        let patch = worktree::uncommitted_patch(&wt.path, &scratch_index, GENERATED)?;
+        if !patch.is_empty() {
+            append_bytes(&mut tar, &dir.join("changes.patch"), &patch)?;
+        }
+
+        if let Some(Err(e)) = state.as_ref().map(|s| transcript::archive_sessions(groot_dir, s)) {
+            warn!("Warning: failed to copy the agent sessions of {task_name}: {e}");
+        }
+        let transcripts = transcript::transcripts_dir(groot_dir, &task_name);
+        if transcripts.is_dir() {
+            tar.append_dir_all(dir.join("transcripts"), &transcripts)?;
+        }
+
+        workers.push(ExportedWorker {
+            task_name,
+            branch: branch_name,
+            task_type: state.as_ref().and_then(|s| s.task_type.clone()),
+            grove: state.as_ref().is_some_and(|s| s.is_grove()),
+            commits,
+            uncommitted: !patch.is_empty(),
+        });
+    }
+
+    let manifest = Manifest {
+        format: ARCHIVE_FORMAT,
+        project: ctx.project.project_name.clone(),
+        exported_at: Utc::now(),
+        base_branch: base.to_string(),
+        workers,
+    };
+    append_bytes(&mut tar, Path::new("manifest.json"), &serde_json::to_vec_pretty(&manifest)?)?;
+    tar.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+fn append_bytes<W: std::io::Write>(tar: &mut tar::Builder<W>, path: &Path, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    tar.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+/// Restore an archive written by [`export`] into this repo: config files that
+/// don't exist here yet, then each worker as a stopped one (its branch and a
+/// worktree with its uncommitted changes, ready to plant again). Workers whose
+/// worktree, state, or branch already exists here, or that fail to restore,
+/// are skipped.
+pub fn import(git: &GitRepo, archive: &Path) -> Result<Imported> {
+    let groot_dir = git.groot_dir();
+    let staging = groot_dir.join(format!(".import-{}", std::process::id()));
+    let file = File::open(archive)
+        .map_err(|e| GrootError::Other(format!("Cannot open {}: {e}", archive.display())))?;
+    tar::Archive::new(GzDecoder::new(file))
+        .unpack(&staging)
+        .map_err(|e| GrootError::Other(format!("{} is not a groot archive: {e}", archive.display())))?;
+    let imported = restore(git, &groot_dir, &staging);
+    let _ = std::fs::remove_dir_all(&staging);
+    imported
+}
+
+fn restore(git: &GitRepo, groot_dir: &Path, staging: &Path) -> Result<Imported> {
+    let manifest: Manifest = std::fs::read_to_string(staging.join("manifest.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .ok_or_else(|| GrootError::Other("The archive has no readable manifest.json".to_string()))?;
+    if manifest.format > ARCHIVE_FORMAT {
+        return Err(GrootError::Other(format!(
+            "The archive was written by a newer groot (format {}); upgrade groot to import it",
+            manifest.format
+        )));
+    }
+
+    let mut imported = Imported {
+        project: manifest.project,
+        exported_at: manifest.exported_at,
+        config: Vec::new(),
+        config_kept: Vec::new(),
+        workers: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in CONFIG_ENTRIES {
+        let from = staging.join("groot").join(entry);
+        if !from.exists() {
+            continue;
+        }
+        if groot_dir.join(entry).exists() {
+            imported.config_kept.push(entry.to_string());
+        } else {
+            std::fs::rename(&from, groot_dir.join(entry))?;
+            imported.config.push(entry.to_string());
+        }
+    }
+
+    // This clone's local.yml (kept, or just restored) decides where worktrees go
+    let worktrees_dir = LocalConfig::load(&groot_dir.join("local.yml"))?.worktrees_root(groot_dir);
+    let store = store::open(groot_dir)?;
+    for worker in manifest.workers {
+        // The manifest may come from anywhere; its names become paths and refs
+        if let Some(why) = unsafe_names(&worker) {
+            imported.skipped.push((worker.task_name, why));
+            continue;
+        }
+        let worktree_path = worktrees_dir.join(&worker.task_name);
+        let conflict = if worktree_path.exists() {
+            Some("its worktree already exists".to_string())
+        } else if store.load_worker(&worker.task_name)?.is_some() {
+            Some("a worker by that name is planted".to_string())
+        } else if branch::branch_exists(git, &worker.branch) {
+            Some(format!("branch {} already exists", worker.branch))
+        } else {
+            None
+        };
+        if let Some(why) = conflict {
+            imported.skipped.push((worker.task_name, why));
+            continue;
+        }
+
+        info!("Importing {} ({})", worker.task_name, worker.branch);
+        let dir = staging.join(worker_dir(&worker.task_name));
+        if let Err(e) = restore_worker(git, &dir, &worktree_path, &worker, &manifest.base_branch) {
+            let _ = worktree::remove_worktree(&git.root, &worktree_path);
+            let _ = branch::delete_branch(git, &worker.branch);
+            imported.skipped.push((worker.task_name, e.to_string()));
+            continue;
+        }
+        let transcripts = transcript::transcripts_dir(groot_dir, &worker.task_name);
+        if dir.join("transcripts").is_dir() && !transcripts.exists() {
+            std::fs::create_dir_all(groot_dir.join("transcripts"))?;
+            std::fs::rename(dir.join("transcripts"), &transcripts)?;
+        }
+        imported.workers.push(worker);
+    }
+    Ok(imported)
+}
+
+/// Why a worker's names can't be used as they are, if they can't: its task
+/// name must be a single path component, since it names directories under
+/// `.groot`, and its branch a plain branch name that isn't a git option.
+fn unsafe_names(worker: &ExportedWorker) -> Option<String> {
+    let name = worker.task_name.as_str();
+    let mut components = Path::new(name).components();
+    let single = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+    if !single || name.contains(['/', '\\']) {
+        return Some(format!("'{name}' isn't a valid task name"));
+    }
+    let branch = worker.branch.as_str();
+    if branch.starts_with('-') || branch.split('/').any(|part| matches!(part, "" | "." | "..")) {
+        return Some(format!("'{branch}' isn't a valid branch name"));
+    }
+    None
+}
+
+/// Recreate one worker's branch and worktree from its directory in the archive.
+fn restore_worker(git: &GitRepo, dir: &Path, worktree_path: &Path, worker: &ExportedWorker, base: &str) -> Result<()> {
+    let bundle_path = dir.join("branch.bundle");
+    if bundle_path.exists() {
+        bundle::fetch(&git.root, &bundle_path, &worker.branch)?;
+    } else {
+        branch::create_branch(git, &worker.branch, base)?;
+    }
+    worktree::create_worktree(&git.root, worktree_path, &worker.branch)?;
+    let patch = dir.join("changes.patch");
+    if patch.exists() {
+        worktree::apply_patch(worktree_path, &patch)?;
+    }
+    Ok(())
+}
+
+/// The command that plants an imported worker again, as it was exported.
+pub fn replant_command(worker: &ExportedWorker) -> String {
+    let kind = if worker.grove { "grove" } else { "tree" };
+    let task_type = worker.task_type.as_deref().unwrap_or("feature");
+    format!("groot {kind} plant {} -t {task_type}", worker.task_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replant_command() {
+        let mut worker = ExportedWorker {
+            task_name: "add-auth".to_string(),
+            branch: "demo/feature/add-auth".to_string(),
+            task_type: Some("bugfix".to_string()),
+            grove: true,
+            commits: Some(2),
+            uncommitted: false,
+        };
+        assert_eq!(replant_command(&worker), "groot grove plant add-auth -t bugfix");
+        worker.grove = false;
+        worker.task_type = None;
+        assert_eq!(replant_command(&worker), "groot tree plant add-auth -t feature");
+    }
+
+    #[test]
+    fn test_unsafe_names_are_skipped() {
+        let worker = |task_name: &str, branch: &str| ExportedWorker {
+            task_name: task_name.to_string(),
+            branch: branch.to_string(),
+            task_type: None,
+            grove: false,
+            commits: None,
+            uncommitted: false,
+        };
+        assert_eq!(unsafe_names(&worker("add-auth", "demo/feature/add-auth")), None);
+        for name in ["../../x", "..", ".", "a/b", "a\\b", "/etc", ""] {
+            assert!(unsafe_names(&worker(name, "demo/x")).is_some(), "{name}");
+        }
+        for branch in ["--upload-pack=x", "demo/../x", "demo//x", ""] {
+            assert!(unsafe_names(&worker("x", branch)).is_some(), "{branch}");
+        }
+    }
+}
@@ -1,68 +1,268 @@
 use std::path::Path;
+use std::sync::Arc;
+
+use tracing::warn;
 
 use crate::compose::{manager as compose_mgr, ports};
+use crate::config::lock;
 use crate::error::Result;
+use crate::store;
+use crate::terminal::TerminalBackend;
 use crate::tmux::{session, workspace};
 
+use super::grove as orch_grove;
 use super::state::GroveState;
 
 /// Find orphaned groves (state file exists but tmux session is gone)
 pub fn find_orphans(groot_dir: &Path) -> Result<Vec<GroveState>> {
-    let groves_dir = groot_dir.join("groves");
-    if !groves_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut orphans = Vec::new();
-    for entry in std::fs::read_dir(&groves_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().is_some_and(|ext| ext == "json") {
-            if let Ok(state) = GroveState::load(&path) {
-                // Check if the per-grove tmux session still exists
-                let session_alive = state
-                    .tmux_session
-                    .as_ref()
-                    .is_some_and(|ws| session::session_exists(ws));
-                if !session_alive {
-                    orphans.push(state);
-                }
-            }
-        }
-    }
+    // One `tmux list-sessions` for all workers rather than a check per worker
+    let sessions = session::list_sessions();
+    Ok(orphaned(orch_grove::list_groves(groot_dir)?, &sessions))
+}
 
-    Ok(orphans)
+/// The workers whose per-grove tmux session isn't among `sessions`. Terminal
+/// tabs can't be tracked, so those workers are never orphans.
+fn orphaned(workers: Vec<GroveState>, sessions: &[String]) -> Vec<GroveState> {
+    workers
+        .into_iter()
+        .filter(|state| {
+            state.terminal_backend == TerminalBackend::Tmux
+                && !state.tmux_session.as_ref().is_some_and(|ws| sessions.contains(ws))
+        })
+        .collect()
 }
 
-/// Clean up an orphaned grove's resources
-pub fn cleanup_orphan(groot_dir: &Path, repo_root: &Path, state: &GroveState) -> Result<()> {
-    // Tear down compose stack if present (best-effort)
+/// What went wrong tearing down one orphan.
+type TeardownResult = std::result::Result<(), String>;
+
+/// Tear down what an orphaned grove has running or on disk: its compose
+/// stack and files or remote environment, tmux session, and worktree. Touches nothing shared with
+/// other workers, so orphans can be torn down concurrently. Every step is
+/// tried even when an earlier one fails.
+fn tear_down(groot_dir: &Path, repo_root: &Path, state: &GroveState) -> TeardownResult {
+    let mut failed = Vec::new();
+    // Tear down compose stack if present
     if let Some(ref cf) = state.compose_file {
-        let _ = compose_mgr::down(cf);
-        let _ = ports::release(groot_dir, &state.task_name);
+        if let Err(e) = compose_mgr::down(cf) {
+            failed.push(format!("compose down: {e}"));
+        }
         let compose_dir = groot_dir.join("compose").join(&state.task_name);
         let _ = std::fs::remove_dir_all(compose_dir);
     }
 
+    // Delete the remote environment, which nothing would track once the
+    // state is gone
+    if let Some(ref remote) = state.remote
+        && let Err(e) = remote.delete()
+    {
+        failed.push(format!("remote: {e}"));
+    }
+
     // Tear down per-grove tmux session if present
     if let Some(ref ws) = state.tmux_session {
         workspace::destroy_worker_session(ws);
     }
 
     // Remove worktree if it exists
-    if state.worktree_path.exists() {
-        let _ = crate::git::worktree::remove_worktree(repo_root, &state.worktree_path);
+    if state.worktree_path.exists()
+        && let Err(e) = crate::git::worktree::remove_worktree(repo_root, &state.worktree_path)
+    {
+        failed.push(format!("worktree: {e}"));
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(failed.join("; "))
+    }
+}
+
+/// Run `tear_down` for every orphan at once on the blocking pool, and wait
+/// for all of them: one that fails or panics doesn't keep the others from
+/// finishing. Returns each orphan's name and result, in order.
+async fn tear_down_all<F>(orphans: &[GroveState], tear_down: F) -> Vec<(String, TeardownResult)>
+where
+    F: Fn(&GroveState) -> TeardownResult + Send + Sync + 'static,
+{
+    let tear_down = Arc::new(tear_down);
+    let teardowns: Vec<_> = orphans
+        .iter()
+        .map(|state| {
+            let (tear_down, state) = (tear_down.clone(), state.clone());
+            (state.task_name.clone(), tokio::task::spawn_blocking(move || tear_down(&state)))
+        })
+        .collect();
+    let mut results = Vec::with_capacity(teardowns.len());
+    for (name, teardown) in teardowns {
+        let result = teardown.await.unwrap_or_else(|e| Err(format!("teardown panicked: {e}")));
+        results.push((name, result));
+    }
+    results
+}
+
+/// Drop an orphaned grove's ports, state, and lock file.
+fn forget(groot_dir: &Path, state: &GroveState) {
+    if state.compose_file.is_some() {
+        let _ = ports::release(groot_dir, &state.task_name);
     }
 
-    // Remove state file
-    let state_path = GroveState::state_path(groot_dir, &state.task_name);
-    let _ = std::fs::remove_file(state_path);
+    // Remove state
+    let _ = store::remove_worker(groot_dir, &state.task_name);
+    orch_grove::notify_stopped(groot_dir, state, false);
 
-    // Remove lock file
+    // Remove lock file, unless a plant of the same name holds it
     let lock_path = groot_dir
         .join("locks")
         .join(format!("{}.lock", state.task_name));
-    let _ = std::fs::remove_file(lock_path);
+    if lock_path.exists() {
+        let _ = lock::clear(&lock_path);
+    }
+}
+
+/// Clean up orphaned groves' resources. The slow part (compose down, remote
+/// delete, tmux, git) runs for all of them at once on the blocking pool; releasing ports
+/// and state, which share files, follows one at a time.
+///
+/// Returns the names of the orphans whose teardown failed; they're warned
+/// about, and forgotten all the same.
+pub async fn cleanup_orphans(groot_dir: &Path, repo_root: &Path, orphans: &[GroveState]) -> Vec<String> {
+    let (dir, root) = (groot_dir.to_path_buf(), repo_root.to_path_buf());
+    let results = tear_down_all(orphans, move |state| tear_down(&dir, &root, state)).await;
+    for state in orphans {
+        forget(groot_dir, state);
+    }
+    results
+        .into_iter()
+        .filter_map(|(name, result)| {
+            let e = result.err()?;
+            warn!("Warning: could not fully tear down '{name}': {e}");
+            Some(name)
+        })
+        .collect()
+}
 
-    Ok(())
+/// Stop every worker whose tmux session has closed: tear down its compose stack,
+/// release its ports, and drop its state file, but keep the worktree and branch.
+/// Groves whose stack is still shared by a running tree are left alone.
+///
+/// Returns the names of the workers that were stopped.
+pub fn reap_closed_sessions(groot_dir: &Path) -> Result<Vec<String>> {
+    let mut reaped = Vec::new();
+    for state in find_orphans(groot_dir)? {
+        match orch_grove::stop(groot_dir, &state.task_name, false) {
+            Ok(()) => reaped.push(state.task_name),
+            Err(e) => warn!("Warning: could not stop '{}': {e}", state.task_name),
+        }
+    }
+    Ok(reaped)
+}
+
+/// Stop every worker that has outlived its profile's TTL, keeping the worktree
+/// and branch like `reap_closed_sessions`.
+///
+/// Returns the names of the workers that were stopped.
+pub fn reap_expired(groot_dir: &Path) -> Result<Vec<String>> {
+    let mut reaped = Vec::new();
+    for state in orch_grove::list_groves(groot_dir)?.into_iter().filter(|s| s.is_expired()) {
+        match orch_grove::stop(groot_dir, &state.task_name, false) {
+            Ok(()) => reaped.push(state.task_name),
+            Err(e) => warn!("Warning: could not stop expired '{}': {e}", state.task_name),
+        }
+    }
+    Ok(reaped)
+}
+
+/// Register a global tmux `session-closed` hook that runs `groot worker reap`
+/// in this repository, so killing a worker session by hand also stops its
+/// compose stack instead of leaving it running until the next cleanup.
+pub fn register_auto_stop_hook(repo_root: &Path) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let (command, marker) = auto_stop_hook(repo_root, &exe);
+    session::ensure_global_hook("session-closed", &command, &marker)
+}
+
+/// The hook command for `repo_root`, and the part of it that tells whether
+/// this repository's hook is registered: its exact `cd '<root>' &&`, so
+/// `/a/proj` doesn't match `/a/proj2`. Both are as `show-hooks` prints them.
+fn auto_stop_hook(repo_root: &Path, exe: &Path) -> (String, String) {
+    let cd = format!("cd {} &&", session::shell_quote(&repo_root.to_string_lossy()));
+    let shell = format!("{cd} {} worker reap >/dev/null 2>&1", session::shell_quote(&exe.to_string_lossy()));
+    // run-shell expands formats in its command, so a literal `#` is `##`
+    let quoted = |s: &str| session::tmux_double_quote(&s.replace('#', "##"));
+    (format!("run-shell -b {}", quoted(&shell)), quoted(&cd).trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str, extra: &str) -> GroveState {
+        GroveState::parse(&format!(
+            r#"{{"task_name":"{name}","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-03T09:00:00Z","pid":null{extra}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_orphaned_workers() {
+        let workers = vec![
+            worker("alive", r#","tmux_session":"groot-alive""#),
+            worker("closed", r#","tmux_session":"groot-closed""#),
+            worker("no-session", ""),
+            worker("tab", r#","tmux_session":"groot-tab","terminal_backend":"kitty""#),
+        ];
+        let names: Vec<_> = orphaned(workers, &["groot-alive".to_string()])
+            .into_iter()
+            .map(|s| s.task_name)
+            .collect();
+        assert_eq!(names, ["closed", "no-session"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_teardowns_run_together_and_all_report() {
+        let remote = r#","remote":{"backend":"devpod","id":"groot-no-such-env","ssh_host":"groot-no-such-env.devpod"}"#;
+        let orphans = [
+            worker("a", ""),
+            worker("fails", ""),
+            worker("panics", ""),
+            worker("remote", remote),
+            worker("b", ""),
+        ];
+        // Each healthy teardown waits for the other, so they must run at once
+        let both = Arc::new(std::sync::Barrier::new(2));
+        let results = tear_down_all(&orphans, move |state| match state.task_name.as_str() {
+            "fails" => Err("compose down: exit 1".to_string()),
+            "panics" => panic!("boom"),
+            "remote" => tear_down(Path::new("/nonexistent/.groot"), Path::new("/nonexistent"), state),
+            _ => {
+                both.wait();
+                Ok(())
+            }
+        })
+        .await;
+
+        let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["a", "fails", "panics", "remote", "b"]);
+        assert_eq!(results[0].1, Ok(()));
+        assert_eq!(results[1].1, Err("compose down: exit 1".to_string()));
+        assert!(results[2].1.as_ref().unwrap_err().starts_with("teardown panicked"));
+        // The remote environment's delete was attempted, and failed without devpod
+        if which::which("devpod").is_err() {
+            assert!(results[3].1.as_ref().unwrap_err().starts_with("remote: "));
+        }
+        assert_eq!(results[4].1, Ok(()));
+    }
+
+    #[test]
+    fn test_auto_stop_hook_command_and_marker() {
+        let (command, marker) = auto_stop_hook(Path::new("/a/proj"), Path::new("/bin/groot"));
+        assert_eq!(command, r#"run-shell -b "cd '/a/proj' && '/bin/groot' worker reap >/dev/null 2>&1""#);
+        assert_eq!(marker, "cd '/a/proj' &&");
+        let (sibling, _) = auto_stop_hook(Path::new("/a/proj2"), Path::new("/bin/groot"));
+        assert!(!sibling.contains(&marker));
+
+        let (command, marker) = auto_stop_hook(Path::new(r#"/a/"$x`#"#), Path::new("/bin/groot"));
+        assert_eq!(marker, r#"cd '/a/\"\$x`##' &&"#);
+        assert!(command.starts_with(r#"run-shell -b "cd '/a/\"\$x`##' && "#));
+    }
 }
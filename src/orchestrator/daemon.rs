@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::compose::manager as compose_mgr;
+use crate::error::{GrootError, Result};
+use crate::git::worktree;
+use crate::notify;
+use crate::store;
+
+use super::activity::{self, AgentActivity};
+use super::cleanup;
+use super::grove as orch_grove;
+use super::state::{GroveState, WorkerHealth};
+
+/// Actions kept in the status, newest last.
+const RECENT_ACTIONS: usize = 20;
+
+/// How often the daemon does its rounds and what it watches for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonOptions {
+    pub interval_secs: u64,
+    /// Minutes without output before an agent is flagged as idle
+    pub idle_mins: u64,
+    /// Send desktop notifications about agents and unhealthy stacks
+    pub notify: bool,
+}
+
+/// Something the daemon did or noticed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Action {
+    pub at: DateTime<Utc>,
+    pub worker: String,
+    pub what: String,
+}
+
+/// What `groot daemon status` reports, as of the daemon's last round.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    pub options: DaemonOptions,
+    pub rounds: u64,
+    pub last_round: Option<DateTime<Utc>>,
+    /// Each worker's agent, as of the last round
+    pub agents: BTreeMap<String, AgentActivity>,
+    /// Groves whose containers aren't all ready, with which aren't
+    pub unhealthy: BTreeMap<String, String>,
+    pub recent: Vec<Action>,
+}
+
+impl DaemonStatus {
+    fn record(&mut self, worker: &str, what: String) {
+        info!("{worker}: {what}");
+        self.recent.push(Action { at: Utc::now(), worker: worker.to_string(), what });
+        let excess = self.recent.len().saturating_sub(RECENT_ACTIONS);
+        self.recent.drain(..excess);
+    }
+}
+
+/// What's worth announcing about an agent that was `before` last round and
+/// is `now`: that it exited, or that it just got stuck.
+fn agent_news(before: Option<AgentActivity>, now: AgentActivity) -> Option<String> {
+    let was_running = before.is_some_and(|b| !matches!(b, AgentActivity::NoAgent | AgentActivity::NoSession));
+    if was_running && now == AgentActivity::NoAgent {
+        Some("agent finished".to_string())
+    } else if now.is_stuck() && !before.is_some_and(AgentActivity::is_stuck) {
+        Some(format!("agent {}", now.describe()))
+    } else {
+        None
+    }
+}
+
+/// Keep what a round saw of `worker` in its state, when that changed.
+fn record_health(groot_dir: &Path, worker: &GroveState, dirty: bool, unhealthy: Option<&String>) {
+    let changed = |h: &WorkerHealth| h.dirty != dirty || h.unhealthy.as_ref() != unhealthy;
+    if !worker.health.as_ref().is_none_or(changed) {
+        return;
+    }
+    let health = WorkerHealth { since: Utc::now(), dirty, unhealthy: unhealthy.cloned() };
+    if let Err(e) = store::update_worker(groot_dir, &worker.task_name, |s| s.health = Some(health)) {
+        warn!("Warning: could not record the health of '{}': {e}", worker.task_name);
+    }
+}
+
+/// The daemon's socket, `.groot/daemon.sock`.
+pub fn socket_path(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("daemon.sock")
+}
+
+/// One round: clean up orphans, stop workers past their TTL, then look at
+/// every remaining worker's agent and compose stack, noting (and with
+/// `notify`, announcing) what changed since the last round.
+async fn round(groot_dir: &Path, repo_root: &Path, status: &Mutex<DaemonStatus>) -> Result<()> {
+    let orphans = cleanup::find_orphans(groot_dir)?;
+    cleanup::cleanup_orphans(groot_dir, repo_root, &orphans).await;
+    let expired = cleanup::reap_expired(groot_dir)?;
+
+    let (idle_after, notify) = {
+        let status = status.lock().unwrap_or_else(|e| e.into_inner());
+        (Duration::from_secs(status.options.idle_mins * 60), status.options.notify)
+    };
+    let workers = orch_grove::list_groves(groot_dir)?;
+    let worktrees: Vec<&Path> = workers.iter().map(|w| w.worktree_path.as_path()).collect();
+    let dirty = worktree::dirty_worktrees(&worktrees);
+    let mut agents = BTreeMap::new();
+    let mut unhealthy = BTreeMap::new();
+    for worker in &workers {
+        agents.insert(worker.task_name.clone(), activity::check(groot_dir, worker, idle_after));
+        if let Some(cf) = &worker.compose_file {
+            let not_ready: Vec<String> = compose_mgr::ps(cf)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| !c.is_ready())
+                .map(|c| format!("{} {}", c.service, if c.health.is_empty() { &c.state } else { &c.health }))
+                .collect();
+            if !not_ready.is_empty() {
+                unhealthy.insert(worker.task_name.clone(), not_ready.join(", "));
+            }
+        }
+    }
+    for (worker, dirty) in workers.iter().zip(dirty) {
+        record_health(groot_dir, worker, dirty, unhealthy.get(&worker.task_name));
+    }
+
+    let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+    for state in &orphans {
+        status.record(&state.task_name, "cleaned up (session gone)".to_string());
+    }
+    for name in &expired {
+        status.record(name, "stopped (TTL passed)".to_string());
+    }
+    let mut announce: Vec<(String, String)> = agents
+        .iter()
+        .filter_map(|(name, now)| Some((name.clone(), agent_news(status.agents.get(name).copied(), *now)?)))
+        .collect();
+    for (name, services) in &unhealthy {
+        if !status.unhealthy.contains_key(name) {
+            announce.push((name.clone(), format!("containers not ready: {services}")));
+        }
+    }
+    for (name, what) in announce {
+        if notify {
+            notify::desktop(&format!("groot: {name}"), &what);
+        }
+        status.record(&name, what);
+    }
+    status.agents = agents;
+    status.unhealthy = unhealthy;
+    status.rounds += 1;
+    status.last_round = Some(Utc::now());
+    Ok(())
+}
+
+/// Run the daemon in this process until it's asked to stop (or interrupted):
+/// a round every `interval_secs`, and `status` / `stop` requests answered on
+/// its socket. Fails if another daemon is already serving this repo.
+#[cfg(unix)]
+pub async fn serve(groot_dir: &Path, repo_root: &Path, options: DaemonOptions) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let socket = socket_path(groot_dir);
+    if let Some(running) = status(groot_dir) {
+        return Err(GrootError::Other(format!("A groot daemon is already running (pid {})", running.pid)));
+    }
+    // Left behind by a daemon that didn't get to clean up
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket)?;
+    // `kill` (and system shutdown) stops the daemon as cleanly as `stop`
+    let mut terminate = signal(SignalKind::terminate())?;
+    info!("groot daemon {} listening on {}", std::process::id(), socket.display());
+
+    let interval = Duration::from_secs(options.interval_secs.max(1));
+    let status = Arc::new(Mutex::new(DaemonStatus {
+        pid: std::process::id(),
+        started_at: Utc::now(),
+        options,
+        rounds: 0,
+        last_round: None,
+        agents: BTreeMap::new(),
+        unhealthy: BTreeMap::new(),
+        recent: Vec::new(),
+    }));
+
+    let rounds = {
+        let (groot_dir, repo_root, status) = (groot_dir.to_path_buf(), repo_root.to_path_buf(), status.clone());
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = round(&groot_dir, &repo_root, &status).await {
+                    warn!("Warning: daemon round failed: {e}");
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    };
+
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!("Warning: daemon socket: {e}");
+                    continue;
+                }
+            },
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+        };
+        let (reader, mut writer) = stream.into_split();
+        let mut request = String::new();
+        // A client that connects and says nothing mustn't stall the daemon
+        let mut reader = BufReader::new(reader);
+        let _ = tokio::time::timeout(Duration::from_secs(2), reader.read_line(&mut request)).await;
+        // Nothing here may end the loop early, or the socket would be left behind
+        let reply = match request.trim() {
+            "status" => {
+                let status = status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                serde_json::to_string(&status).unwrap_or_else(|e| {
+                    warn!("Warning: could not serialize the daemon status: {e}");
+                    format!("error: could not serialize the daemon status: {e}")
+                })
+            }
+            "stop" => "stopping".to_string(),
+            other => format!("unknown request '{other}'"),
+        };
+        let _ = writer.write_all(format!("{reply}\n").as_bytes()).await;
+        if request.trim() == "stop" {
+            break;
+        }
+    }
+
+    rounds.abort();
+    let _ = std::fs::remove_file(&socket);
+    info!("groot daemon {} stopped", std::process::id());
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn serve(_groot_dir: &Path, _repo_root: &Path, _options: DaemonOptions) -> Result<()> {
+    Err(GrootError::Other("The groot daemon needs Unix domain sockets".to_string()))
+}
+
+/// Send `request` to the daemon and return its reply, or `None` when no
+/// daemon answers.
+#[cfg(unix)]
+fn query(groot_dir: &Path, request: &str) -> Option<String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket_path(groot_dir)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(5))).ok()?;
+    stream.write_all(format!("{request}\n").as_bytes()).ok()?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).ok()?;
+    Some(reply.trim().to_string())
+}
+
+#[cfg(not(unix))]
+fn query(_groot_dir: &Path, _request: &str) -> Option<String> {
+    None
+}
+
+/// The running daemon's status, or `None` if none is running.
+pub fn status(groot_dir: &Path) -> Option<DaemonStatus> {
+    serde_json::from_str(&query(groot_dir, "status")?).ok()
+}
+
+/// Whether a daemon is running for this repo, doing the periodic cleanup
+/// commands otherwise do inline.
+pub fn is_running(groot_dir: &Path) -> bool {
+    query(groot_dir, "status").is_some()
+}
+
+/// Ask the daemon to stop. Returns whether one was running.
+pub fn stop(groot_dir: &Path) -> bool {
+    query(groot_dir, "stop").is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_news() {
+        use AgentActivity::*;
+        assert_eq!(agent_news(Some(Working), NoAgent).as_deref(), Some("agent finished"));
+        assert_eq!(agent_news(Some(Idle { minutes: 12 }), NoAgent).as_deref(), Some("agent finished"));
+        assert_eq!(agent_news(Some(Working), Idle { minutes: 10 }).as_deref(), Some("agent idle for 10m"));
+        assert_eq!(agent_news(None, AwaitingPermission).as_deref(), Some("agent waiting for permission"));
+        // Announced once per episode, not every round it lasts
+        assert_eq!(agent_news(Some(Idle { minutes: 10 }), Idle { minutes: 11 }), None);
+        assert_eq!(agent_news(Some(NoAgent), NoAgent), None);
+        assert_eq!(agent_news(None, NoAgent), None);
+        assert_eq!(agent_news(Some(Idle { minutes: 10 }), Working), None);
+    }
+}
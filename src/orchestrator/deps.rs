@@ -0,0 +1,218 @@
+//! Ordering between tasks: a worker planted with `--depends-on` builds on
+//! other tasks and is blocked until they finish. A task has finished once its
+//! worker was uprooted, as the event log records, or, while it's still
+//! planted, once it was marked complete or its branch has new commits and all
+//! of them are on the base branch.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::error::{GrootError, Result};
+use crate::git::repo::GitRepo;
+use crate::git::worktree;
+use crate::notify::events::{self, LoggedEvent};
+use crate::orchestrator::state::GroveState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepStatus {
+    Finished,
+    /// Planted, with work not on the base branch yet
+    InProgress,
+    /// Never planted, as far as the event log goes
+    Unknown,
+}
+
+impl DepStatus {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Finished => "finished",
+            Self::InProgress => "in progress",
+            Self::Unknown => "never planted",
+        }
+    }
+}
+
+/// How far along every task groot knows of is, and what the planted ones
+/// depend on.
+#[derive(Debug, Default)]
+pub struct Progress {
+    status: HashMap<String, DepStatus>,
+    depends_on: HashMap<String, Vec<String>>,
+}
+
+impl Progress {
+    /// Read the event log and check the planted workers' branches against `base`.
+    pub fn load(git: &GitRepo, groot_dir: &Path, base: &str, workers: &[GroveState]) -> Self {
+        let mut progress = Self::from_events(&events::read(groot_dir));
+        let branches: Vec<&str> = workers.iter().map(|w| w.branch.as_str()).collect();
+        let distances = worktree::ahead_behind_all(&git.repo, &branches, base);
+        let tips = worktree::tip_times(&git.repo, &branches);
+        for w in workers {
+            // A new branch is level with the base too; it has to have moved since
+            let merged = distances.get(&w.branch).is_some_and(|(ahead, _)| *ahead == 0)
+                && tips.get(&w.branch).is_some_and(|at| *at > w.created_at);
+            progress.planted(w, merged);
+        }
+        progress
+    }
+
+    /// Tasks uprooted (finished) or spawned again since, from the event log.
+    fn from_events(events: &[LoggedEvent]) -> Self {
+        let mut progress = Self::default();
+        for e in events {
+            let Some(worker) = &e.worker else {
+                continue;
+            };
+            match e.event.as_str() {
+                "worker_spawned" => progress.status.insert(worker.clone(), DepStatus::InProgress),
+                "worker_stopped" if e.uprooted == Some(true) => {
+                    progress.status.insert(worker.clone(), DepStatus::Finished)
+                }
+                _ => None,
+            };
+        }
+        progress
+    }
+
+    fn planted(&mut self, worker: &GroveState, merged: bool) {
+        let finished = merged || worker.completed_at.is_some();
+        let status = if finished { DepStatus::Finished } else { DepStatus::InProgress };
+        self.status.insert(worker.task_name.clone(), status);
+        self.depends_on.insert(worker.task_name.clone(), worker.depends_on.clone());
+    }
+
+    pub fn status(&self, task: &str) -> DepStatus {
+        self.status.get(task).copied().unwrap_or(DepStatus::Unknown)
+    }
+
+    /// Of `depends_on`, the tasks that haven't finished.
+    pub fn blockers(&self, depends_on: &[String]) -> Vec<String> {
+        depends_on
+            .iter()
+            .filter(|d| self.status(d) != DepStatus::Finished)
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `parent` has subtasks, all of them finished, and hasn't been
+    /// marked complete itself yet.
+    pub fn ready_to_complete(&self, parent: &GroveState) -> bool {
+        parent.completed_at.is_none() && !parent.subtasks.is_empty() && self.blockers(&parent.subtasks).is_empty()
+    }
+
+    /// Everything `task` depends on, directly or through planted workers'
+    /// own dependencies, depth first as `(depth, task, status)`. Each task is
+    /// listed once, so a cycle ends the walk.
+    pub fn chain(&self, task: &str) -> Vec<(usize, String, DepStatus)> {
+        let mut chain = Vec::new();
+        let mut seen = HashSet::from([task.to_string()]);
+        self.walk(task, 0, &mut seen, &mut chain);
+        chain
+    }
+
+    fn walk(&self, task: &str, depth: usize, seen: &mut HashSet<String>, chain: &mut Vec<(usize, String, DepStatus)>) {
+        for dep in self.depends_on.get(task).into_iter().flatten() {
+            if seen.insert(dep.clone()) {
+                chain.push((depth, dep.clone(), self.status(dep)));
+                self.walk(dep, depth + 1, seen, chain);
+            }
+        }
+    }
+}
+
+/// Refuse to plant `task` while any of `depends_on` hasn't finished, or with
+/// `force` only warn.
+pub fn check(progress: &Progress, task: &str, depends_on: &[String], force: bool) -> Result<()> {
+    if depends_on.iter().any(|d| d == task) {
+        return Err(GrootError::Other(format!("'{task}' can't depend on itself")));
+    }
+    let blockers = progress.blockers(depends_on);
+    if blockers.is_empty() {
+        return Ok(());
+    }
+    let blocked_by = blockers
+        .iter()
+        .map(|b| format!("{b} ({})", progress.status(b).describe()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if force {
+        warn!("Warning: planting '{task}' although it's blocked by {blocked_by}");
+        return Ok(());
+    }
+    Err(GrootError::Other(format!(
+        "'{task}' is blocked by {blocked_by}; plant it anyway with --force"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(line: &str) -> LoggedEvent {
+        serde_json::from_str(line).unwrap()
+    }
+
+    #[test]
+    fn test_progress_from_events_and_chain() {
+        let mut progress = Progress::from_events(&[
+            event(r#"{"event":"worker_spawned","worker":"schema","timestamp":"2026-01-01T00:00:00Z"}"#),
+            event(r#"{"event":"worker_stopped","worker":"schema","uprooted":true,"timestamp":"2026-01-02T00:00:00Z"}"#),
+            event(r#"{"event":"worker_spawned","worker":"api","timestamp":"2026-01-03T00:00:00Z"}"#),
+            event(r#"{"event":"worker_stopped","worker":"api","uprooted":false,"timestamp":"2026-01-04T00:00:00Z"}"#),
+        ]);
+        assert_eq!(progress.status("schema"), DepStatus::Finished);
+        assert_eq!(progress.status("api"), DepStatus::InProgress);
+        assert_eq!(progress.status("typo"), DepStatus::Unknown);
+
+        let api: GroveState = GroveState::parse(
+            r#"{"task_name":"api","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-03T00:00:00Z","pid":null,"depends_on":["schema","ui"]}"#,
+        )
+        .unwrap();
+        progress.planted(&api, false);
+        progress.depends_on.insert("ui".to_string(), vec!["api".to_string()]);
+
+        let deps = ["api".to_string(), "schema".to_string()];
+        assert_eq!(progress.blockers(&deps), ["api"]);
+        assert!(check(&progress, "ui", &deps, false).is_err());
+        assert!(check(&progress, "ui", &deps, true).is_ok());
+        assert!(check(&progress, "api", &["api".to_string()], true).is_err());
+
+        let chain: Vec<_> = progress.chain("api").into_iter().map(|(d, t, _)| (d, t)).collect();
+        assert_eq!(chain, [(0, "schema".to_string()), (0, "ui".to_string())]);
+    }
+
+    #[test]
+    fn test_parent_ready_to_complete_once_last_subtask_finishes() {
+        let mut progress = Progress::from_events(&[
+            event(r#"{"event":"worker_spawned","worker":"auth-db","timestamp":"2026-01-01T00:00:00Z"}"#),
+            event(r#"{"event":"worker_stopped","worker":"auth-db","uprooted":true,"timestamp":"2026-01-02T00:00:00Z"}"#),
+        ]);
+        let mut parent: GroveState = GroveState::parse(
+            r#"{"task_name":"auth","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null,"subtasks":["auth-db","auth-ui"]}"#,
+        )
+        .unwrap();
+        let mut ui = parent.clone();
+        ui.task_name = "auth-ui".to_string();
+        ui.subtasks.clear();
+        progress.planted(&ui, false);
+        assert!(!progress.ready_to_complete(&parent));
+
+        // The last subtask's branch lands on the base branch
+        progress.planted(&ui, true);
+        assert!(progress.ready_to_complete(&parent));
+
+        // Once marked complete it's finished, and isn't offered again
+        parent.completed_at = Some("2026-01-03T00:00:00Z".parse().unwrap());
+        assert!(!progress.ready_to_complete(&parent));
+        progress.planted(&parent, false);
+        assert_eq!(progress.status("auth"), DepStatus::Finished);
+
+        parent.subtasks.clear();
+        parent.completed_at = None;
+        assert!(!progress.ready_to_complete(&parent));
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+use sysinfo::Disks;
+
+use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
+
+/// Free space on a filesystem planting fills.
+#[derive(Debug, Clone, Serialize)]
+pub struct FreeSpace {
+    /// What lives there: `worktrees`, `Docker data-root`, or both
+    pub holds: String,
+    pub mount: PathBuf,
+    pub free_mb: u64,
+}
+
+impl FreeSpace {
+    /// `/home (worktrees)`
+    pub fn location(&self) -> String {
+        format!("{} ({})", self.mount.display(), self.holds)
+    }
+}
+
+/// The mount (and its free MB) among `mounts` that holds `path`: the longest
+/// mount point it's under.
+fn mount_for(mounts: &[(PathBuf, u64)], path: &Path) -> Option<(PathBuf, u64)> {
+    mounts
+        .iter()
+        .filter(|(mount, _)| path.starts_with(mount))
+        .max_by_key(|(mount, _)| mount.components().count())
+        .cloned()
+}
+
+/// The mount holding `path` (or, before it's created, its nearest existing
+/// parent) and its free space in MB, if it can be determined.
+pub fn free_space(path: &Path) -> Option<(PathBuf, u64)> {
+    let path = path.ancestors().find(|p| p.exists())?.canonicalize().ok()?;
+    let mounts: Vec<_> = Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .map(|disk| (disk.mount_point().to_path_buf(), disk.available_space() / (1024 * 1024)))
+        .collect();
+    mount_for(&mounts, &path)
+}
+
+/// Docker's data-root (`/var/lib/docker`), when it's on this machine's
+/// filesystem. With Docker Desktop it's inside a VM and this is `None`.
+fn docker_root() -> Option<PathBuf> {
+    let output = Command::new("docker")
+        .args(["info", "--format", "{{.DockerRootDir}}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let root = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    root.exists().then_some(root)
+}
+
+/// What `docker system prune` could free, from `docker system df`: e.g.
+/// `Images 1.2GB (40%), Build Cache 3GB`. `None` if nothing or unknown.
+pub fn docker_reclaimable() -> Option<String> {
+    let output = Command::new("docker")
+        .args(["system", "df", "--format", "{{.Type}}\t{{.Reclaimable}}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    summarize_df(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn summarize_df(stdout: &str) -> Option<String> {
+    let parts: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(_, reclaimable)| !reclaimable.trim().starts_with("0B"))
+        .map(|(kind, reclaimable)| format!("{kind} {}", reclaimable.trim()))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// Free space where worktrees go (`worktrees_dir`) and, with `docker`, where
+/// Docker keeps images and volumes; one entry when they're on the same
+/// filesystem.
+pub fn survey(worktrees_dir: &Path, docker: bool) -> Vec<FreeSpace> {
+    let mut found: Vec<FreeSpace> = Vec::new();
+    let targets = [
+        Some(("worktrees", worktrees_dir.to_path_buf())),
+        docker.then(docker_root).flatten().map(|root| ("Docker data-root", root)),
+    ];
+    for (holds, path) in targets.into_iter().flatten() {
+        let Some((mount, free_mb)) = free_space(&path) else {
+            continue;
+        };
+        match found.iter_mut().find(|f| f.mount == mount) {
+            Some(same) => same.holds = format!("{}, {holds}", same.holds),
+            None => found.push(FreeSpace {
+                holds: holds.to_string(),
+                mount,
+                free_mb,
+            }),
+        }
+    }
+    found
+}
+
+/// Fail with `InsufficientDiskSpace` if a filesystem from [`survey`] has
+/// less than `min_mb` free. Filesystems that can't be read are let through.
+pub fn check(worktrees_dir: &Path, min_mb: u64, docker: bool) -> Result<()> {
+    let survey = survey(worktrees_dir, docker);
+    match survey.iter().find(|f| f.free_mb < min_mb) {
+        Some(low) => Err(GrootError::InsufficientDiskSpace {
+            location: low.location(),
+            available_mb: low.free_mb,
+            required_mb: min_mb,
+            reclaimable: if docker { docker_reclaimable() } else { None },
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mount_for_and_docker_df() {
+        let mounts = vec![
+            (PathBuf::from("/"), 100),
+            (PathBuf::from("/home"), 200),
+            (PathBuf::from("/home/me/src"), 300),
+        ];
+        assert_eq!(mount_for(&mounts, Path::new("/home/me/src/app/.groot")).unwrap().1, 300);
+        assert_eq!(mount_for(&mounts, Path::new("/home/other")).unwrap().1, 200);
+        assert_eq!(mount_for(&mounts, Path::new("/var/lib/docker")).unwrap().1, 100);
+        // A path component, not a string prefix
+        assert_eq!(mount_for(&mounts, Path::new("/homework")).unwrap().1, 100);
+
+        let df = "Images\t1.2GB (40%)\nContainers\t0B (0%)\nLocal Volumes\t512MB (10%)\nBuild Cache\t3GB\n";
+        assert_eq!(summarize_df(df).unwrap(), "Images 1.2GB (40%), Local Volumes 512MB (10%), Build Cache 3GB");
+        assert_eq!(summarize_df("Images\t0B (0%)\n"), None);
+    }
+}
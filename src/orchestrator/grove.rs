@@ -1,296 +1,445 @@
 use std::path::Path;
-use std::time::Duration;
+use std::process::Command;
+use std::time::{Duration, Instant};
 
-use sysinfo::Disks;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, warn};
 
 use crate::claude_md;
-use crate::compose::{db as compose_db, manager as compose_mgr, ports};
+use crate::compose::{caches as compose_caches, db as compose_db, diagnostics, manager as compose_mgr, ports, sandbox as compose_sandbox};
+use crate::config::local::{Operation, PostPlantIn};
 use crate::config::lock::FileLock;
-use crate::config::project::ProjectConfig;
+use crate::config::state_dir;
+use crate::config::webhook::WebhookEvent;
+use crate::context::GrootContext;
+use crate::detector::database::DbEngine;
+use crate::detector::{linter, package_manager, rules, test_framework};
 use crate::error::{GrootError, Result};
-use crate::git::{branch, repo::GitRepo, worktree};
+use crate::git::{branch, gitignore, worktree};
+use crate::notify;
+use crate::process::CommandExt;
+use crate::progress::Step;
+use crate::store;
+use crate::terminal::{self, TerminalBackend};
 use crate::tmux::{session, workspace};
+use crate::wsl;
+
+use super::disk;
+use super::remote::RemoteBackend;
+use super::journal::{PlantJournal, PlantRequest, PlantStep};
+use super::state::{GroveState, PromptVia, STATE_SCHEMA_VERSION};
+use super::transcript;
+
+/// Whether a worker gets a compose stack of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stack {
+    /// None: a tree, possibly sharing a grove's stack
+    None,
+    /// The app and its services
+    Compose,
+    /// The app and its services, plus the agent in its own container behind
+    /// an allowlisting proxy
+    Sandboxed,
+    /// An environment provisioned by a remote backend instead of a local stack
+    Remote(RemoteBackend),
+}
 
-use super::state::GroveState;
+impl Stack {
+    /// Whether the worker gets a local compose stack.
+    pub fn has_compose(self) -> bool {
+        matches!(self, Stack::Compose | Stack::Sandboxed)
+    }
+}
 
 /// Plant a new grove/tree: create branch, worktree, optionally start compose stack,
-/// create tmux workspace, save state.
+/// create tmux workspace, save state. Each step is journaled first, so a
+/// failure (or a later `groot worker resume`) undoes exactly what was created.
 pub fn plant(
-    git: &GitRepo,
-    groot_dir: &Path,
+    ctx: &GrootContext,
     task_name: &str,
     branch_name: &str,
     task_type: &str,
-    tmux_session: &str,
-    min_disk_mb: u64,
-    initial_command: Option<&str>,
-    enable_compose: bool,
-    compose_health_timeout_secs: u64,
-    compose_post_start: &[String],
+    initial_prompt: Option<&claude_md::InitialPrompt>,
+    stack: Stack,
     db_clone: bool,
     db_source: Option<&str>,
     shared_grove: Option<&str>,
     shared_compose_ports: Option<&ports::AllocatedPorts>,
+    layout_name: Option<&str>,
 ) -> Result<GroveState> {
+    let git = &ctx.git;
+    let groot_dir = ctx.groot_dir.as_path();
+    let project_config = &ctx.project;
+    let tmux_session = ctx.local.tmux_session_name.as_str();
+    let compose_health_timeout_secs = ctx.local.compose_health_timeout_secs;
+    let compose_post_start = ctx.local.compose_post_start.for_task_type(task_type);
+    let backend = ctx.local.terminal_backend;
+
     // 1. Acquire lock
     let lock_path = groot_dir.join("locks").join(format!("{task_name}.lock"));
     let _lock = FileLock::acquire(&lock_path)?;
 
     // 2. Check for duplicate
-    let state_path = GroveState::state_path(groot_dir, task_name);
-    if state_path.exists() {
+    let store = store::open(groot_dir)?;
+    if store.load_worker(task_name)?.is_some() {
         return Err(GrootError::GroveAlreadyExists(task_name.to_string()));
     }
 
     // 3. Check disk space
-    check_disk_space(min_disk_mb)?;
+    let worktrees_dir = ctx.local.worktrees_root(groot_dir);
+    disk::check(&worktrees_dir, ctx.local.min_disk_space_mb, stack.has_compose())?;
+
+    let ttl = project_config.profile(task_type).ttl()?;
+
+    // 3½. Resolve the workspace template up front so a missing or broken
+    // template fails before anything has been created
+    let ws_template = workspace::resolve_template(groot_dir, layout_name, task_type)?;
+    if backend != TerminalBackend::Tmux && !ctx.probes.terminal(backend) {
+        return Err(GrootError::Other(format!(
+            "terminal_backend is '{0}' but '{0}' was not found on PATH",
+            backend.name()
+        )));
+    }
 
-    // 4. Create branch (skip if it already exists from task creation)
-    let branch_created = if !branch::branch_exists(git, branch_name) {
-        branch::create_branch(git, branch_name)?;
-        true
-    } else {
-        false
+    match stack {
+        Stack::Remote(remote) if !remote.is_available() => {
+            return Err(GrootError::Other(format!(
+                "Planting on {0} needs the {0} CLI, which was not found on PATH",
+                remote.name()
+            )));
+        }
+        _ => {}
+    }
+
+    if project_config.is_protected(branch_name) {
+        return Err(GrootError::Other(format!(
+            "Refusing to plant a worker on protected branch '{branch_name}'"
+        )));
+    }
+
+    // 3¾. Journal the plant, taking over what an interrupted plant of this
+    // task left behind (its process is gone: we hold the task's lock)
+    let request = PlantRequest {
+        task_type: task_type.to_string(),
+        stack,
+        db_clone,
+        shared_grove: shared_grove.map(|s| s.to_string()),
+        layout: layout_name.map(|s| s.to_string()),
+        prompt: initial_prompt.cloned(),
+    };
+    let mut journal = match PlantJournal::load(groot_dir, task_name)? {
+        Some(interrupted) => {
+            info!("Continuing an interrupted plant of '{task_name}'");
+            interrupted.resume(git, groot_dir, branch_name, request)
+        }
+        None => PlantJournal::new(task_name, branch_name, request),
+    };
+    journal.save(groot_dir)?;
+    let fail = |journal: &PlantJournal, operation: &str, e: GrootError| {
+        journal.roll_back(git, groot_dir);
+        e.context(operation, Some(task_name))
     };
 
+    // 4. Create branch from the base branch (skip if it already exists from task creation)
+    if !branch::branch_exists(git, branch_name) {
+        journal.record(groot_dir, PlantStep::BranchCreated)?;
+        if let Err(e) = branch::create_branch(git, branch_name, project_config.base_branch()) {
+            return Err(fail(&journal, "Creating the branch", e));
+        }
+    }
+
     // 5. Create worktree (or reuse existing one from a previous `stop`)
-    let worktree_path = groot_dir.join("worktrees").join(task_name);
+    let worktree_path = worktrees_dir.join(task_name);
     let reusing_worktree = worktree::worktree_exists(&worktree_path);
+    if wsl::on_windows_drive(&worktree_path) {
+        warn!(
+            "Warning: {} is on a Windows drive; bind mounts and file watching from there are slow under WSL. \
+             Set worktrees_dir in .groot/local.yml to a directory on the Linux filesystem (e.g. ~/groot-worktrees/{})",
+            worktree_path.display(),
+            project_config.project_name
+        );
+    }
 
     if reusing_worktree {
-        println!("Reusing existing worktree at {}", worktree_path.display());
-    } else if let Err(e) = worktree::create_worktree(&git.root, &worktree_path, branch_name) {
-        if branch_created {
-            let _ = branch::delete_branch(git, branch_name);
+        info!("Reusing existing worktree at {}", worktree_path.display());
+    } else {
+        let step = Step::start("worktree", "Creating worktree");
+        journal.record(groot_dir, PlantStep::WorktreeCreated { path: worktree_path.clone() })?;
+        if let Err(e) = worktree::create_worktree(&git.root, &worktree_path, branch_name) {
+            return Err(fail(&journal, "Creating the worktree", e));
         }
-        return Err(e);
+        step.done();
     }
 
-    // 5½. Copy essential files into worktree from repo root.
-    for filename in &["Dockerfile.dev", "Dockerfile.groot", ".env", "config/master.key"] {
-        let repo_file = git.root.join(filename);
-        let worktree_file = worktree_path.join(filename);
-        if repo_file.exists() {
-            if let Some(parent) = worktree_file.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-            let _ = std::fs::copy(&repo_file, &worktree_file);
+    // 5½. Copy untracked essentials (worktree_copy_files) into the worktree
+    // and create worktree_create_dirs. The managed block in info/exclude
+    // keeps them out of commits whatever the branch's .gitignore says.
+    let ignored = gitignore::entries(git, &state_dir::name(), project_config);
+    if let Err(e) = gitignore::sync_exclude(git, &ignored) {
+        warn!("Warning: failed to update the groot block in .git/info/exclude: {e}");
+    }
+    if !gitignore::is_current(&git.root.join(".gitignore"), &ignored) {
+        warn!("Warning: the groot block in .gitignore is missing or out of date; run `groot gitignore sync`");
+    }
+    let copied = copy_files(&git.root, &worktree_path, &project_config.copy_files());
+    for rel in gitignore::not_ignored(&worktree_path, &copied) {
+        warn!("Warning: {rel} was copied into the worktree but isn't ignored there and could be committed");
+    }
+    for dir in &project_config.worktree_create_dirs {
+        if let Err(e) = std::fs::create_dir_all(worktree_path.join(dir)) {
+            warn!("Warning: failed to create {dir} in worktree: {e}");
         }
     }
 
-    // Warn if .env exists but may not be gitignored
-    let env_file = git.root.join(".env");
-    if env_file.exists() {
-        let gitignore = git.root.join(".gitignore");
-        let is_ignored = std::fs::read_to_string(&gitignore)
-            .map(|c| c.lines().any(|l| l.trim() == ".env"))
-            .unwrap_or(false);
-        if !is_ignored {
-            eprintln!(
-                "Warning: .env exists but is not in .gitignore — secrets may be committed!"
-            );
+    // 5-remote. Provision the environment on a remote backend instead of a
+    // local stack
+    let mut remote = None;
+    if let Stack::Remote(backend) = stack {
+        let step = Step::start("provision", format!("Provisioning a {} environment", backend.name()));
+        journal.record(groot_dir, PlantStep::RemoteProvisioned { backend, id: backend.env_id(task_name) })?;
+        match backend.provision(task_name, &worktree_path, ctx.local.devpod_provider.as_deref()) {
+            Ok(env) => remote = Some(env),
+            Err(e) => return Err(fail(&journal, "Provisioning the environment", e)),
         }
+        step.done();
     }
 
     // 5a-5d. Optionally start compose stack
     let mut compose_file = None;
     let mut compose_ports = None;
 
-    if enable_compose {
+    let sandbox = stack == Stack::Sandboxed;
+    if stack.has_compose() {
         // 5a. Check docker compose is available
-        if let Err(e) = compose_mgr::check_available() {
-            if !reusing_worktree {
-                let _ = worktree::remove_worktree(&git.root, &worktree_path);
-            }
-            if branch_created {
-                let _ = branch::delete_branch(git, branch_name);
-            }
-            return Err(e);
+        if let Err(e) = ctx.probes.compose() {
+            return Err(fail(&journal, "Checking docker compose", e));
         }
 
         // 5b. Allocate ports
+        journal.record(groot_dir, PlantStep::PortsAllocated)?;
         let allocated = match ports::allocate(groot_dir, task_name) {
             Ok(p) => p,
-            Err(e) => {
-                if !reusing_worktree {
-                    let _ = worktree::remove_worktree(&git.root, &worktree_path);
-                }
-                if branch_created {
-                    let _ = branch::delete_branch(git, branch_name);
-                }
-                return Err(e);
-            }
+            Err(e) => return Err(fail(&journal, "Allocating ports", e)),
         };
 
         // 5b½. Check ports are actually available on the host
         if let Err(e) = ports::check_ports_available(&allocated) {
-            let _ = ports::release(groot_dir, task_name);
-            if !reusing_worktree {
-                let _ = worktree::remove_worktree(&git.root, &worktree_path);
-            }
-            if branch_created {
-                let _ = branch::delete_branch(git, branch_name);
-            }
-            return Err(e);
+            return Err(fail(&journal, "Checking ports", e));
         }
 
         // 5c. Generate compose file
+        let compose_dir = groot_dir.join("compose").join(task_name);
+        journal.record(groot_dir, PlantStep::ComposeGenerated { dir: compose_dir })?;
         let cf = match compose_mgr::generate_compose_file(
             groot_dir,
+            project_config,
             task_name,
             &worktree_path,
             &allocated,
+            sandbox,
         ) {
             Ok(cf) => cf,
-            Err(e) => {
-                let _ = ports::release(groot_dir, task_name);
-                if !reusing_worktree {
-                    let _ = worktree::remove_worktree(&git.root, &worktree_path);
-                }
-                if branch_created {
-                    let _ = branch::delete_branch(git, branch_name);
-                }
-                return Err(e);
-            }
+            Err(e) => return Err(fail(&journal, "Generating the compose file", e)),
         };
 
         // 5d. Start compose stack
+        let step = Step::start("compose_up", "Building and starting containers");
+        let stack_started = Instant::now();
+        journal.record(groot_dir, PlantStep::ComposeStarted { file: cf.clone() })?;
         if let Err(e) = compose_mgr::up(&cf) {
-            let _ = ports::release(groot_dir, task_name);
-            let compose_dir = groot_dir.join("compose").join(task_name);
-            let _ = std::fs::remove_dir_all(compose_dir);
-            if !reusing_worktree {
-                let _ = worktree::remove_worktree(&git.root, &worktree_path);
-            }
-            if branch_created {
-                let _ = branch::delete_branch(git, branch_name);
-            }
-            return Err(e);
+            let error = e.to_string();
+            notify::finished(groot_dir, Operation::ComposeHealthy, task_name, stack_started.elapsed(), Some(&error));
+            return Err(fail(&journal, "Starting the compose stack", e));
         }
+        step.done();
 
         // 5e. Wait for containers to be healthy
+        let step = Step::start("compose_health", "Waiting for containers");
         if let Err(e) = compose_mgr::wait_healthy(
             &cf,
             Duration::from_secs(compose_health_timeout_secs),
         ) {
-            let _ = compose_mgr::down(&cf);
-            let _ = ports::release(groot_dir, task_name);
-            let compose_dir = groot_dir.join("compose").join(task_name);
-            let _ = std::fs::remove_dir_all(compose_dir);
-            if !reusing_worktree {
-                let _ = worktree::remove_worktree(&git.root, &worktree_path);
-            }
-            if branch_created {
-                let _ = branch::delete_branch(git, branch_name);
+            // Capture the evidence before rolling back takes the stack down
+            match diagnostics::capture(groot_dir, task_name, &cf) {
+                Ok(dir) => warn!("Diagnostics (container status, logs, compose file) saved to {}", dir.display()),
+                Err(d) => warn!("Warning: failed to save diagnostics: {d}"),
             }
-            return Err(e);
+            let error = e.to_string();
+            notify::finished(groot_dir, Operation::ComposeHealthy, task_name, stack_started.elapsed(), Some(&error));
+            return Err(fail(&journal, "Waiting for containers", e));
         }
+        step.done();
+        notify::finished(groot_dir, Operation::ComposeHealthy, task_name, stack_started.elapsed(), None);
+
+        let engine = project_config.database.unwrap_or_default();
 
         // 5e½. Create test database (non-fatal: warn on failure, don't tear down)
-        compose_db::create_test_database(&cf, task_name);
+        compose_db::create_test_database(&cf, task_name, engine);
 
         // 5e¾. Database setup (non-fatal: warn on failure, don't tear down)
+        let db_setup = compose_db::db_setup_for(project_config.primary().as_deref(), engine);
         if db_clone {
             let source = if let Some(src) = db_source {
                 src.to_string()
             } else {
-                match compose_db::detect_source_db(&worktree_path) {
+                match compose_db::detect_source_db(&worktree_path, engine) {
                     Ok(url) => {
-                        println!("Auto-detected source database: {url}");
+                        info!("Auto-detected source database: {}", compose_db::redact_url(&url));
                         url
                     }
                     Err(e) => {
-                        eprintln!("Warning: could not detect source database: {e}");
-                        eprintln!("  Use --db-source to specify explicitly, or set compose_db_source in local.yml");
+                        warn!("Warning: could not detect source database: {e}");
+                        warn!("  Use --db-source to specify explicitly, or set compose_db_source in local.yml");
                         String::new()
                     }
                 }
             };
 
             if !source.is_empty() {
-                if let Err(e) = compose_db::clone_database(&cf, &source, task_name) {
-                    eprintln!("Warning: database clone failed: {e}");
-                    eprintln!("  The grove is running but the database may be empty.");
-                    eprintln!("  You can retry with: groot grove transplant {task_name}");
+                let step = Step::start("db_clone", "Cloning database");
+                let started = Instant::now();
+                let cloned = compose_db::clone_database(&cf, &source, task_name, engine);
+                notify_db_cloned(groot_dir, task_name, &cloned, started.elapsed());
+                if let Err(e) = cloned {
+                    drop(step);
+                    warn!("Warning: database clone failed: {e}");
+                    warn!("  The grove is running but the database may be empty.");
+                    warn!("  You can retry with: groot grove transplant {task_name}");
+                } else {
+                    step.done();
                 }
             }
         } else {
-            compose_db::setup_database(&cf);
+            let step = Step::start("db_setup", "Setting up database");
+            compose_db::setup_database(&cf, &db_setup);
+            step.done();
         }
 
         // 5e⅞. Set up test database schema (non-fatal: warn on failure, don't tear down)
-        compose_db::setup_test_schema(&cf);
+        if db_setup.test_prepare.is_some() {
+            let step = Step::start("db_test_prepare", "Preparing test database");
+            compose_db::setup_test_schema(&cf, &db_setup);
+            step.done();
+        }
 
-        // 5f. Run post-start hooks (warn on failure, don't tear down)
+        // 5f. Run post-start hooks (warn on failure, don't tear down), starting
+        // from and then filling the shared node_modules with `node_modules: seeded`
+        compose_caches::seed_node_modules(&cf);
+        let step = (!compose_post_start.is_empty()).then(|| Step::start("post_start_hooks", "Running post-start hooks"));
         for hook in compose_post_start {
-            println!("Running post-start hook: {hook}");
+            info!("Running post-start hook: {hook}");
             match compose_mgr::exec(&cf, "app", hook) {
-                Ok(()) => println!("  Hook succeeded: {hook}"),
-                Err(e) => eprintln!("  Warning: hook failed: {e}"),
+                Ok(()) => info!("  Hook succeeded: {hook}"),
+                Err(e) => warn!("  Warning: hook failed: {e}"),
             }
         }
+        if let Some(step) = step {
+            step.done();
+        }
+        compose_caches::save_node_modules_seed(&cf);
 
         compose_file = Some(cf);
         compose_ports = Some(allocated);
     }
 
-    // 5g. Generate CLAUDE.local.md in worktree (non-fatal)
-    {
-        let config_path = groot_dir.join("config.yml");
-        let project_name = ProjectConfig::load(&config_path)
-            .map(|c| c.project_name)
-            .unwrap_or_default();
-        let detected_types = ProjectConfig::load(&config_path)
-            .map(|c| c.detected_types.join(", "))
-            .unwrap_or_default();
-
-        let is_shared = shared_grove.is_some();
-        let compose_file_str = compose_file
-            .as_ref()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default();
-        let compose_project_str = compose_file
-            .as_ref()
-            .map(|p| compose_mgr::project_name(p))
-            .unwrap_or_default();
-
-        // Port values: prefer shared_compose_ports, then own compose_ports, then defaults
-        let effective_ports = shared_compose_ports.or(compose_ports.as_ref());
-
-        let vars = claude_md::ClaudeMdVars {
-            worktree_path: &worktree_path.to_string_lossy(),
-            worker_name: task_name,
-            branch_name,
-            project_name: &project_name,
-            task_type,
-            detected_types: &detected_types,
-            compose_enabled: compose_file.is_some() && !is_shared,
-            compose_file: &compose_file_str,
-            compose_project: &compose_project_str,
-            app_port: effective_ports.map(|p| p.app).unwrap_or(3000),
-            db_port: effective_ports.map(|p| p.db).unwrap_or(5432),
-            redis_port: effective_ports.map(|p| p.redis).unwrap_or(6379),
-            shared_compose: is_shared,
-            shared_grove_name: shared_grove.unwrap_or(""),
-        };
+    // 5h. Install a tree's dependencies on the host (warn on failure, don't
+    // tear down), here or ahead of the agent in the first pane
+    let post_plant = if stack == Stack::None {
+        ctx.local.tree_post_plant_for(task_type, &worktree_path)
+    } else {
+        Vec::new()
+    };
+    let nix = stack == Stack::None && project_config.uses_nix(&worktree_path);
+    let post_plant_in_pane = ctx.local.tree_post_plant_in == PostPlantIn::Pane
+        && backend == TerminalBackend::Tmux
+        && !ws_template.windows.is_empty();
+    if !post_plant.is_empty() && !post_plant_in_pane {
+        let step = Step::start("post_plant_hooks", "Installing dependencies");
+        for hook in &post_plant {
+            info!("Running post-plant hook: {hook}");
+            let command = if nix { workspace::in_nix_develop(hook) } else { hook.clone() };
+            let ran = Command::new("sh")
+                .args(["-c", &command])
+                .current_dir(&worktree_path)
+                .checked_output(&format!("'{hook}' failed"));
+            match ran {
+                Ok(_) => info!("  Hook succeeded: {hook}"),
+                Err(e) => warn!("  Warning: hook failed: {e}"),
+            }
+        }
+        step.done();
+    }
+
+    // The worker's state, saved once its workspace is open
+    let ws_name = workspace::worker_session_name(tmux_session, task_name);
+    let created_at = chrono::Utc::now();
+    let mut state = GroveState {
+        schema_version: STATE_SCHEMA_VERSION,
+        task_name: task_name.to_string(),
+        branch: branch_name.to_string(),
+        worktree_path: worktree_path.clone(),
+        tmux_window: None,
+        container_id: None,
+        created_at,
+        pid: None,
+        compose_file,
+        compose_ports,
+        tmux_session: (backend == TerminalBackend::Tmux).then(|| ws_name.clone()),
+        shared_grove: shared_grove.map(|s| s.to_string()),
+        shared_compose_ports: shared_compose_ports.cloned(),
+        task_type: Some(task_type.to_string()),
+        tmux_layout: layout_name.map(|s| s.to_string()),
+        terminal_backend: backend,
+        expires_at: ttl.map(|ttl| created_at + ttl),
+        sandbox,
+        prompts: Vec::new(),
+        last_run: None,
+        remote,
+        depends_on: Vec::new(),
+        labels: Vec::new(),
+        parent: None,
+        fan_out: None,
+        model: initial_prompt.and_then(|p| p.model.clone()),
+        subtasks: Vec::new(),
+        completed_at: None,
+        description: initial_prompt.map(|p| p.description.clone()),
+        notes: Vec::new(),
+        health: None,
+    };
 
-        match claude_md::generate(&worktree_path, groot_dir, &vars) {
-            Ok(()) => println!("Generated CLAUDE.local.md in worktree"),
-            Err(e) => eprintln!("Warning: failed to generate CLAUDE.local.md: {e}"),
+    // 5g. Generate CLAUDE.local.md in worktree (non-fatal), and render the
+    // initial prompt with the same variables
+    let description = initial_prompt.map_or("", |p| p.description.as_str());
+    let prompt = with_claude_vars(ctx, &state, description, |vars| {
+        match claude_md::generate(&worktree_path, groot_dir, vars) {
+            Ok(()) => info!("Generated CLAUDE.local.md in worktree"),
+            Err(e) => warn!("Warning: failed to generate CLAUDE.local.md: {e}"),
         }
+        match claude_md::generate_settings(&worktree_path, groot_dir, vars) {
+            Ok(true) => info!("Generated .claude/settings.local.json in worktree"),
+            Ok(false) => {}
+            Err(e) => warn!("Warning: failed to generate .claude/settings.local.json: {e}"),
+        }
+        initial_prompt.map(|p| p.render(vars))
+    });
+    if let Some(prompt) = &prompt {
+        state.record_prompt(PromptVia::Plant, prompt);
     }
+    let initial_command = prompt
+        .as_ref()
+        .map(|p| format!("{} --prompt {}", agent_command(&state), session::shell_quote(p)));
+    let initial_command = initial_command.as_deref();
 
     // 6. Create per-grove tmux workspace session
-    let ws_template = workspace::load_template(groot_dir)?
-        .unwrap_or_else(workspace::default_template);
-
     // When sharing a grove's compose, use shared ports for template vars but don't
     // pass compose_file so panes run commands locally instead of via `docker compose exec`.
-    let effective_ports = shared_compose_ports.or(compose_ports.as_ref());
+    let effective_ports = shared_compose_ports.or(state.compose_ports.as_ref());
     let effective_compose_file = if shared_grove.is_some() {
         None
     } else {
-        compose_file.as_deref()
+        state.compose_file.as_deref()
     };
 
     let vars = workspace::WorkspaceVars {
@@ -301,70 +450,247 @@ pub fn plant(
         redis_port: effective_ports.map(|p| p.redis),
         compose_file: effective_compose_file,
     };
-    let rendered = workspace::render_template(&ws_template, &vars);
-    let ws_name = workspace::worker_session_name(tmux_session, task_name);
+    let mut rendered = workspace::render_template(&ws_template, &vars);
+    if sandbox {
+        // Panes that start the agent start it in its container instead
+        for pane in rendered.windows.iter_mut().flat_map(|w| w.panes.iter_mut()) {
+            let command = pane.command.as_deref().unwrap_or_default();
+            if command == "claude" || command.starts_with("claude ") {
+                pane.host = false;
+                pane.service = Some(compose_sandbox::AGENT_SERVICE.to_string());
+                pane.exec_user = None;
+            }
+        }
+    }
+
+    if nix {
+        workspace::wrap_in_nix(&mut rendered);
+    }
+    if let Some(remote) = &state.remote {
+        remote.wrap_panes(&mut rendered);
+    }
+
+    let env = workspace::session_environment(&vars);
+
+    let step = Step::start("workspace", "Opening workspace");
+    journal.record(groot_dir, PlantStep::WorkspaceOpened { session: ws_name.clone() })?;
+    let opened = match backend {
+        TerminalBackend::Tmux => workspace::create_worker_session(
+            &ws_name,
+            &rendered,
+            &worktree_path,
+            effective_compose_file,
+            &env,
+        ),
+        // Terminal tabs get the initial command while they are opened
+        _ => terminal::open_workspace(
+            backend,
+            &ws_name,
+            &rendered,
+            &worktree_path,
+            effective_compose_file,
+            &env,
+            initial_command,
+        ),
+    };
 
-    if let Err(e) = workspace::create_worker_session(&ws_name, &rendered, &worktree_path, effective_compose_file) {
-        workspace::destroy_worker_session(&ws_name);
-        if let Some(ref cf) = compose_file {
-            let _ = compose_mgr::down(cf);
-            let _ = ports::release(groot_dir, task_name);
-            let compose_dir = groot_dir.join("compose").join(task_name);
-            let _ = std::fs::remove_dir_all(compose_dir);
+    if let Err(e) = opened {
+        return Err(fail(&journal, "Opening the workspace", e));
+    }
+    step.done();
+
+    // 7. Record the agent pane and send the initial command if provided
+    if backend == TerminalBackend::Tmux && !ws_template.windows.is_empty() {
+        let target = workspace::first_pane_target(&ws_name);
+        if let Err(e) = transcript::record_pane(groot_dir, task_name, &target) {
+            warn!("Warning: failed to record the agent pane: {e}");
         }
-        if !reusing_worktree {
-            let _ = worktree::remove_worktree(&git.root, &worktree_path);
+        if post_plant_in_pane && !post_plant.is_empty() {
+            let hooks = post_plant.join(" && ");
+            let hooks = if nix { workspace::in_nix_develop(&hooks) } else { hooks };
+            session::send_keys_to_pane(&target, &hooks)
+                .unwrap_or_else(|e| warn!("Warning: failed to send post-plant hooks to workspace: {e}"));
         }
-        if branch_created {
-            let _ = branch::delete_branch(git, branch_name);
+        if let Some(cmd) = initial_command {
+            if let Err(e) = session::send_literal_keys(&target, cmd) {
+                warn!("Warning: failed to send initial command to workspace: {e}");
+            }
         }
-        return Err(e);
     }
 
-    // 7. Send initial command if provided
-    if let Some(cmd) = initial_command {
-        if let Some(first_win) = ws_template.windows.first() {
-            let target = format!("{ws_name}:{}.0", first_win.name);
-            if let Err(e) = session::send_keys_to_pane(&target, cmd) {
-                eprintln!("Warning: failed to send initial command to workspace: {e}");
+    // 8. Save state
+    if let Err(e) = store.save_worker(&state) {
+        return Err(fail(&journal, "Saving the worker state", e));
+    }
+    journal.finish(groot_dir);
+
+    Ok(state)
+}
+
+/// Send the `worker_spawned` or `worker_failed` event for a plant that
+/// took `took`, and a desktop notification if it was slow. A worker planted
+/// with a prompt runs the `on_active` hooks too.
+pub fn notify_planted(groot_dir: &Path, task_name: &str, branch_name: &str, planted: &Result<GroveState>, took: Duration) {
+    let error = planted.as_ref().err().map(|e| e.to_string());
+    notify::finished(groot_dir, Operation::Plant, task_name, took, error.as_deref());
+    let duration_ms = took.as_millis() as u64;
+    match planted {
+        Ok(state) => {
+            notify::event(
+                groot_dir,
+                WebhookEvent::WorkerSpawned,
+                task_name,
+                json!({
+                    "branch": branch_name,
+                    "kind": state.kind(),
+                    "task_type": state.task_type,
+                    "worktree": state.worktree_path,
+                    "duration_ms": duration_ms,
+                }),
+            );
+            if state.initial_prompt().is_some() {
+                notify::agent_prompted(groot_dir, task_name, PromptVia::Plant.as_str());
             }
         }
+        Err(e) => notify::event(
+            groot_dir,
+            WebhookEvent::WorkerFailed,
+            task_name,
+            json!({ "branch": branch_name, "stage": "plant", "error": e.to_string(), "duration_ms": duration_ms }),
+        ),
     }
+}
 
-    // 8. Save state
-    let state = GroveState {
-        task_name: task_name.to_string(),
-        branch: branch_name.to_string(),
-        worktree_path: worktree_path.clone(),
-        tmux_window: None,
-        container_id: None,
-        created_at: chrono::Utc::now(),
-        pid: None,
-        compose_file,
-        compose_ports,
-        tmux_session: Some(ws_name.clone()),
-        shared_grove: shared_grove.map(|s| s.to_string()),
-        shared_compose_ports: shared_compose_ports.cloned(),
+/// Send the `worker_stopped` event; `uprooted` when the worktree and
+/// branch went too.
+pub fn notify_stopped(groot_dir: &Path, state: &GroveState, uprooted: bool) {
+    let lifetime = chrono::Utc::now() - state.created_at;
+    notify::event(
+        groot_dir,
+        WebhookEvent::WorkerStopped,
+        &state.task_name,
+        json!({
+            "branch": state.branch,
+            "uprooted": uprooted,
+            "task_type": state.task_type,
+            "lifetime_secs": lifetime.num_seconds().max(0),
+        }),
+    );
+}
+
+/// Send the `db_clone_finished` event, and a desktop notification if the
+/// clone was slow.
+pub fn notify_db_cloned(groot_dir: &Path, task_name: &str, cloned: &Result<()>, took: Duration) {
+    let error = cloned.as_ref().err().map(|e| e.to_string());
+    notify::finished(groot_dir, Operation::DbClone, task_name, took, error.as_deref());
+    let duration_ms = took.as_millis() as u64;
+    let details = match cloned {
+        Ok(()) => json!({ "success": true, "duration_ms": duration_ms }),
+        Err(e) => json!({ "success": false, "error": e.to_string(), "duration_ms": duration_ms }),
     };
+    notify::event(groot_dir, WebhookEvent::DbCloneFinished, task_name, details);
+}
 
-    if let Err(e) = state.save(&state_path) {
-        workspace::destroy_worker_session(&ws_name);
-        if let Some(ref cf) = state.compose_file {
-            let _ = compose_mgr::down(cf);
-            let _ = ports::release(groot_dir, task_name);
-            let compose_dir = groot_dir.join("compose").join(task_name);
-            let _ = std::fs::remove_dir_all(compose_dir);
-        }
-        if !reusing_worktree {
-            let _ = worktree::remove_worktree(&git.root, &worktree_path);
-        }
-        if branch_created {
-            let _ = branch::delete_branch(git, branch_name);
-        }
-        return Err(e);
+/// The shell command that starts the worker's agent: `claude`, or for a
+/// sandboxed worker `claude` inside its `agent` container.
+pub fn agent_command(worker: &GroveState) -> String {
+    let agent = match &worker.compose_file {
+        Some(cf) if worker.sandbox => format!(
+            "{} {} claude",
+            workspace::exec_prefix(cf),
+            compose_sandbox::AGENT_SERVICE
+        ),
+        _ => "claude".to_string(),
+    };
+    match &worker.model {
+        Some(model) => format!("{agent} --model {}", session::shell_quote(model)),
+        None => agent,
     }
+}
 
-    Ok(state)
+/// Compute the CLAUDE.local.md variables (also used to render prompts) for
+/// `worker` and pass them to `f`.
+pub fn with_claude_vars<R>(
+    ctx: &GrootContext,
+    worker: &GroveState,
+    description: &str,
+    f: impl FnOnce(&claude_md::ClaudeMdVars) -> R,
+) -> R {
+    let project_config = &ctx.project;
+    let worktree_path = &worker.worktree_path;
+    let detected_types = project_config.detected_types.join(", ");
+    let primary_type = project_config.primary().unwrap_or_default();
+
+    let is_shared = worker.shared_grove.is_some();
+    let compose_file_str = worker
+        .compose_file
+        .as_ref()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let compose_project_str = worker
+        .compose_file
+        .as_ref()
+        .map(|p| compose_mgr::project_name(p))
+        .unwrap_or_default();
+
+    // Port values: prefer shared_compose_ports, then own compose_ports, then defaults
+    let effective_ports = worker.shared_compose_ports.as_ref().or(worker.compose_ports.as_ref());
+
+    let package_managers = package_manager::detect(worktree_path);
+    let install_command = package_managers
+        .first()
+        .and_then(|pm| package_manager::install_command(worktree_path, pm))
+        .unwrap_or("bundle install");
+    let pkg_manager = package_managers.join(", ");
+    let tests = test_framework::detect(worktree_path);
+    let test_frameworks = tests.iter().map(|t| t.name).collect::<Vec<_>>().join(", ");
+    let test_commands = tests.iter().map(|t| t.command.as_str()).collect::<Vec<_>>().join("\n");
+    let lints = linter::detect(worktree_path);
+    let linters = lints.iter().map(|l| l.name).collect::<Vec<_>>().join(", ");
+    let lint_commands = lints.iter().map(|l| l.command.as_str()).collect::<Vec<_>>().join("\n");
+    // Compose stacks assume Postgres when the project's engine is unknown
+    let has_stack = worker.compose_file.is_some() || is_shared;
+    let notes = worker.notes_markdown();
+    let db_engine = project_config
+        .database
+        .or(has_stack.then(DbEngine::default))
+        .map_or("", |e| e.name());
+
+    f(&claude_md::ClaudeMdVars {
+        worktree_path: &worktree_path.to_string_lossy(),
+        worker_name: &worker.task_name,
+        branch_name: &worker.branch,
+        project_name: &project_config.project_name,
+        task_type: worker.task_type.as_deref().unwrap_or("feature"),
+        detected_types: &detected_types,
+        primary_type: &primary_type,
+        compose_enabled: worker.compose_file.is_some() && !is_shared,
+        compose_file: &compose_file_str,
+        compose_project: &compose_project_str,
+        app_port: effective_ports.map(|p| p.app).unwrap_or(3000),
+        db_port: effective_ports.map(|p| p.db).unwrap_or(5432),
+        redis_port: effective_ports.map(|p| p.redis).unwrap_or(6379),
+        shared_compose: is_shared,
+        shared_grove_name: worker.shared_grove.as_deref().unwrap_or(""),
+        pkg_manager: &pkg_manager,
+        install_command,
+        test_frameworks: &test_frameworks,
+        test_commands: &test_commands,
+        linters: &linters,
+        lint_commands: &lint_commands,
+        db_engine,
+        description,
+        notes: &notes,
+    })
+}
+
+/// Render CLAUDE.local.md into the worker's worktree again, e.g. after a note
+/// was added.
+pub fn refresh_claude_md(ctx: &GrootContext, worker: &GroveState) -> Result<()> {
+    let description = worker.description.as_deref().unwrap_or("");
+    with_claude_vars(ctx, worker, description, |vars| {
+        claude_md::generate(&worker.worktree_path, &ctx.groot_dir, vars)
+    })
 }
 
 /// Find trees that share a grove's compose stack.
@@ -379,12 +705,7 @@ fn find_sharing_trees(groot_dir: &Path, grove_name: &str) -> Vec<String> {
 
 /// Stop a grove/tree: tear down ephemeral resources (compose, tmux, state) but keep worktree + branch.
 pub fn stop(groot_dir: &Path, task_name: &str, force: bool) -> Result<()> {
-    let state_path = GroveState::state_path(groot_dir, task_name);
-    if !state_path.exists() {
-        return Err(GrootError::GroveNotFound(task_name.to_string()));
-    }
-
-    let state = GroveState::load(&state_path)?;
+    let state = get_grove_by_name(groot_dir, task_name)?;
 
     // Block or auto-stop sharing trees
     if state.compose_file.is_some() {
@@ -398,9 +719,9 @@ pub fn stop(groot_dir: &Path, task_name: &str, force: bool) -> Result<()> {
                 )));
             }
             for tree_name in &sharing {
-                eprintln!("Stopping sharing tree '{tree_name}'...");
+                info!("Stopping sharing tree '{tree_name}'...");
                 if let Err(e) = stop(groot_dir, tree_name, false) {
-                    eprintln!("Warning: failed to stop sharing tree '{tree_name}': {e}");
+                    warn!("Warning: failed to stop sharing tree '{tree_name}': {e}");
                 }
             }
         }
@@ -409,40 +730,46 @@ pub fn stop(groot_dir: &Path, task_name: &str, force: bool) -> Result<()> {
     // Tear down compose stack if present
     if let Some(ref cf) = state.compose_file {
         if let Err(e) = compose_mgr::down(cf) {
-            eprintln!("Warning: compose down failed: {e}");
+            warn!("Warning: compose down failed: {e}");
         }
         let _ = ports::release(groot_dir, task_name);
         let compose_dir = groot_dir.join("compose").join(task_name);
         let _ = std::fs::remove_dir_all(compose_dir);
     }
 
-    // Kill per-grove tmux session
+    if let Some(ref remote) = state.remote {
+        remote
+            .stop()
+            .unwrap_or_else(|e| warn!("Warning: stopping the {} environment failed: {e}", remote.backend.name()));
+    }
+
+    // Kill per-grove tmux session, then keep the agent's session files
     if let Some(ref ws) = state.tmux_session {
         workspace::destroy_worker_session(ws);
     }
+    if let Err(e) = transcript::archive_sessions(groot_dir, &state) {
+        warn!("Warning: failed to archive agent transcripts: {e}");
+    }
 
-    // Remove state file (but NOT worktree or branch)
-    std::fs::remove_file(&state_path)?;
+    // Remove state (but NOT worktree or branch)
+    store::remove_worker(groot_dir, task_name)?;
 
     // Remove lock file if it exists
     let lock_path = groot_dir.join("locks").join(format!("{task_name}.lock"));
     let _ = std::fs::remove_file(lock_path);
 
+    notify_stopped(groot_dir, &state, false);
     Ok(())
 }
 
 /// Uproot a grove/tree: tear down compose stack, remove tmux session, worktree, branch, and state file.
 /// If the worktree has uncommitted changes or unpushed commits and `force` is false,
 /// returns an error suggesting `stop` or `uproot --force`.
-pub fn uproot(git: &GitRepo, groot_dir: &Path, task_name: &str, force: bool) -> Result<()> {
-    let state_path = GroveState::state_path(groot_dir, task_name);
-    if !state_path.exists() {
-        return Err(GrootError::GroveNotFound(task_name.to_string()));
-    }
-
-    let state = GroveState::load(&state_path)?;
+pub fn uproot(ctx: &GrootContext, task_name: &str, force: bool) -> Result<()> {
+    let (git, groot_dir) = (&ctx.git, ctx.groot_dir.as_path());
+    let state = get_grove_by_name(groot_dir, task_name)?;
 
-    let kind = if state.compose_file.is_some() { "grove" } else { "tree" };
+    let kind = state.kind();
 
     // Block or auto-stop sharing trees
     if state.compose_file.is_some() {
@@ -456,9 +783,9 @@ pub fn uproot(git: &GitRepo, groot_dir: &Path, task_name: &str, force: bool) ->
                 )));
             }
             for tree_name in &sharing {
-                eprintln!("Stopping sharing tree '{tree_name}'...");
+                info!("Stopping sharing tree '{tree_name}'...");
                 if let Err(e) = stop(groot_dir, tree_name, false) {
-                    eprintln!("Warning: failed to stop sharing tree '{tree_name}': {e}");
+                    warn!("Warning: failed to stop sharing tree '{tree_name}': {e}");
                 }
             }
         }
@@ -467,7 +794,7 @@ pub fn uproot(git: &GitRepo, groot_dir: &Path, task_name: &str, force: bool) ->
     // Check for dirty worktree before destroying
     if !force && state.worktree_path.exists() {
         let has_changes = worktree::has_uncommitted_changes(&state.worktree_path);
-        let ahead = worktree::commits_ahead_of(&git.root, &state.branch, "main");
+        let ahead = worktree::commits_ahead_of(&git.root, &state.branch, ctx.project.base_branch());
 
         if has_changes || ahead > 0 {
             let mut reasons = Vec::new();
@@ -490,17 +817,26 @@ pub fn uproot(git: &GitRepo, groot_dir: &Path, task_name: &str, force: bool) ->
     // Tear down compose stack if present
     if let Some(ref cf) = state.compose_file {
         if let Err(e) = compose_mgr::down(cf) {
-            eprintln!("Warning: compose down failed: {e}");
+            warn!("Warning: compose down failed: {e}");
         }
         let _ = ports::release(groot_dir, task_name);
         let compose_dir = groot_dir.join("compose").join(task_name);
         let _ = std::fs::remove_dir_all(compose_dir);
     }
 
-    // Kill per-grove tmux session
+    if let Some(ref remote) = state.remote {
+        remote
+            .delete()
+            .unwrap_or_else(|e| warn!("Warning: deleting the {} environment failed: {e}", remote.backend.name()));
+    }
+
+    // Kill per-grove tmux session, then keep the agent's session files
     if let Some(ref ws) = state.tmux_session {
         workspace::destroy_worker_session(ws);
     }
+    if let Err(e) = transcript::archive_sessions(groot_dir, &state) {
+        warn!("Warning: failed to archive agent transcripts: {e}");
+    }
 
     // Remove worktree
     if state.worktree_path.exists() {
@@ -510,60 +846,146 @@ pub fn uproot(git: &GitRepo, groot_dir: &Path, task_name: &str, force: bool) ->
     // Delete branch
     let _ = branch::delete_branch(git, &state.branch);
 
-    // Remove state file
-    std::fs::remove_file(&state_path)?;
+    // Remove state
+    store::remove_worker(groot_dir, task_name)?;
 
     // Remove lock file if it exists
     let lock_path = groot_dir.join("locks").join(format!("{task_name}.lock"));
     let _ = std::fs::remove_file(lock_path);
 
+    notify_stopped(groot_dir, &state, true);
     Ok(())
 }
 
-/// List all groves from state files
+/// List all groves from the state store, oldest first
 pub fn list_groves(groot_dir: &Path) -> Result<Vec<GroveState>> {
-    let groves_dir = groot_dir.join("groves");
-    if !groves_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut groves = Vec::new();
-    for entry in std::fs::read_dir(&groves_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().is_some_and(|ext| ext == "json") {
-            if let Ok(state) = GroveState::load(&path) {
-                groves.push(state);
-            }
-        }
-    }
+    store::open(groot_dir)?.list_workers()
+}
 
-    Ok(groves)
+/// `GroveNotFound` for `task_name`, suggesting the closest planted names.
+pub fn not_found(groot_dir: &Path, task_name: &str) -> GrootError {
+    let names: Vec<String> = list_groves(groot_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|g| g.task_name)
+        .collect();
+    GrootError::GroveNotFound {
+        name: task_name.to_string(),
+        suggestions: crate::error::similar_names(task_name, &names),
+    }
 }
 
 /// Get a grove by name
 pub fn get_grove_by_name(groot_dir: &Path, task_name: &str) -> Result<GroveState> {
-    let state_path = GroveState::state_path(groot_dir, task_name);
-    if !state_path.exists() {
-        return Err(GrootError::GroveNotFound(task_name.to_string()));
-    }
-    GroveState::load(&state_path)
+    store::open(groot_dir)?
+        .load_worker(task_name)?
+        .ok_or_else(|| not_found(groot_dir, task_name))
 }
 
-fn check_disk_space(min_mb: u64) -> Result<()> {
-    let disks = Disks::new_with_refreshed_list();
-    for disk in disks.list() {
-        if disk.mount_point() == Path::new("/") {
-            let available_mb = disk.available_space() / (1024 * 1024);
-            if available_mb < min_mb {
-                return Err(GrootError::InsufficientDiskSpace {
-                    available_mb,
-                    required_mb: min_mb,
-                });
+/// Copy what the `patterns` globs match under `root` to the same place under
+/// `worktree`, warning about what fails to copy. Patterns matching nothing
+/// are skipped. Returns the paths copied, relative to both.
+fn copy_files(root: &Path, worktree: &Path, patterns: &[&str]) -> Vec<String> {
+    let mut copied = Vec::new();
+    for pattern in patterns {
+        for rel in rules::glob_paths(root, pattern) {
+            match copy_path(&root.join(&rel), &worktree.join(&rel)) {
+                Ok(()) => copied.push(rel),
+                Err(e) => warn!("Warning: failed to copy {rel} into worktree: {e}"),
             }
-            return Ok(());
         }
     }
-    // If we can't determine disk space, proceed anyway
-    Ok(())
+    copied
+}
+
+/// Copy a file, directory (recursively), or symlink (as a symlink, on unix)
+/// to `dest`, creating parent directories.
+fn copy_path(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let meta = std::fs::symlink_metadata(src)?;
+    if meta.file_type().is_symlink() {
+        return copy_link(src, dest);
+    }
+    if meta.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    std::fs::copy(src, dest).map(|_| ())
+}
+
+#[cfg(unix)]
+fn copy_link(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let target = std::fs::read_link(src)?;
+    let _ = std::fs::remove_file(dest);
+    std::os::unix::fs::symlink(target, dest)
+}
+
+/// Elsewhere a link's kind (file or directory) decides how to create it, so
+/// copy the file it points to instead.
+#[cfg(not(unix))]
+fn copy_link(src: &Path, dest: &Path) -> std::io::Result<()> {
+    std::fs::copy(src, dest).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_path_files_and_directories() {
+        let src = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        std::fs::write(src.path().join(".env"), "SECRET=1\n").unwrap();
+        std::fs::create_dir_all(src.path().join("certs/dev")).unwrap();
+        std::fs::write(src.path().join("certs/dev/key.pem"), "key").unwrap();
+
+        // Parent directories of the destination are created
+        copy_path(&src.path().join(".env"), &dest.path().join("a/b/.env")).unwrap();
+        assert_eq!(std::fs::read_to_string(dest.path().join("a/b/.env")).unwrap(), "SECRET=1\n");
+
+        copy_path(&src.path().join("certs"), &dest.path().join("certs")).unwrap();
+        assert_eq!(std::fs::read_to_string(dest.path().join("certs/dev/key.pem")).unwrap(), "key");
+
+
+        let missing = copy_path(&src.path().join("nope"), &dest.path().join("nope")).unwrap_err();
+        assert_eq!(missing.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_path_keeps_links() {
+        let src = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        std::fs::write(src.path().join(".env"), "SECRET=1\n").unwrap();
+        std::os::unix::fs::symlink(".env", src.path().join("env-link")).unwrap();
+
+        copy_path(&src.path().join("env-link"), &dest.path().join("env-link")).unwrap();
+        assert_eq!(std::fs::read_link(dest.path().join("env-link")).unwrap(), Path::new(".env"));
+    }
+
+    #[test]
+    fn test_copy_files_into_worktree() {
+        let root = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join(".env"), "A=1\n").unwrap();
+        std::fs::create_dir_all(root.path().join("config/credentials")).unwrap();
+        std::fs::write(root.path().join("config/master.key"), "k").unwrap();
+        std::fs::write(root.path().join("config/credentials/dev.key"), "d").unwrap();
+
+        let copied = copy_files(
+            root.path(),
+            worktree.path(),
+            &[".env", "Dockerfile.dev", "config/master.key", "config/credentials"],
+        );
+        // Dockerfile.dev doesn't exist, so it's skipped
+        assert_eq!(copied, [".env", "config/master.key", "config/credentials"]);
+        assert_eq!(std::fs::read_to_string(worktree.path().join("config/credentials/dev.key")).unwrap(), "d");
+        assert!(!worktree.path().join("Dockerfile.dev").exists());
+    }
 }
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::atomic;
+use crate::claude_md::InitialPrompt;
+use crate::compose::{manager as compose_mgr, ports};
+use crate::error::{GrootError, Result};
+use crate::git::{branch, repo::GitRepo, worktree};
+use crate::tmux::workspace;
+
+use super::grove::Stack;
+use super::remote::{RemoteBackend, RemoteEnv};
+
+/// What a plant in progress has created so far, kept in
+/// `.groot/journal/<task>.json`. Each step is recorded before it is carried
+/// out, and the journal is deleted once the worker's state is saved, so a
+/// journal without a live plant (after a crash, Ctrl-C, or kill) lists
+/// everything to undo or reuse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantJournal {
+    pub task_name: String,
+    pub branch: String,
+    pub started_at: DateTime<Utc>,
+    /// The process that ran the plant
+    pub pid: u32,
+    /// How the plant was asked for, so `groot worker resume` can repeat it
+    pub request: PlantRequest,
+    pub steps: Vec<PlantStep>,
+}
+
+/// A plant's options, minus the database source (which may be a secret).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantRequest {
+    pub task_type: String,
+    pub stack: Stack,
+    pub db_clone: bool,
+    pub shared_grove: Option<String>,
+    pub layout: Option<String>,
+    pub prompt: Option<InitialPrompt>,
+}
+
+/// Something a plant created, and that rolling it back removes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum PlantStep {
+    BranchCreated,
+    WorktreeCreated { path: PathBuf },
+    PortsAllocated,
+    ComposeGenerated { dir: PathBuf },
+    ComposeStarted { file: PathBuf },
+    RemoteProvisioned { backend: RemoteBackend, id: String },
+    WorkspaceOpened { session: String },
+}
+
+impl PlantStep {
+    /// Whether a continued plant picks up what this step created. The stack
+    /// is stopped (so its ports check passes) and the workspace closed, to
+    /// be started afresh.
+    fn reusable(&self, with_stack: bool) -> bool {
+        match self {
+            PlantStep::BranchCreated | PlantStep::WorktreeCreated { .. } => true,
+            PlantStep::PortsAllocated | PlantStep::ComposeGenerated { .. } => with_stack,
+            PlantStep::ComposeStarted { .. }
+            | PlantStep::RemoteProvisioned { .. }
+            | PlantStep::WorkspaceOpened { .. } => false,
+        }
+    }
+}
+
+pub fn journal_dir(groot_dir: &Path) -> PathBuf {
+    groot_dir.join("journal")
+}
+
+fn journal_path(groot_dir: &Path, task_name: &str) -> PathBuf {
+    journal_dir(groot_dir).join(format!("{task_name}.json"))
+}
+
+impl PlantJournal {
+    pub fn new(task_name: &str, branch: &str, request: PlantRequest) -> Self {
+        Self {
+            task_name: task_name.to_string(),
+            branch: branch.to_string(),
+            started_at: Utc::now(),
+            pid: std::process::id(),
+            request,
+            steps: Vec::new(),
+        }
+    }
+
+    /// The journal of an unfinished plant of `task_name`, if there is one.
+    pub fn load(groot_dir: &Path, task_name: &str) -> Result<Option<Self>> {
+        let path = journal_path(groot_dir, task_name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| GrootError::Other(format!("Unreadable plant journal {}: {e}", path.display())))
+    }
+
+    /// Every unfinished plant's journal, unreadable ones skipped.
+    pub fn list(groot_dir: &Path) -> Vec<Self> {
+        let mut journals: Vec<Self> = std::fs::read_dir(journal_dir(groot_dir))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+            .filter_map(|c| serde_json::from_str(&c).ok())
+            .collect();
+        journals.sort_by_key(|j| j.started_at);
+        journals
+    }
+
+    pub fn save(&self, groot_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(journal_dir(groot_dir))?;
+        atomic::write(&journal_path(groot_dir, &self.task_name), serde_json::to_string_pretty(self)?)
+    }
+
+    /// Record `step` before carrying it out.
+    pub fn record(&mut self, groot_dir: &Path, step: PlantStep) -> Result<()> {
+        if !self.steps.contains(&step) {
+            self.steps.push(step);
+        }
+        self.save(groot_dir)
+    }
+
+    /// The plant finished: nothing is left to undo.
+    pub fn finish(&self, groot_dir: &Path) {
+        let _ = std::fs::remove_file(journal_path(groot_dir, &self.task_name));
+    }
+
+    /// Undo every recorded step, newest first, then delete the journal.
+    /// Best effort: a step that fails to undo doesn't stop the others.
+    pub fn roll_back(&self, git: &GitRepo, groot_dir: &Path) {
+        for step in self.steps.iter().rev() {
+            undo(git, groot_dir, &self.task_name, &self.branch, step);
+        }
+        self.finish(groot_dir);
+    }
+
+    /// Take over an interrupted plant of the same task as a new plant on
+    /// `branch` with `request`. What the new plant would create again (the
+    /// branch and worktree, and the ports and compose files if it has a
+    /// stack) is kept, and stays in the journal so a failure still removes
+    /// it; the rest is undone.
+    pub fn resume(self, git: &GitRepo, groot_dir: &Path, branch: &str, request: PlantRequest) -> Self {
+        let with_stack = request.stack.has_compose();
+        let same_branch = self.branch == branch;
+        let mut steps = Vec::new();
+        for step in self.steps.iter().rev() {
+            if same_branch && step.reusable(with_stack) {
+                steps.push(step.clone());
+            } else {
+                undo(git, groot_dir, &self.task_name, &self.branch, step);
+            }
+        }
+        steps.reverse();
+        Self {
+            steps,
+            ..Self::new(&self.task_name, branch, request)
+        }
+    }
+}
+
+fn undo(git: &GitRepo, groot_dir: &Path, task_name: &str, branch_name: &str, step: &PlantStep) {
+    info!("Undoing: {}", describe(step));
+    match step {
+        PlantStep::BranchCreated => {
+            let _ = branch::delete_branch(git, branch_name);
+        }
+        PlantStep::WorktreeCreated { path } => {
+            let _ = worktree::remove_worktree(&git.root, path);
+        }
+        PlantStep::PortsAllocated => {
+            let _ = ports::release(groot_dir, task_name);
+        }
+        PlantStep::ComposeGenerated { dir } => {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+        PlantStep::ComposeStarted { file } => {
+            let _ = compose_mgr::down(file);
+        }
+        PlantStep::RemoteProvisioned { backend, id } => {
+            let env = RemoteEnv { backend: *backend, id: id.clone(), provider: None, ssh_host: String::new() };
+            let _ = env.delete();
+        }
+        PlantStep::WorkspaceOpened { session } => workspace::destroy_worker_session(session),
+    }
+}
+
+pub fn describe(step: &PlantStep) -> String {
+    match step {
+        PlantStep::BranchCreated => "branch".to_string(),
+        PlantStep::WorktreeCreated { path } => format!("worktree {}", path.display()),
+        PlantStep::PortsAllocated => "ports".to_string(),
+        PlantStep::ComposeGenerated { dir } => format!("compose files in {}", dir.display()),
+        PlantStep::ComposeStarted { .. } => "compose stack".to_string(),
+        PlantStep::RemoteProvisioned { backend, id } => format!("{} environment '{id}'", backend.name()),
+        PlantStep::WorkspaceOpened { session } => format!("workspace '{session}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_round_trip_and_reuse() {
+        let step = PlantStep::WorktreeCreated { path: PathBuf::from("/w") };
+        let json = serde_json::to_string(&step).unwrap();
+        assert_eq!(json, r#"{"step":"worktree_created","path":"/w"}"#);
+        assert_eq!(serde_json::from_str::<PlantStep>(&json).unwrap(), step);
+
+        assert!(PlantStep::PortsAllocated.reusable(true));
+        assert!(!PlantStep::PortsAllocated.reusable(false));
+        assert!(!PlantStep::ComposeStarted { file: PathBuf::from("/c") }.reusable(true));
+        assert!(!PlantStep::WorkspaceOpened { session: "s".into() }.reusable(true));
+    }
+}
@@ -1,3 +1,14 @@
+pub mod activity;
+pub mod archive;
 pub mod cleanup;
+pub mod daemon;
+pub mod deps;
+pub mod disk;
 pub mod grove;
+pub mod journal;
+pub mod remote;
+pub mod run;
+pub mod stats;
 pub mod state;
+pub mod timesheet;
+pub mod transcript;
@@ -0,0 +1,138 @@
+use std::path::Path;
+use std::process::Command;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::process::CommandExt;
+use crate::tmux::session;
+use crate::tmux::workspace::WorkspaceTemplate;
+
+/// Where a worker's environment can be provisioned instead of a local
+/// compose stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteBackend {
+    /// A DevPod workspace: the worktree's dev container, on whichever
+    /// provider DevPod is set up with (a cloud VM, Kubernetes, or local Docker)
+    Devpod,
+}
+
+/// A provisioned environment and how to reach it, kept in the worker's state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteEnv {
+    pub backend: RemoteBackend,
+    /// The backend's name for the environment
+    pub id: String,
+    /// The DevPod provider it runs on; DevPod's default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Host alias the backend added to `~/.ssh/config`, for editors and `ssh`
+    pub ssh_host: String,
+}
+
+impl RemoteBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Devpod => "devpod",
+        }
+    }
+
+    /// Check that the backend's CLI is installed.
+    pub fn is_available(&self) -> bool {
+        which::which(self.name()).is_ok()
+    }
+
+    /// The environment's name for a worker: `groot-<task>`.
+    pub fn env_id(&self, task_name: &str) -> String {
+        format!("groot-{task_name}")
+    }
+
+    /// Create (or start again) the environment for a worktree, building its
+    /// dev container, and return how to reach it.
+    pub fn provision(&self, task_name: &str, worktree: &Path, provider: Option<&str>) -> Result<RemoteEnv> {
+        let id = self.env_id(task_name);
+        match self {
+            Self::Devpod => {
+                let mut cmd = Command::new("devpod");
+                cmd.arg("up").arg(worktree).args(["--id", &id, "--ide", "none"]);
+                if let Some(provider) = provider {
+                    cmd.args(["--provider", provider]);
+                }
+                cmd.checked_output("devpod up failed")?;
+                Ok(RemoteEnv {
+                    backend: *self,
+                    ssh_host: format!("{id}.devpod"),
+                    id,
+                    provider: provider.map(str::to_string),
+                })
+            }
+        }
+    }
+}
+
+impl RemoteEnv {
+    /// Stop the environment, keeping it to be started again.
+    pub fn stop(&self) -> Result<()> {
+        match self.backend {
+            RemoteBackend::Devpod => {
+                Command::new("devpod")
+                    .args(["stop", &self.id])
+                    .checked_output("devpod stop failed")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the environment and whatever it holds.
+    pub fn delete(&self) -> Result<()> {
+        match self.backend {
+            RemoteBackend::Devpod => {
+                Command::new("devpod")
+                    .args(["delete", &self.id, "--force"])
+                    .checked_output("devpod delete failed")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The shell command that runs `command` (or a login shell) inside the
+    /// environment, from a local pane.
+    pub fn shell_command(&self, command: Option<&str>) -> String {
+        match (self.backend, command) {
+            (RemoteBackend::Devpod, Some(command)) => {
+                format!("devpod ssh {} --command {}", self.id, session::shell_quote(command))
+            }
+            (RemoteBackend::Devpod, None) => format!("devpod ssh {}", self.id),
+        }
+    }
+
+    /// Point every pane of a workspace into the environment, so the local
+    /// session is a window onto it.
+    pub fn wrap_panes(&self, template: &mut WorkspaceTemplate) {
+        for pane in template.windows.iter_mut().flat_map(|w| w.panes.iter_mut()) {
+            pane.command = Some(self.shell_command(pane.command.as_deref()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_command() {
+        let env = RemoteEnv {
+            backend: RemoteBackend::Devpod,
+            id: "groot-fix-login".to_string(),
+            provider: Some("aws".to_string()),
+            ssh_host: "groot-fix-login.devpod".to_string(),
+        };
+        assert_eq!(env.shell_command(None), "devpod ssh groot-fix-login");
+        assert_eq!(
+            env.shell_command(Some("bin/rails s -p 3000")),
+            "devpod ssh groot-fix-login --command 'bin/rails s -p 3000'"
+        );
+    }
+}
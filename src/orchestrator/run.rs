@@ -0,0 +1,170 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use crate::compose::{manager as compose_mgr, sandbox};
+use crate::config::local::Operation;
+use crate::config::webhook::WebhookEvent;
+use crate::error::{GrootError, Result};
+use crate::notify;
+use crate::orchestrator::grove;
+use crate::orchestrator::state::{GroveState, PromptVia};
+use crate::process::{command_line, CommandExt};
+use crate::store;
+
+/// The agent CLI a headless run invokes.
+pub const AGENT: &str = "claude";
+
+/// One headless agent run: when it started, how long it took, how it exited,
+/// and where its output was captured. Also written to `run.json` in `log_dir`,
+/// so it outlives the worker's state.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// `None` when the agent was killed by a signal.
+    pub exit_code: Option<i32>,
+    /// `.groot/runs/<task>/<timestamp>/`, holding `stdout.json` and `stderr.log`.
+    pub log_dir: PathBuf,
+}
+
+impl RunRecord {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// The agent CLI for `worker`: on the host, or in a sandboxed worker's
+/// `agent` container, with the worker's model.
+fn agent(worker: &GroveState) -> Command {
+    let mut cmd = match &worker.compose_file {
+        Some(cf) if worker.sandbox => {
+            let mut cmd = Command::new("docker");
+            cmd.args(["compose", "-f"])
+                .arg(cf)
+                .args(["-p", &compose_mgr::project_name(cf), "exec", "-T", sandbox::AGENT_SERVICE, AGENT]);
+            cmd
+        }
+        _ => Command::new(AGENT),
+    };
+    if let Some(model) = &worker.model {
+        cmd.args(["--model", model]);
+    }
+    cmd
+}
+
+/// Whether the agent CLI is installed.
+pub fn agent_available() -> bool {
+    which::which(AGENT).is_ok()
+}
+
+fn agent_error(e: std::io::Error) -> GrootError {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => GrootError::Other(format!(
+            "`{AGENT}` was not found on PATH; install Claude Code to run the agent from groot"
+        )),
+        _ => GrootError::Other(format!("Failed to run `{AGENT}`: {e}")),
+    }
+}
+
+/// Ask the agent a one-off question in `dir` and return its text answer.
+pub fn ask(dir: &Path, prompt: &str) -> Result<String> {
+    ask_about(dir, prompt, "")
+}
+
+/// Like [`ask`], with `input` (a diff, say) piped to the agent's stdin
+/// rather than passed as an argument, so its size isn't limited.
+pub fn ask_about(dir: &Path, prompt: &str, input: &str) -> Result<String> {
+    let mut cmd = Command::new(AGENT);
+    cmd.args(["-p", prompt, "--output-format", "text"]).current_dir(dir);
+    debug!("$ {}", command_line(&cmd));
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(agent_error)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::Other(format!("`{AGENT}` failed: {}", stderr.trim())));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Add a prompt typed into the worker's workspace to its prompt history.
+pub fn record_typed_prompt(groot_dir: &Path, task_name: &str, prompt: &str) -> Result<()> {
+    store::update_worker(groot_dir, task_name, |state| state.record_prompt(PromptVia::Typed, prompt))?
+        .ok_or_else(|| grove::not_found(groot_dir, task_name))?;
+    notify::agent_prompted(groot_dir, task_name, PromptVia::Typed.as_str());
+    Ok(())
+}
+
+/// Directory holding every headless run of `task_name`.
+pub fn runs_dir(groot_dir: &Path, task_name: &str) -> PathBuf {
+    groot_dir.join("runs").join(task_name)
+}
+
+/// Run the agent non-interactively in the worker's worktree with `prompt`,
+/// capturing its JSON output and stderr under `.groot/runs/<task>/`, then
+/// record the run in the worker's state.
+pub fn run_headless(groot_dir: &Path, worker: &GroveState, prompt: &str) -> Result<RunRecord> {
+    let started_at = Utc::now();
+    let log_dir = runs_dir(groot_dir, &worker.task_name).join(started_at.format("%Y%m%d-%H%M%S").to_string());
+    std::fs::create_dir_all(&log_dir)?;
+    let stdout = File::create(log_dir.join("stdout.json"))?;
+    let stderr = File::create(log_dir.join("stderr.log"))?;
+
+    notify::agent_prompted(groot_dir, &worker.task_name, PromptVia::Headless.as_str());
+    let started = Instant::now();
+    let status = agent(worker)
+        .args(["-p", prompt, "--output-format", "json"])
+        .current_dir(&worker.worktree_path)
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr)
+        .logged_status()
+        .map_err(agent_error)?;
+
+    let record = RunRecord {
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        exit_code: status.code(),
+        log_dir,
+    };
+    std::fs::write(record.log_dir.join("run.json"), serde_json::to_string_pretty(&record)?)?;
+
+    // Reload so a state change made while the agent ran isn't overwritten
+    let updated = store::update_worker(groot_dir, &worker.task_name, |state| {
+        state.record_prompt(PromptVia::Headless, prompt);
+        state.last_run = Some(record.clone());
+    })?;
+    if updated.is_none() {
+        return Err(grove::not_found(groot_dir, &worker.task_name));
+    }
+
+    let (event, details) = if record.succeeded() {
+        (WebhookEvent::TaskCompleted, json!({ "duration_ms": record.duration_ms, "log_dir": record.log_dir }))
+    } else {
+        (WebhookEvent::WorkerFailed, json!({ "stage": "run", "exit_code": record.exit_code, "log_dir": record.log_dir }))
+    };
+    notify::event(groot_dir, event, &worker.task_name, details);
+    let error = (!record.succeeded()).then(|| match record.exit_code {
+        Some(code) => format!("exit code {code}"),
+        None => "killed".to_string(),
+    });
+    let took = Duration::from_millis(record.duration_ms);
+    notify::finished(groot_dir, Operation::AgentDone, &worker.task_name, took, error.as_deref());
+
+    Ok(record)
+}
@@ -4,10 +4,22 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::compose::ports::AllocatedPorts;
-use crate::error::Result;
+use crate::atomic;
+use crate::error::{GrootError, Result};
+use crate::orchestrator::remote::RemoteEnv;
+use crate::orchestrator::run::RunRecord;
+use crate::terminal::TerminalBackend;
+
+/// Version of the state file format this groot writes. Older files are
+/// migrated on load; newer ones are refused rather than misread.
+pub const STATE_SCHEMA_VERSION: u32 = 1;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GroveState {
+    /// Format version the file was written with; 0 for files from before it
+    /// was recorded.
+    #[serde(default)]
+    pub schema_version: u32,
     pub task_name: String,
     pub branch: String,
     pub worktree_path: PathBuf,
@@ -27,25 +39,253 @@ pub struct GroveState {
     pub shared_grove: Option<String>,
     #[serde(default)]
     pub shared_compose_ports: Option<AllocatedPorts>,
+    /// Task type the worker was planted with (used to re-resolve its layout).
+    #[serde(default)]
+    pub task_type: Option<String>,
+    /// Named layout passed with `--layout` at plant time, if any.
+    #[serde(default)]
+    pub tmux_layout: Option<String>,
+    /// Where the workspace was opened. Only tmux sessions can be tracked and closed.
+    #[serde(default)]
+    pub terminal_backend: TerminalBackend,
+    /// When the worker outlives its profile's TTL; `groot worker reap` stops it after this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The agent runs in the compose stack's `agent` service, behind the proxy.
+    #[serde(default)]
+    pub sandbox: bool,
+    /// Every prompt given to the worker's agent, oldest first: the one it was
+    /// planted with, then each `groot worker run`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<PromptRecord>,
+    /// The most recent `groot worker run --headless`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<RunRecord>,
+    /// The environment a remote backend provisioned for a grove planted
+    /// with `--remote`, instead of a compose stack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteEnv>,
+    /// Tasks this one builds on (`--depends-on`); it's blocked until they finish
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Labels given with `--label`, sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// The worker this one is a subtask of (`--parent`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// The fan-out this worker is a variant of (`worker fan-out`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan_out: Option<FanOut>,
+    /// The model the agent runs with (`fan-out --models`); claude's default
+    /// when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Workers planted as subtasks of this one, uprooted ones included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<String>,
+    /// When the task was marked complete (`worker complete`, or when its last
+    /// subtask finished); tasks depending on it are no longer blocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// The task description the worker was planted with, kept so
+    /// CLAUDE.local.md can be rendered again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Notes added with `groot worker note`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<NoteRecord>,
+    /// What the daemon last saw of the worktree and compose stack, so
+    /// `statusline` can report it without running git or docker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<WorkerHealth>,
+}
+
+/// Which variant of a fanned-out task a worker is.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FanOut {
+    /// The task name given to `worker fan-out`
+    pub task: String,
+    /// Its number, from 1
+    pub variant: usize,
+}
+
+/// A worker's worktree and compose stack as of a daemon round.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WorkerHealth {
+    /// When it last changed
+    pub since: DateTime<Utc>,
+    /// The worktree has uncommitted changes
+    pub dirty: bool,
+    /// The compose services that aren't ready, with their state; `None` when
+    /// all are, or there's no stack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unhealthy: Option<String>,
+}
+
+/// A timestamped entry in a worker's journal.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteRecord {
+    pub at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// A rendered prompt as the agent received it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptRecord {
+    pub sent_at: DateTime<Utc>,
+    pub via: PromptVia,
+    pub text: String,
+}
+
+/// How a prompt reached the agent.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptVia {
+    /// Launched with the workspace at plant time
+    Plant,
+    /// Typed into the workspace by `groot worker run`
+    Typed,
+    /// `groot worker run --headless`
+    Headless,
+}
+
+impl PromptVia {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PromptVia::Plant => "plant",
+            PromptVia::Typed => "typed",
+            PromptVia::Headless => "headless",
+        }
+    }
 }
 
 impl GroveState {
     pub fn load(path: &Path) -> Result<Self> {
         let contents = std::fs::read_to_string(path)?;
-        let state: Self = serde_json::from_str(&contents)?;
+        Self::parse(&contents)
+            .map_err(|e| GrootError::Other(format!("Unreadable state file {}: {e}", path.display())))
+    }
+
+    /// Parse a state file, migrating it from older schema versions.
+    pub fn parse(contents: &str) -> std::result::Result<Self, String> {
+        let mut value: serde_json::Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        let version = value.get("schema_version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version > u64::from(STATE_SCHEMA_VERSION) {
+            return Err(format!(
+                "schema version {version} is newer than this groot supports ({STATE_SCHEMA_VERSION}); upgrade groot"
+            ));
+        }
+        if version < 1 {
+            migrate_v0(&mut value);
+        }
+        let mut state: Self = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        state.schema_version = STATE_SCHEMA_VERSION;
         Ok(state)
     }
 
+    /// Write the state atomically, so a crash mid-save leaves the old file.
     pub fn save(&self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         let contents = serde_json::to_string_pretty(self)?;
-        std::fs::write(path, contents)?;
-        Ok(())
+        atomic::write(path, contents)
+    }
+
+    /// The prompt the worker was planted with: the task it was created for.
+    pub fn initial_prompt(&self) -> Option<&str> {
+        self.prompts
+            .iter()
+            .find(|p| p.via == PromptVia::Plant)
+            .map(|p| p.text.as_str())
+    }
+
+    /// Add `text` to the prompt history.
+    pub fn record_prompt(&mut self, via: PromptVia, text: &str) {
+        self.prompts.push(PromptRecord {
+            sent_at: Utc::now(),
+            via,
+            text: text.to_string(),
+        });
+    }
+
+    /// The notes as a Markdown list, oldest first, for `{{NOTES}}`.
+    pub fn notes_markdown(&self) -> String {
+        self.notes
+            .iter()
+            .map(|n| {
+                let at = n.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                format!("- {at}: {}", n.text.trim().replace('\n', "\n  "))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Whether the worker has outlived its profile's TTL.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|at| at <= Utc::now())
+    }
+
+    /// Whether the worker is a grove, with a compose stack or a remote
+    /// environment, rather than a tree.
+    pub fn is_grove(&self) -> bool {
+        self.compose_file.is_some() || self.remote.is_some()
+    }
+
+    pub fn kind(&self) -> &'static str {
+        if self.is_grove() { "grove" } else { "tree" }
     }
 
     pub fn state_path(groot_dir: &Path, task_name: &str) -> PathBuf {
         groot_dir.join("groves").join(format!("{task_name}.json"))
     }
 }
+
+/// v0 kept only the planted prompt, as `prompt`; v1 keeps a history.
+fn migrate_v0(value: &mut serde_json::Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+    let legacy = obj.remove("prompt");
+    if let (Some(serde_json::Value::String(text)), false) = (legacy, obj.contains_key("prompts")) {
+        let sent_at = obj.get("created_at").cloned().unwrap_or_default();
+        obj.insert(
+            "prompts".to_string(),
+            serde_json::json!([{ "sent_at": sent_at, "via": "plant", "text": text }]),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_prompt_is_the_plant_prompt() {
+        let json = r#"{"task_name":"t","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null}"#;
+        let mut state: GroveState = serde_json::from_str(json).unwrap();
+        assert!(state.prompts.is_empty() && state.initial_prompt().is_none());
+
+        state.record_prompt(PromptVia::Typed, "also fix the tests");
+        state.record_prompt(PromptVia::Plant, "fix the login");
+        assert_eq!(state.initial_prompt(), Some("fix the login"));
+        let saved = serde_json::to_value(&state).unwrap();
+        assert_eq!(saved["prompts"][0]["via"], "typed");
+    }
+
+    #[test]
+    fn test_parse_migrates_and_rejects_newer_versions() {
+        let json = r#"{"task_name":"t","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-01T00:00:00Z","pid":null,"prompt":"fix the login"}"#;
+        let state = GroveState::parse(json).unwrap();
+        assert_eq!(state.schema_version, STATE_SCHEMA_VERSION);
+        assert_eq!(state.initial_prompt(), Some("fix the login"));
+        assert_eq!(state.prompts[0].sent_at, state.created_at);
+
+        let newer = json.replacen('{', r#"{"schema_version":99,"#, 1);
+        assert!(GroveState::parse(&newer).unwrap_err().contains("newer than this groot"));
+        assert!(GroveState::parse(r#"{"task_name":"t""#).is_err());
+    }
+}
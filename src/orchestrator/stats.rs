@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::Serialize;
+
+use crate::notify::events::LoggedEvent;
+
+/// History summarised from the event log.
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    /// The oldest event considered
+    pub since: Option<DateTime<Utc>>,
+    /// The last N ISO weeks, oldest first, empty weeks included
+    pub weeks: Vec<Week>,
+    pub plant: Timing,
+    pub failed_plants: usize,
+    pub db_clone: Timing,
+    pub lifetime_by_type: Vec<Lifetime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Week {
+    /// ISO week, e.g. `2026-W41`
+    pub week: String,
+    pub spawned: usize,
+    pub mean_plant_ms: Option<u64>,
+}
+
+/// How many times something finished successfully, and how long it took on average.
+#[derive(Debug, Default, Serialize)]
+pub struct Timing {
+    pub count: usize,
+    pub mean_ms: Option<u64>,
+}
+
+/// How long workers of a task type stayed planted, planting to stop or uproot.
+#[derive(Debug, Serialize)]
+pub struct Lifetime {
+    pub task_type: String,
+    pub stopped: usize,
+    pub mean_secs: u64,
+}
+
+fn mean(values: &[u64]) -> Option<u64> {
+    (!values.is_empty()).then(|| values.iter().sum::<u64>() / values.len() as u64)
+}
+
+fn timing(values: &[u64]) -> Timing {
+    Timing { count: values.len(), mean_ms: mean(values) }
+}
+
+fn named<'a>(events: &'a [LoggedEvent], name: &'a str) -> impl Iterator<Item = &'a LoggedEvent> {
+    events.iter().filter(move |e| e.event == name)
+}
+
+fn iso_week(t: DateTime<Utc>) -> String {
+    let week = t.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+/// Summarise `events`: spawns per week for the `weeks` weeks up to `now`,
+/// and mean plant time, clone time, and lifetime over the whole log.
+pub fn compute(events: &[LoggedEvent], now: DateTime<Utc>, weeks: u32) -> Stats {
+    let spawned: Vec<&LoggedEvent> = named(events, "worker_spawned").collect();
+    let weeks = (0..weeks)
+        .rev()
+        .map(|back| {
+            let week = iso_week(now - Duration::weeks(back.into()));
+            let planted: Vec<&&LoggedEvent> = spawned.iter().filter(|e| iso_week(e.timestamp) == week).collect();
+            let took: Vec<u64> = planted.iter().filter_map(|e| e.duration_ms).collect();
+            Week { week, spawned: planted.len(), mean_plant_ms: mean(&took) }
+        })
+        .collect();
+
+    let plant_ms: Vec<u64> = spawned.iter().filter_map(|e| e.duration_ms).collect();
+    let failed_plants = named(events, "worker_failed").filter(|e| e.stage.as_deref() == Some("plant")).count();
+    let clone_ms: Vec<u64> = named(events, "db_clone_finished")
+        .filter(|e| e.success == Some(true))
+        .filter_map(|e| e.duration_ms)
+        .collect();
+
+    let mut lifetimes: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    for e in named(events, "worker_stopped") {
+        if let Some(secs) = e.lifetime_secs {
+            let task_type = e.task_type.clone().unwrap_or_else(|| "unknown".to_string());
+            lifetimes.entry(task_type).or_default().push(secs);
+        }
+    }
+
+    Stats {
+        since: events.iter().map(|e| e.timestamp).min(),
+        weeks,
+        plant: timing(&plant_ms),
+        failed_plants,
+        db_clone: timing(&clone_ms),
+        lifetime_by_type: lifetimes
+            .into_iter()
+            .map(|(task_type, secs)| Lifetime {
+                task_type,
+                stopped: secs.len(),
+                mean_secs: mean(&secs).unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_stats() {
+        let log = r#"{"event":"worker_spawned","worker":"a","timestamp":"2026-10-05T10:00:00Z","task_type":"feature","duration_ms":4000}
+{"event":"worker_spawned","worker":"b","timestamp":"2026-10-14T10:00:00Z","task_type":"bugfix","duration_ms":2000}
+{"event":"worker_spawned","worker":"c","timestamp":"2026-10-15T10:00:00Z","task_type":"bugfix","duration_ms":6000}
+{"event":"worker_failed","worker":"d","timestamp":"2026-10-15T11:00:00Z","stage":"plant","duration_ms":500}
+{"event":"worker_failed","worker":"c","timestamp":"2026-10-15T12:00:00Z","stage":"run"}
+{"event":"db_clone_finished","worker":"c","timestamp":"2026-10-15T10:00:00Z","success":true,"duration_ms":30000}
+{"event":"db_clone_finished","worker":"d","timestamp":"2026-10-15T10:00:00Z","success":false,"duration_ms":100}
+{"event":"worker_stopped","worker":"b","timestamp":"2026-10-15T10:00:00Z","task_type":"bugfix","lifetime_secs":3600}
+{"event":"worker_stopped","worker":"a","timestamp":"2026-10-15T10:00:00Z","task_type":"bugfix","lifetime_secs":7200}"#;
+        let events: Vec<LoggedEvent> = log.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        let now: DateTime<Utc> = "2026-10-16T00:00:00Z".parse().unwrap();
+        let stats = compute(&events, now, 3);
+
+        let weeks: Vec<_> = stats.weeks.iter().map(|w| (w.week.as_str(), w.spawned, w.mean_plant_ms)).collect();
+        assert_eq!(weeks, [("2026-W40", 0, None), ("2026-W41", 1, Some(4000)), ("2026-W42", 2, Some(4000))]);
+        assert_eq!((stats.plant.count, stats.plant.mean_ms), (3, Some(4000)));
+        assert_eq!(stats.failed_plants, 1);
+        assert_eq!((stats.db_clone.count, stats.db_clone.mean_ms), (1, Some(30000)));
+        assert_eq!(stats.lifetime_by_type.len(), 1);
+        assert_eq!((stats.lifetime_by_type[0].stopped, stats.lifetime_by_type[0].mean_secs), (2, 5400));
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::notify::events::{self, LoggedEvent};
+use crate::orchestrator::state::GroveState;
+
+/// One stretch a task was planted: from a plant to the stop or uproot that
+/// ended it, or still running.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    pub started: DateTime<Utc>,
+    pub stopped: Option<DateTime<Utc>>,
+}
+
+impl Span {
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        self.stopped.unwrap_or(now) - self.started
+    }
+}
+
+/// The time each task has been planted, across stops and re-plants, from
+/// the event log.
+#[derive(Debug, Default)]
+pub struct Timesheet {
+    spans: HashMap<String, Vec<Span>>,
+}
+
+impl Timesheet {
+    /// Read the event log; `workers` are the planted ones, whose spans are
+    /// still running.
+    pub fn load(groot_dir: &Path, workers: &[GroveState]) -> Self {
+        let mut timesheet = Self::from_events(&events::read(groot_dir));
+        timesheet.settle(workers);
+        timesheet
+    }
+
+    fn from_events(events: &[LoggedEvent]) -> Self {
+        let mut timesheet = Self::default();
+        for e in events {
+            let Some(worker) = &e.worker else {
+                continue;
+            };
+            let spans = timesheet.spans.entry(worker.clone()).or_default();
+            let open = spans.last_mut().filter(|s| s.stopped.is_none());
+            match (e.event.as_str(), open) {
+                // A plant whose stop was never logged ended when the task was planted again
+                ("worker_spawned", open) => {
+                    if let Some(open) = open {
+                        open.stopped = Some(e.timestamp);
+                    }
+                    spans.push(Span { started: e.timestamp, stopped: None });
+                }
+                ("worker_stopped", Some(open)) => open.stopped = Some(e.timestamp),
+                // Planted before the log began
+                ("worker_stopped", None) => {
+                    if let Some(secs) = e.lifetime_secs {
+                        let started = e.timestamp - Duration::seconds(secs as i64);
+                        spans.push(Span { started, stopped: Some(e.timestamp) });
+                    }
+                }
+                _ => {}
+            }
+        }
+        timesheet
+    }
+
+    /// Give every planted worker a running span, and drop the running spans
+    /// of tasks that aren't planted any more: when those ended is unknown.
+    fn settle(&mut self, workers: &[GroveState]) {
+        for spans in self.spans.values_mut() {
+            spans.retain(|s| s.stopped.is_some());
+        }
+        for w in workers {
+            let spans = self.spans.entry(w.task_name.clone()).or_default();
+            spans.push(Span { started: w.created_at, stopped: None });
+        }
+        self.spans.retain(|_, spans| !spans.is_empty());
+    }
+
+    /// The task's spans, oldest first.
+    pub fn spans(&self, task: &str) -> &[Span] {
+        self.spans.get(task).map_or(&[], Vec::as_slice)
+    }
+
+    /// How long the task has been planted in all.
+    pub fn total(&self, task: &str, now: DateTime<Utc>) -> Duration {
+        self.spans(task).iter().map(|s| s.duration(now)).fold(Duration::zero(), |a, b| a + b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(json: &str) -> LoggedEvent {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_spans_across_replants() {
+        let events = [
+            event(r#"{"event":"worker_stopped","worker":"old","lifetime_secs":3600,"timestamp":"2026-01-01T10:00:00Z"}"#),
+            event(r#"{"event":"worker_spawned","worker":"api","timestamp":"2026-01-02T09:00:00Z"}"#),
+            event(r#"{"event":"worker_stopped","worker":"api","timestamp":"2026-01-02T11:00:00Z"}"#),
+            event(r#"{"event":"worker_spawned","worker":"api","timestamp":"2026-01-03T09:00:00Z"}"#),
+            event(r#"{"event":"worker_spawned","worker":"gone","timestamp":"2026-01-03T09:00:00Z"}"#),
+        ];
+        let mut timesheet = Timesheet::from_events(&events);
+        let planted = GroveState::parse(
+            r#"{"task_name":"api","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"2026-01-03T09:00:00Z","pid":null}"#,
+        )
+        .unwrap();
+        timesheet.settle(&[planted]);
+
+        let now = at("2026-01-03T09:30:00Z");
+        assert_eq!(timesheet.total("old", now), Duration::hours(1));
+        assert_eq!(timesheet.spans("api").len(), 2);
+        assert_eq!(timesheet.total("api", now), Duration::minutes(150));
+        assert!(timesheet.spans("gone").is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::orchestrator::state::GroveState;
+use crate::tmux::session;
+
+/// File the agent pane is recorded to, inside [`transcripts_dir`].
+pub const PANE_LOG: &str = "pane.log";
+
+/// Directory holding a worker's transcripts: the recorded agent pane and the
+/// agent's session JSONL files. Kept after the worker is stopped or uprooted.
+pub fn transcripts_dir(groot_dir: &Path, task_name: &str) -> PathBuf {
+    groot_dir.join("transcripts").join(task_name)
+}
+
+/// Append everything the agent pane prints to `pane.log`.
+pub fn record_pane(groot_dir: &Path, task_name: &str, target: &str) -> Result<()> {
+    let dir = transcripts_dir(groot_dir, task_name);
+    std::fs::create_dir_all(&dir)?;
+    let log = dir.join(PANE_LOG).to_string_lossy().to_string();
+    session::pipe_pane(target, &format!("cat >> {}", session::shell_quote(&log)))
+}
+
+/// Where Claude Code keeps the session JSONL files for sessions started in
+/// `worktree`: `~/.claude/projects/<path with every other character as ->`.
+fn agent_sessions_dir(worktree: &Path) -> Option<PathBuf> {
+    let config_dir = std::env::var_os("CLAUDE_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".claude")))?;
+    let encoded: String = worktree
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    Some(config_dir.join("projects").join(encoded))
+}
+
+/// Copy the agent's session JSONL files for the worker's worktree into its
+/// transcripts directory. Returns how many were copied.
+pub fn archive_sessions(groot_dir: &Path, state: &GroveState) -> Result<usize> {
+    let Some(source) = agent_sessions_dir(&state.worktree_path) else {
+        return Ok(0);
+    };
+    let Ok(entries) = std::fs::read_dir(&source) else {
+        return Ok(0);
+    };
+    let dest = transcripts_dir(groot_dir, &state.task_name).join("sessions");
+    let mut copied = 0;
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            std::fs::create_dir_all(&dest)?;
+            if let Some(name) = path.file_name() {
+                std::fs::copy(&path, dest.join(name))?;
+                copied += 1;
+            }
+        }
+    }
+    Ok(copied)
+}
+
+/// Task names with a transcripts directory, sorted.
+pub fn list(groot_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(groot_dir.join("transcripts"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_sessions_dir_encodes_path() {
+        let dir = agent_sessions_dir(Path::new("/repo/.groot/worktrees/fix_login")).unwrap();
+        assert_eq!(dir.file_name().unwrap(), "-repo--groot-worktrees-fix-login");
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::atomic;
+use crate::compose::manager as compose_mgr;
+use crate::error::{GrootError, Result};
+use crate::terminal::TerminalBackend;
+use crate::tmux::session;
+
+/// Checks for the tools groot shells out to (`tmux`, `docker compose`, a
+/// terminal backend), cached for the life of the process and, for `ttl`, in
+/// `.groot/probes.json`, so commands run in quick succession don't each
+/// re-run them.
+///
+/// Only successful probes are written to disk: a tool that was missing is
+/// checked again by the next command, so installing it (or starting Docker)
+/// takes effect at once.
+pub struct Probes {
+    path: PathBuf,
+    ttl: Duration,
+    seen: Mutex<HashMap<String, bool>>,
+}
+
+/// `.groot/probes.json`: when each tool was last found to work.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProbeCache {
+    #[serde(flatten)]
+    passed: HashMap<String, DateTime<Utc>>,
+}
+
+impl Probes {
+    /// Probes cached in `groot_dir` for `ttl_secs`; 0 caches them in memory only.
+    pub fn new(groot_dir: &Path, ttl_secs: u64) -> Self {
+        Self {
+            path: groot_dir.join("probes.json"),
+            ttl: Duration::seconds(ttl_secs as i64),
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether tmux is installed.
+    pub fn tmux(&self) -> bool {
+        self.check("tmux", session::is_available)
+    }
+
+    /// Whether a terminal backend's CLI is installed.
+    pub fn terminal(&self, backend: TerminalBackend) -> bool {
+        self.check(backend.name(), || backend.is_available())
+    }
+
+    /// Fails with `ComposeNotAvailable` unless `docker compose` works.
+    pub fn compose(&self) -> Result<()> {
+        if self.check("compose", || compose_mgr::check_available().is_ok()) {
+            Ok(())
+        } else {
+            Err(GrootError::ComposeNotAvailable)
+        }
+    }
+
+    fn check(&self, name: &str, probe: impl FnOnce() -> bool) -> bool {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(ok) = seen.get(name) {
+            return *ok;
+        }
+        // Only a fresh probe restarts the clock, so a cached result expires
+        let ok = self.passed_recently(name) || {
+            let ok = probe();
+            if ok {
+                self.remember(name);
+            }
+            ok
+        };
+        seen.insert(name.to_string(), ok);
+        ok
+    }
+
+    fn load(&self) -> ProbeCache {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    fn passed_recently(&self, name: &str) -> bool {
+        self.ttl > Duration::zero()
+            && self.load().passed.get(name).is_some_and(|at| Utc::now() - *at < self.ttl)
+    }
+
+    /// Record a passing probe. Best effort: a cache that can't be written just
+    /// means probing again.
+    fn remember(&self, name: &str) {
+        if self.ttl <= Duration::zero() || !self.path.parent().is_some_and(Path::is_dir) {
+            return;
+        }
+        let mut cache = self.load();
+        cache.passed.insert(name.to_string(), Utc::now());
+        if let Ok(json) = serde_json::to_string_pretty(&cache) {
+            let _ = atomic::write(&self.path, json);
+        }
+    }
+
+    /// Forget every cached result, so the next check of each tool runs it.
+    pub fn clear(&self) {
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probes_cache_successes_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let probes = Probes::new(dir.path(), 60);
+        assert!(probes.check("tmux", || true));
+        assert!(!probes.check("docker", || false));
+        // Answered from memory without probing again
+        assert!(probes.check("tmux", || panic!("probed twice")));
+        assert!(!probes.check("docker", || panic!("probed twice")));
+
+        // A later command trusts the recent success but re-checks the failure
+        let next = Probes::new(dir.path(), 60);
+        assert!(next.check("tmux", || panic!("probed despite the cache")));
+        assert!(next.check("docker", || true));
+
+        let uncached = Probes::new(dir.path(), 0);
+        assert!(!uncached.check("tmux", || false));
+
+        next.clear();
+        assert!(!dir.path().join("probes.json").exists());
+    }
+}
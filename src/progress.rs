@@ -0,0 +1,236 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use console::style;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use tracing::{Level, Metadata, debug, info};
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::cli::output::WorkerInfo;
+
+/// Whether steps draw spinners, and whether info-level log lines are printed
+/// above the spinner (`-v`) rather than folded into its message.
+static MODE: OnceLock<(bool, bool)> = OnceLock::new();
+
+/// The spinner of the step in progress; log output goes around it.
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// `--porcelain`: steps and log lines are reported as JSON events on stdout.
+static PORCELAIN: AtomicBool = AtomicBool::new(false);
+
+/// Turn spinners on for this invocation. They stay off unless stderr is a
+/// terminal, so piped or `--quiet` runs only see the log lines.
+pub fn init(spinners: bool, verbose: bool) {
+    let spinners = spinners && console::Term::stderr().is_term();
+    let _ = MODE.set((spinners, verbose));
+}
+
+/// Report progress as [`Event`] lines on stdout instead of spinners, for
+/// wrappers that draw their own progress UI.
+pub fn enable_porcelain() {
+    PORCELAIN.store(true, Ordering::Relaxed);
+}
+
+pub fn is_porcelain() -> bool {
+    PORCELAIN.load(Ordering::Relaxed)
+}
+
+/// Run `f` and log how long `what` took at debug level (`-v`, and always in
+/// the log file), to see where a slow listing spends its time.
+pub fn timed<T>(what: &str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    debug!("{what} took {} ms", started.elapsed().as_millis());
+    result
+}
+
+/// One line of `--porcelain` output. `step` is a stable identifier
+/// (`worktree`, `compose_up`, `compose_health`, `db_clone`, `db_setup`,
+/// `db_test_prepare`, `post_start_hooks`, `workspace`); `name` is for display.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    StepStarted { step: &'a str, name: &'a str },
+    StepFinished { step: &'a str, elapsed_ms: u64 },
+    StepFailed { step: &'a str, elapsed_ms: u64 },
+    Log { level: &'a str, message: &'a str },
+    Planted { worker: &'a WorkerInfo },
+    Error {
+        message: &'a str,
+        hint: Option<&'a str>,
+        /// The command line that failed, when a subprocess did
+        #[serde(skip_serializing_if = "Option::is_none")]
+        command: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stderr: Option<&'a str>,
+    },
+}
+
+/// Print `event` as one JSON line on stdout.
+pub fn emit(event: &Event<'_>) {
+    if let Ok(line) = serde_json::to_string(event) {
+        println!("{line}");
+    }
+}
+
+/// A slow step (creating the worktree, starting containers, cloning the
+/// database) shown as a spinner with its name and elapsed time, then as a
+/// `✓`/`✗` line once it ends. Without a terminal it just logs its name, and
+/// with `--porcelain` it emits started/finished/failed events.
+///
+/// Dropping a step without calling [`Step::done`] marks it failed, so an early
+/// `?` return still leaves the `✗` line behind.
+pub struct Step {
+    id: &'static str,
+    name: String,
+    started: Instant,
+    bar: Option<ProgressBar>,
+    finished: bool,
+}
+
+impl Step {
+    pub fn start(id: &'static str, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let spinners = MODE.get().is_some_and(|(spinners, _)| *spinners);
+        let bar = if is_porcelain() {
+            emit(&Event::StepStarted { step: id, name: &name });
+            None
+        } else if spinners {
+            let bar = ProgressBar::new_spinner()
+                .with_style(
+                    ProgressStyle::with_template("{spinner:.cyan} {prefix} {elapsed:.dim} {wide_msg:.dim}")
+                        .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+                )
+                .with_prefix(name.clone());
+            bar.enable_steady_tick(Duration::from_millis(100));
+            set_active(Some(bar.clone()));
+            Some(bar)
+        } else {
+            info!("{name}...");
+            None
+        };
+        Self {
+            id,
+            name,
+            started: Instant::now(),
+            bar,
+            finished: false,
+        }
+    }
+
+    /// End the step successfully.
+    pub fn done(mut self) {
+        self.finish(true);
+    }
+
+    fn finish(&mut self, ok: bool) {
+        self.finished = true;
+        if is_porcelain() {
+            let (step, elapsed_ms) = (self.id, self.started.elapsed().as_millis() as u64);
+            emit(&if ok {
+                Event::StepFinished { step, elapsed_ms }
+            } else {
+                Event::StepFailed { step, elapsed_ms }
+            });
+            return;
+        }
+        let Some(bar) = self.bar.take() else {
+            return;
+        };
+        set_active(None);
+        bar.finish_and_clear();
+        let mark = if ok {
+            style("✓").green().bold()
+        } else {
+            style("✗").red().bold()
+        };
+        let elapsed = format!("({:.1}s)", self.started.elapsed().as_secs_f64());
+        eprintln!("{mark} {} {}", self.name, style(elapsed).dim());
+    }
+}
+
+impl Drop for Step {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.finish(false);
+        }
+    }
+}
+
+fn set_active(bar: Option<ProgressBar>) {
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = bar;
+    }
+}
+
+/// `MakeWriter` for the log subscriber: writes to stderr without tearing the
+/// active spinner. While a step runs, info lines become its message (they're
+/// the step's own progress) unless `-v` asked for every line. With
+/// `--porcelain`, each line is a `log` event instead.
+pub struct StderrWriter;
+
+impl<'a> MakeWriter<'a> for StderrWriter {
+    type Writer = LogLine;
+
+    fn make_writer(&'a self) -> LogLine {
+        LogLine {
+            level: Level::WARN,
+            buf: Vec::new(),
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> LogLine {
+        LogLine {
+            level: *meta.level(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// One formatted log event, written out when dropped.
+pub struct LogLine {
+    level: Level,
+    buf: Vec<u8>,
+}
+
+impl Write for LogLine {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LogLine {
+    fn drop(&mut self) {
+        if is_porcelain() {
+            let text = console::strip_ansi_codes(&String::from_utf8_lossy(&self.buf)).to_string();
+            let text = text.trim();
+            // `-v` prefixes the level, which the event already carries
+            let message = text.strip_prefix(self.level.as_str()).unwrap_or(text).trim_start();
+            let level = self.level.as_str().to_ascii_lowercase();
+            emit(&Event::Log { level: &level, message });
+            return;
+        }
+        let write = |buf: &[u8]| {
+            let _ = std::io::stderr().write_all(buf);
+        };
+        let active = ACTIVE.lock().ok().and_then(|a| a.clone());
+        let Some(bar) = active else {
+            write(&self.buf);
+            return;
+        };
+        let verbose = MODE.get().is_some_and(|(_, verbose)| *verbose);
+        if self.level == Level::INFO && !verbose {
+            bar.set_message(String::from_utf8_lossy(&self.buf).trim().to_string());
+        } else {
+            bar.suspend(|| write(&self.buf));
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{Backend, Store};
+use crate::atomic;
+use crate::compose::ports::AllocatedPorts;
+use crate::error::{GrootError, Result};
+use crate::orchestrator::state::GroveState;
+
+/// One file per worker in `groves/`, and `ports.json`.
+pub struct JsonStore {
+    groot_dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct PortRegistry {
+    allocations: HashMap<String, AllocatedPorts>,
+}
+
+impl JsonStore {
+    pub fn new(groot_dir: &Path) -> Self {
+        Self {
+            groot_dir: groot_dir.to_path_buf(),
+        }
+    }
+
+    fn ports_path(&self) -> PathBuf {
+        self.groot_dir.join("ports.json")
+    }
+
+    /// Delete every state file and `ports.json`.
+    pub fn clear(&self) -> Result<()> {
+        for path in self.state_files()? {
+            std::fs::remove_file(path)?;
+        }
+        match std::fs::remove_file(self.ports_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn state_files(&self) -> Result<Vec<PathBuf>> {
+        let groves_dir = self.groot_dir.join("groves");
+        if !groves_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&groves_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
+
+impl Store for JsonStore {
+    fn backend(&self) -> Backend {
+        Backend::Json
+    }
+
+    fn load_worker(&self, task_name: &str) -> Result<Option<GroveState>> {
+        let path = GroveState::state_path(&self.groot_dir, task_name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        GroveState::load(&path).map(Some)
+    }
+
+    fn save_worker(&self, state: &GroveState) -> Result<()> {
+        state.save(&GroveState::state_path(&self.groot_dir, &state.task_name))
+    }
+
+    fn remove_worker(&self, task_name: &str) -> Result<()> {
+        match std::fs::remove_file(GroveState::state_path(&self.groot_dir, task_name)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn list_workers(&self) -> Result<Vec<GroveState>> {
+        let mut workers = Vec::new();
+        for path in self.state_files()? {
+            match GroveState::load(&path) {
+                Ok(state) => workers.push(state),
+                Err(e) => warn!("Warning: skipping {e}"),
+            }
+        }
+        workers.sort_by_key(|w| w.created_at);
+        Ok(workers)
+    }
+
+    /// Empty when `ports.json` doesn't exist yet. A corrupt registry is an
+    /// error: allocating from an empty one would hand out ports in use.
+    fn load_ports(&self) -> Result<HashMap<String, AllocatedPorts>> {
+        let path = self.ports_path();
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let registry: PortRegistry = serde_json::from_str(&contents).map_err(|e| {
+            GrootError::Other(format!(
+                "{} is corrupt ({e}); fix it or delete it, then re-plant the workers it listed",
+                path.display()
+            ))
+        })?;
+        Ok(registry.allocations)
+    }
+
+    fn save_ports(&self, allocations: &HashMap<String, AllocatedPorts>) -> Result<()> {
+        let registry = PortRegistry {
+            allocations: allocations.clone(),
+        };
+        atomic::write(&self.ports_path(), serde_json::to_string_pretty(&registry)?)
+    }
+}
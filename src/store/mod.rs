@@ -0,0 +1,215 @@
+//! Where worker state and port allocations are kept: JSON files under
+//! `.groot/` (the default), or a single SQLite database, `.groot/state.db`.
+//! A state directory uses SQLite once `groot migrate --state-backend sqlite`
+//! has created the database.
+
+pub mod json;
+pub mod sqlite;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+use crate::compose::ports::AllocatedPorts;
+use crate::config::lock::FileLock;
+use crate::error::Result;
+use crate::orchestrator::state::GroveState;
+
+/// Storage for the state groot keeps about its workers.
+pub trait Store {
+    fn backend(&self) -> Backend;
+
+    /// The worker's state, `None` if it isn't planted.
+    fn load_worker(&self, task_name: &str) -> Result<Option<GroveState>>;
+
+    /// Insert or replace the worker's state.
+    fn save_worker(&self, state: &GroveState) -> Result<()>;
+
+    /// Forget the worker. Not an error if it isn't there.
+    fn remove_worker(&self, task_name: &str) -> Result<()>;
+
+    /// Every readable worker, oldest first. Unreadable entries are skipped
+    /// with a warning.
+    fn list_workers(&self) -> Result<Vec<GroveState>>;
+
+    /// Ports allocated to each worker.
+    fn load_ports(&self) -> Result<HashMap<String, AllocatedPorts>>;
+
+    /// Replace every port allocation.
+    fn save_ports(&self, allocations: &HashMap<String, AllocatedPorts>) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// `groves/<task>.json` and `ports.json`
+    Json,
+    /// `state.db`
+    Sqlite,
+}
+
+impl Backend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Backend::Json => "json",
+            Backend::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// The store the state directory uses.
+pub fn open(groot_dir: &Path) -> Result<Box<dyn Store>> {
+    open_backend(groot_dir, current_backend(groot_dir))
+}
+
+pub fn current_backend(groot_dir: &Path) -> Backend {
+    if groot_dir.join(sqlite::DB_FILE).exists() {
+        Backend::Sqlite
+    } else {
+        Backend::Json
+    }
+}
+
+fn open_backend(groot_dir: &Path, backend: Backend) -> Result<Box<dyn Store>> {
+    Ok(match backend {
+        Backend::Json => Box::new(json::JsonStore::new(groot_dir)),
+        Backend::Sqlite => Box::new(sqlite::SqliteStore::open(groot_dir)?),
+    })
+}
+
+/// How long a command waits for another to finish updating shared state
+/// (`ports.json`, a worker's state) before giving up.
+pub const LOCK_WAIT: Duration = Duration::from_secs(10);
+
+/// Lock serializing changes to one worker's state, `locks/<task>.state.lock`.
+/// Separate from the task's plant lock, which a plant holds throughout.
+fn state_lock_path(groot_dir: &Path, task_name: &str) -> PathBuf {
+    groot_dir.join("locks").join(format!("{task_name}.state.lock"))
+}
+
+/// Load a worker's state, apply `change`, and save it, holding the worker's
+/// state lock so concurrent updates (a prompt typed while a headless run
+/// finishes, say) don't drop each other's changes. `None` if the worker
+/// isn't planted.
+pub fn update_worker(
+    groot_dir: &Path,
+    task_name: &str,
+    change: impl FnOnce(&mut GroveState),
+) -> Result<Option<GroveState>> {
+    let _lock = FileLock::acquire_within(&state_lock_path(groot_dir, task_name), LOCK_WAIT)?;
+    let store = open(groot_dir)?;
+    let Some(mut state) = store.load_worker(task_name)? else {
+        return Ok(None);
+    };
+    change(&mut state);
+    store.save_worker(&state)?;
+    Ok(Some(state))
+}
+
+/// Forget a worker under its state lock, so an update in flight can't write
+/// it back.
+pub fn remove_worker(groot_dir: &Path, task_name: &str) -> Result<()> {
+    let _lock = FileLock::acquire_within(&state_lock_path(groot_dir, task_name), LOCK_WAIT)?;
+    open(groot_dir)?.remove_worker(task_name)
+}
+
+/// Move every worker and port allocation to `to`, then drop the old
+/// backend's files. Returns how many workers were moved.
+pub fn switch_backend(groot_dir: &Path, to: Backend) -> Result<usize> {
+    // Port allocation holds this lock too, so no plant can slip in between
+    let _lock = FileLock::acquire_within(&groot_dir.join("ports.json.lock"), LOCK_WAIT)?;
+    let from = open(groot_dir)?;
+    if from.backend() == to {
+        return Ok(0);
+    }
+    let workers = from.list_workers()?;
+    let ports = from.load_ports()?;
+
+    let dest = open_backend(groot_dir, to)?;
+    for worker in &workers {
+        dest.save_worker(worker)?;
+    }
+    dest.save_ports(&ports)?;
+    drop(dest);
+
+    match from.backend() {
+        Backend::Json => json::JsonStore::new(groot_dir).clear()?,
+        Backend::Sqlite => {
+            drop(from);
+            sqlite::remove(groot_dir)?;
+        }
+    }
+    Ok(workers.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str, created_at: &str) -> GroveState {
+        let json = format!(
+            r#"{{"task_name":"{name}","branch":"b","worktree_path":"/w","container_id":null,
+            "created_at":"{created_at}","pid":null}}"#
+        );
+        GroveState::parse(&json).unwrap()
+    }
+
+    #[test]
+    fn test_switch_backend_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let groot_dir = dir.path();
+        let json = open(groot_dir).unwrap();
+        json.save_worker(&worker("later", "2026-02-01T00:00:00Z")).unwrap();
+        json.save_worker(&worker("first", "2026-01-01T00:00:00Z")).unwrap();
+        let ports = HashMap::from([("first".to_string(), AllocatedPorts { app: 3001, db: 5433, redis: 6380 })]);
+        json.save_ports(&ports).unwrap();
+
+        assert_eq!(switch_backend(groot_dir, Backend::Sqlite).unwrap(), 2);
+        let db = open(groot_dir).unwrap();
+        assert_eq!(db.backend(), Backend::Sqlite);
+        assert!(!groot_dir.join("ports.json").exists());
+        let names: Vec<_> = db.list_workers().unwrap().into_iter().map(|w| w.task_name).collect();
+        assert_eq!(names, ["first", "later"]);
+        assert_eq!(db.load_ports().unwrap()["first"].db, 5433);
+
+        db.remove_worker("later").unwrap();
+        assert!(db.load_worker("later").unwrap().is_none());
+        drop(db);
+
+        assert_eq!(switch_backend(groot_dir, Backend::Json).unwrap(), 1);
+        assert!(!groot_dir.join(sqlite::DB_FILE).exists());
+        assert_eq!(open(groot_dir).unwrap().load_worker("first").unwrap().unwrap().branch, "b");
+    }
+
+    #[test]
+    fn test_concurrent_updates_keep_every_change() {
+        use crate::orchestrator::state::PromptVia;
+
+        let dir = tempfile::tempdir().unwrap();
+        let groot_dir = dir.path().to_path_buf();
+        open(&groot_dir).unwrap().save_worker(&worker("busy", "2026-01-01T00:00:00Z")).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|w| {
+                let groot_dir = groot_dir.clone();
+                std::thread::spawn(move || {
+                    for i in 0..10 {
+                        update_worker(&groot_dir, "busy", |s| s.record_prompt(PromptVia::Typed, &format!("{w}-{i}")))
+                            .unwrap()
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let state = open(&groot_dir).unwrap().load_worker("busy").unwrap().unwrap();
+        assert_eq!(state.prompts.len(), 80);
+        assert!(update_worker(&groot_dir, "gone", |_| {}).unwrap().is_none());
+        remove_worker(&groot_dir, "busy").unwrap();
+        assert!(update_worker(&groot_dir, "busy", |_| panic!("updated a removed worker")).unwrap().is_none());
+    }
+}
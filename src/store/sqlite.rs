@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tracing::warn;
+
+use super::{Backend, Store};
+use crate::compose::ports::AllocatedPorts;
+use crate::error::{GrootError, Result};
+use crate::orchestrator::state::GroveState;
+
+/// The database file in the state directory.
+pub const DB_FILE: &str = "state.db";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS workers (
+    task_name TEXT PRIMARY KEY,
+    created_at TEXT NOT NULL,
+    state TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS ports (
+    worker TEXT PRIMARY KEY,
+    app INTEGER NOT NULL,
+    db INTEGER NOT NULL,
+    redis INTEGER NOT NULL
+);
+";
+
+/// Every worker's state as a JSON document in `workers`, one row per port
+/// allocation in `ports`. Writes are transactions, so concurrent groot
+/// processes see all of an update or none of it.
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    pub fn open(groot_dir: &Path) -> Result<Self> {
+        let conn = Connection::open(groot_dir.join(DB_FILE))?;
+        conn.busy_timeout(Duration::from_secs(10))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+}
+
+/// Delete the database, once nothing has it open.
+pub fn remove(groot_dir: &Path) -> Result<()> {
+    for suffix in ["", "-journal", "-wal", "-shm"] {
+        let path = groot_dir.join(format!("{DB_FILE}{suffix}"));
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_row(task_name: &str, json: &str) -> Result<GroveState> {
+    GroveState::parse(json)
+        .map_err(|e| GrootError::Other(format!("Unreadable state for '{task_name}' in {DB_FILE}: {e}")))
+}
+
+impl Store for SqliteStore {
+    fn backend(&self) -> Backend {
+        Backend::Sqlite
+    }
+
+    fn load_worker(&self, task_name: &str) -> Result<Option<GroveState>> {
+        let json: Option<String> = self
+            .conn
+            .query_row("SELECT state FROM workers WHERE task_name = ?1", [task_name], |row| row.get(0))
+            .optional()?;
+        json.map(|json| parse_row(task_name, &json)).transpose()
+    }
+
+    fn save_worker(&self, state: &GroveState) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO workers (task_name, created_at, state) VALUES (?1, ?2, ?3)",
+            params![state.task_name, state.created_at.to_rfc3339(), serde_json::to_string(state)?],
+        )?;
+        Ok(())
+    }
+
+    fn remove_worker(&self, task_name: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM workers WHERE task_name = ?1", [task_name])?;
+        Ok(())
+    }
+
+    fn list_workers(&self) -> Result<Vec<GroveState>> {
+        let mut stmt = self.conn.prepare("SELECT task_name, state FROM workers ORDER BY created_at")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut workers = Vec::new();
+        for row in rows {
+            let (task_name, json) = row?;
+            match parse_row(&task_name, &json) {
+                Ok(state) => workers.push(state),
+                Err(e) => warn!("Warning: skipping {e}"),
+            }
+        }
+        Ok(workers)
+    }
+
+    fn load_ports(&self) -> Result<HashMap<String, AllocatedPorts>> {
+        let mut stmt = self.conn.prepare("SELECT worker, app, db, redis FROM ports")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                AllocatedPorts {
+                    app: row.get(1)?,
+                    db: row.get(2)?,
+                    redis: row.get(3)?,
+                },
+            ))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn save_ports(&self, allocations: &HashMap<String, AllocatedPorts>) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM ports", [])?;
+        for (worker, ports) in allocations {
+            tx.execute(
+                "INSERT INTO ports (worker, app, db, redis) VALUES (?1, ?2, ?3, ?4)",
+                params![worker, ports.app, ports.db, ports.redis],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::{GrootError, Result};
+use crate::process;
+use crate::tmux::session;
+use crate::tmux::workspace::{self, WorkspaceTemplate};
+
+/// Where worker workspaces are opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalBackend {
+    /// A detached per-worker tmux session (the default)
+    #[default]
+    Tmux,
+    /// Tabs in the running kitty instance, via `kitty @` remote control
+    Kitty,
+    /// Tabs in the running WezTerm instance, via `wezterm cli`
+    Wezterm,
+    /// Tabs in iTerm2 on macOS, via its AppleScript interface (`osascript`)
+    Iterm,
+}
+
+impl TerminalBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Tmux => "tmux",
+            Self::Kitty => "kitty",
+            Self::Wezterm => "wezterm",
+            Self::Iterm => "iterm",
+        }
+    }
+
+    /// The CLI groot drives the backend with.
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Iterm => "osascript",
+            other => other.name(),
+        }
+    }
+
+    /// Check that the backend's CLI is installed.
+    pub fn is_available(&self) -> bool {
+        which::which(self.program()).is_ok()
+    }
+}
+
+/// Run a backend CLI command and return its trimmed stdout, writing `stdin` if given.
+fn run(program: &str, args: &[String], stdin: Option<&str>) -> Result<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    debug!("$ {}", process::command_line(&cmd));
+    let mut child = cmd
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let (Some(text), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(text.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::Other(format!(
+            "{program} {} failed: {}",
+            args.iter().take(2).cloned().collect::<Vec<_>>().join(" "),
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Map a tmux layout name to the closest kitty layout.
+fn kitty_layout(tmux_layout: &str) -> &'static str {
+    match tmux_layout {
+        "main-vertical" => "tall",
+        "main-horizontal" => "fat",
+        "even-horizontal" => "horizontal",
+        "even-vertical" => "vertical",
+        _ => "grid",
+    }
+}
+
+/// Open a rendered workspace template in a terminal emulator instead of tmux:
+/// one tab per window, one split per pane. Pane commands are wrapped for
+/// compose exactly like tmux panes, and `initial_command` is typed into the
+/// first pane.
+pub fn open_workspace(
+    backend: TerminalBackend,
+    tab_prefix: &str,
+    template: &WorkspaceTemplate,
+    default_dir: &Path,
+    compose_file: Option<&Path>,
+    env: &[(&str, String)],
+    initial_command: Option<&str>,
+) -> Result<()> {
+    let exec_prefix = compose_file.map(workspace::exec_prefix);
+    let mut first_pane: Option<String> = None;
+    // A window with `focus: true` wins; otherwise the first focused pane
+    let mut focus_pane: Option<String> = None;
+    let mut window_focused = false;
+
+    for window in &template.windows {
+        let title = format!("{tab_prefix}:{}", window.name);
+        let mut pane_ids: Vec<String> = Vec::new();
+        let mut window_focus_pane: Option<String> = None;
+
+        for pane in &window.panes {
+            let dir = pane
+                .directory
+                .as_deref()
+                .map(Path::new)
+                .unwrap_or(default_dir);
+            let id = match backend {
+                TerminalBackend::Kitty => kitty_launch(&title, dir, env, pane_ids.first())?,
+                TerminalBackend::Wezterm => wezterm_spawn(&title, dir, env, pane_ids.last())?,
+                TerminalBackend::Iterm => iterm_open(&title, dir, env, pane_ids.last())?,
+                TerminalBackend::Tmux => {
+                    return Err(GrootError::Other(
+                        "tmux workspaces are created with create_worker_session".to_string(),
+                    ));
+                }
+            };
+
+            if let Some(cmd) = workspace::pane_command(pane, exec_prefix.as_deref()) {
+                send_text(backend, &id, &cmd)?;
+            }
+            if pane.focus {
+                window_focus_pane = Some(id.clone());
+            }
+            pane_ids.push(id);
+        }
+
+        if let (TerminalBackend::Kitty, Some(first)) = (backend, pane_ids.first()) {
+            // Layouts missing from kitty's enabled_layouts are refused; keep the default then
+            let _ = run(
+                "kitty",
+                &[
+                    "@".into(),
+                    "goto-layout".into(),
+                    "--match".into(),
+                    format!("window_id:{first}"),
+                    kitty_layout(&window.layout).into(),
+                ],
+                None,
+            );
+        }
+
+        if window.focus && !window_focused {
+            focus_pane = window_focus_pane.or_else(|| pane_ids.first().cloned());
+            window_focused = true;
+        } else if focus_pane.is_none() {
+            focus_pane = window_focus_pane;
+        }
+        if first_pane.is_none() {
+            first_pane = pane_ids.first().cloned();
+        }
+    }
+
+    if let (Some(cmd), Some(id)) = (initial_command, first_pane.as_ref()) {
+        send_text(backend, id, cmd)?;
+    }
+    if let Some(id) = focus_pane {
+        focus(backend, &id)?;
+    }
+
+    Ok(())
+}
+
+/// Launch a kitty window: a new tab for the first pane of a window, otherwise a
+/// split in the tab that holds `tab_of`. Returns the new window id.
+fn kitty_launch(title: &str, dir: &Path, env: &[(&str, String)], tab_of: Option<&String>) -> Result<String> {
+    let mut args: Vec<String> = vec!["@".into(), "launch".into()];
+    match tab_of {
+        None => args.extend(["--type=tab".into(), "--tab-title".into(), title.into()]),
+        Some(id) => args.extend([
+            "--type=window".into(),
+            "--match".into(),
+            format!("window_id:{id}"),
+        ]),
+    }
+    args.push("--cwd".into());
+    args.push(dir.to_string_lossy().to_string());
+    for (key, value) in env {
+        args.push("--env".into());
+        args.push(format!("{key}={value}"));
+    }
+    run("kitty", &args, None)
+}
+
+/// Spawn a WezTerm pane: a new tab for the first pane of a window, otherwise a
+/// split below `split_from`. Returns the new pane id.
+fn wezterm_spawn(title: &str, dir: &Path, env: &[(&str, String)], split_from: Option<&String>) -> Result<String> {
+    let mut args: Vec<String> = vec!["cli".into()];
+    match split_from {
+        None => args.push("spawn".into()),
+        Some(id) => args.extend(["split-pane".into(), "--pane-id".into(), id.clone()]),
+    }
+    args.push("--cwd".into());
+    args.push(dir.to_string_lossy().to_string());
+
+    // `wezterm cli` has no env flag: start the user's shell through `env`
+    args.push("--".into());
+    args.push("env".into());
+    args.extend(env.iter().map(|(key, value)| format!("{key}={value}")));
+    args.push(std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()));
+
+    let id = run("wezterm", &args, None)?;
+    if split_from.is_none() {
+        run(
+            "wezterm",
+            &["cli".into(), "set-tab-title".into(), "--pane-id".into(), id.clone(), title.into()],
+            None,
+        )?;
+    }
+    Ok(id)
+}
+
+/// Finds an iTerm session by id across every window and tab.
+const ITERM_FIND_SESSION: &str = r#"on findSession(sid)
+    tell application "iTerm2"
+        repeat with w in windows
+            repeat with t in tabs of w
+                repeat with s in sessions of t
+                    if (id of s) is sid then return s
+                end repeat
+            end repeat
+        end repeat
+    end tell
+    error "iTerm session " & sid & " not found"
+end findSession
+"#;
+
+/// Quote `text` as an AppleScript string literal.
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Run an AppleScript against iTerm2, with the `findSession` handler
+/// available, and return what it returns. The script goes through stdin so
+/// nothing in it is quoted twice.
+fn iterm(script: &str) -> Result<String> {
+    run("osascript", &["-".into()], Some(&format!("{ITERM_FIND_SESSION}{script}")))
+}
+
+/// Open an iTerm session: a new tab for the first pane of a window (and a new
+/// window if none is open), otherwise a split below `split_from`. iTerm can't
+/// be given a directory or environment, so they're typed into the new shell.
+/// Returns the new session id.
+fn iterm_open(title: &str, dir: &Path, env: &[(&str, String)], split_from: Option<&String>) -> Result<String> {
+    let setup = applescript_string(&iterm_setup(dir, env));
+    let script = match split_from {
+        None => format!(
+            r#"tell application "iTerm2"
+    if (count of windows) = 0 then
+        set s to current session of (create window with default profile)
+    else
+        tell current window to set s to current session of (create tab with default profile)
+    end if
+    tell s
+        set name to {}
+        write text {setup}
+    end tell
+    return id of s
+end tell
+"#,
+            applescript_string(title)
+        ),
+        Some(id) => format!(
+            r#"set p to my findSession({})
+tell application "iTerm2"
+    tell p to set s to (split horizontally with default profile)
+    tell s to write text {setup}
+    return id of s
+end tell
+"#,
+            applescript_string(id)
+        ),
+    };
+    iterm(&script)
+}
+
+/// The shell line that moves a new iTerm session to `dir` and exports `env`.
+fn iterm_setup(dir: &Path, env: &[(&str, String)]) -> String {
+    let mut line = format!("cd {}", session::shell_quote(&dir.to_string_lossy()));
+    for (key, value) in env {
+        line.push_str(&format!(" && export {key}={}", session::shell_quote(value)));
+    }
+    line
+}
+
+/// Type a command into a pane and press Enter. The text goes through stdin so
+/// it is sent verbatim.
+fn send_text(backend: TerminalBackend, id: &str, text: &str) -> Result<()> {
+    let line = format!("{text}\r");
+    match backend {
+        TerminalBackend::Kitty => run(
+            "kitty",
+            &["@".into(), "send-text".into(), "--match".into(), format!("id:{id}"), "--stdin".into()],
+            Some(&line),
+        ),
+        TerminalBackend::Wezterm => run(
+            "wezterm",
+            &["cli".into(), "send-text".into(), "--pane-id".into(), id.into(), "--no-paste".into()],
+            Some(&line),
+        ),
+        TerminalBackend::Iterm => iterm(&format!(
+            "tell application \"iTerm2\" to tell my findSession({}) to write text {}\n",
+            applescript_string(id),
+            applescript_string(text)
+        )),
+        TerminalBackend::Tmux => Ok(String::new()),
+    }
+    .map(|_| ())
+}
+
+/// Focus a pane (and with it, its tab).
+fn focus(backend: TerminalBackend, id: &str) -> Result<()> {
+    match backend {
+        TerminalBackend::Kitty => run(
+            "kitty",
+            &["@".into(), "focus-window".into(), "--match".into(), format!("id:{id}")],
+            None,
+        ),
+        TerminalBackend::Wezterm => run(
+            "wezterm",
+            &["cli".into(), "activate-pane".into(), "--pane-id".into(), id.into()],
+            None,
+        ),
+        TerminalBackend::Iterm => iterm(&format!(
+            "tell application \"iTerm2\"\n    activate\n    tell my findSession({}) to select\nend tell\n",
+            applescript_string(id)
+        )),
+        TerminalBackend::Tmux => Ok(String::new()),
+    }
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_from_config() {
+        let backend: TerminalBackend = serde_yml::from_str("wezterm").unwrap();
+        assert_eq!(backend, TerminalBackend::Wezterm);
+        assert_eq!(TerminalBackend::default(), TerminalBackend::Tmux);
+        assert_eq!(kitty_layout("main-vertical"), "tall");
+        assert_eq!(kitty_layout("tiled"), "grid");
+        let backend: TerminalBackend = serde_yml::from_str("iterm").unwrap();
+        assert_eq!(backend.program(), "osascript");
+    }
+
+    #[test]
+    fn test_iterm_script_quoting() {
+        assert_eq!(applescript_string(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
+        assert_eq!(
+            iterm_setup(Path::new("/w/it's"), &[("GROOT_TASK", "a b".to_string())]),
+            "cd '/w/it'\\''s' && export GROOT_TASK='a b'"
+        );
+    }
+}
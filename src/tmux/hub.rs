@@ -0,0 +1,124 @@
+use std::path::Path;
+use std::process::Command;
+
+use tracing::warn;
+
+use crate::error::Result;
+use crate::process::CommandExt;
+
+use super::session;
+
+/// Name of the hub window that runs the worker monitor.
+pub const DASHBOARD_WINDOW: &str = "dashboard";
+
+/// Command run in the dashboard window.
+const DASHBOARD_COMMAND: &str = "groot worker monitor --watch";
+
+/// Window option marking a worker window the hub linked in. It stays on the
+/// window after the worker's session is gone.
+const LINK_MARKER: &str = "@groot-hub-link";
+
+/// A window of the hub session.
+#[derive(Debug, PartialEq)]
+struct HubWindow {
+    index: u32,
+    name: String,
+    /// Linked in from a worker session by a refresh (or, for hubs from before
+    /// the marker, linked into another session)
+    worker: bool,
+}
+
+/// Every window in a session.
+fn list_indexed_windows(session_name: &str) -> Vec<HubWindow> {
+    let format = format!("#{{window_index}} #{{?#{{{LINK_MARKER}}},1,#{{window_linked}}}} #{{window_name}}");
+    Command::new("tmux")
+        .args(["list-windows", "-t", session_name, "-F", &format])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_windows(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parse `<index> <worker 0|1> <name>` lines.
+fn parse_windows(stdout: &str) -> Vec<HubWindow> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ' ');
+            Some(HubWindow {
+                index: fields.next()?.parse().ok()?,
+                worker: fields.next()? == "1",
+                name: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Create or refresh the hub session.
+///
+/// The hub holds a `dashboard` window running `groot worker monitor --watch`,
+/// followed by the first window of every live worker session linked in, so a
+/// single attach gives an overview of — and quick switching between — all workers.
+/// Windows linked by a previous refresh are unlinked first, which also drops
+/// windows of workers whose session has since gone away. Windows opened in
+/// the hub by hand are left alone.
+///
+/// Returns the number of worker windows linked.
+pub fn refresh(hub_session: &str, worker_sessions: &[String], working_dir: &Path) -> Result<usize> {
+    let fresh = !session::session_exists(hub_session);
+    if fresh {
+        session::create_session(hub_session, working_dir, &[])?;
+    }
+
+    let windows = list_indexed_windows(hub_session);
+    let mut has_dashboard = false;
+    for window in &windows {
+        if window.name == DASHBOARD_WINDOW {
+            has_dashboard = true;
+        } else if fresh {
+            // The window tmux created with the session becomes the dashboard
+            let target = format!("{hub_session}:{}", window.index);
+            session::rename_window(&target, DASHBOARD_WINDOW)?;
+            session::send_keys_to_pane(&target, DASHBOARD_COMMAND)?;
+            has_dashboard = true;
+        } else if window.worker {
+            // `-k` kills it only when no worker session has it any more
+            let _ = session::unlink_window(&format!("{hub_session}:{}", window.index));
+        }
+    }
+
+    if !has_dashboard {
+        session::create_window(hub_session, DASHBOARD_WINDOW, working_dir)?;
+        let target = format!("{hub_session}:{DASHBOARD_WINDOW}");
+        session::send_keys_to_pane(&target, DASHBOARD_COMMAND)?;
+    }
+
+    let mut linked = 0;
+    for ws in worker_sessions {
+        if let Some(first) = session::list_window_indices(ws).first() {
+            let source = format!("{ws}:{first}");
+            let linked_in = session::set_window_option(&source, LINK_MARKER, "1")
+                .and_then(|()| session::link_window(&source, hub_session));
+            match linked_in {
+                Ok(()) => linked += 1,
+                Err(e) => warn!("Warning: failed to link '{ws}' into hub: {e}"),
+            }
+        }
+    }
+
+    Ok(linked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_worker_windows_are_unlinked() {
+        let windows = parse_windows("0 0 dashboard\n1 1 groot-fix-login\n2 0 my scratch\n");
+        let worker: Vec<&str> = windows.iter().filter(|w| w.worker).map(|w| w.name.as_str()).collect();
+        assert_eq!(worker, ["groot-fix-login"]);
+        assert_eq!(windows[2], HubWindow { index: 2, name: "my scratch".to_string(), worker: false });
+    }
+}
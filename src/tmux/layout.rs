@@ -1,6 +1,7 @@
 use std::process::Command;
 
 use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
 
 pub const VALID_LAYOUTS: &[&str] = &[
     "tiled",
@@ -20,7 +21,7 @@ pub fn apply_layout(session_name: &str, layout: &str) -> Result<()> {
 
     let output = Command::new("tmux")
         .args(["select-layout", "-t", session_name, layout])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
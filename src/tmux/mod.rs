@@ -1,3 +1,4 @@
+pub mod hub;
 pub mod layout;
 pub mod session;
 pub mod workspace;
@@ -2,26 +2,28 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
 
 /// Check if tmux is available
 pub fn is_available() -> bool {
     which::which("tmux").is_ok()
 }
 
-/// Check if a tmux session exists
+/// Check if a tmux session exists. The name must match exactly: tmux
+/// otherwise takes it as a prefix, and the hub `groot` would be found in
+/// a worker's `groot-<task>`.
 pub fn session_exists(session_name: &str) -> bool {
     Command::new("tmux")
-        .args(["has-session", "-t", session_name])
-        .output()
+        .args(["has-session", "-t", &format!("={session_name}")])
+        .logged_output()
         .is_ok_and(|o| o.status.success())
 }
 
 /// Create a new tmux session (detached)
-pub fn create_session(session_name: &str, working_dir: &Path) -> Result<()> {
+pub fn create_session(session_name: &str, working_dir: &Path, env: &[(&str, String)]) -> Result<()> {
     let output = Command::new("tmux")
-        .args(["new-session", "-d", "-s", session_name, "-c"])
-        .arg(working_dir)
-        .output()?;
+        .args(new_session_args(session_name, working_dir, env))
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -32,11 +34,22 @@ pub fn create_session(session_name: &str, working_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// `tmux new-session` arguments. `env` is set in the session environment
+/// before the first pane's shell starts, so that pane sees it too.
+fn new_session_args(session_name: &str, working_dir: &Path, env: &[(&str, String)]) -> Vec<String> {
+    let mut args: Vec<String> = ["new-session", "-d", "-s", session_name].map(str::to_string).into();
+    for (key, value) in env {
+        args.extend(["-e".to_string(), format!("{key}={value}")]);
+    }
+    args.extend(["-c".to_string(), working_dir.to_string_lossy().to_string()]);
+    args
+}
+
 /// Create a new window in an existing session
 pub fn create_window(session_name: &str, window_name: &str, working_dir: &Path) -> Result<()> {
     // Ensure session exists first
     if !session_exists(session_name) {
-        create_session(session_name, working_dir)?;
+        create_session(session_name, working_dir, &[])?;
         // Rename the first window (use {end} to handle any base-index setting)
         let output = Command::new("tmux")
             .args([
@@ -45,7 +58,7 @@ pub fn create_window(session_name: &str, window_name: &str, working_dir: &Path)
                 &format!("{session_name}:{{end}}"),
                 window_name,
             ])
-            .output()?;
+            .logged_output()?;
         if !output.status.success() {
             // Fallback: try without token (older tmux)
             let output2 = Command::new("tmux")
@@ -55,7 +68,7 @@ pub fn create_window(session_name: &str, window_name: &str, working_dir: &Path)
                     session_name,
                     window_name,
                 ])
-                .output()?;
+                .logged_output()?;
             if !output2.status.success() {
                 let stderr = String::from_utf8_lossy(&output2.stderr);
                 return Err(GrootError::TmuxCommand(format!(
@@ -70,7 +83,7 @@ pub fn create_window(session_name: &str, window_name: &str, working_dir: &Path)
     let output = Command::new("tmux")
         .args(["new-window", "-a", "-t", session_name, "-n", window_name, "-c"])
         .arg(working_dir)
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -89,7 +102,7 @@ pub fn kill_window(session_name: &str, window_name: &str) -> Result<()> {
             "-t",
             &format!("{session_name}:{window_name}"),
         ])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -110,7 +123,7 @@ pub fn window_exists(session_name: &str, window_name: &str) -> bool {
             "-F",
             "#{window_name}",
         ])
-        .output()
+        .logged_output()
         .is_ok_and(|o| {
             o.status.success()
                 && String::from_utf8_lossy(&o.stdout)
@@ -123,7 +136,7 @@ pub fn window_exists(session_name: &str, window_name: &str) -> bool {
 pub fn attach_session(session_name: &str) -> Result<()> {
     let status = Command::new("tmux")
         .args(["attach-session", "-t", session_name])
-        .status()?;
+        .logged_status()?;
 
     if !status.success() {
         return Err(GrootError::TmuxCommand(
@@ -138,7 +151,7 @@ pub fn send_keys(session_name: &str, window_name: &str, command: &str) -> Result
     let target = format!("{session_name}:{window_name}");
     let output = Command::new("tmux")
         .args(["send-keys", "-t", &target, command, "Enter"])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -149,12 +162,86 @@ pub fn send_keys(session_name: &str, window_name: &str, command: &str) -> Result
     Ok(())
 }
 
+/// Type `text` into a pane literally (`send-keys -l`), then press Enter.
+///
+/// Unlike `send_keys_to_pane`, tmux does not interpret key names or a trailing
+/// `;` in the text, so arbitrary prose reaches the program intact.
+pub fn send_literal_keys(target: &str, text: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["send-keys", "-t", target, "-l", "--", text])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to send keys to pane: {stderr}"
+        )));
+    }
+
+    let output = Command::new("tmux")
+        .args(["send-keys", "-t", target, "Enter"])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to send keys to pane: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Paste `text` into a pane as one bracketed paste (`paste-buffer -p`), then
+/// press Enter, so a program reading the pane gets multi-line text as a single
+/// input rather than one line per Enter.
+pub fn paste_into_pane(target: &str, text: &str) -> Result<()> {
+    const BUFFER: &str = "groot-paste";
+    let steps: [&[&str]; 3] = [
+        &["set-buffer", "-b", BUFFER, "--", text],
+        &["paste-buffer", "-p", "-d", "-b", BUFFER, "-t", target],
+        &["send-keys", "-t", target, "Enter"],
+    ];
+    for args in steps {
+        let output = Command::new("tmux").args(args).logged_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GrootError::TmuxCommand(format!(
+                "Failed to paste into pane: {stderr}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Pipe everything a pane prints to `command` (`pipe-pane -o`), e.g. to record
+/// it to a file. Does nothing if the pane is already piped.
+pub fn pipe_pane(target: &str, command: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["pipe-pane", "-o", "-t", target, command])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to pipe pane: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Quote `s` as a single POSIX shell word. Everything inside single quotes is
+/// literal (including `$`, backticks, and newlines); embedded single quotes
+/// are closed, escaped, and reopened.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
 /// Split a window to create a new pane
 pub fn split_window(target: &str, working_dir: &Path) -> Result<()> {
     let output = Command::new("tmux")
         .args(["split-window", "-t", target, "-v", "-c"])
         .arg(working_dir)
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -169,7 +256,7 @@ pub fn split_window(target: &str, working_dir: &Path) -> Result<()> {
 pub fn send_keys_to_pane(target: &str, command: &str) -> Result<()> {
     let output = Command::new("tmux")
         .args(["send-keys", "-t", target, command, "Enter"])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -184,7 +271,7 @@ pub fn send_keys_to_pane(target: &str, command: &str) -> Result<()> {
 pub fn select_pane(target: &str) -> Result<()> {
     let output = Command::new("tmux")
         .args(["select-pane", "-t", target])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -195,11 +282,26 @@ pub fn select_pane(target: &str) -> Result<()> {
     Ok(())
 }
 
+/// Select (focus) a specific window
+pub fn select_window(target: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["select-window", "-t", target])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to select window: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
 /// Apply a layout to a window (e.g., tiled, main-vertical)
 pub fn apply_window_layout(target: &str, layout: &str) -> Result<()> {
     let output = Command::new("tmux")
         .args(["select-layout", "-t", target, layout])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -210,11 +312,26 @@ pub fn apply_window_layout(target: &str, layout: &str) -> Result<()> {
     Ok(())
 }
 
+/// Set a variable in a session's environment; panes created afterwards inherit it.
+pub fn set_environment(session_name: &str, key: &str, value: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["set-environment", "-t", session_name, key, value])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to set environment: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
 /// Kill an entire tmux session
 pub fn kill_session(session_name: &str) -> Result<()> {
     let output = Command::new("tmux")
         .args(["kill-session", "-t", session_name])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -235,7 +352,7 @@ pub fn list_windows(session_name: &str) -> Result<Vec<String>> {
             "-F",
             "#{window_name}",
         ])
-        .output()?;
+        .logged_output()?;
 
     if !output.status.success() {
         return Ok(Vec::new());
@@ -246,3 +363,294 @@ pub fn list_windows(session_name: &str) -> Result<Vec<String>> {
         .map(|s| s.to_string())
         .collect())
 }
+
+/// Every pane of a session as (pane id, command running in it), in window and
+/// pane order. Empty when the session doesn't exist.
+pub fn list_panes(session_name: &str) -> Vec<(String, String)> {
+    Command::new("tmux")
+        .args(["list-panes", "-s", "-t", session_name, "-F", "#{pane_id}\t#{pane_current_command}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter_map(|l| l.split_once('\t'))
+                .map(|(id, command)| (id.to_string(), command.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The visible contents of a pane (`capture-pane -p`), or `None` when it
+/// can't be read.
+pub fn capture_pane(target: &str) -> Option<String> {
+    Command::new("tmux")
+        .args(["capture-pane", "-p", "-t", target])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+}
+
+/// Unix time of the last output in the window holding `target`.
+pub fn window_activity(target: &str) -> Option<i64> {
+    Command::new("tmux")
+        .args(["display-message", "-p", "-t", target, "#{window_activity}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+}
+
+/// List the names of all running sessions (empty when no server is running).
+pub fn list_sessions() -> Vec<String> {
+    Command::new("tmux")
+        .args(["list-sessions", "-F", "#{session_name}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Link a window into another session, appending it after the session's last window.
+/// `-d` keeps the target session's current window selected.
+pub fn link_window(source: &str, target_session: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(link_window_args(source, target_session))
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to link window: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// `tmux link-window` arguments linking `source` (`session:index`) in the
+/// background at the next free index of `target_session`.
+fn link_window_args(source: &str, target_session: &str) -> [String; 6] {
+    ["link-window", "-d", "-s", source, "-t", &format!("{target_session}:")].map(str::to_string)
+}
+
+/// `tmux unlink-window` arguments for `target`, killing it if it isn't
+/// linked anywhere else.
+fn unlink_window_args(target: &str) -> [&str; 4] {
+    ["unlink-window", "-k", "-t", target]
+}
+
+/// Unlink a window from its session, killing it if it isn't linked anywhere else.
+pub fn unlink_window(target: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(unlink_window_args(target))
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to unlink window: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// List the window indices of a session, in order.
+pub fn list_window_indices(session_name: &str) -> Vec<u32> {
+    Command::new("tmux")
+        .args(["list-windows", "-t", session_name, "-F", "#{window_index}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter_map(|l| l.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Count the panes in a window target (e.g., "session:1").
+pub fn count_panes(target: &str) -> usize {
+    Command::new("tmux")
+        .args(["list-panes", "-t", target, "-F", "#{pane_index}"])
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().count())
+        .unwrap_or(0)
+}
+
+/// Create a detached window at a specific index of an existing session.
+pub fn create_window_at(session_name: &str, index: u32, window_name: &str, working_dir: &Path) -> Result<()> {
+    let output = Command::new("tmux")
+        .args([
+            "new-window",
+            "-d",
+            "-t",
+            &format!("{session_name}:{index}"),
+            "-n",
+            window_name,
+            "-c",
+        ])
+        .arg(working_dir)
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to create window: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Rename a window. Renaming also turns off tmux's automatic-rename for it.
+pub fn rename_window(target: &str, window_name: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["rename-window", "-t", target, window_name])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to rename window: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Set a window option, e.g. a user option (`@name`) to recognise the window by.
+/// It's the window's, so every session the window is linked into sees it.
+pub fn set_window_option(target: &str, option: &str, value: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["set-option", "-w", "-t", target, option, value])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to set {option}: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Switch the current tmux client to another session (for use inside tmux).
+pub fn switch_client(session_name: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["switch-client", "-t", session_name])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to switch client: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Run a command in a popup over the current client (tmux 3.2+). `-E` closes
+/// the popup when the command exits.
+pub fn display_popup(command: &str, working_dir: &Path, title: &str) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(["display-popup", "-E", "-w", "80%", "-h", "60%", "-T", title, "-d"])
+        .arg(working_dir)
+        .arg(command)
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to open popup: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+/// Quote `s` as one double-quoted tmux command argument, escaping what tmux
+/// would otherwise unescape or expand there: `\`, `"` and `$`.
+pub fn tmux_double_quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        if matches!(c, '\\' | '"' | '$') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Append a command to a global tmux hook unless one containing `marker` is
+/// already registered (hooks are arrays, so repeated `set-hook -a` would stack).
+/// `marker` is matched against the hooks as `show-hooks` prints them.
+pub fn ensure_global_hook(hook: &str, command: &str, marker: &str) -> Result<()> {
+    let existing = Command::new("tmux")
+        .args(["show-hooks", "-g", hook])
+        .logged_output()?;
+    if existing.status.success() && String::from_utf8_lossy(&existing.stdout).contains(marker) {
+        return Ok(());
+    }
+
+    let output = Command::new("tmux")
+        .args(["set-hook", "-ag", hook, command])
+        .logged_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GrootError::TmuxCommand(format!(
+            "Failed to set {hook} hook: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_session_args_set_the_first_pane_env() {
+        let env = [("WORKER_NAME", "fix-login".to_string()), ("APP_PORT", "3001".to_string())];
+        assert_eq!(
+            new_session_args("groot-fix-login", Path::new("/w"), &env),
+            [
+                "new-session", "-d", "-s", "groot-fix-login",
+                "-e", "WORKER_NAME=fix-login", "-e", "APP_PORT=3001", "-c", "/w",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_and_unlink_window_args() {
+        assert_eq!(
+            link_window_args("groot-fix-login:1", "groot-hub"),
+            ["link-window", "-d", "-s", "groot-fix-login:1", "-t", "groot-hub:"]
+        );
+        assert_eq!(unlink_window_args("groot-hub:3"), ["unlink-window", "-k", "-t", "groot-hub:3"]);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's; $HOME `x`"), "'it'\\''s; $HOME `x`'");
+        assert_eq!(shell_quote("line one\nline two"), "'line one\nline two'");
+    }
+
+    #[test]
+    fn test_tmux_double_quote() {
+        assert_eq!(tmux_double_quote("plain 'x'"), "\"plain 'x'\"");
+        assert_eq!(tmux_double_quote(r#"a"b$c\d`e"#), r#""a\"b\$c\\d`e""#);
+    }
+}
@@ -1,11 +1,13 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::claude_md;
 use crate::compose::manager as compose_mgr;
-use crate::error::Result;
+use crate::error::{GrootError, Result};
+use crate::process::CommandExt;
 
-use super::session;
+use super::{layout, session};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspaceTemplate {
@@ -19,6 +21,13 @@ pub struct WindowTemplate {
     pub layout: String,
     #[serde(default)]
     pub panes: Vec<PaneTemplate>,
+    /// Only create this window when the condition holds, e.g. `"COMPOSE_ENABLED"`
+    /// or `"!COMPOSE_ENABLED"`. Windows without a condition are always created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    /// Select this window when the session is created, so attaching lands on it.
+    #[serde(default)]
+    pub focus: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,6 +43,12 @@ pub struct PaneTemplate {
     /// if a compose stack is running.
     #[serde(default)]
     pub host: bool,
+    /// Compose service to exec into instead of `app` (e.g. `worker`, `db`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    /// User to run the pane command as inside the container (`exec -u`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_user: Option<String>,
 }
 
 fn default_layout() -> String {
@@ -61,7 +76,168 @@ pub fn load_template(groot_dir: &Path) -> Result<Option<WorkspaceTemplate>> {
     Ok(Some(template))
 }
 
-/// Replace `{{VAR}}` placeholders in all command and directory strings.
+/// Path of a named workspace template: `.groot/tmux-layouts/<name>.json`.
+/// A name that would reach outside that directory is refused.
+pub fn named_template_path(groot_dir: &Path, name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(GrootError::Other(format!(
+            "Invalid layout name '{name}': use the file name in .groot/tmux-layouts/ without '.json'"
+        )));
+    }
+    Ok(groot_dir.join("tmux-layouts").join(format!("{name}.json")))
+}
+
+/// Load a named workspace template from `.groot/tmux-layouts/<name>.json`.
+pub fn load_named_template(groot_dir: &Path, name: &str) -> Result<WorkspaceTemplate> {
+    let path = named_template_path(groot_dir, name)?;
+    if !path.exists() {
+        return Err(GrootError::TemplateNotFound(format!(
+            "{name} (expected {})",
+            path.display()
+        )));
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    let template: WorkspaceTemplate = serde_json::from_str(&contents)?;
+    Ok(template)
+}
+
+/// List the names of all templates in `.groot/tmux-layouts/`, sorted.
+pub fn list_named_templates(groot_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(groot_dir.join("tmux-layouts"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Pick the workspace template for a new worker, in priority order:
+///
+/// 1. `--layout <name>` → `.groot/tmux-layouts/<name>.json` (must exist)
+/// 2. `.groot/tmux-layouts/<task_type>.json`
+/// 3. `.groot/tmux-layout.json`
+/// 4. The built-in default
+pub fn resolve_template(
+    groot_dir: &Path,
+    layout_name: Option<&str>,
+    task_type: &str,
+) -> Result<WorkspaceTemplate> {
+    if let Some(name) = layout_name {
+        return load_named_template(groot_dir, name);
+    }
+    if named_template_path(groot_dir, task_type).is_ok_and(|p| p.exists()) {
+        return load_named_template(groot_dir, task_type);
+    }
+    Ok(load_template(groot_dir)?.unwrap_or_else(default_template))
+}
+
+/// Placeholders substituted by `render_template`.
+pub const TEMPLATE_VARS: &[&str] = &[
+    "WORKTREE_PATH",
+    "WORKER_NAME",
+    "APP_PORT",
+    "DB_PORT",
+    "REDIS_PORT",
+    "COMPOSE_FILE",
+    "COMPOSE_PROJECT",
+];
+
+/// Names accepted by a window's `when` condition.
+pub const CONDITIONAL_VARS: &[&str] = &["COMPOSE_ENABLED"];
+
+/// Evaluate a window's `when` condition against the render variables.
+fn condition_holds(condition: Option<&str>, vars: &WorkspaceVars) -> bool {
+    let Some(condition) = condition else {
+        return true;
+    };
+    let condition = condition.trim();
+    let (negated, key) = match condition.strip_prefix('!') {
+        Some(k) => (true, k.trim()),
+        None => (false, condition),
+    };
+    let value = match key {
+        "COMPOSE_ENABLED" => vars.compose_file.is_some(),
+        _ => false,
+    };
+    value != negated
+}
+
+/// Check a workspace template for problems that would otherwise only surface
+/// mid-plant: unknown placeholders, invalid layouts, and empty windows.
+/// Returns one human-readable message per problem (empty when the template is valid).
+pub fn validate_template(template: &WorkspaceTemplate) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if template.windows.is_empty() {
+        problems.push("template has no windows".to_string());
+    }
+
+    let focused = template.windows.iter().filter(|w| w.focus).count();
+    if focused > 1 {
+        problems.push(format!("{focused} windows have focus: true (at most one allowed)"));
+    }
+
+    for (win_idx, window) in template.windows.iter().enumerate() {
+        let label = format!("window {win_idx} ('{}')", window.name);
+
+        if window.name.trim().is_empty() {
+            problems.push(format!("{label}: name is empty"));
+        }
+        if !layout::VALID_LAYOUTS.contains(&window.layout.as_str()) {
+            problems.push(format!(
+                "{label}: invalid layout '{}' (valid: {})",
+                window.layout,
+                layout::VALID_LAYOUTS.join(", ")
+            ));
+        }
+        if window.panes.is_empty() {
+            problems.push(format!("{label}: has no panes"));
+        }
+        if let Some(ref condition) = window.when {
+            let key = condition.trim().trim_start_matches('!').trim();
+            if !CONDITIONAL_VARS.contains(&key) {
+                problems.push(format!(
+                    "{label}: unknown condition '{key}' (valid: {})",
+                    CONDITIONAL_VARS.join(", ")
+                ));
+            }
+        }
+
+        let mut fields = vec![("name".to_string(), window.name.as_str())];
+        for (pane_idx, pane) in window.panes.iter().enumerate() {
+            if let Some(ref cmd) = pane.command {
+                fields.push((format!("pane {pane_idx} command"), cmd.as_str()));
+            }
+            if let Some(ref dir) = pane.directory {
+                fields.push((format!("pane {pane_idx} directory"), dir.as_str()));
+            }
+            if pane.host && (pane.service.is_some() || pane.exec_user.is_some()) {
+                problems.push(format!(
+                    "{label}: pane {pane_idx} sets service/exec_user but runs on the host"
+                ));
+            }
+        }
+
+        for (field, value) in fields {
+            for name in claude_md::find_placeholders(value) {
+                if !TEMPLATE_VARS.contains(&name.as_str()) {
+                    problems.push(format!("{label}: unknown placeholder {{{{{name}}}}} in {field}"));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// Replace `{{VAR}}` placeholders in all command and directory strings, and drop
+/// windows whose `when` condition does not hold.
 pub fn render_template(template: &WorkspaceTemplate, vars: &WorkspaceVars) -> WorkspaceTemplate {
     let compose_file_str = vars
         .compose_file
@@ -88,9 +264,12 @@ pub fn render_template(template: &WorkspaceTemplate, vars: &WorkspaceVars) -> Wo
         windows: template
             .windows
             .iter()
+            .filter(|w| condition_holds(w.when.as_deref(), vars))
             .map(|w| WindowTemplate {
                 name: substitute(&w.name),
                 layout: w.layout.clone(),
+                when: None,
+                focus: w.focus,
                 panes: w
                     .panes
                     .iter()
@@ -99,6 +278,8 @@ pub fn render_template(template: &WorkspaceTemplate, vars: &WorkspaceVars) -> Wo
                         directory: p.directory.as_ref().map(|d| substitute(d)),
                         focus: p.focus,
                         host: p.host,
+                        service: p.service.clone(),
+                        exec_user: p.exec_user.clone(),
                     })
                     .collect(),
             })
@@ -106,6 +287,38 @@ pub fn render_template(template: &WorkspaceTemplate, vars: &WorkspaceVars) -> Wo
     }
 }
 
+/// Worker context exported into the session environment. Ports and compose
+/// variables are only included when the worker has them.
+pub fn session_environment(vars: &WorkspaceVars) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("WORKER_NAME", vars.worker_name.to_string()),
+        ("WORKTREE_PATH", vars.worktree_path.to_string()),
+    ];
+    let ports = [
+        ("APP_PORT", vars.app_port),
+        ("DB_PORT", vars.db_port),
+        ("REDIS_PORT", vars.redis_port),
+    ];
+    for (key, port) in ports {
+        if let Some(port) = port {
+            env.push((key, port.to_string()));
+        }
+    }
+    if let Some(cf) = vars.compose_file {
+        env.push(("COMPOSE_FILE", cf.to_string_lossy().to_string()));
+        env.push(("COMPOSE_PROJECT", compose_mgr::project_name(cf)));
+    }
+    env
+}
+
+/// Set each variable in the session environment.
+fn export_environment(session_name: &str, env: &[(&str, String)]) -> Result<()> {
+    for (key, value) in env {
+        session::set_environment(session_name, key, value)?;
+    }
+    Ok(())
+}
+
 /// Compute the per-worker session name: `"{hub}-{task}"`.
 pub fn worker_session_name(hub_session: &str, task_name: &str) -> String {
     format!("{hub_session}-{task_name}")
@@ -117,15 +330,20 @@ pub fn worker_session_exists(hub_session: &str, task_name: &str) -> bool {
     session::session_exists(&name)
 }
 
-/// Query tmux's base-index setting (default 0, some users set to 1).
-fn get_base_index() -> u32 {
+/// Parse the value printed by `tmux show-option -gv <index option>`.
+fn parse_index_option(stdout: &str) -> Option<u32> {
+    stdout.trim().parse().ok()
+}
+
+/// Query a numeric tmux option, defaulting to 0 when unset or unreadable.
+fn get_index_option(args: &[&str]) -> u32 {
     std::process::Command::new("tmux")
-        .args(["show-option", "-gv", "base-index"])
-        .output()
+        .args(args)
+        .logged_output()
         .ok()
         .and_then(|o| {
             if o.status.success() {
-                String::from_utf8_lossy(&o.stdout).trim().parse().ok()
+                parse_index_option(&String::from_utf8_lossy(&o.stdout))
             } else {
                 None
             }
@@ -133,29 +351,110 @@ fn get_base_index() -> u32 {
         .unwrap_or(0)
 }
 
+/// Query tmux's base-index setting (default 0, some users set to 1).
+fn get_base_index() -> u32 {
+    get_index_option(&["show-option", "-gv", "base-index"])
+}
+
+/// Query tmux's pane-base-index window option (default 0, some users set to 1).
+fn get_pane_base_index() -> u32 {
+    get_index_option(&["show-option", "-gwv", "pane-base-index"])
+}
+
+/// Target for the `pane_idx`-th pane (0-based, in creation order) of a window.
+fn pane_target(win_target: &str, pane_base_index: u32, pane_idx: usize) -> String {
+    format!("{win_target}.{}", pane_base_index + pane_idx as u32)
+}
+
+/// Target for the first pane of a session's first window, honoring both
+/// `base-index` and `pane-base-index`.
+pub fn first_pane_target(session_name: &str) -> String {
+    pane_target(&format!("{session_name}:{}", get_base_index()), get_pane_base_index(), 0)
+}
+
+/// Target and current command of the pane the agent runs in: the first pane
+/// running `agent`, else the first pane, where groot launches it.
+pub fn agent_pane(session_name: &str, agent: &str) -> Option<(String, String)> {
+    let panes = session::list_panes(session_name);
+    let first = panes.first().cloned()?;
+    Some(panes.into_iter().find(|(_, command)| command == agent).unwrap_or(first))
+}
+
+/// `docker compose exec` prefix for running pane commands inside a grove's containers.
+/// The service (and user) are appended per pane by `pane_command`.
+pub fn exec_prefix(compose_file: &Path) -> String {
+    let project = compose_mgr::project_name(compose_file);
+    format!(
+        "docker compose -f \"{}\" -p \"{}\" exec",
+        compose_file.to_string_lossy(),
+        project,
+    )
+}
+
+/// The command to send to a pane, if any.
+///
+/// Compose active + non-host pane: wrap the command (or a shell) with `docker compose exec`
+/// into the pane's `service` (default `app`), as `exec_user` if set.
+/// Host pane or no compose: run the command directly.
+pub fn pane_command(pane: &PaneTemplate, exec_prefix: Option<&str>) -> Option<String> {
+    match (exec_prefix, pane.host) {
+        (Some(prefix), false) => {
+            let user = pane
+                .exec_user
+                .as_ref()
+                .map(|u| format!("-u {u} "))
+                .unwrap_or_default();
+            Some(format!(
+                "{prefix} {user}{} {}",
+                pane.service.as_deref().unwrap_or("app"),
+                pane.command.as_deref().unwrap_or("bash")
+            ))
+        }
+        _ => pane.command.clone(),
+    }
+}
+
+/// `command` run in the flake's devshell. Compound commands go through `sh -c`
+/// so all of them run inside it.
+pub fn in_nix_develop(command: &str) -> String {
+    if command.contains(['&', '|', ';', '<', '>', '$', '`', '(']) {
+        format!("nix develop -c sh -c {}", session::shell_quote(command))
+    } else {
+        format!("nix develop -c {command}")
+    }
+}
+
+/// Run every pane of a compose-less worker in the flake's devshell: commands
+/// are wrapped with `nix develop -c`, and panes without one open the devshell.
+pub fn wrap_in_nix(template: &mut WorkspaceTemplate) {
+    for pane in template.windows.iter_mut().flat_map(|w| w.panes.iter_mut()) {
+        pane.command = Some(match pane.command.take() {
+            Some(command) => in_nix_develop(&command),
+            None => "nix develop".to_string(),
+        });
+    }
+}
+
 /// Create a per-worker tmux session with windows and panes from the template.
 ///
 /// When `compose_file` is `Some`, non-host panes have their commands wrapped with
-/// `docker compose exec app` so they run inside the container instead of on the host.
+/// `docker compose exec <service>` so they run inside a container instead of on the host.
+/// `env` is set in the session environment as the session is created, so
+/// every pane has it, the first one (where the agent runs) included.
 pub fn create_worker_session(
     session_name: &str,
     template: &WorkspaceTemplate,
     default_dir: &Path,
     compose_file: Option<&Path>,
+    env: &[(&str, String)],
 ) -> Result<()> {
     // Build the exec prefix once if compose is active
-    let exec_prefix = compose_file.map(|cf| {
-        let project = compose_mgr::project_name(cf);
-        format!(
-            "docker compose -f \"{}\" -p \"{}\" exec app",
-            cf.to_string_lossy(),
-            project,
-        )
-    });
+    let exec_prefix = compose_file.map(exec_prefix);
 
     // Use window indices for targeting — window names are unreliable due to
     // automatic-rename and other tmux config that can change names after creation.
     let base_index = get_base_index();
+    let pane_base_index = get_pane_base_index();
 
     for (win_idx, window) in template.windows.iter().enumerate() {
         let win_index = base_index + win_idx as u32;
@@ -163,11 +462,11 @@ pub fn create_worker_session(
 
         if win_idx == 0 {
             // Create the session with the first window
-            session::create_session(session_name, default_dir)?;
+            session::create_session(session_name, default_dir, env)?;
             // Rename the default window (best-effort, purely cosmetic)
             let _ = std::process::Command::new("tmux")
                 .args(["rename-window", "-t", &win_target, &window.name])
-                .output();
+                .logged_output();
         } else {
             // Create additional windows
             session::create_window(session_name, &window.name, default_dir)?;
@@ -187,8 +486,8 @@ pub fn create_worker_session(
                 session::split_window(&win_target, &pane_dir)?;
             } else if pane.directory.is_some() {
                 // For the first pane, send a cd if a custom directory is set
-                let pane_target = format!("{win_target}.0");
-                session::send_keys_to_pane(&pane_target, &format!("cd {}", pane_dir.display()))?;
+                let target = pane_target(&win_target, pane_base_index, 0);
+                session::send_keys_to_pane(&target, &format!("cd {}", pane_dir.display()))?;
             }
 
             if pane.focus {
@@ -203,36 +502,117 @@ pub fn create_worker_session(
 
         // Send commands to each pane
         for (pane_idx, pane) in window.panes.iter().enumerate() {
-            let pane_target = format!("{win_target}.{pane_idx}");
-
-            match (&exec_prefix, pane.host) {
-                // Compose active + non-host pane: wrap command with docker compose exec
-                (Some(prefix), false) => {
-                    if let Some(ref cmd) = pane.command {
-                        session::send_keys_to_pane(&pane_target, &format!("{prefix} {cmd}"))?;
-                    } else {
-                        session::send_keys_to_pane(&pane_target, &format!("{prefix} bash"))?;
-                    }
-                }
-                // Host pane or no compose: run command directly (current behavior)
-                _ => {
-                    if let Some(ref cmd) = pane.command {
-                        session::send_keys_to_pane(&pane_target, cmd)?;
-                    }
-                }
+            if let Some(cmd) = pane_command(pane, exec_prefix.as_deref()) {
+                let target = pane_target(&win_target, pane_base_index, pane_idx);
+                session::send_keys_to_pane(&target, &cmd)?;
             }
         }
 
         // Select the focused pane
         if let Some(idx) = focus_pane {
-            let pane_target = format!("{win_target}.{idx}");
-            session::select_pane(&pane_target)?;
+            session::select_pane(&pane_target(&win_target, pane_base_index, idx))?;
         }
     }
 
+    // Select the focused window last, since creating windows changes the selection
+    if let Some(win_idx) = template.windows.iter().position(|w| w.focus) {
+        session::select_window(&format!("{session_name}:{}", base_index + win_idx as u32))?;
+    }
+
     Ok(())
 }
 
+/// What `reload_worker_session` changed in a live session.
+#[derive(Debug, Default)]
+pub struct ReloadSummary {
+    pub windows_created: Vec<String>,
+    pub panes_created: usize,
+    pub commands_sent: usize,
+}
+
+/// Reconcile a running per-worker session with a (rendered) template.
+///
+/// Template windows are matched to live windows by position. Missing windows
+/// and missing trailing panes are created and get their commands; existing
+/// panes are never killed. With `resend`, commands are also sent again to
+/// panes that already existed. `env` is re-exported into the session environment.
+pub fn reload_worker_session(
+    session_name: &str,
+    template: &WorkspaceTemplate,
+    default_dir: &Path,
+    compose_file: Option<&Path>,
+    env: &[(&str, String)],
+    resend: bool,
+) -> Result<ReloadSummary> {
+    if !session::session_exists(session_name) {
+        return Err(GrootError::TmuxCommand(format!(
+            "Session '{session_name}' is not running"
+        )));
+    }
+    export_environment(session_name, env)?;
+
+    let exec_prefix = compose_file.map(exec_prefix);
+    let pane_base_index = get_pane_base_index();
+    let mut indices = session::list_window_indices(session_name);
+    let mut summary = ReloadSummary::default();
+
+    for (win_idx, window) in template.windows.iter().enumerate() {
+        let (win_index, existing_panes, new_window) = match indices.get(win_idx) {
+            Some(&index) => {
+                let panes = session::count_panes(&format!("{session_name}:{index}"));
+                // Every live window has at least one pane
+                (index, panes.max(1), false)
+            }
+            None => {
+                let index = indices
+                    .last()
+                    .map(|last| last + 1)
+                    .unwrap_or_else(get_base_index);
+                session::create_window_at(session_name, index, &window.name, default_dir)?;
+                indices.push(index);
+                summary.windows_created.push(window.name.clone());
+                // A new window starts with one pane
+                (index, 1, true)
+            }
+        };
+        let win_target = format!("{session_name}:{win_index}");
+
+        for (pane_idx, pane) in window.panes.iter().enumerate() {
+            let is_new = new_window || pane_idx >= existing_panes;
+            let pane_dir = pane
+                .directory
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| default_dir.to_path_buf());
+
+            if pane_idx >= existing_panes {
+                // Split the last pane so the new pane is appended in creation order
+                let last = pane_target(&win_target, pane_base_index, pane_idx - 1);
+                session::split_window(&last, &pane_dir)?;
+                summary.panes_created += 1;
+            } else if new_window && pane.directory.is_some() {
+                let target = pane_target(&win_target, pane_base_index, 0);
+                session::send_keys_to_pane(&target, &format!("cd {}", pane_dir.display()))?;
+            }
+
+            if !is_new && !resend {
+                continue;
+            }
+            if let Some(cmd) = pane_command(pane, exec_prefix.as_deref()) {
+                let target = pane_target(&win_target, pane_base_index, pane_idx);
+                session::send_keys_to_pane(&target, &cmd)?;
+                summary.commands_sent += 1;
+            }
+        }
+
+        if window.panes.len() > 1 && window.panes.len() > existing_panes {
+            session::apply_window_layout(&win_target, &window.layout)?;
+        }
+    }
+
+    Ok(summary)
+}
+
 /// Destroy a per-worker session. Ignores errors (session may already be gone).
 pub fn destroy_worker_session(session_name: &str) {
     let _ = session::kill_session(session_name);
@@ -245,63 +625,270 @@ pub fn default_template() -> WorkspaceTemplate {
             WindowTemplate {
                 name: "server".to_string(),
                 layout: "tiled".to_string(),
+                when: None,
+                focus: false,
                 panes: vec![
                     PaneTemplate {
                         command: Some("tail -f log/development.log".to_string()),
                         directory: None,
                         focus: false,
                         host: false,
+                        service: None,
+                        exec_user: None,
                     },
                     PaneTemplate {
                         command: Some("rails console".to_string()),
                         directory: None,
                         focus: false,
                         host: false,
+                        service: None,
+                        exec_user: None,
                     },
                     PaneTemplate {
                         command: Some("bundle exec sidekiq".to_string()),
                         directory: None,
                         focus: false,
                         host: false,
+                        service: None,
+                        exec_user: None,
                     },
                     PaneTemplate {
                         command: None,
                         directory: None,
                         focus: false,
                         host: false,
+                        service: None,
+                        exec_user: None,
                     },
                 ],
             },
             WindowTemplate {
                 name: "editor".to_string(),
                 layout: "main-vertical".to_string(),
+                when: None,
+                focus: true,
                 panes: vec![
                     PaneTemplate {
                         command: Some("vim".to_string()),
                         directory: None,
                         focus: true,
                         host: true,
+                        service: None,
+                        exec_user: None,
                     },
                     PaneTemplate {
                         command: None,
                         directory: None,
                         focus: false,
                         host: true,
+                        service: None,
+                        exec_user: None,
                     },
                     PaneTemplate {
                         command: Some("claude".to_string()),
                         directory: None,
                         focus: false,
                         host: true,
+                        service: None,
+                        exec_user: None,
                     },
                     PaneTemplate {
                         command: Some("rails console".to_string()),
                         directory: None,
                         focus: false,
                         host: false,
+                        service: None,
+                        exec_user: None,
                     },
                 ],
             },
+            WindowTemplate {
+                name: "logs".to_string(),
+                layout: "tiled".to_string(),
+                when: Some("COMPOSE_ENABLED".to_string()),
+                focus: false,
+                panes: vec![PaneTemplate {
+                    command: Some(
+                        "docker compose -f \"{{COMPOSE_FILE}}\" -p \"{{COMPOSE_PROJECT}}\" logs -f app"
+                            .to_string(),
+                    ),
+                    directory: None,
+                    focus: false,
+                    host: true,
+                    service: None,
+                    exec_user: None,
+                }],
+            },
         ],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_template_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let groot_dir = dir.path();
+        let first_window = |layout: Option<&str>, task_type: &str| {
+            resolve_template(groot_dir, layout, task_type).map(|t| t.windows[0].name.clone())
+        };
+        let write = |path: PathBuf, window: &str| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, format!(r#"{{"windows":[{{"name":"{window}"}}]}}"#)).unwrap();
+        };
+
+        assert_eq!(first_window(None, "bugfix").unwrap(), "server");
+        write(groot_dir.join("tmux-layout.json"), "project");
+        assert_eq!(first_window(None, "bugfix").unwrap(), "project");
+        write(named_template_path(groot_dir, "bugfix").unwrap(), "bugfix");
+        assert_eq!(first_window(None, "bugfix").unwrap(), "bugfix");
+        assert_eq!(first_window(None, "feature").unwrap(), "project");
+        write(named_template_path(groot_dir, "review").unwrap(), "review");
+        assert_eq!(first_window(Some("review"), "bugfix").unwrap(), "review");
+        assert!(first_window(Some("missing"), "bugfix").is_err());
+    }
+
+    #[test]
+    fn test_layout_names_stay_in_the_layouts_dir() {
+        let groot_dir = Path::new("/repo/.groot");
+        assert_eq!(
+            named_template_path(groot_dir, "review").unwrap(),
+            Path::new("/repo/.groot/tmux-layouts/review.json")
+        );
+        for name in ["../../x", "a/b", "..", "", "a\\b"] {
+            assert!(named_template_path(groot_dir, name).is_err(), "{name}");
+        }
+        // A task type that isn't a valid name falls through to the defaults
+        assert!(resolve_template(groot_dir, None, "../x").is_ok());
+    }
+
+    #[test]
+    fn test_parse_index_option() {
+        assert_eq!(parse_index_option("0\n"), Some(0));
+        assert_eq!(parse_index_option("1\n"), Some(1));
+        assert_eq!(parse_index_option(" 2 "), Some(2));
+        assert_eq!(parse_index_option(""), None);
+        assert_eq!(parse_index_option("invalid option: pane-base-index\n"), None);
+    }
+
+    fn vars_with_compose(compose_file: Option<&Path>) -> WorkspaceVars<'_> {
+        WorkspaceVars {
+            worktree_path: "/tmp/worktrees/fix-login",
+            worker_name: "fix-login",
+            app_port: Some(3001),
+            db_port: Some(5433),
+            redis_port: Some(6380),
+            compose_file,
+        }
+    }
+
+    #[test]
+    fn test_default_template_logs_window_requires_compose() {
+        let compose_file = Path::new("/tmp/compose/fix-login/docker-compose.yml");
+
+        let rendered = render_template(&default_template(), &vars_with_compose(Some(compose_file)));
+        let logs = rendered.windows.iter().find(|w| w.name == "logs").unwrap();
+        assert_eq!(
+            logs.panes[0].command.as_deref(),
+            Some("docker compose -f \"/tmp/compose/fix-login/docker-compose.yml\" -p \"groot-fix-login\" logs -f app")
+        );
+        assert!(logs.panes[0].host);
+
+        let rendered = render_template(&default_template(), &vars_with_compose(None));
+        assert!(rendered.windows.iter().all(|w| w.name != "logs"));
+    }
+
+    #[test]
+    fn test_negated_window_condition() {
+        let vars = vars_with_compose(None);
+        assert!(condition_holds(None, &vars));
+        assert!(!condition_holds(Some("COMPOSE_ENABLED"), &vars));
+        assert!(condition_holds(Some("!COMPOSE_ENABLED"), &vars));
+    }
+
+    #[test]
+    fn test_session_environment() {
+        let compose_file = Path::new("/tmp/compose/fix-login/docker-compose.yml");
+        let env = session_environment(&vars_with_compose(Some(compose_file)));
+        let get = |key: &str| env.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str());
+        assert_eq!(get("WORKER_NAME"), Some("fix-login"));
+        assert_eq!(get("APP_PORT"), Some("3001"));
+        assert_eq!(get("COMPOSE_PROJECT"), Some("groot-fix-login"));
+
+        let tree = WorkspaceVars {
+            app_port: None,
+            db_port: None,
+            redis_port: None,
+            ..vars_with_compose(None)
+        };
+        let env = session_environment(&tree);
+        assert_eq!(
+            env.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+            ["WORKER_NAME", "WORKTREE_PATH"]
+        );
+    }
+
+    #[test]
+    fn test_pane_command_wraps_non_host_panes() {
+        let pane = |command: Option<&str>, host: bool| PaneTemplate {
+            command: command.map(|c| c.to_string()),
+            directory: None,
+            focus: false,
+            host,
+            service: None,
+            exec_user: None,
+        };
+        let prefix = Some("docker compose exec");
+
+        assert_eq!(
+            pane_command(&pane(Some("rails console"), false), prefix).as_deref(),
+            Some("docker compose exec app rails console")
+        );
+        let sidekiq = PaneTemplate {
+            service: Some("worker".to_string()),
+            exec_user: Some("deploy".to_string()),
+            ..pane(Some("bundle exec sidekiq"), false)
+        };
+        assert_eq!(
+            pane_command(&sidekiq, prefix).as_deref(),
+            Some("docker compose exec -u deploy worker bundle exec sidekiq")
+        );
+        assert_eq!(
+            pane_command(&pane(None, false), prefix).as_deref(),
+            Some("docker compose exec app bash")
+        );
+        assert_eq!(pane_command(&pane(Some("vim"), true), prefix).as_deref(), Some("vim"));
+        assert_eq!(pane_command(&pane(None, false), None), None);
+    }
+
+    #[test]
+    fn test_wrap_in_nix() {
+        let mut template = default_template();
+        template.windows.truncate(1);
+        template.windows[0].panes = vec![
+            PaneTemplate { command: Some("claude".to_string()), ..template.windows[0].panes[0].clone() },
+            PaneTemplate { command: None, ..template.windows[0].panes[0].clone() },
+            PaneTemplate { command: Some("bin/setup && bin/dev".to_string()), ..template.windows[0].panes[0].clone() },
+        ];
+        wrap_in_nix(&mut template);
+        let commands: Vec<_> = template.windows[0].panes.iter().map(|p| p.command.as_deref().unwrap()).collect();
+        assert_eq!(
+            commands,
+            ["nix develop -c claude", "nix develop", "nix develop -c sh -c 'bin/setup && bin/dev'"]
+        );
+    }
+
+    #[test]
+    fn test_pane_target_default_base() {
+        assert_eq!(pane_target("groot-fix:0", 0, 0), "groot-fix:0.0");
+        assert_eq!(pane_target("groot-fix:0", 0, 3), "groot-fix:0.3");
+    }
+
+    #[test]
+    fn test_pane_target_pane_base_index_one() {
+        assert_eq!(pane_target("groot-fix:1", 1, 0), "groot-fix:1.1");
+        assert_eq!(pane_target("groot-fix:1", 1, 3), "groot-fix:1.4");
+    }
+}
@@ -0,0 +1,79 @@
+//! Running under WSL2, where a repo on the Windows drive (`/mnt/c/...`)
+//! makes every bind mount and file watch cross the 9P bridge, and Docker may
+//! be the Windows `docker.exe` that expects Windows paths.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::process::CommandExt;
+
+/// Whether groot is running inside WSL.
+pub fn is_wsl() -> bool {
+    std::env::var_os("WSL_DISTRO_NAME").is_some()
+        || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| release.to_lowercase().contains("microsoft"))
+}
+
+/// The Windows drive `path` is on, when it's a `/mnt/<drive>/...` mount.
+pub fn windows_drive(path: &Path) -> Option<char> {
+    let mut components = path.strip_prefix("/mnt").ok()?.components();
+    let drive = components.next()?.as_os_str().to_str()?;
+    let mut chars = drive.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_alphabetic() => Some(letter.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// Whether `path` is on a Windows drive seen from WSL, where bind mounts
+/// are slow.
+pub fn on_windows_drive(path: &Path) -> bool {
+    is_wsl() && windows_drive(path).is_some()
+}
+
+/// A place for worktrees on the Linux filesystem: `~/groot-worktrees/<project>`.
+pub fn linux_worktrees_dir(project: &str) -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join("groot-worktrees").join(project))
+}
+
+/// Whether `docker` is the Windows CLI (`docker.exe` from Docker Desktop on
+/// the Windows PATH) rather than a Linux one, so it needs Windows paths.
+fn windows_docker() -> bool {
+    which::which("docker").is_ok_and(|docker| {
+        let docker = docker.canonicalize().unwrap_or(docker);
+        docker.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe")) || windows_drive(&docker).is_some()
+    })
+}
+
+/// `path` as the Docker CLI in use needs it in a compose file: unchanged,
+/// except for a Windows `docker.exe` under WSL, which gets `wslpath -w`'s
+/// Windows form (`C:\...` or `\\wsl$\<distro>\...`).
+pub fn docker_path(path: &Path) -> String {
+    let unix = path.to_string_lossy().to_string();
+    if !is_wsl() || !windows_docker() {
+        return unix;
+    }
+    Command::new("wslpath")
+        .arg("-w")
+        .arg(path)
+        .logged_output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or(unix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_drive() {
+        assert_eq!(windows_drive(Path::new("/mnt/c/Users/me/app")), Some('C'));
+        assert_eq!(windows_drive(Path::new("/mnt/d")), Some('D'));
+        assert_eq!(windows_drive(Path::new("/mnt/wsl/shared")), None);
+        assert_eq!(windows_drive(Path::new("/home/me/app")), None);
+        assert_eq!(windows_drive(Path::new("/mntc/app")), None);
+    }
+}